@@ -1,16 +1,26 @@
+#[cfg(feature = "recording")]
+use crate::recording::{
+    RecordedSession, ReplayEventHandler, ReplayPacing, SessionEntry, SessionRecorder,
+};
+#[cfg(feature = "theme-file")]
+use crate::theme_file::ThemeWatcher;
 use crate::{
     command_executor,
     controls::{
         button_handler, chart_handler, checkbox_handler, combobox_handler, dialog_handler,
-        label_handler, menu_handler, panel_handler, progress_handler, radiobutton_handler,
-        richedit_handler, splitter_handler, styling_handler, tab_bar_handler,
-        toggle_switch_handler, treeview_handler,
+        image_handler::{self, SharedImageRegistry},
+        label_handler, list_view_handler, menu_handler, numeric_input_handler, panel_handler,
+        progress_handler, radiobutton_handler, richedit_handler, slider_handler, splitter_handler,
+        status_bar_handler, styling_handler, tab_bar_handler, toggle_switch_handler,
+        toolbar_handler, tooltip_handler, treeview_handler,
     },
     error::{PlatformError, Result as PlatformResult},
-    styling::{ControlStyle, FontWeight, ParsedControlStyle, StyleId},
+    styling::{ControlStyle, ParsedControlStyle, StyleId},
+    styling_primitives::resolve_style,
+    theme::Theme,
     types::{
-        AppEvent, ControlId, PlatformCommand, PlatformEventHandler, UiStateProvider, WindowConfig,
-        WindowId,
+        AppEvent, ControlId, ImageId, ListViewDataProvider, PlatformCommand, PlatformEventHandler,
+        UiStateProvider, WindowConfig, WindowId,
     },
     window_common,
 };
@@ -20,16 +30,15 @@ use windows::{
     Win32::{
         Foundation::{GetLastError, HINSTANCE, HWND, LPARAM, WPARAM},
         Graphics::Gdi::{
-            CLIP_DEFAULT_PRECIS, CreateFontW, CreateSolidBrush, DEFAULT_CHARSET, DEFAULT_QUALITY,
-            FF_DONTCARE, FW_BOLD, FW_NORMAL, GetDC, GetDeviceCaps, HBRUSH, HFONT, InvalidateRect,
-            LOGPIXELSY, OUT_DEFAULT_PRECIS, ReleaseDC,
+            CreateSolidBrush, HBRUSH, InvalidateRect, RDW_ALLCHILDREN, RDW_ERASE, RDW_FRAME,
+            RDW_INVALIDATE, RedrawWindow,
         },
         System::Com::{CoInitializeEx, CoUninitialize},
         System::LibraryLoader::{GetModuleHandleW, LoadLibraryW},
-        System::WindowsProgramming::MulDiv,
         UI::Controls::{
-            ICC_PROGRESS_CLASS, ICC_TREEVIEW_CLASSES, INITCOMMONCONTROLSEX, InitCommonControlsEx,
-            PBM_SETBARCOLOR, PBM_SETBKCOLOR, SetWindowTheme, TVM_SETBKCOLOR, TVM_SETTEXTCOLOR,
+            ICC_LISTVIEW_CLASSES, ICC_PROGRESS_CLASS, ICC_TREEVIEW_CLASSES, INITCOMMONCONTROLSEX,
+            InitCommonControlsEx, PBM_SETBARCOLOR, PBM_SETBKCOLOR, SetWindowTheme, TVM_SETBKCOLOR,
+            TVM_SETTEXTCOLOR,
         },
         UI::WindowsAndMessaging::*,
     },
@@ -47,6 +56,7 @@ static LOAD_RICHEDIT_DLL_ONCE: Once = Once::new();
 
 // Type alias for the complex UI state provider type to reduce type complexity.
 type UiStateProviderHolder = Mutex<Option<Weak<Mutex<dyn UiStateProvider>>>>;
+type ListViewDataProviderHolder = Mutex<Option<Weak<Mutex<dyn ListViewDataProvider>>>>;
 
 /*
  * Internal state for the Win32 platform layer.
@@ -64,11 +74,28 @@ pub(crate) struct Win32ApiInternalState {
     active_windows: RwLock<HashMap<WindowId, window_common::NativeWindowData>>,
     application_event_handler: Mutex<Option<Weak<Mutex<dyn PlatformEventHandler>>>>,
     ui_state_provider: UiStateProviderHolder,
+    list_view_data_provider: ListViewDataProviderHolder,
     // Stores processed, native-ready style definitions, keyed by a semantic ID.
+    // Each is parsed from the style merged over its base chain.
     defined_styles: RwLock<HashMap<StyleId, Arc<ParsedControlStyle>>>,
+    // The styles as defined, with their `base` references, so styles derived
+    // from a redefined base can be re-resolved.
+    style_definitions: RwLock<HashMap<StyleId, ControlStyle>>,
+    // Parsed styles of controls that inherit from styled ancestors, keyed by
+    // the control's style followed by its ancestors' styles, nearest first.
+    inherited_styles: RwLock<HashMap<Vec<StyleId>, Arc<ParsedControlStyle>>>,
+    // The active theme; owner-drawn controls take their palettes from it on creation.
+    theme: RwLock<Arc<Theme>>,
+    // Decoded images by ImageId; controls that draw images hold a clone of the Arc.
+    images: SharedImageRegistry,
     // The application name, used for window class registration.
     app_name_for_class: String,
     is_quitting: AtomicUsize, // 0 = false, 1 = true
+    #[cfg(feature = "recording")]
+    session_recorder: RwLock<Option<Arc<SessionRecorder>>>,
+    // Polled by the message loop; a changed theme file is re-applied.
+    #[cfg(feature = "theme-file")]
+    theme_watcher: Mutex<Option<ThemeWatcher>>,
 }
 
 // SAFETY: All fields are Send + Sync or wrapped in thread-safe containers, and trait objects are required to be Send + Sync.
@@ -133,6 +160,10 @@ impl Win32ApiInternalState {
         &self.ui_state_provider
     }
 
+    pub(crate) fn list_view_data_provider(&self) -> &ListViewDataProviderHolder {
+        &self.list_view_data_provider
+    }
+
     pub(crate) fn app_name_for_class(&self) -> &str {
         &self.app_name_for_class
     }
@@ -155,7 +186,7 @@ impl Win32ApiInternalState {
 
             let icex = INITCOMMONCONTROLSEX {
                 dwSize: std::mem::size_of::<INITCOMMONCONTROLSEX>() as u32,
-                dwICC: ICC_TREEVIEW_CLASSES | ICC_PROGRESS_CLASS,
+                dwICC: ICC_TREEVIEW_CLASSES | ICC_PROGRESS_CLASS | ICC_LISTVIEW_CLASSES,
             };
             if !InitCommonControlsEx(&icex).as_bool() {
                 log::error!(
@@ -177,9 +208,18 @@ impl Win32ApiInternalState {
                 active_windows: RwLock::new(HashMap::new()),
                 application_event_handler: Mutex::new(None),
                 ui_state_provider: Mutex::new(None),
+                list_view_data_provider: Mutex::new(None),
                 defined_styles: RwLock::new(HashMap::new()),
+                style_definitions: RwLock::new(HashMap::new()),
+                inherited_styles: RwLock::new(HashMap::new()),
+                theme: RwLock::new(Arc::new(Theme::dark())),
+                images: SharedImageRegistry::default(),
                 app_name_for_class,
                 is_quitting: AtomicUsize::new(0),
+                #[cfg(feature = "recording")]
+                session_recorder: RwLock::new(None),
+                #[cfg(feature = "theme-file")]
+                theme_watcher: Mutex::new(None),
             }))
         }
     }
@@ -188,6 +228,8 @@ impl Win32ApiInternalState {
     // This centralizes the logic for locking, upgrading the weak reference,
     // and calling the handler.
     pub(crate) fn send_event(self: &Arc<Self>, event: AppEvent) {
+        #[cfg(feature = "recording")]
+        self.with_session_recorder(|recorder| recorder.record_event(&event));
         let event_handler_opt = self
             .application_event_handler
             .lock()
@@ -206,6 +248,41 @@ impl Win32ApiInternalState {
         }
     }
 
+    #[cfg(feature = "recording")]
+    pub(crate) fn with_session_recorder(&self, f: impl FnOnce(&SessionRecorder)) {
+        if let Ok(guard) = self.session_recorder.read()
+            && let Some(recorder) = guard.as_ref()
+        {
+            f(recorder);
+        }
+    }
+
+    /*
+     * Reloads the watched theme file if it changed and applies it. A file that
+     * no longer loads is reported and the current theme is kept.
+     * [CDU-Styling-ThemeFileV1]
+     */
+    #[cfg(feature = "theme-file")]
+    fn poll_theme_watcher(self: &Arc<Self>) {
+        let reloaded = match self.theme_watcher.lock() {
+            Ok(mut guard) => guard.as_mut().and_then(ThemeWatcher::poll),
+            Err(e) => {
+                log::error!("Platform: Failed to lock theme watcher: {e:?}");
+                None
+            }
+        };
+        match reloaded {
+            Some(Ok(theme)) => {
+                log::info!("Platform: Theme file changed, applying '{}'", theme.name);
+                if let Err(e) = self.apply_theme(theme) {
+                    log::error!("Platform: Failed to apply reloaded theme: {e:?}");
+                }
+            }
+            Some(Err(e)) => log::warn!("Platform: Theme file reload failed: {e}"),
+            None => {}
+        }
+    }
+
     /*
      * Removes the data for a given window ID from the active windows map.
      * This is a map-level operation that acquires a write lock, removes the
@@ -411,6 +488,25 @@ impl Win32ApiInternalState {
      */
     fn execute_platform_command(self: &Arc<Self>, command: PlatformCommand) -> PlatformResult<()> {
         log::trace!("Platform: Executing command: {command:?}");
+        #[cfg(feature = "recording")]
+        self.with_session_recorder(|recorder| recorder.record_command(&command));
+        // Text and style changes can alter the measured size of `SizeMode::Auto` controls.
+        let remeasure_window = match &command {
+            PlatformCommand::SetControlText { window_id, .. }
+            | PlatformCommand::SetInputText { window_id, .. }
+            | PlatformCommand::UpdateLabelText { window_id, .. }
+            | PlatformCommand::SetComboBoxItems { window_id, .. }
+            | PlatformCommand::ApplyStyleToControl { window_id, .. } => Some(*window_id),
+            _ => None,
+        };
+        self.dispatch_platform_command(command)?;
+        if let Some(window_id) = remeasure_window {
+            self.relayout_if_auto_sized(window_id);
+        }
+        Ok(())
+    }
+
+    fn dispatch_platform_command(self: &Arc<Self>, command: PlatformCommand) -> PlatformResult<()> {
         match command {
             PlatformCommand::SetWindowTitle { window_id, title } => {
                 command_executor::execute_set_window_title(self, window_id, &title)
@@ -542,12 +638,14 @@ impl Win32ApiInternalState {
                 parent_control_id,
                 control_id,
                 text,
+                image,
             } => button_handler::handle_create_button_command(
                 self,
                 window_id,
                 parent_control_id,
                 control_id,
                 text,
+                image,
             ),
             PlatformCommand::CreateTreeView {
                 window_id,
@@ -749,18 +847,26 @@ impl Win32ApiInternalState {
                 control_id,
                 parent_control_id,
                 items,
+                images,
+                tooltips,
             } => tab_bar_handler::handle_create_tab_bar_command(
                 self,
                 window_id,
                 control_id,
                 parent_control_id,
                 items,
+                images,
+                tooltips,
             ),
             PlatformCommand::SetTabBarItems {
                 window_id,
                 control_id,
                 items,
-            } => tab_bar_handler::handle_set_tab_bar_items(self, window_id, control_id, items),
+                images,
+                tooltips,
+            } => tab_bar_handler::handle_set_tab_bar_items(
+                self, window_id, control_id, items, images, tooltips,
+            ),
             PlatformCommand::SetTabBarSelection {
                 window_id,
                 control_id,
@@ -844,6 +950,7 @@ impl Win32ApiInternalState {
                 self, window_id, control_id, item_id,
             ),
             PlatformCommand::DefineStyle { style_id, style } => self.define_style(style_id, style),
+            PlatformCommand::ApplyTheme { theme } => self.apply_theme(theme),
             PlatformCommand::ApplyStyleToControl {
                 window_id,
                 control_id,
@@ -882,7 +989,7 @@ impl Win32ApiInternalState {
                 self,
                 window_id,
                 control_id,
-                toggle_switch_handler::ToggleSwitchPalette {
+                crate::theme::ToggleSwitchPalette {
                     background,
                     pill_off,
                     pill_on,
@@ -890,17 +997,213 @@ impl Win32ApiInternalState {
                     text,
                 },
             ),
+            PlatformCommand::CreateSlider {
+                window_id,
+                parent_control_id,
+                control_id,
+                range,
+                orientation,
+                value,
+            } => slider_handler::handle_create_slider_command(
+                self,
+                window_id,
+                parent_control_id,
+                control_id,
+                range,
+                orientation,
+                value,
+            ),
+            PlatformCommand::SetSliderValue {
+                window_id,
+                control_id,
+                value,
+            } => {
+                slider_handler::handle_set_slider_value_command(self, window_id, control_id, value)
+            }
+            PlatformCommand::SetSliderStyle {
+                window_id,
+                control_id,
+                background,
+                track,
+                fill,
+                thumb,
+                tick,
+            } => slider_handler::handle_set_slider_style_command(
+                self,
+                window_id,
+                control_id,
+                crate::theme::SliderPalette {
+                    background,
+                    track,
+                    fill,
+                    thumb,
+                    tick,
+                },
+            ),
+            PlatformCommand::CreateNumericInput {
+                window_id,
+                parent_control_id,
+                control_id,
+                spec,
+                value,
+            } => numeric_input_handler::handle_create_numeric_input_command(
+                self,
+                window_id,
+                parent_control_id,
+                control_id,
+                spec,
+                value,
+            ),
+            PlatformCommand::SetNumericValue {
+                window_id,
+                control_id,
+                value,
+            } => numeric_input_handler::handle_set_numeric_value_command(
+                self, window_id, control_id, value,
+            ),
+            PlatformCommand::CreateStatusBar {
+                window_id,
+                parent_control_id,
+                control_id,
+                segments,
+            } => status_bar_handler::handle_create_status_bar_command(
+                self,
+                window_id,
+                parent_control_id,
+                control_id,
+                segments,
+            ),
+            PlatformCommand::SetStatusSegmentText {
+                window_id,
+                control_id,
+                segment,
+                text,
+                severity,
+            } => status_bar_handler::handle_set_status_segment_text_command(
+                self, window_id, control_id, segment, text, severity,
+            ),
+            PlatformCommand::SetStatusSegmentProgress {
+                window_id,
+                control_id,
+                segment,
+                progress,
+            } => status_bar_handler::handle_set_status_segment_progress_command(
+                self, window_id, control_id, segment, progress,
+            ),
+            PlatformCommand::CreateToolbar {
+                window_id,
+                parent_control_id,
+                control_id,
+                items,
+            } => toolbar_handler::handle_create_toolbar_command(
+                self,
+                window_id,
+                parent_control_id,
+                control_id,
+                items,
+            ),
+            PlatformCommand::SetToolbarItemEnabled {
+                window_id,
+                control_id,
+                action_id,
+                enabled,
+            } => toolbar_handler::handle_set_toolbar_item_enabled_command(
+                self, window_id, control_id, action_id, enabled,
+            ),
+            PlatformCommand::SetToolbarItemChecked {
+                window_id,
+                control_id,
+                action_id,
+                checked,
+            } => toolbar_handler::handle_set_toolbar_item_checked_command(
+                self, window_id, control_id, action_id, checked,
+            ),
+            PlatformCommand::RegisterImage { image_id, bytes } => {
+                self.register_image(image_id, &bytes)
+            }
+            PlatformCommand::CreateImage {
+                window_id,
+                parent_control_id,
+                control_id,
+                image_id,
+            } => image_handler::handle_create_image_command(
+                self,
+                window_id,
+                parent_control_id,
+                control_id,
+                image_id,
+            ),
+            PlatformCommand::SetImage {
+                window_id,
+                control_id,
+                image_id,
+            } => image_handler::handle_set_image_command(self, window_id, control_id, image_id),
+            PlatformCommand::ShowContextMenu {
+                window_id,
+                control_id,
+                menu_items,
+                screen_x,
+                screen_y,
+            } => menu_handler::handle_show_context_menu_command(
+                self, window_id, control_id, menu_items, screen_x, screen_y,
+            ),
+            PlatformCommand::SetControlTooltip {
+                window_id,
+                control_id,
+                text,
+                options,
+            } => tooltip_handler::handle_set_control_tooltip_command(
+                self, window_id, control_id, text, options,
+            ),
+            PlatformCommand::CreateListView {
+                window_id,
+                parent_control_id,
+                control_id,
+                columns,
+            } => list_view_handler::handle_create_list_view_command(
+                self,
+                window_id,
+                parent_control_id,
+                control_id,
+                columns,
+            ),
+            PlatformCommand::SetListViewRows {
+                window_id,
+                control_id,
+                rows,
+            } => list_view_handler::handle_set_list_view_rows(self, window_id, control_id, rows),
+            PlatformCommand::SetListViewRowCount {
+                window_id,
+                control_id,
+                row_count,
+            } => list_view_handler::handle_set_list_view_row_count(
+                self, window_id, control_id, row_count,
+            ),
+            PlatformCommand::SetListViewSortIndicator {
+                window_id,
+                control_id,
+                column,
+                direction,
+            } => list_view_handler::handle_set_list_view_sort_indicator(
+                self, window_id, control_id, column, direction,
+            ),
+            PlatformCommand::SetListViewSelection {
+                window_id,
+                control_id,
+                row,
+            } => {
+                list_view_handler::handle_set_list_view_selection(self, window_id, control_id, row)
+            }
         }
     }
 
     /*
-     * Translates a platform-agnostic `ControlStyle` into native Win32 resources and stores
-     * the resulting `ParsedControlStyle` inside the shared registry. All GDI objects are
-     * wrapped by `ParsedControlStyle`, ensuring deterministic cleanup when they drop.
+     * Executes the `DefineStyle` command.
      *
-     * Centralizing the parsing here keeps the style map private while still allowing
-     * callers to define styles with a single method call. Early returns prevent partially
-     * constructed styles from polluting the registry.
+     * Records the definition, rejecting one whose base chain leads back to
+     * itself, then re-parses it and every style derived from it. Controls that
+     * use any of those styles get them re-applied, so redefining a base font
+     * updates every window that uses it. [CDU-Styling-InheritanceV1]
      */
     pub(crate) fn define_style(
         self: &Arc<Self>,
@@ -909,91 +1212,114 @@ impl Win32ApiInternalState {
     ) -> PlatformResult<()> {
         // [CDU-Styling-DefineV1] Style definitions are parsed once and stored so multiple controls can reference them by `StyleId`.
         log::debug!("Win32ApiInternalState: define_style for StyleId::{style_id:?}");
+        let changed = self.store_style_definition(style_id, style)?;
+        self.reapply_styles(&changed);
+        Ok(())
+    }
 
-        // --- Parse FontDescription into HFONT ---
-        let font_handle: Option<HFONT> = if let Some(font_desc) = style.font.as_ref() {
-            let hdc_screen = unsafe { GetDC(None) };
-            if hdc_screen.is_invalid() {
-                log::error!("Win32ApiInternalState: Could not acquire screen DC for style font.");
-                return Err(PlatformError::OperationFailed(
-                    "Could not acquire screen DC for font creation".to_string(),
-                ));
+    /*
+     * Stores `style` as the definition of `style_id` and re-parses it and the
+     * styles whose base chains include it. Returns the re-parsed style ids.
+     */
+    fn store_style_definition(
+        self: &Arc<Self>,
+        style_id: StyleId,
+        style: ControlStyle,
+    ) -> PlatformResult<Vec<StyleId>> {
+        let resolved: Vec<(StyleId, ControlStyle)> = {
+            let mut definitions = self.style_definitions.write().map_err(|e| {
+                log::error!("Failed to acquire write lock on style definitions: {e:?}");
+                PlatformError::OperationFailed("RwLock poisoned on style definitions".to_string())
+            })?;
+            let previous = definitions.insert(style_id, style);
+            if let Err(err) = resolve_style(style_id, &definitions) {
+                match previous {
+                    Some(previous) => definitions.insert(style_id, previous),
+                    None => definitions.remove(&style_id),
+                };
+                return Err(err);
             }
+            definitions
+                .keys()
+                .filter(|id| **id == style_id || derives_from(**id, style_id, &definitions))
+                .filter_map(|id| {
+                    let resolved = resolve_style(*id, &definitions).ok().flatten()?;
+                    Some((*id, resolved))
+                })
+                .collect()
+        };
 
-            let logical_font_height = if let Some(point_size) = font_desc.size {
-                -unsafe { MulDiv(point_size, GetDeviceCaps(Some(hdc_screen), LOGPIXELSY), 72) }
-            } else {
-                0
-            };
-
-            unsafe { ReleaseDC(None, hdc_screen) };
-
-            let weight = match font_desc.weight {
-                Some(FontWeight::Bold) => FW_BOLD.0 as i32,
-                _ => FW_NORMAL.0 as i32,
-            };
+        // Any inherited combination may include a changed style.
+        if let Ok(mut inherited) = self.inherited_styles.write() {
+            inherited.clear();
+        }
 
-            let font_name = font_desc.name.as_deref().unwrap_or("MS Shell Dlg 2");
-            let font_name_hstring = HSTRING::from(font_name);
-
-            let h_font = unsafe {
-                CreateFontW(
-                    logical_font_height,
-                    0,
-                    0,
-                    0,
-                    weight,
-                    0,
-                    0,
-                    0,
-                    DEFAULT_CHARSET,
-                    OUT_DEFAULT_PRECIS,
-                    CLIP_DEFAULT_PRECIS,
-                    DEFAULT_QUALITY,
-                    FF_DONTCARE.0 as u32,
-                    &font_name_hstring,
-                )
-            };
+        let mut changed = Vec::with_capacity(resolved.len());
+        for (id, style) in resolved {
+            self.parse_and_store_style(id, style)?;
+            changed.push(id);
+        }
+        Ok(changed)
+    }
 
-            if h_font.is_invalid() {
-                log::error!(
-                    "Win32ApiInternalState: CreateFontW failed while defining StyleId::{style_id:?}: {:?}",
-                    unsafe { GetLastError() }
-                );
-                return Err(PlatformError::OperationFailed(
-                    "CreateFontW failed during style definition".to_string(),
-                ));
+    /*
+     * Re-applies the styles of every control that uses one of `changed`, and of
+     * every styled control inside such a control, then re-lays out the window.
+     */
+    fn reapply_styles(self: &Arc<Self>, changed: &[StyleId]) {
+        let windows: Vec<(WindowId, Vec<(ControlId, StyleId)>)> = match self.active_windows.read() {
+            Ok(windows_map) => windows_map
+                .iter()
+                .map(|(window_id, window_data)| {
+                    let mut affected: Vec<(ControlId, StyleId)> = Vec::new();
+                    for (control_id, _, _, style_id) in window_data.themed_controls() {
+                        if let Some(style_id) = style_id
+                            && changed.contains(&style_id)
+                        {
+                            affected.push((control_id, style_id));
+                            affected.extend(window_data.styled_descendants(control_id));
+                        }
+                    }
+                    affected.sort_by_key(|(control_id, _)| control_id.raw());
+                    affected.dedup();
+                    (*window_id, affected)
+                })
+                .filter(|(_, affected)| !affected.is_empty())
+                .collect(),
+            Err(e) => {
+                log::warn!("Failed to acquire read lock to re-apply changed styles: {e:?}");
+                return;
             }
-            Some(h_font)
-        } else {
-            None
         };
-
-        // --- Parse background_color into HBRUSH ---
-        let background_brush: Option<HBRUSH> = if let Some(color) = style.background_color.as_ref()
-        {
-            let color_ref = styling_handler::color_to_colorref(color);
-            let h_brush = unsafe { CreateSolidBrush(color_ref) };
-            if h_brush.is_invalid() {
-                log::error!(
-                    "Win32ApiInternalState: CreateSolidBrush failed while defining StyleId::{style_id:?}: {:?}",
-                    unsafe { GetLastError() }
-                );
-                return Err(PlatformError::OperationFailed(
-                    "CreateSolidBrush failed during style definition".to_string(),
-                ));
+        for (window_id, affected) in windows {
+            for (control_id, style_id) in affected {
+                if let Err(e) = self.apply_style_to_single_control(window_id, control_id, style_id)
+                {
+                    log::warn!(
+                        "Re-applying {style_id:?} to ControlID {} failed: {e:?}",
+                        control_id.raw()
+                    );
+                }
             }
-            Some(h_brush)
-        } else {
-            None
-        };
+            self.relayout_if_auto_sized(window_id);
+        }
+    }
 
-        let parsed_style = ParsedControlStyle {
-            font_handle,
-            text_color: style.text_color,
-            background_color: style.background_color,
-            background_brush,
-        };
+    /*
+     * Translates a platform-agnostic `ControlStyle` into native Win32 resources and stores
+     * the resulting `ParsedControlStyle` inside the shared registry. All GDI objects are
+     * wrapped by `ParsedControlStyle`, ensuring deterministic cleanup when they drop.
+     *
+     * Centralizing the parsing here keeps the style map private while still allowing
+     * callers to define styles with a single method call. Early returns prevent partially
+     * constructed styles from polluting the registry.
+     */
+    fn parse_and_store_style(
+        self: &Arc<Self>,
+        style_id: StyleId,
+        style: ControlStyle,
+    ) -> PlatformResult<()> {
+        let parsed_style = parse_style(style_id, style)?;
 
         match self.defined_styles.write() {
             Ok(mut styles_map) => {
@@ -1012,11 +1338,13 @@ impl Win32ApiInternalState {
                 for window_data in windows_map.values() {
                     let hwnd = window_data.get_hwnd();
                     if !hwnd.is_invalid() {
-                        window_common::try_enable_dark_mode(hwnd);
+                        window_common::apply_native_color_mode(hwnd);
                     }
                 }
             } else {
-                log::warn!("Failed to acquire read lock to enable dark mode on active windows.");
+                log::warn!(
+                    "Failed to acquire read lock to apply the native color mode to active windows."
+                );
             }
         }
         Ok(())
@@ -1025,12 +1353,32 @@ impl Win32ApiInternalState {
     /*
      * Executes the `ApplyStyleToControl` command.
      *
+     * Applies the style to the control and then re-applies the styles of the
+     * styled controls inside it, which inherit unset fonts and colors from it.
+     */
+    fn execute_apply_style_to_control(
+        self: &Arc<Self>,
+        window_id: WindowId,
+        control_id: ControlId,
+        style_id: StyleId,
+    ) -> PlatformResult<()> {
+        self.apply_style_to_single_control(window_id, control_id, style_id)?;
+        let descendants = self.with_window_data_read(window_id, |window_data| {
+            Ok(window_data.styled_descendants(control_id))
+        })?;
+        for (descendant_id, descendant_style) in descendants {
+            self.apply_style_to_single_control(window_id, descendant_id, descendant_style)?;
+        }
+        Ok(())
+    }
+
+    /*
      * This method applies a previously defined style to a specific control. It
      * updates the window's internal mapping, sends a `WM_SETFONT` message if a
      * font is part of the style, and invalidates the control to force a repaint,
      * which will trigger color changes via `WM_CTLCOLOR...` messages.
      */
-    fn execute_apply_style_to_control(
+    fn apply_style_to_single_control(
         self: &Arc<Self>,
         window_id: WindowId,
         control_id: ControlId,
@@ -1052,7 +1400,10 @@ impl Win32ApiInternalState {
             })
         })?;
 
-        let parsed_style = self.get_parsed_style(style_id);
+        // The style as resolved for this control, including what it inherits from its panels.
+        let parsed_style = self.with_window_data_read(window_id, |window_data| {
+            Ok(self.parsed_style_for_control(window_data, control_id))
+        })?;
 
         // Check if this is a button and enable owner-draw if style provides colors
         if let Ok(Some(control_kind)) = self.with_window_data_read(window_id, |window_data| {
@@ -1069,6 +1420,8 @@ impl Win32ApiInternalState {
                         let new_style = (current_style & !WINDOW_STYLE(BS_TYPEMASK as u32))
                             | WINDOW_STYLE(BS_OWNERDRAW as u32);
                         SetWindowLongW(control_hwnd, GWL_STYLE, new_style.0 as i32);
+                        // Owner-drawn buttons get no hot state; track hover ourselves.
+                        button_handler::install_hover_tracking(control_hwnd);
                         // SWP_FRAMECHANGED forces the window to recalculate its frame
                         _ = SetWindowPos(
                             control_hwnd,
@@ -1123,6 +1476,36 @@ impl Win32ApiInternalState {
             ) {
                 window_common::apply_button_dark_mode_classic_render(control_hwnd);
             }
+            // Custom-drawn controls resolve borders, padding and state colors while painting.
+            else if control_kind == window_common::ControlKind::TabBar
+                && let Some(ref style) = parsed_style
+            {
+                tab_bar_handler::apply_control_style(control_hwnd, Arc::clone(style));
+            } else if control_kind == window_common::ControlKind::ToggleSwitch
+                && let Some(ref style) = parsed_style
+            {
+                toggle_switch_handler::apply_control_style(control_hwnd, Arc::clone(style));
+            } else if control_kind == window_common::ControlKind::Slider
+                && let Some(ref style) = parsed_style
+            {
+                slider_handler::apply_control_style(control_hwnd, Arc::clone(style));
+            } else if control_kind == window_common::ControlKind::StatusBar
+                && let Some(ref style) = parsed_style
+            {
+                status_bar_handler::apply_control_style(control_hwnd, Arc::clone(style));
+            } else if control_kind == window_common::ControlKind::Toolbar
+                && let Some(ref style) = parsed_style
+            {
+                toolbar_handler::apply_control_style(control_hwnd, Arc::clone(style));
+            } else if control_kind == window_common::ControlKind::Image
+                && let Some(ref style) = parsed_style
+            {
+                image_handler::apply_control_style(control_hwnd, Arc::clone(style));
+            }
+            // The container draws nothing; its EDIT repaints through WM_CTLCOLOREDIT.
+            else if control_kind == window_common::ControlKind::NumericInput {
+                numeric_input_handler::refresh(control_hwnd);
+            }
             // RichEdit uses dedicated messages for background/text color.
             else if control_kind == window_common::ControlKind::RichEdit
                 && let Some(ref style) = parsed_style
@@ -1130,11 +1513,17 @@ impl Win32ApiInternalState {
                 let (background, foreground) = richedit_handler::style_colors_for_rich_edit(style);
                 richedit_handler::apply_rich_edit_colors(control_hwnd, background, foreground);
             }
+            // ListView uses dedicated messages for background/text color.
+            else if control_kind == window_common::ControlKind::ListView
+                && let Some(ref style) = parsed_style
+            {
+                list_view_handler::apply_control_style(control_hwnd, style);
+            }
         }
 
         if let Some(parsed_style) = parsed_style {
             // Apply the font if one is defined in the style.
-            if let Some(hfont) = parsed_style.font_handle
+            if let Some(hfont) = parsed_style.font_for_window(control_hwnd)
                 && !hfont.is_invalid()
             {
                 unsafe {
@@ -1201,6 +1590,333 @@ impl Win32ApiInternalState {
             .ok()
             .and_then(|styles_map| styles_map.get(&style_id).cloned())
     }
+
+    /*
+     * The parsed style a control renders with: its applied style merged over
+     * its base chain, with unset fonts and colors inherited from the styles of
+     * its ancestor panels. Controls without an applied style have none.
+     * Combinations are parsed once and cached until a style is redefined.
+     * [CDU-Styling-InheritanceV1]
+     */
+    pub(crate) fn parsed_style_for_control(
+        &self,
+        window_data: &window_common::NativeWindowData,
+        control_id: ControlId,
+    ) -> Option<Arc<ParsedControlStyle>> {
+        let style_id = window_data.get_style_for_control(control_id)?;
+        let ancestors = window_data.styled_ancestors(control_id);
+        if ancestors.is_empty() {
+            return self.get_parsed_style(style_id);
+        }
+        let mut key = vec![style_id];
+        key.extend(&ancestors);
+        if let Some(cached) = self
+            .inherited_styles
+            .read()
+            .ok()
+            .and_then(|inherited| inherited.get(&key).cloned())
+        {
+            return Some(cached);
+        }
+
+        let (own, inherited) = {
+            let definitions = self.style_definitions.read().ok()?;
+            let own = resolve_style(style_id, &definitions).ok().flatten()?;
+            let inherited = ancestors
+                .iter()
+                .filter_map(|id| resolve_style(*id, &definitions).ok().flatten())
+                .fold(own.clone(), |style, parent| {
+                    style.inherit_from_parent(&parent)
+                });
+            (own, inherited)
+        };
+        let parsed = if inherited == own {
+            self.get_parsed_style(style_id)?
+        } else {
+            match parse_style(style_id, inherited) {
+                Ok(parsed) => Arc::new(parsed),
+                Err(e) => {
+                    log::warn!(
+                        "Inherited style for ControlID {} could not be parsed: {e:?}",
+                        control_id.raw()
+                    );
+                    return self.get_parsed_style(style_id);
+                }
+            }
+        };
+        if let Ok(mut inherited_styles) = self.inherited_styles.write() {
+            inherited_styles.insert(key, Arc::clone(&parsed));
+        }
+        Some(parsed)
+    }
+
+    /// The active theme; the dark theme until `ApplyTheme` replaces it.
+    pub(crate) fn current_theme(&self) -> Arc<Theme> {
+        match self.theme.read() {
+            Ok(theme) => Arc::clone(&theme),
+            Err(e) => {
+                log::error!("Failed to acquire read lock on theme: {e:?}");
+                Arc::new(Theme::dark())
+            }
+        }
+    }
+
+    /// The shared image registry, for controls that draw registered images.
+    pub(crate) fn image_registry(&self) -> SharedImageRegistry {
+        Arc::clone(&self.images)
+    }
+
+    /*
+     * Executes the `RegisterImage` command: decodes the bytes into the shared
+     * registry, then repaints every window so controls that already refer to
+     * the id show it. Tree views pick up new images when next populated.
+     * [CDU-ImagesV1]
+     */
+    fn register_image(&self, image_id: ImageId, bytes: &[u8]) -> PlatformResult<()> {
+        self.images
+            .write()
+            .map_err(|e| {
+                log::error!("Failed to acquire write lock on image registry: {e:?}");
+                PlatformError::OperationFailed("RwLock poisoned on image registry".to_string())
+            })?
+            .register(image_id, bytes)?;
+        let hwnds: Vec<HWND> = match self.active_windows.read() {
+            Ok(windows_map) => windows_map.values().map(|data| data.get_hwnd()).collect(),
+            Err(e) => {
+                log::error!("Failed to acquire read lock on windows map: {e:?}");
+                Vec::new()
+            }
+        };
+        for hwnd in hwnds.into_iter().filter(|hwnd| !hwnd.is_invalid()) {
+            unsafe {
+                let _ = RedrawWindow(Some(hwnd), None, None, RDW_INVALIDATE | RDW_ALLCHILDREN);
+            }
+        }
+        Ok(())
+    }
+
+    /*
+     * Executes the `ApplyTheme` command.
+     *
+     * Switches the native color mode first so that re-applying styles below can
+     * still force classic rendering where controls need it, then defines every
+     * style of the theme and re-applies the applied styles (fonts, native control
+     * colors). Owner-drawn controls get the theme's palettes pushed into their
+     * per-instance state, and every window is repainted, including frames and
+     * menu bars, and re-laid out in case fonts changed measured sizes.
+     * [CDU-Styling-ThemeV1]
+     */
+    fn apply_theme(self: &Arc<Self>, theme: Theme) -> PlatformResult<()> {
+        log::debug!("Win32ApiInternalState: apply_theme '{}'", theme.name);
+        // [CDU-Styling-ColorV1] Unreadable text is reported, not rejected.
+        for warning in theme.contrast_warnings(crate::color::WCAG_AA_NORMAL_TEXT) {
+            log::warn!(
+                "Theme '{}': {:?} text {} on {} in state {:?} has contrast {:.2}:1",
+                theme.name,
+                warning.style_id,
+                warning.text,
+                warning.background,
+                warning.state,
+                warning.ratio
+            );
+        }
+        let theme = Arc::new(theme);
+        match self.theme.write() {
+            Ok(mut current) => *current = Arc::clone(&theme),
+            Err(e) => {
+                log::error!("Failed to acquire write lock on theme: {e:?}");
+                return Err(PlatformError::OperationFailed(
+                    "RwLock poisoned on theme".to_string(),
+                ));
+            }
+        }
+        window_common::set_native_dark_mode(theme.is_dark);
+        crate::controls::dark_border::set_border_color(&theme.border);
+
+        // Every styled control is re-applied below, so only store the definitions here.
+        for (style_id, style) in &theme.styles {
+            self.store_style_definition(*style_id, style.clone())?;
+        }
+
+        let windows: Vec<(WindowId, HWND, Vec<_>, Vec<HWND>)> = {
+            let windows_map = self.active_windows.read().map_err(|e| {
+                log::error!("Failed to acquire read lock on windows map: {e:?}");
+                PlatformError::OperationFailed("RwLock poisoned on windows map".to_string())
+            })?;
+            windows_map
+                .iter()
+                .map(|(window_id, window_data)| {
+                    (
+                        *window_id,
+                        window_data.get_hwnd(),
+                        window_data.themed_controls(),
+                        window_data.control_tooltips(),
+                    )
+                })
+                .collect()
+        };
+
+        let tab_bar_palette =
+            tab_bar_handler::TabBarPalette::from_colors(theme.resolved_tab_bar_colors());
+        for (window_id, hwnd, controls, control_tooltips) in windows {
+            if hwnd.is_invalid() {
+                continue;
+            }
+            window_common::apply_native_color_mode(hwnd);
+            for hwnd_tooltip in control_tooltips {
+                tooltip_handler::apply_tooltip_palette(hwnd_tooltip, &theme.tooltip);
+            }
+            for (control_id, kind, control_hwnd, style_id) in controls {
+                match kind {
+                    Some(window_common::ControlKind::Chart) => {
+                        chart_handler::apply_chart_palette(control_hwnd, theme.chart.clone());
+                    }
+                    Some(window_common::ControlKind::Splitter) => {
+                        splitter_handler::apply_splitter_palette(
+                            control_hwnd,
+                            theme.splitter.clone(),
+                        );
+                    }
+                    Some(window_common::ControlKind::TabBar) => {
+                        tab_bar_handler::apply_tab_bar_palette(
+                            control_hwnd,
+                            tab_bar_palette.clone(),
+                        );
+                    }
+                    Some(window_common::ControlKind::ToggleSwitch) => {
+                        window_common::apply_native_color_mode(control_hwnd);
+                        toggle_switch_handler::apply_toggle_switch_palette(
+                            control_hwnd,
+                            theme.toggle_switch.clone(),
+                        );
+                    }
+                    Some(window_common::ControlKind::Slider) => {
+                        slider_handler::apply_slider_palette(control_hwnd, theme.slider.clone());
+                    }
+                    Some(window_common::ControlKind::StatusBar) => {
+                        status_bar_handler::apply_status_bar_palette(
+                            control_hwnd,
+                            theme.status_bar.clone(),
+                        );
+                    }
+                    Some(window_common::ControlKind::Toolbar) => {
+                        toolbar_handler::apply_toolbar_palette(control_hwnd, theme.toolbar.clone());
+                    }
+                    Some(window_common::ControlKind::Image) => {
+                        image_handler::apply_image_background(
+                            control_hwnd,
+                            theme.colors.window_background.clone(),
+                        );
+                    }
+                    Some(window_common::ControlKind::NumericInput) => {
+                        numeric_input_handler::apply_native_color_mode_to_children(control_hwnd);
+                    }
+                    Some(
+                        window_common::ControlKind::Edit
+                        | window_common::ControlKind::RichEdit
+                        | window_common::ControlKind::TreeView
+                        | window_common::ControlKind::ComboBox
+                        | window_common::ControlKind::ListView
+                        | window_common::ControlKind::ProgressBar,
+                    ) => window_common::apply_native_color_mode(control_hwnd),
+                    _ => {}
+                }
+                let item_tooltip = match kind {
+                    Some(window_common::ControlKind::TreeView) => {
+                        treeview_handler::tooltip_of(control_hwnd)
+                    }
+                    Some(window_common::ControlKind::TabBar) => {
+                        tab_bar_handler::tooltip_of(control_hwnd)
+                    }
+                    Some(window_common::ControlKind::Toolbar) => {
+                        toolbar_handler::tooltip_of(control_hwnd)
+                    }
+                    _ => None,
+                };
+                if let Some(hwnd_tooltip) = item_tooltip {
+                    tooltip_handler::apply_tooltip_palette(hwnd_tooltip, &theme.tooltip);
+                }
+                if let Some(style_id) = style_id
+                    && let Err(e) =
+                        self.apply_style_to_single_control(window_id, control_id, style_id)
+                {
+                    log::warn!(
+                        "ApplyTheme: re-applying {style_id:?} to ControlID {} failed: {e:?}",
+                        control_id.raw()
+                    );
+                }
+            }
+            unsafe {
+                let _ = DrawMenuBar(hwnd);
+                let _ = RedrawWindow(
+                    Some(hwnd),
+                    None,
+                    None,
+                    RDW_INVALIDATE | RDW_ERASE | RDW_FRAME | RDW_ALLCHILDREN,
+                );
+            }
+            self.relayout_if_auto_sized(window_id);
+        }
+        Ok(())
+    }
+}
+
+/*
+ * Creates the native resources for a resolved style: the background brush,
+ * and the font at the system DPI (fonts for other DPIs are created on first
+ * use). The description itself is kept for owner-draw paths.
+ */
+fn parse_style(style_id: StyleId, style: ControlStyle) -> PlatformResult<ParsedControlStyle> {
+    // --- Parse background_color into HBRUSH ---
+    let background_brush: Option<HBRUSH> = if let Some(color) = style.background_color.as_ref() {
+        let color_ref = styling_handler::color_to_colorref(color);
+        let h_brush = unsafe { CreateSolidBrush(color_ref) };
+        if h_brush.is_invalid() {
+            log::error!(
+                "Win32ApiInternalState: CreateSolidBrush failed while defining StyleId::{style_id:?}: {:?}",
+                unsafe { GetLastError() }
+            );
+            return Err(PlatformError::OperationFailed(
+                "CreateSolidBrush failed during style definition".to_string(),
+            ));
+        }
+        Some(h_brush)
+    } else {
+        None
+    };
+
+    let has_font = style.font.is_some();
+    let parsed = ParsedControlStyle::new(style, background_brush);
+    // --- Parse FontDescription into HFONT ---
+    if has_font
+        && parsed
+            .font_for_dpi(window_common::system_dpi_scale())
+            .is_none()
+    {
+        log::error!(
+            "Win32ApiInternalState: CreateFontW failed while defining StyleId::{style_id:?}"
+        );
+        return Err(PlatformError::OperationFailed(
+            "CreateFontW failed during style definition".to_string(),
+        ));
+    }
+    Ok(parsed)
+}
+
+/// Whether `style_id`'s base chain includes `base_id`. Chains are cycle-free.
+fn derives_from(
+    style_id: StyleId,
+    base_id: StyleId,
+    definitions: &HashMap<StyleId, ControlStyle>,
+) -> bool {
+    let mut next = definitions.get(&style_id).and_then(|style| style.base);
+    while let Some(id) = next {
+        if id == base_id {
+            return true;
+        }
+        next = definitions.get(&id).and_then(|style| style.base);
+    }
+    false
 }
 
 impl Drop for Win32ApiInternalState {
@@ -1225,6 +1941,7 @@ impl PlatformInterface {
      * Initializes the internal Win32 state and registers the main window class.
      */
     pub fn new(app_name_for_class: String) -> PlatformResult<Self> {
+        window_common::init_per_monitor_dpi_awareness();
         window_common::init_app_dark_mode();
         let internal_state = Win32ApiInternalState::new(app_name_for_class)?;
         window_common::register_window_class(&internal_state)?;
@@ -1277,9 +1994,92 @@ impl PlatformInterface {
             return Err(e);
         }
 
+        #[cfg(feature = "recording")]
+        self.internal_state
+            .with_session_recorder(|recorder| recorder.record_window_created(window_id, &config));
         Ok(window_id)
     }
 
+    /*
+     * Attaches the provider that supplies the cell texts of ListViews in
+     * virtual mode (see `PlatformCommand::SetListViewRowCount`). Only a weak
+     * reference is kept. [CDU-Control-ListViewV1]
+     */
+    pub fn set_list_view_data_provider(&self, provider: Arc<Mutex<dyn ListViewDataProvider>>) {
+        match self.internal_state.list_view_data_provider.lock() {
+            Ok(mut guard) => *guard = Some(Arc::downgrade(&provider)),
+            Err(e) => log::error!("Platform: Failed to lock list view data provider: {e:?}"),
+        }
+    }
+
+    /*
+     * Installs (or removes, with `None`) a recorder that writes every created
+     * window, executed command and emitted event to a session trace.
+     * [CDU-Tech-SessionRecordingV1]
+     */
+    #[cfg(feature = "recording")]
+    pub fn set_session_recorder(&self, recorder: Option<Arc<SessionRecorder>>) {
+        match self.internal_state.session_recorder.write() {
+            Ok(mut guard) => *guard = recorder,
+            Err(e) => log::error!("Platform: Failed to lock session recorder: {e:?}"),
+        }
+    }
+
+    /*
+     * Watches a theme file (or stops watching, with `None`). While
+     * `main_event_loop` runs, the file is checked every
+     * `ThemeWatcher::DEFAULT_POLL_INTERVAL` and re-applied to all windows with
+     * `ApplyTheme` semantics whenever it changes. The current contents are not
+     * applied by this call; load them with `ThemeFile::load_theme` first.
+     * [CDU-Styling-ThemeFileV1]
+     */
+    #[cfg(feature = "theme-file")]
+    pub fn watch_theme_file(&self, path: Option<std::path::PathBuf>) {
+        match self.internal_state.theme_watcher.lock() {
+            Ok(mut guard) => *guard = path.map(ThemeWatcher::new),
+            Err(e) => log::error!("Platform: Failed to lock theme watcher: {e:?}"),
+        }
+    }
+
+    /*
+     * Replays a recorded session against the native UI: the recorded windows
+     * are created in order, then the recorded commands are run through
+     * `main_event_loop` by a `ReplayEventHandler` standing in for the
+     * application. Returns the handler once the loop exits, so the caller can
+     * compare `received_events` with the recorded events.
+     */
+    #[cfg(feature = "recording")]
+    pub fn replay_session(
+        &self,
+        session: RecordedSession,
+        pacing: ReplayPacing,
+        ui_state_provider: Arc<Mutex<dyn UiStateProvider>>,
+    ) -> PlatformResult<Arc<Mutex<ReplayEventHandler>>> {
+        for recorded in &session.entries {
+            if let SessionEntry::WindowCreated {
+                window_id,
+                title,
+                width,
+                height,
+            } = &recorded.entry
+            {
+                let created = self.create_window(WindowConfig {
+                    title,
+                    width: *width,
+                    height: *height,
+                })?;
+                if created != *window_id {
+                    log::warn!(
+                        "Platform: Replay created {created:?} for recorded {window_id:?}; later commands may target the wrong window"
+                    );
+                }
+            }
+        }
+        let handler = Arc::new(Mutex::new(ReplayEventHandler::new(session, pacing)));
+        self.main_event_loop(handler.clone(), ui_state_provider, Vec::new())?;
+        Ok(handler)
+    }
+
     /*
      * Takes the application's event handler and a list of initial commands.
      * Processes initial commands, then enters the message loop, dequeuing and
@@ -1351,6 +2151,9 @@ impl PlatformInterface {
                     }
                 }
 
+                #[cfg(feature = "theme-file")]
+                self.internal_state.poll_theme_watcher();
+
                 // Process at most one OS message per cycle. If no messages are queued,
                 // sleep briefly so command dequeue stays responsive without user input.
                 if PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
@@ -1423,6 +2226,7 @@ mod tests {
     use std::sync::Arc;
 
     use super::*;
+    use crate::dpi::DpiScale;
     use crate::types::TreeItemId;
     use crate::window_common::NativeWindowData;
     use windows::Win32::{Foundation::HWND, UI::Controls::HTREEITEM};
@@ -1504,10 +2308,38 @@ mod tests {
         let parsed = state
             .get_parsed_style(StyleId::DefaultText)
             .expect("style stored");
-        assert!(parsed.font_handle.is_none());
+        assert!(parsed.font_for_dpi(DpiScale::DEFAULT).is_none());
         assert!(parsed.background_brush.is_none());
     }
 
+    #[test]
+    // [CDU-Styling-InheritanceV1] Derived styles are re-parsed when their base is redefined.
+    fn redefining_a_base_style_reparses_derived_styles() {
+        // Arrange
+        let state = Win32ApiInternalState::new("StyleTest".to_string()).unwrap();
+        let colored = |r| ControlStyle {
+            text_color: Some(crate::styling::Color::rgb(r, 0, 0)),
+            ..ControlStyle::default()
+        };
+        state.define_style(StyleId::Custom(1), colored(10)).unwrap();
+        state
+            .define_style(
+                StyleId::Custom(2),
+                ControlStyle {
+                    base: Some(StyleId::Custom(1)),
+                    ..ControlStyle::default()
+                },
+            )
+            .unwrap();
+
+        // Act
+        state.define_style(StyleId::Custom(1), colored(20)).unwrap();
+
+        // Assert
+        let derived = state.get_parsed_style(StyleId::Custom(2)).unwrap();
+        assert_eq!(derived.text_color.as_ref().map(|c| c.r), Some(20));
+    }
+
     #[test]
     fn generate_unique_window_id_produces_unique_values() {
         // Arrange
@@ -0,0 +1,1174 @@
+/*
+ * Typed, fluent construction of a window's control tree. Instead of spelling
+ * out a `Create*` command, a `LayoutRule` and an `ApplyStyleToControl` per
+ * control, an application nests `ControlBuilder`s:
+ *
+ * ```
+ * use commanductui::builder::{ControlBuilder, WindowBuilder};
+ * use commanductui::{StyleId, WindowId, types::ControlId};
+ *
+ * let built = WindowBuilder::new(WindowId::new(1))
+ *     .child(
+ *         ControlBuilder::panel(ControlId::new(1))
+ *             .dock_top(30)
+ *             .child(ControlBuilder::button(ControlId::new(2), "Go").style(StyleId::DefaultButton)),
+ *     )
+ *     .build()
+ *     .unwrap();
+ * let initial_commands = built.into_commands();
+ * ```
+ *
+ * `WindowBuilder::build` checks that control ids are unique, that every parent
+ * named with `child_of` exists and that kind-specific options fit the control,
+ * validates the layout rules and emits the commands parents first. Siblings
+ * without an explicit `order` are docked in declaration order.
+ * [CDU-TypedBuilderV1]
+ */
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::layout::{self, Rect};
+use crate::numeric::NumericSpec;
+use crate::slider::{SliderOrientation, SliderRange};
+use crate::status_bar::StatusSegment;
+use crate::styling_primitives::{ControlStyle, StyleId};
+use crate::toolbar::ToolbarItem;
+use crate::tooltip::TooltipOptions;
+use crate::types::{
+    ContainerLayout, ControlId, DockStyle, ImageId, LabelClass, LayoutAxis, LayoutRule,
+    ListViewColumn, MenuItemConfig, PlatformCommand, SizeConstraints, SizeMode,
+    SplitterOrientation, WindowId,
+};
+
+/*
+ * The control type and the state its create command (and the follow-up
+ * commands for initial state) need. With the `serde` feature it deserializes
+ * internally tagged by `kind`, which is how UI description files name it.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", deny_unknown_fields)
+)]
+pub enum ControlSpec {
+    Panel,
+    Button {
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        image: Option<ImageId>,
+    },
+    Label {
+        #[cfg_attr(feature = "serde", serde(default))]
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        class: LabelClass,
+    },
+    Input {
+        #[cfg_attr(feature = "serde", serde(default))]
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        read_only: bool,
+        #[cfg_attr(feature = "serde", serde(default))]
+        multiline: bool,
+        #[cfg_attr(feature = "serde", serde(default))]
+        vertical_scroll: bool,
+    },
+    RichEdit,
+    TreeView,
+    Chart,
+    ProgressBar,
+    Splitter {
+        orientation: SplitterOrientation,
+    },
+    ComboBox {
+        #[cfg_attr(feature = "serde", serde(default))]
+        items: Vec<String>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        selected_index: Option<usize>,
+    },
+    RadioButton {
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        group_start: bool,
+        #[cfg_attr(feature = "serde", serde(default))]
+        checked: bool,
+    },
+    CheckBox {
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        checked: bool,
+    },
+    TabBar {
+        #[cfg_attr(feature = "serde", serde(default))]
+        items: Vec<String>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        selected_index: Option<usize>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        images: Vec<Option<ImageId>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        tooltips: Vec<Option<String>>,
+    },
+    ToggleSwitch {
+        #[cfg_attr(feature = "serde", serde(default))]
+        label: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        checked: bool,
+    },
+    ListView {
+        #[cfg_attr(feature = "serde", serde(default))]
+        columns: Vec<ListViewColumn>,
+    },
+    Slider {
+        range: SliderRange,
+        #[cfg_attr(feature = "serde", serde(default))]
+        orientation: SliderOrientation,
+        #[cfg_attr(feature = "serde", serde(default))]
+        value: i32,
+    },
+    NumericInput {
+        spec: NumericSpec,
+        #[cfg_attr(feature = "serde", serde(default))]
+        value: f64,
+    },
+    StatusBar {
+        segments: Vec<StatusSegment>,
+    },
+    Toolbar {
+        items: Vec<ToolbarItem>,
+    },
+    Image {
+        #[cfg_attr(feature = "serde", serde(default))]
+        image: Option<ImageId>,
+    },
+}
+
+/*
+ * One control and its subtree. The layout setters fill in the control's
+ * `LayoutRule`; its id and parent are taken from the tree at build time.
+ */
+#[derive(Debug, Clone)]
+pub struct ControlBuilder {
+    id: ControlId,
+    spec: ControlSpec,
+    style: Option<StyleId>,
+    tooltip: Option<String>,
+    rule: Option<LayoutRule>,
+    order: Option<u32>,
+    children: Vec<ControlBuilder>,
+    misuse: Option<String>,
+}
+
+impl ControlBuilder {
+    pub fn new(id: ControlId, spec: ControlSpec) -> Self {
+        Self {
+            id,
+            spec,
+            style: None,
+            tooltip: None,
+            rule: None,
+            order: None,
+            children: Vec::new(),
+            misuse: None,
+        }
+    }
+
+    pub fn panel(id: ControlId) -> Self {
+        Self::new(id, ControlSpec::Panel)
+    }
+
+    pub fn button(id: ControlId, text: impl Into<String>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::Button {
+                text: text.into(),
+                image: None,
+            },
+        )
+    }
+
+    pub fn label(id: ControlId, text: impl Into<String>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::Label {
+                text: text.into(),
+                class: LabelClass::Default,
+            },
+        )
+    }
+
+    pub fn input(id: ControlId, text: impl Into<String>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::Input {
+                text: text.into(),
+                read_only: false,
+                multiline: false,
+                vertical_scroll: false,
+            },
+        )
+    }
+
+    pub fn rich_edit(id: ControlId) -> Self {
+        Self::new(id, ControlSpec::RichEdit)
+    }
+
+    pub fn tree_view(id: ControlId) -> Self {
+        Self::new(id, ControlSpec::TreeView)
+    }
+
+    pub fn chart(id: ControlId) -> Self {
+        Self::new(id, ControlSpec::Chart)
+    }
+
+    pub fn progress_bar(id: ControlId) -> Self {
+        Self::new(id, ControlSpec::ProgressBar)
+    }
+
+    pub fn splitter(id: ControlId, orientation: SplitterOrientation) -> Self {
+        Self::new(id, ControlSpec::Splitter { orientation })
+    }
+
+    pub fn combo_box(id: ControlId) -> Self {
+        Self::new(
+            id,
+            ControlSpec::ComboBox {
+                items: Vec::new(),
+                selected_index: None,
+            },
+        )
+    }
+
+    pub fn radio_button(id: ControlId, text: impl Into<String>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::RadioButton {
+                text: text.into(),
+                group_start: false,
+                checked: false,
+            },
+        )
+    }
+
+    pub fn check_box(id: ControlId, text: impl Into<String>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::CheckBox {
+                text: text.into(),
+                checked: false,
+            },
+        )
+    }
+
+    pub fn tab_bar(id: ControlId) -> Self {
+        Self::new(
+            id,
+            ControlSpec::TabBar {
+                items: Vec::new(),
+                selected_index: None,
+                images: Vec::new(),
+                tooltips: Vec::new(),
+            },
+        )
+    }
+
+    pub fn toggle_switch(id: ControlId, label: impl Into<String>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::ToggleSwitch {
+                label: label.into(),
+                checked: false,
+            },
+        )
+    }
+
+    pub fn list_view(id: ControlId, columns: impl IntoIterator<Item = ListViewColumn>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::ListView {
+                columns: columns.into_iter().collect(),
+            },
+        )
+    }
+
+    pub fn slider(id: ControlId, range: SliderRange) -> Self {
+        Self::new(
+            id,
+            ControlSpec::Slider {
+                range,
+                orientation: SliderOrientation::default(),
+                value: range.min,
+            },
+        )
+    }
+
+    pub fn numeric_input(id: ControlId, spec: NumericSpec) -> Self {
+        let value = spec.min;
+        Self::new(id, ControlSpec::NumericInput { spec, value })
+    }
+
+    pub fn status_bar(id: ControlId, segments: impl IntoIterator<Item = StatusSegment>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::StatusBar {
+                segments: segments.into_iter().collect(),
+            },
+        )
+    }
+
+    pub fn toolbar(id: ControlId, items: impl IntoIterator<Item = ToolbarItem>) -> Self {
+        Self::new(
+            id,
+            ControlSpec::Toolbar {
+                items: items.into_iter().collect(),
+            },
+        )
+    }
+
+    pub fn image(id: ControlId, image_id: ImageId) -> Self {
+        Self::new(
+            id,
+            ControlSpec::Image {
+                image: Some(image_id),
+            },
+        )
+    }
+
+    pub fn id(&self) -> ControlId {
+        self.id
+    }
+
+    // --- Children, style and kind-specific options ---
+
+    pub fn child(mut self, child: ControlBuilder) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn children(mut self, children: impl IntoIterator<Item = ControlBuilder>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    pub fn style(mut self, style_id: StyleId) -> Self {
+        self.style = Some(style_id);
+        self
+    }
+
+    /// Hover help, attached with `SetControlTooltip` and default options.
+    pub fn tooltip(mut self, text: impl Into<String>) -> Self {
+        self.tooltip = Some(text.into());
+        self
+    }
+
+    /// Label classification; labels only.
+    pub fn class(mut self, label_class: LabelClass) -> Self {
+        match &mut self.spec {
+            ControlSpec::Label { class, .. } => *class = label_class,
+            _ => self.reject("class()"),
+        }
+        self
+    }
+
+    /// Read-only, multi-line and scrolling flags; inputs only.
+    pub fn input_options(
+        mut self,
+        read_only: bool,
+        multiline: bool,
+        vertical_scroll: bool,
+    ) -> Self {
+        match &mut self.spec {
+            ControlSpec::Input {
+                read_only: r,
+                multiline: m,
+                vertical_scroll: v,
+                ..
+            } => (*r, *m, *v) = (read_only, multiline, vertical_scroll),
+            _ => self.reject("input_options()"),
+        }
+        self
+    }
+
+    /// Initial check state of check boxes, radio buttons and toggle switches.
+    pub fn checked(mut self, value: bool) -> Self {
+        match &mut self.spec {
+            ControlSpec::CheckBox { checked, .. }
+            | ControlSpec::RadioButton { checked, .. }
+            | ControlSpec::ToggleSwitch { checked, .. } => *checked = value,
+            _ => self.reject("checked()"),
+        }
+        self
+    }
+
+    /// Starts a new radio button group; radio buttons only.
+    pub fn group_start(mut self) -> Self {
+        match &mut self.spec {
+            ControlSpec::RadioButton { group_start, .. } => *group_start = true,
+            _ => self.reject("group_start()"),
+        }
+        self
+    }
+
+    /// Entries of a combo box or labels of a tab bar.
+    pub fn items<S: Into<String>>(mut self, values: impl IntoIterator<Item = S>) -> Self {
+        let values = values.into_iter().map(Into::into).collect();
+        match &mut self.spec {
+            ControlSpec::ComboBox { items, .. } | ControlSpec::TabBar { items, .. } => {
+                *items = values
+            }
+            _ => self.reject("items()"),
+        }
+        self
+    }
+
+    /// Initially selected entry of a combo box or tab bar.
+    pub fn selected(mut self, index: usize) -> Self {
+        match &mut self.spec {
+            ControlSpec::ComboBox { selected_index, .. }
+            | ControlSpec::TabBar { selected_index, .. } => *selected_index = Some(index),
+            _ => self.reject("selected()"),
+        }
+        self
+    }
+
+    /// Image drawn before the text; buttons only.
+    pub fn leading_image(mut self, image_id: ImageId) -> Self {
+        match &mut self.spec {
+            ControlSpec::Button { image, .. } => *image = Some(image_id),
+            _ => self.reject("leading_image()"),
+        }
+        self
+    }
+
+    /// Images of a tab bar's tabs, by index; tab bars only.
+    pub fn images(mut self, values: impl IntoIterator<Item = Option<ImageId>>) -> Self {
+        let values = values.into_iter().collect();
+        match &mut self.spec {
+            ControlSpec::TabBar { images, .. } => *images = values,
+            _ => self.reject("images()"),
+        }
+        self
+    }
+
+    /// Hover help of a tab bar's tabs, by index; tab bars only.
+    pub fn tooltips(mut self, values: impl IntoIterator<Item = Option<String>>) -> Self {
+        let values = values.into_iter().collect();
+        match &mut self.spec {
+            ControlSpec::TabBar { tooltips, .. } => *tooltips = values,
+            _ => self.reject("tooltips()"),
+        }
+        self
+    }
+
+    /// Track direction; sliders only.
+    pub fn orientation(mut self, value: SliderOrientation) -> Self {
+        match &mut self.spec {
+            ControlSpec::Slider { orientation, .. } => *orientation = value,
+            _ => self.reject("orientation()"),
+        }
+        self
+    }
+
+    /// Initial slider value, snapped to its range; sliders only.
+    pub fn value(mut self, initial: i32) -> Self {
+        match &mut self.spec {
+            ControlSpec::Slider { value, .. } => *value = initial,
+            _ => self.reject("value()"),
+        }
+        self
+    }
+
+    /// Initial value of a numeric input, clamped to its range; numeric inputs only.
+    pub fn numeric_value(mut self, initial: f64) -> Self {
+        match &mut self.spec {
+            ControlSpec::NumericInput { value, .. } => *value = initial,
+            _ => self.reject("numeric_value()"),
+        }
+        self
+    }
+
+    // Options are checked when the tree is built so the chain stays fluent.
+    fn reject(&mut self, option: &str) {
+        if self.misuse.is_none() {
+            self.misuse = Some(format!(
+                "{option} does not apply to {} control {}",
+                spec_name(&self.spec),
+                self.id.raw()
+            ));
+        }
+    }
+
+    // --- Layout ---
+
+    /// Replaces the whole layout rule; its control id, parent and order are
+    /// ignored in favour of the tree position and `order`.
+    pub fn layout(mut self, rule: LayoutRule) -> Self {
+        self.rule = Some(rule);
+        self
+    }
+
+    pub fn dock(mut self, dock_style: DockStyle) -> Self {
+        self.rule_mut().dock_style = dock_style;
+        self
+    }
+
+    pub fn dock_top(self, height: i32) -> Self {
+        self.dock(DockStyle::Top).fixed_size(height)
+    }
+
+    pub fn dock_bottom(self, height: i32) -> Self {
+        self.dock(DockStyle::Bottom).fixed_size(height)
+    }
+
+    pub fn dock_left(self, width: i32) -> Self {
+        self.dock(DockStyle::Left).fixed_size(width)
+    }
+
+    pub fn dock_right(self, width: i32) -> Self {
+        self.dock(DockStyle::Right).fixed_size(width)
+    }
+
+    pub fn fill(self) -> Self {
+        self.dock(DockStyle::Fill)
+    }
+
+    pub fn proportional(self, weight: f32, axis: LayoutAxis) -> Self {
+        self.dock(DockStyle::ProportionalFill { weight, axis })
+    }
+
+    pub fn grid_cell(self, row: u32, column: u32) -> Self {
+        self.dock(DockStyle::GridCell {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+        })
+    }
+
+    pub fn flow_item(self) -> Self {
+        self.dock(DockStyle::FlowItem)
+    }
+
+    /// Places the control at parent-relative `bounds` (`DockStyle::None`).
+    pub fn absolute(mut self, bounds: Rect) -> Self {
+        let rule = self.rule_mut();
+        rule.dock_style = DockStyle::None;
+        rule.absolute_bounds = Some(bounds);
+        self
+    }
+
+    pub fn fixed_size(mut self, size: i32) -> Self {
+        let rule = self.rule_mut();
+        rule.fixed_size = Some(size);
+        rule.size_mode = SizeMode::Fixed;
+        self
+    }
+
+    /// Sizes the control from its measured content (`SizeMode::Auto`).
+    pub fn auto_size(mut self) -> Self {
+        let rule = self.rule_mut();
+        rule.fixed_size = None;
+        rule.size_mode = SizeMode::Auto;
+        self
+    }
+
+    pub fn order(mut self, order: u32) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Margins as (top, right, bottom, left).
+    pub fn margin(mut self, margin: (i32, i32, i32, i32)) -> Self {
+        self.rule_mut().margin = margin;
+        self
+    }
+
+    pub fn constraints(mut self, constraints: SizeConstraints) -> Self {
+        self.rule_mut().constraints = constraints;
+        self
+    }
+
+    pub fn preferred_size(mut self, width: i32, height: i32) -> Self {
+        self.rule_mut().preferred_size = Some((width, height));
+        self
+    }
+
+    /// How this control arranges its own children.
+    pub fn container(mut self, container: ContainerLayout) -> Self {
+        self.rule_mut().container = Some(container);
+        self
+    }
+
+    fn rule_mut(&mut self) -> &mut LayoutRule {
+        self.rule
+            .get_or_insert_with(|| LayoutRule::new(self.id, DockStyle::None))
+    }
+
+    fn push_commands(
+        &self,
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        commands: &mut Vec<PlatformCommand>,
+    ) {
+        use ControlSpec as Kind;
+        let control_id = self.id;
+        let create = match &self.spec {
+            Kind::Panel => PlatformCommand::CreatePanel {
+                window_id,
+                parent_control_id,
+                control_id,
+            },
+            Kind::Button { text, image } => PlatformCommand::CreateButton {
+                window_id,
+                parent_control_id,
+                control_id,
+                text: text.clone(),
+                image: *image,
+            },
+            Kind::Label { text, class } => PlatformCommand::CreateLabel {
+                window_id,
+                parent_control_id,
+                control_id,
+                initial_text: text.clone(),
+                class: *class,
+            },
+            Kind::Input {
+                text,
+                read_only,
+                multiline,
+                vertical_scroll,
+            } => PlatformCommand::CreateInput {
+                window_id,
+                parent_control_id,
+                control_id,
+                initial_text: text.clone(),
+                read_only: *read_only,
+                multiline: *multiline,
+                vertical_scroll: *vertical_scroll,
+            },
+            Kind::RichEdit => PlatformCommand::CreateRichEdit {
+                window_id,
+                parent_control_id,
+                control_id,
+            },
+            Kind::TreeView => PlatformCommand::CreateTreeView {
+                window_id,
+                parent_control_id,
+                control_id,
+            },
+            Kind::Chart => PlatformCommand::CreateChart {
+                window_id,
+                parent_control_id,
+                control_id,
+            },
+            Kind::ProgressBar => PlatformCommand::CreateProgressBar {
+                window_id,
+                parent_control_id,
+                control_id,
+            },
+            Kind::Splitter { orientation } => PlatformCommand::CreateSplitter {
+                window_id,
+                parent_control_id,
+                control_id,
+                orientation: *orientation,
+            },
+            Kind::ComboBox { .. } => PlatformCommand::CreateComboBox {
+                window_id,
+                parent_control_id,
+                control_id,
+            },
+            Kind::RadioButton {
+                text, group_start, ..
+            } => PlatformCommand::CreateRadioButton {
+                window_id,
+                parent_control_id,
+                control_id,
+                text: text.clone(),
+                group_start: *group_start,
+            },
+            Kind::CheckBox { text, .. } => PlatformCommand::CreateCheckBox {
+                window_id,
+                parent_control_id,
+                control_id,
+                text: text.clone(),
+            },
+            Kind::TabBar {
+                items,
+                images,
+                tooltips,
+                ..
+            } => PlatformCommand::CreateTabBar {
+                window_id,
+                control_id,
+                parent_control_id,
+                items: items.clone(),
+                images: images.clone(),
+                tooltips: tooltips.clone(),
+            },
+            Kind::ToggleSwitch { label, checked } => PlatformCommand::CreateToggleSwitch {
+                window_id,
+                parent_control_id,
+                control_id,
+                label: label.clone(),
+                checked: *checked,
+            },
+            Kind::ListView { columns } => PlatformCommand::CreateListView {
+                window_id,
+                parent_control_id,
+                control_id,
+                columns: columns.clone(),
+            },
+            Kind::Slider {
+                range,
+                orientation,
+                value,
+            } => PlatformCommand::CreateSlider {
+                window_id,
+                parent_control_id,
+                control_id,
+                range: *range,
+                orientation: *orientation,
+                value: *value,
+            },
+            Kind::NumericInput { spec, value } => PlatformCommand::CreateNumericInput {
+                window_id,
+                parent_control_id,
+                control_id,
+                spec: spec.clone(),
+                value: *value,
+            },
+            Kind::StatusBar { segments } => PlatformCommand::CreateStatusBar {
+                window_id,
+                parent_control_id,
+                control_id,
+                segments: segments.clone(),
+            },
+            Kind::Toolbar { items } => PlatformCommand::CreateToolbar {
+                window_id,
+                parent_control_id,
+                control_id,
+                items: items.clone(),
+            },
+            Kind::Image { image } => PlatformCommand::CreateImage {
+                window_id,
+                parent_control_id,
+                control_id,
+                image_id: *image,
+            },
+        };
+        commands.push(create);
+
+        // Initial state the create commands do not carry.
+        match &self.spec {
+            Kind::ComboBox {
+                items,
+                selected_index,
+            } => {
+                if !items.is_empty() {
+                    commands.push(PlatformCommand::SetComboBoxItems {
+                        window_id,
+                        control_id,
+                        items: items.clone(),
+                    });
+                }
+                if selected_index.is_some() {
+                    commands.push(PlatformCommand::SetComboBoxSelection {
+                        window_id,
+                        control_id,
+                        selected_index: *selected_index,
+                    });
+                }
+            }
+            Kind::RadioButton { checked: true, .. } => {
+                commands.push(PlatformCommand::SetRadioButtonChecked {
+                    window_id,
+                    control_id,
+                    checked: true,
+                });
+            }
+            Kind::CheckBox { checked: true, .. } => {
+                commands.push(PlatformCommand::SetCheckBoxChecked {
+                    window_id,
+                    control_id,
+                    checked: true,
+                });
+            }
+            Kind::TabBar {
+                selected_index: Some(selected_index),
+                ..
+            } => {
+                commands.push(PlatformCommand::SetTabBarSelection {
+                    window_id,
+                    control_id,
+                    selected_index: *selected_index,
+                });
+            }
+            _ => {}
+        }
+        if let Some(style_id) = self.style {
+            commands.push(PlatformCommand::ApplyStyleToControl {
+                window_id,
+                control_id,
+                style_id,
+            });
+        }
+        if let Some(text) = &self.tooltip {
+            commands.push(PlatformCommand::SetControlTooltip {
+                window_id,
+                control_id,
+                text: text.clone(),
+                options: TooltipOptions::default(),
+            });
+        }
+    }
+}
+
+fn spec_name(spec: &ControlSpec) -> &'static str {
+    match spec {
+        ControlSpec::Panel => "Panel",
+        ControlSpec::Button { .. } => "Button",
+        ControlSpec::Label { .. } => "Label",
+        ControlSpec::Input { .. } => "Input",
+        ControlSpec::RichEdit => "RichEdit",
+        ControlSpec::TreeView => "TreeView",
+        ControlSpec::Chart => "Chart",
+        ControlSpec::ProgressBar => "ProgressBar",
+        ControlSpec::Splitter { .. } => "Splitter",
+        ControlSpec::ComboBox { .. } => "ComboBox",
+        ControlSpec::RadioButton { .. } => "RadioButton",
+        ControlSpec::CheckBox { .. } => "CheckBox",
+        ControlSpec::TabBar { .. } => "TabBar",
+        ControlSpec::ToggleSwitch { .. } => "ToggleSwitch",
+        ControlSpec::ListView { .. } => "ListView",
+        ControlSpec::Slider { .. } => "Slider",
+        ControlSpec::NumericInput { .. } => "NumericInput",
+        ControlSpec::StatusBar { .. } => "StatusBar",
+        ControlSpec::Toolbar { .. } => "Toolbar",
+        ControlSpec::Image { .. } => "Image",
+    }
+}
+
+/*
+ * The commands that build a window's content, and the layout rules among them
+ * (also available separately for applications that compose `DefineLayout`
+ * themselves).
+ */
+#[derive(Debug, Clone)]
+pub struct BuiltWindow {
+    pub window_id: WindowId,
+    pub commands: Vec<PlatformCommand>,
+    pub layout_rules: Vec<LayoutRule>,
+}
+
+impl BuiltWindow {
+    /// All commands, followed by one `DefineLayout` when any control has a rule.
+    pub fn into_commands(self) -> Vec<PlatformCommand> {
+        let mut commands = self.commands;
+        if !self.layout_rules.is_empty() {
+            commands.push(PlatformCommand::DefineLayout {
+                window_id: self.window_id,
+                rules: self.layout_rules,
+            });
+        }
+        commands
+    }
+}
+
+/// Collects a window's title, styles, menu and control trees.
+#[derive(Debug, Clone)]
+pub struct WindowBuilder {
+    window_id: WindowId,
+    title: Option<String>,
+    styles: Vec<(StyleId, ControlStyle)>,
+    menu: Vec<MenuItemConfig>,
+    roots: Vec<(Option<ControlId>, ControlBuilder)>,
+}
+
+impl WindowBuilder {
+    pub fn new(window_id: WindowId) -> Self {
+        Self {
+            window_id,
+            title: None,
+            styles: Vec::new(),
+            menu: Vec::new(),
+            roots: Vec::new(),
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn define_style(mut self, style_id: StyleId, style: ControlStyle) -> Self {
+        self.styles.push((style_id, style));
+        self
+    }
+
+    pub fn menu(mut self, menu_items: Vec<MenuItemConfig>) -> Self {
+        self.menu = menu_items;
+        self
+    }
+
+    /// Adds a control tree directly under the window's client area.
+    pub fn child(mut self, control: ControlBuilder) -> Self {
+        self.roots.push((None, control));
+        self
+    }
+
+    /*
+     * Adds a control tree under `parent`, which must be declared somewhere in
+     * this builder, before or after. Useful when the tree is assembled in
+     * pieces.
+     */
+    pub fn child_of(mut self, parent: ControlId, control: ControlBuilder) -> Self {
+        self.roots.push((Some(parent), control));
+        self
+    }
+
+    /*
+     * Validates the trees and returns the commands: title, style definitions,
+     * menu, then per control (parents first) its create command, initial state
+     * and `ApplyStyleToControl`.
+     */
+    pub fn build(self) -> PlatformResult<BuiltWindow> {
+        let mut flat = Vec::new();
+        for (parent, root) in &self.roots {
+            flatten(*parent, root, &mut flat);
+        }
+        let ordered = creation_order(&flat)?;
+
+        let mut layout_rules = Vec::new();
+        let mut next_order: HashMap<Option<ControlId>, u32> = HashMap::new();
+        for (parent, control) in &flat {
+            let sibling_index = next_order.entry(*parent).or_default();
+            let order = control.order.unwrap_or(*sibling_index);
+            *sibling_index += 1;
+            if let Some(rule) = &control.rule {
+                layout_rules.push(LayoutRule {
+                    control_id: control.id,
+                    parent_control_id: *parent,
+                    order,
+                    ..rule.clone()
+                });
+            }
+        }
+        layout::validate_layout_rules(&layout_rules)?;
+
+        let window_id = self.window_id;
+        let mut commands = Vec::new();
+        if let Some(title) = self.title {
+            commands.push(PlatformCommand::SetWindowTitle { window_id, title });
+        }
+        commands.extend(
+            self.styles
+                .into_iter()
+                .map(|(style_id, style)| PlatformCommand::DefineStyle { style_id, style }),
+        );
+        if !self.menu.is_empty() {
+            commands.push(PlatformCommand::CreateMainMenu {
+                window_id,
+                menu_items: self.menu,
+            });
+        }
+        for (parent, control) in ordered {
+            control.push_commands(window_id, parent, &mut commands);
+        }
+        Ok(BuiltWindow {
+            window_id,
+            commands,
+            layout_rules,
+        })
+    }
+}
+
+// Depth-first, so declaration order is kept among siblings.
+fn flatten<'a>(
+    parent: Option<ControlId>,
+    control: &'a ControlBuilder,
+    out: &mut Vec<(Option<ControlId>, &'a ControlBuilder)>,
+) {
+    out.push((parent, control));
+    for child in &control.children {
+        flatten(Some(control.id), child, out);
+    }
+}
+
+/*
+ * Checks ids, options and parents and orders the controls so every parent
+ * precedes its children, keeping declaration order among controls at the
+ * same depth.
+ */
+fn creation_order<'a>(
+    flat: &[(Option<ControlId>, &'a ControlBuilder)],
+) -> PlatformResult<Vec<(Option<ControlId>, &'a ControlBuilder)>> {
+    let mut parent_of: HashMap<ControlId, Option<ControlId>> = HashMap::new();
+    for (parent, control) in flat {
+        if let Some(misuse) = &control.misuse {
+            return Err(PlatformError::OperationFailed(format!(
+                "UI build rejected: {misuse}."
+            )));
+        }
+        if parent_of.insert(control.id, *parent).is_some() {
+            return Err(PlatformError::OperationFailed(format!(
+                "UI build rejected: control {} is declared more than once.",
+                control.id.raw()
+            )));
+        }
+    }
+    let mut depths = Vec::with_capacity(flat.len());
+    for &(parent, control) in flat {
+        let mut depth = 0;
+        let mut visited = HashSet::from([control.id]);
+        let mut current = (control.id, parent);
+        while let (child_id, Some(parent_id)) = current {
+            let Some(grandparent) = parent_of.get(&parent_id) else {
+                return Err(PlatformError::OperationFailed(format!(
+                    "UI build rejected: control {} names unknown parent {}.",
+                    child_id.raw(),
+                    parent_id.raw()
+                )));
+            };
+            if !visited.insert(parent_id) {
+                return Err(PlatformError::OperationFailed(format!(
+                    "UI build rejected: control {} is its own ancestor.",
+                    control.id.raw()
+                )));
+            }
+            depth += 1;
+            current = (parent_id, *grandparent);
+        }
+        depths.push((depth, (parent, control)));
+    }
+    depths.sort_by_key(|(depth, _)| *depth);
+    Ok(depths.into_iter().map(|(_, entry)| entry).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessPlatform;
+    use crate::types::WindowConfig;
+
+    const TOOLBAR: ControlId = ControlId::new(1);
+    const GO: ControlId = ControlId::new(2);
+    const MODE: ControlId = ControlId::new(3);
+    const TREE: ControlId = ControlId::new(4);
+    const WRAP: ControlId = ControlId::new(5);
+
+    fn setup() -> (HeadlessPlatform, WindowId) {
+        let mut platform = HeadlessPlatform::new();
+        let window_id = platform
+            .create_window(WindowConfig {
+                title: "Built",
+                width: 400,
+                height: 300,
+            })
+            .unwrap();
+        (platform, window_id)
+    }
+
+    #[test]
+    // [CDU-TypedBuilderV1] A nested builder produces commands, initial state, styles and layout.
+    fn builder_produces_commands_that_build_the_tree() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let builder = WindowBuilder::new(window_id)
+            .title("Built")
+            .child(
+                ControlBuilder::panel(TOOLBAR)
+                    .dock_top(30)
+                    .child(
+                        ControlBuilder::button(GO, "Go")
+                            .style(StyleId::DefaultButton)
+                            .dock_left(80),
+                    )
+                    .child(
+                        ControlBuilder::combo_box(MODE)
+                            .items(["Fast", "Slow"])
+                            .selected(1)
+                            .fill(),
+                    ),
+            )
+            .child(ControlBuilder::tree_view(TREE).fill())
+            .child_of(
+                TOOLBAR,
+                ControlBuilder::check_box(WRAP, "Wrap").checked(true),
+            );
+
+        // Act
+        let built = builder.build().unwrap();
+        let rule_orders: Vec<(ControlId, u32)> = built
+            .layout_rules
+            .iter()
+            .map(|r| (r.control_id, r.order))
+            .collect();
+        for command in built.into_commands() {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Assert
+        assert_eq!(
+            rule_orders,
+            vec![(TOOLBAR, 0), (GO, 0), (MODE, 1), (TREE, 1)]
+        );
+        let window = platform.window(window_id).unwrap();
+        assert_eq!(window.children(Some(TOOLBAR)), vec![GO, MODE, WRAP]);
+        assert_eq!(
+            window.control(GO).unwrap().style_id,
+            Some(StyleId::DefaultButton)
+        );
+        assert_eq!(window.control(MODE).unwrap().selected_index, Some(1));
+        assert!(window.control(WRAP).unwrap().checked);
+        assert_eq!(
+            window.control(TOOLBAR).unwrap().rect,
+            Some(Rect::new(0, 0, 400, 30))
+        );
+        assert_eq!(
+            window.control(TREE).unwrap().rect,
+            Some(Rect::new(0, 30, 400, 300))
+        );
+        assert_eq!(
+            window.control(GO).unwrap().rect,
+            Some(Rect::new(0, 0, 80, 30))
+        );
+    }
+
+    #[test]
+    // [CDU-TypedBuilderV1] Duplicate ids, unknown parents and misplaced options fail at build time.
+    fn build_rejects_duplicates_unknown_parents_and_misused_options() {
+        // Arrange
+        let duplicate = WindowBuilder::new(WindowId::new(1))
+            .child(ControlBuilder::panel(TOOLBAR).child(ControlBuilder::panel(TOOLBAR)));
+        let orphan =
+            WindowBuilder::new(WindowId::new(1)).child_of(TREE, ControlBuilder::button(GO, "Go"));
+        let misuse = WindowBuilder::new(WindowId::new(1))
+            .child(ControlBuilder::panel(TOOLBAR).checked(true));
+
+        // Act
+        let messages: Vec<String> = [duplicate, orphan, misuse]
+            .into_iter()
+            .map(|b| b.build().unwrap_err().to_string())
+            .collect();
+
+        // Assert
+        assert!(messages[0].contains("control 1 is declared more than once"));
+        assert!(messages[1].contains("control 2 names unknown parent 4"));
+        assert!(messages[2].contains("checked() does not apply to Panel control 1"));
+    }
+
+    #[test]
+    fn build_validates_layout_rules() {
+        // Arrange
+        let builder = WindowBuilder::new(WindowId::new(1))
+            .child(ControlBuilder::tree_view(TREE).fill())
+            .child(ControlBuilder::rich_edit(GO).fill());
+
+        // Act
+        let result = builder.build();
+
+        // Assert
+        assert!(matches!(result, Err(PlatformError::OperationFailed(msg)) if msg.contains("Fill")));
+    }
+}
@@ -0,0 +1,456 @@
+/*
+ * The portable `Color` type and the color math built on it: parsing and
+ * formatting of `#RGB`, `#RRGGBB`, `#RRGGBBAA`, `rgb()` and `rgba()` strings,
+ * HSL lightening and darkening, per-channel offsets, mixing, alpha
+ * compositing, and WCAG 2.x contrast ratios for checking that text stays
+ * readable on its background.
+ *
+ * Native backends paint opaque colors; a translucent color is flattened onto
+ * the color behind it with `over` before it is converted. With the `serde`
+ * feature a color serializes as `{ r, g, b }` (plus `a` when translucent) and
+ * deserializes from that form or from any string `parse` accepts, so theme and
+ * description files can write `text_color = "#E0E5EC"`.
+ * [CDU-Styling-ColorV1]
+ */
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{PlatformError, Result as PlatformResult};
+
+/// Minimum WCAG AA contrast ratio for normal-size text.
+pub const WCAG_AA_NORMAL_TEXT: f64 = 4.5;
+/// Minimum WCAG AA contrast ratio for large text and user interface components.
+pub const WCAG_AA_LARGE_TEXT: f64 = 3.0;
+
+/// An sRGB color with an alpha channel; `a` is 255 for opaque colors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    // Opaque colors leave it out, so version 1 payloads and descriptions load unchanged.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_opaque_alpha"))]
+    pub a: u8,
+}
+
+#[cfg(feature = "serde")]
+fn is_opaque_alpha(a: &u8) -> bool {
+    *a == 255
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Color {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ColorVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ColorVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for ColorVisitor {
+    type Value = Color;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a color string such as \"#RRGGBB\" or a table with r, g, b and optional a")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, text: &str) -> Result<Color, E> {
+        Color::parse(text).map_err(|e| match e {
+            PlatformError::OperationFailed(msg) => E::custom(msg),
+            other => E::custom(other),
+        })
+    }
+
+    fn visit_map<M: serde::de::MapAccess<'de>>(self, mut map: M) -> Result<Color, M::Error> {
+        let (mut r, mut g, mut b, mut a) = (None, None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            let slot = match key.as_str() {
+                "r" => &mut r,
+                "g" => &mut g,
+                "b" => &mut b,
+                "a" => &mut a,
+                _ => {
+                    map.next_value::<serde::de::IgnoredAny>()?;
+                    continue;
+                }
+            };
+            if slot.is_some() {
+                return Err(serde::de::Error::custom(format!("duplicate field `{key}`")));
+            }
+            *slot = Some(map.next_value::<u8>()?);
+        }
+        Ok(Color::rgba(
+            r.ok_or_else(|| serde::de::Error::missing_field("r"))?,
+            g.ok_or_else(|| serde::de::Error::missing_field("g"))?,
+            b.ok_or_else(|| serde::de::Error::missing_field("b"))?,
+            a.unwrap_or(255),
+        ))
+    }
+}
+
+impl Default for Color {
+    /// Opaque black.
+    fn default() -> Self {
+        Color::BLACK
+    }
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    pub const fn with_alpha(&self, a: u8) -> Self {
+        Color::rgba(self.r, self.g, self.b, a)
+    }
+
+    pub const fn is_opaque(&self) -> bool {
+        self.a == 255
+    }
+
+    /*
+     * Parses `#RGB`, `#RRGGBB`, `#RRGGBBAA`, `rgb(r, g, b)` or
+     * `rgba(r, g, b, a)`, where channels are 0-255 and `a` is 0.0-1.0 as in CSS.
+     * Surrounding whitespace and letter case are ignored.
+     */
+    pub fn parse(text: &str) -> PlatformResult<Self> {
+        let trimmed = text.trim();
+        let lower = trimmed.to_ascii_lowercase();
+        let parsed = if let Some(hex) = lower.strip_prefix('#') {
+            parse_hex(hex)
+        } else if let Some(args) = lower
+            .strip_prefix("rgba(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            parse_functional(args, true)
+        } else if let Some(args) = lower
+            .strip_prefix("rgb(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            parse_functional(args, false)
+        } else {
+            None
+        };
+        parsed.ok_or_else(|| {
+            PlatformError::OperationFailed(format!(
+                "Invalid color '{trimmed}': expected #RGB, #RRGGBB, #RRGGBBAA, rgb(r, g, b) or rgba(r, g, b, a)"
+            ))
+        })
+    }
+
+    /// `#RRGGBB` for opaque colors, `#RRGGBBAA` otherwise.
+    pub fn to_hex(&self) -> String {
+        if self.is_opaque() {
+            format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02X}{:02X}{:02X}{:02X}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// Hue in degrees (0-360), saturation and lightness (0.0-1.0).
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness);
+        }
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// The opaque color with the given hue (degrees), saturation and lightness.
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        let channel = |c: f64| to_channel((c + m) * 255.0);
+        Color::rgb(channel(r), channel(g), channel(b))
+    }
+
+    /// Raises the HSL lightness by `amount` (0.0-1.0), keeping hue, saturation and alpha.
+    pub fn lighten(&self, amount: f64) -> Self {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Color::from_hsl(hue, saturation, lightness + amount).with_alpha(self.a)
+    }
+
+    /// Lowers the HSL lightness by `amount` (0.0-1.0), keeping hue, saturation and alpha.
+    pub fn darken(&self, amount: f64) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Adds `amount` to each of r, g and b, clamped to 0-255; alpha is kept.
+    pub fn offset(&self, amount: i16) -> Self {
+        let shift = |c: u8| (c as i16 + amount).clamp(0, 255) as u8;
+        Color::rgba(shift(self.r), shift(self.g), shift(self.b), self.a)
+    }
+
+    /// Like `mix` with a whole `percent` (0-100), but each channel is truncated rather than rounded.
+    pub fn mix_percent(&self, other: &Color, percent: u8) -> Self {
+        let percent = percent.min(100) as u16;
+        let blend = |a: u8, b: u8| ((a as u16 * (100 - percent) + b as u16 * percent) / 100) as u8;
+        Color::rgba(
+            blend(self.r, other.r),
+            blend(self.g, other.g),
+            blend(self.b, other.b),
+            blend(self.a, other.a),
+        )
+    }
+
+    /// Blends toward `other` by `amount`: 0.0 gives `self`, 1.0 gives `other`.
+    pub fn mix(&self, other: &Color, amount: f64) -> Self {
+        let amount = amount.clamp(0.0, 1.0);
+        let blend = |a: u8, b: u8| to_channel(a as f64 * (1.0 - amount) + b as f64 * amount);
+        Color::rgba(
+            blend(self.r, other.r),
+            blend(self.g, other.g),
+            blend(self.b, other.b),
+            blend(self.a, other.a),
+        )
+    }
+
+    /// Composites `self` over `background` (source-over); opaque backgrounds give opaque results.
+    pub fn over(&self, background: &Color) -> Self {
+        let alpha = self.a as f64 / 255.0;
+        let background_alpha = background.a as f64 / 255.0;
+        let out_alpha = alpha + background_alpha * (1.0 - alpha);
+        if out_alpha == 0.0 {
+            return Color::rgba(0, 0, 0, 0);
+        }
+        let blend = |fg: u8, bg: u8| {
+            to_channel(
+                (fg as f64 * alpha + bg as f64 * background_alpha * (1.0 - alpha)) / out_alpha,
+            )
+        };
+        Color::rgba(
+            blend(self.r, background.r),
+            blend(self.g, background.g),
+            blend(self.b, background.b),
+            to_channel(out_alpha * 255.0),
+        )
+    }
+
+    /// WCAG 2.x relative luminance (0.0 for black, 1.0 for white); alpha is ignored.
+    pub fn relative_luminance(&self) -> f64 {
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// WCAG 2.x contrast ratio between two colors, from 1.0 to 21.0.
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+}
+
+impl FromStr for Color {
+    type Err = PlatformError;
+
+    fn from_str(text: &str) -> PlatformResult<Self> {
+        Color::parse(text)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+fn to_channel(value: f64) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let nibble = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok().map(|n| n * 17);
+    match hex.len() {
+        3 => Some(Color::rgb(nibble(0)?, nibble(1)?, nibble(2)?)),
+        6 => Some(Color::rgb(byte(0)?, byte(2)?, byte(4)?)),
+        8 => Some(Color::rgba(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+        _ => None,
+    }
+}
+
+fn parse_functional(args: &str, with_alpha: bool) -> Option<Color> {
+    let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+    if parts.len() != if with_alpha { 4 } else { 3 } {
+        return None;
+    }
+    let channel = |part: &str| part.parse::<u8>().ok();
+    let alpha = if with_alpha {
+        let alpha = parts[3].parse::<f64>().ok()?;
+        if !(0.0..=1.0).contains(&alpha) {
+            return None;
+        }
+        to_channel(alpha * 255.0)
+    } else {
+        255
+    };
+    Some(Color::rgba(
+        channel(parts[0])?,
+        channel(parts[1])?,
+        channel(parts[2])?,
+        alpha,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Styling-ColorV1] Hex and functional notations parse, and hex formatting round trips.
+    fn colors_parse_and_format() {
+        // Arrange
+        let inputs = [
+            "#1E1F22",
+            "#1e1f22",
+            " #FFF ",
+            "#00000080",
+            "rgb(30, 31, 34)",
+            "RGBA(0,0,0,0.5)",
+        ];
+
+        // Act
+        let parsed: Vec<Color> = inputs.iter().map(|s| s.parse().unwrap()).collect();
+
+        // Assert
+        assert_eq!(parsed[0], Color::rgb(0x1E, 0x1F, 0x22));
+        assert_eq!(parsed[1], parsed[0]);
+        assert_eq!(parsed[2], Color::WHITE);
+        assert_eq!(parsed[3], Color::rgba(0, 0, 0, 0x80));
+        assert_eq!(parsed[4], parsed[0]);
+        assert_eq!(parsed[5], parsed[3]);
+        assert_eq!(parsed[0].to_string(), "#1E1F22");
+        assert_eq!(parsed[3].to_hex(), "#00000080");
+        for bad in [
+            "1E1F22",
+            "#12345",
+            "#GGGGGG",
+            "rgb(1, 2)",
+            "rgb(1, 2, 300)",
+            "rgba(1,2,3,2)",
+        ] {
+            assert!(Color::parse(bad).is_err(), "{bad} should not parse");
+        }
+    }
+
+    #[test]
+    // [CDU-Styling-ColorV1] HSL conversion round trips and lighten/darken move only lightness.
+    fn hsl_round_trips_and_adjusts_lightness() {
+        // Arrange
+        let accent = Color::rgb(0x00, 0x80, 0xFF);
+
+        // Act
+        let (hue, saturation, lightness) = accent.to_hsl();
+        let lighter = accent.lighten(0.2);
+        let darker = accent.with_alpha(128).darken(0.2);
+
+        // Assert
+        assert_eq!(Color::from_hsl(hue, saturation, lightness), accent);
+        assert!((hue - 210.0).abs() < 0.5);
+        assert!((lighter.to_hsl().2 - (lightness + 0.2)).abs() < 0.01);
+        assert!((darker.to_hsl().0 - hue).abs() < 1.0);
+        assert_eq!(darker.a, 128);
+        assert_eq!(Color::WHITE.lighten(0.5), Color::WHITE);
+        assert_eq!(Color::BLACK.darken(0.5), Color::BLACK);
+    }
+
+    #[test]
+    // [CDU-Styling-ColorV1] Mixing interpolates channels; compositing flattens alpha onto the background.
+    fn mix_and_over_blend_channels() {
+        // Arrange
+        let background = Color::rgb(0x2E, 0x32, 0x39);
+        let translucent_white = Color::WHITE.with_alpha(51); // 20%
+
+        // Act
+        let mixed = background.mix(&Color::WHITE, 0.2);
+        let composited = translucent_white.over(&background);
+
+        // Assert
+        assert_eq!(mixed, Color::rgb(88, 91, 97));
+        assert_eq!(composited, mixed);
+        assert_eq!(background.mix(&Color::BLACK, 0.0), background);
+        assert_eq!(background.mix(&Color::BLACK, 1.0), Color::BLACK);
+    }
+
+    #[test]
+    // [CDU-Styling-ColorV1] Offsets and percentage mixes use clamped, truncating integer arithmetic.
+    fn offset_and_mix_percent_truncate_per_channel() {
+        // Arrange
+        let background = Color::rgb(0x2E, 0x32, 0x39);
+        let text = Color::rgb(0xE0, 0xE5, 0xEC);
+
+        // Act
+        let lighter = background.offset(15);
+        let clamped = Color::rgba(250, 10, 128, 51).offset(-20);
+        let inactive = background.mix_percent(&text, 40);
+        let pressed = background.mix_percent(&Color::BLACK, 20);
+
+        // Assert
+        assert_eq!(lighter, Color::rgb(61, 65, 72));
+        assert_eq!(clamped, Color::rgba(230, 0, 108, 51));
+        assert_eq!(Color::rgb(250, 0, 0).offset(20), Color::rgb(255, 20, 20));
+        assert_eq!(inactive, Color::rgb(117, 121, 128));
+        assert_eq!(pressed, Color::rgb(36, 40, 45));
+    }
+
+    #[test]
+    // [CDU-Styling-ColorV1] Contrast ratios follow WCAG 2.x.
+    fn contrast_ratio_matches_wcag_reference_values() {
+        // Arrange
+        let gray = Color::rgb(0x76, 0x76, 0x76);
+
+        // Act
+        let extremes = Color::BLACK.contrast_ratio(&Color::WHITE);
+        let gray_on_white = gray.contrast_ratio(&Color::WHITE);
+
+        // Assert
+        assert!((extremes - 21.0).abs() < 1e-9);
+        assert!((Color::WHITE.contrast_ratio(&Color::BLACK) - extremes).abs() < 1e-9);
+        assert!((WCAG_AA_NORMAL_TEXT..4.6).contains(&gray_on_white));
+        assert!((gray.contrast_ratio(&gray) - 1.0).abs() < 1e-9);
+    }
+}
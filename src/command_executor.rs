@@ -14,7 +14,7 @@ use super::controls::{richedit_handler, treeview_handler}; // Ensure treeview_ha
 use super::error::{PlatformError, Result as PlatformResult};
 use super::styling::StyleId;
 use super::types::{CheckState, ControlId, LayoutRule, TreeItemId, WindowId};
-use super::window_common::{ControlKind, ProgrammaticScrollGuard, try_enable_dark_mode};
+use super::window_common::{ControlKind, ProgrammaticScrollGuard, apply_native_color_mode};
 
 use std::sync::Arc;
 use windows::{
@@ -369,7 +369,7 @@ pub(crate) fn execute_create_input(
             .get_parsed_style(StyleId::MainWindowBackground)
             .is_some()
         {
-            try_enable_dark_mode(hwnd_edit);
+            apply_native_color_mode(hwnd_edit);
         }
 
         window_data.register_control_hwnd(control_id, hwnd_edit);
@@ -5,16 +5,23 @@ pub(crate) mod checkbox_handler;
 pub(crate) mod combobox_handler;
 pub(crate) mod dark_border;
 pub(crate) mod dialog_handler;
+pub(crate) mod image_handler;
 pub(crate) mod input_handler;
 pub(crate) mod label_handler;
+pub(crate) mod list_view_handler;
 pub(crate) mod menu_handler;
+pub(crate) mod numeric_input_handler;
 pub(crate) mod paint_router;
 pub(crate) mod panel_handler;
 pub(crate) mod progress_handler;
 pub(crate) mod radiobutton_handler;
 pub(crate) mod richedit_handler;
+pub(crate) mod slider_handler;
 pub(crate) mod splitter_handler;
+pub(crate) mod status_bar_handler;
 pub(crate) mod styling_handler;
 pub(crate) mod tab_bar_handler;
 pub(crate) mod toggle_switch_handler;
+pub(crate) mod toolbar_handler;
+pub(crate) mod tooltip_handler;
 pub(crate) mod treeview_handler;
@@ -1,34 +1,116 @@
 /*
  * Encapsulates Win32-specific operations for button controls.
  * Provides creation of push buttons and translation of button click
- * notifications into platform-agnostic `AppEvent`s.
+ * notifications into platform-agnostic `AppEvent`s. Buttons with an image
+ * are owner-drawn from the start so the image can be drawn at the DPI of
+ * the button's monitor.
  */
 
 use crate::app::Win32ApiInternalState;
-use crate::controls::styling_handler::{color_to_colorref, colorref_to_color};
+use crate::controls::image_handler;
+use crate::controls::styling_handler::{
+    color_to_colorref, colorref_to_color, inset_by_padding, paint_styled_rect,
+};
 use crate::error::{PlatformError, Result as PlatformResult};
+use crate::image::{IMAGE_TEXT_GAP, SMALL_IMAGE_SIZE};
 use crate::styling::Color;
-use crate::types::{AppEvent, ControlId, WindowId};
-use crate::window_common::ControlKind;
+use crate::styling_primitives::InteractionState;
+use crate::types::{AppEvent, ControlId, ImageId, WindowId};
+use crate::window_common::{self, ControlKind};
 
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::sync::Arc;
 use windows::Win32::{
-    Foundation::{COLORREF, HWND, LRESULT},
+    Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, SIZE, WPARAM},
     Graphics::Gdi::{
-        COLOR_BTNFACE, COLOR_BTNTEXT, COLOR_GRAYTEXT, CreateSolidBrush, DT_CENTER, DT_SINGLELINE,
-        DT_VCENTER, DeleteObject, DrawFocusRect, DrawTextW, FillRect, GetSysColor, HDC, HGDIOBJ,
-        InflateRect, OPAQUE, SelectObject, SetBkColor, SetBkMode, SetTextColor, TRANSPARENT,
+        COLOR_BTNFACE, COLOR_BTNTEXT, COLOR_GRAYTEXT, DT_CENTER, DT_LEFT, DT_SINGLELINE,
+        DT_VCENTER, DrawFocusRect, DrawTextW, GetSysColor, GetTextExtentPoint32W, HDC, HGDIOBJ,
+        InflateRect, InvalidateRect, OPAQUE, SelectObject, SetBkColor, SetBkMode, SetTextColor,
+        TRANSPARENT,
+    },
+    UI::Controls::{
+        DRAWITEMSTRUCT, DrawThemeParentBackground, ODS_DISABLED, ODS_FOCUS, ODS_SELECTED,
     },
-    UI::Controls::{DRAWITEMSTRUCT, ODS_DISABLED, ODS_FOCUS, ODS_SELECTED},
+    UI::Input::KeyboardAndMouse::{TME_LEAVE, TRACKMOUSEEVENT, TrackMouseEvent},
+    UI::Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
     UI::WindowsAndMessaging::{
-        BS_PUSHBUTTON, CreateWindowExW, DestroyWindow, GetDlgCtrlID, GetWindowTextLengthW,
-        GetWindowTextW, HMENU, WINDOW_EX_STYLE, WINDOW_STYLE, WS_CHILD, WS_VISIBLE,
+        BS_OWNERDRAW, BS_PUSHBUTTON, CreateWindowExW, DestroyWindow, GetDlgCtrlID,
+        GetWindowTextLengthW, GetWindowTextW, HMENU, WINDOW_EX_STYLE, WINDOW_STYLE, WM_MOUSEMOVE,
+        WM_NCDESTROY, WS_CHILD, WS_VISIBLE,
     },
 };
 use windows::core::{HSTRING, PCWSTR};
 
 const WC_BUTTON: PCWSTR = windows::core::w!("BUTTON");
 
+// WM_MOUSELEAVE is not exported by windows-rs; define the constant directly.
+const WM_MOUSELEAVE: u32 = 0x02A3;
+
+// Identifies our hover-tracking subclass among any others on the button.
+const HOVER_SUBCLASS_ID: usize = 1;
+
+// DIPs between an owner-drawn button's edge and its focus rectangle.
+const FOCUS_RECT_INSET: i32 = 3;
+
+thread_local! {
+    // Owner-drawn buttons currently under the mouse, keyed by raw HWND.
+    static HOVERED_BUTTONS: RefCell<HashSet<isize>> = RefCell::new(HashSet::new());
+}
+
+fn is_hovered(hwnd: HWND) -> bool {
+    HOVERED_BUTTONS.with(|hovered| hovered.borrow().contains(&(hwnd.0 as isize)))
+}
+
+/*
+ * Subclasses an owner-drawn button so it repaints in its style's hover colors
+ * while the mouse is over it; Windows reports no hot state to owner-drawn
+ * buttons. Installing the subclass again only replaces its reference data.
+ */
+pub(crate) fn install_hover_tracking(hwnd: HWND) {
+    unsafe {
+        let _ = SetWindowSubclass(hwnd, Some(hover_subclass_proc), HOVER_SUBCLASS_ID, 0);
+    }
+}
+
+unsafe extern "system" fn hover_subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _subclass_id: usize,
+    _ref_data: usize,
+) -> LRESULT {
+    let key = hwnd.0 as isize;
+    match msg {
+        WM_MOUSEMOVE => {
+            let entered = HOVERED_BUTTONS.with(|hovered| hovered.borrow_mut().insert(key));
+            if entered {
+                let mut tme = TRACKMOUSEEVENT {
+                    cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                    dwFlags: TME_LEAVE,
+                    hwndTrack: hwnd,
+                    dwHoverTime: 0,
+                };
+                unsafe {
+                    let _ = TrackMouseEvent(&mut tme);
+                    let _ = InvalidateRect(Some(hwnd), None, false);
+                }
+            }
+        }
+        WM_MOUSELEAVE if HOVERED_BUTTONS.with(|hovered| hovered.borrow_mut().remove(&key)) => {
+            let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
+        }
+        WM_NCDESTROY => {
+            HOVERED_BUTTONS.with(|hovered| hovered.borrow_mut().remove(&key));
+            let _ =
+                unsafe { RemoveWindowSubclass(hwnd, Some(hover_subclass_proc), HOVER_SUBCLASS_ID) };
+        }
+        _ => {}
+    }
+    unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+}
+
 /*
  * Creates a native push button and registers the resulting HWND in the
  * window's `NativeWindowData`. Fails if the window or control ID are
@@ -47,9 +129,10 @@ pub(crate) fn handle_create_button_command(
     parent_control_id: Option<ControlId>,
     control_id: ControlId,
     text: String,
+    image: Option<ImageId>,
 ) -> PlatformResult<()> {
     log::debug!(
-        "ButtonHandler: handle_create_button_command for WinID {window_id:?}, ParentID {:?}, ControlID {}, Text: '{text}'",
+        "ButtonHandler: handle_create_button_command for WinID {window_id:?}, ParentID {:?}, ControlID {}, Text: '{text}', Image: {image:?}",
         parent_control_id.as_ref().map(|id| id.raw()),
         control_id.raw()
     );
@@ -108,17 +191,25 @@ pub(crate) fn handle_create_button_command(
             )));
         }
         window_data.register_control_kind(control_id, ControlKind::Button);
+        if let Some(image_id) = image {
+            window_data.set_control_image(control_id, image_id);
+        }
         Ok(())
     })?;
 
     // Phase 2: Create the native control without holding any locks.
     let h_instance = internal_state.h_instance();
+    let button_style = if image.is_some() {
+        BS_OWNERDRAW
+    } else {
+        BS_PUSHBUTTON
+    };
     let hwnd_button = unsafe {
         match CreateWindowExW(
             WINDOW_EX_STYLE(0),
             WC_BUTTON,
             &HSTRING::from(text.as_str()),
-            WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+            WS_CHILD | WS_VISIBLE | WINDOW_STYLE(button_style as u32),
             0,
             0,
             10,
@@ -138,6 +229,9 @@ pub(crate) fn handle_create_button_command(
             }
         }
     };
+    if image.is_some() {
+        install_hover_tracking(hwnd_button);
+    }
 
     // Phase 3: Acquire a write lock only to register the new HWND.
     internal_state.with_window_data_write(window_id, |window_data| {
@@ -188,8 +282,8 @@ pub(crate) fn handle_bn_clicked(
 
 /*
  * Handles WM_DRAWITEM for owner-drawn buttons.
- * Renders buttons with custom background and text colors from applied styles.
- * Supports disabled state, pressed state, and focus rectangle.
+ * Renders buttons with the applied style's colors, border, corner radius and
+ * padding, resolving the disabled, pressed, hovered and focused state colors.
  */
 pub(crate) fn handle_wm_drawitem(
     internal_state: &Arc<Win32ApiInternalState>,
@@ -204,50 +298,78 @@ pub(crate) fn handle_wm_drawitem(
         let control_id = ControlId::new(dis.CtlID as i32);
 
         // Get applied style with fallback to system colors
-        let style_id = internal_state
+        let (style, image) = internal_state
             .with_window_data_read(window_id, |window_data| {
-                Ok(window_data.get_style_for_control(control_id))
+                Ok((
+                    internal_state.parsed_style_for_control(window_data, control_id),
+                    window_data.get_control_image(control_id),
+                ))
             })
-            .ok()
-            .flatten();
-        let style = style_id.and_then(|sid| internal_state.get_parsed_style(sid));
+            .unwrap_or_default();
+        let source = style.as_ref().map(|s| &s.source);
+
+        // Resolve the interaction state; owner-drawn buttons get hover from our subclass.
+        let is_disabled = (dis.itemState.0 & ODS_DISABLED.0) != 0;
+        let is_pressed = (dis.itemState.0 & ODS_SELECTED.0) != 0;
+        let is_focused = (dis.itemState.0 & ODS_FOCUS.0) != 0;
+        let interaction = if is_disabled {
+            InteractionState::Disabled
+        } else if is_pressed {
+            InteractionState::Pressed
+        } else if is_hovered(dis.hwndItem) {
+            InteractionState::Hovered
+        } else if is_focused {
+            InteractionState::Focused
+        } else {
+            InteractionState::Normal
+        };
+        let colors = source
+            .map(|s| s.colors_for(interaction))
+            .unwrap_or_default();
 
         // Resolve colors: style values or system defaults as fallback
-        let base_bg = style
-            .as_ref()
-            .and_then(|s| s.background_color.clone())
+        let base_bg = colors
+            .background_color
+            .clone()
             .unwrap_or_else(|| colorref_to_color(COLORREF(GetSysColor(COLOR_BTNFACE))));
-        let base_fg = style
-            .as_ref()
-            .and_then(|s| s.text_color.clone())
+        let base_fg = colors
+            .text_color
+            .clone()
             .unwrap_or_else(|| colorref_to_color(COLORREF(GetSysColor(COLOR_BTNTEXT))));
 
-        // Determine final colors based on button state
-        let is_disabled = (dis.itemState.0 & ODS_DISABLED.0) != 0;
-        let is_pressed = (dis.itemState.0 & ODS_SELECTED.0) != 0;
-
-        let (bg_color, text_color) = if is_disabled {
-            // Disabled: use system gray text, keep background
-            (
-                base_bg,
-                colorref_to_color(COLORREF(GetSysColor(COLOR_GRAYTEXT))),
-            )
-        } else if is_pressed {
-            // Pressed: darken background by 20%
-            let pressed_bg = Color {
-                r: (base_bg.r as u32 * 80 / 100) as u8,
-                g: (base_bg.g as u32 * 80 / 100) as u8,
-                b: (base_bg.b as u32 * 80 / 100) as u8,
-            };
-            (pressed_bg, base_fg)
+        // States the style does not describe keep the built-in look: system gray
+        // text when disabled, background darkened by 20% when pressed.
+        let text_color = if interaction == InteractionState::Disabled
+            && source.is_none_or(|s| s.disabled.as_ref().is_none_or(|d| d.text_color.is_none()))
+        {
+            colorref_to_color(COLORREF(GetSysColor(COLOR_GRAYTEXT)))
         } else {
-            (base_bg, base_fg)
+            base_fg
+        };
+        let bg_color = if interaction == InteractionState::Pressed
+            && source.is_none_or(|s| s.pressed.is_none() && s.hover.is_none())
+        {
+            base_bg.mix_percent(&Color::BLACK, 20)
+        } else {
+            base_bg
         };
 
-        // Fill background
-        let brush = CreateSolidBrush(color_to_colorref(&bg_color));
-        FillRect(dis.hDC, &dis.rcItem, brush);
-        let _ = DeleteObject(brush.into());
+        // Fill background and border; rounded corners show the parent behind them.
+        let scale = window_common::dpi_scale_for_window(dis.hwndItem);
+        let border_width = source.map_or(0, |s| s.border_width_for(interaction));
+        let corner_radius = source.and_then(|s| s.corner_radius).unwrap_or(0).max(0);
+        if corner_radius > 0 {
+            let _ = DrawThemeParentBackground(dis.hwndItem, dis.hDC, None);
+        }
+        paint_styled_rect(
+            dis.hDC,
+            &dis.rcItem,
+            Some(&bg_color),
+            colors.border_color.as_ref(),
+            border_width,
+            corner_radius,
+            scale,
+        );
 
         // Get button text (dynamic length, no hardcoded buffer)
         let text_len = GetWindowTextLengthW(dis.hwndItem);
@@ -261,15 +383,42 @@ pub(crate) fn handle_wm_drawitem(
         // Apply font if available, saving old font for restoration
         let old_font = style
             .as_ref()
-            .and_then(|s| s.font_handle)
+            .and_then(|s| s.font_for_window(dis.hwndItem))
             .map(|font| SelectObject(dis.hDC, HGDIOBJ(font.0)));
 
-        let mut rect = dis.rcItem;
+        let mut rect = inset_by_padding(&dis.rcItem, source.and_then(|s| s.padding), scale);
+        let text = &mut text_buf[..text_len as usize];
+        // [CDU-ImagesV1] The image and the text after it are centered together.
+        let mut alignment = DT_CENTER;
+        if let Some(image_id) = image {
+            let image_size = scale.scale(SMALL_IMAGE_SIZE);
+            let mut text_size = SIZE::default();
+            let gap = if text.is_empty() {
+                0
+            } else {
+                let _ = GetTextExtentPoint32W(dis.hDC, text, &mut text_size);
+                scale.scale(IMAGE_TEXT_GAP)
+            };
+            let content_width = image_size + gap + text_size.cx;
+            let left = rect.left + ((rect.right - rect.left - content_width) / 2).max(0);
+            let top = rect.top + (rect.bottom - rect.top - image_size) / 2;
+            let image_rect = RECT {
+                left,
+                top,
+                right: left + image_size,
+                bottom: top + image_size,
+            };
+            let registry = internal_state.image_registry();
+            if image_handler::draw_registered_image(dis.hDC, &registry, image_id, &image_rect) {
+                rect.left = image_rect.right + gap;
+                alignment = DT_LEFT;
+            }
+        }
         DrawTextW(
             dis.hDC,
-            &mut text_buf[..text_len as usize],
+            text,
             &mut rect,
-            DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+            alignment | DT_VCENTER | DT_SINGLELINE,
         );
 
         // Restore original font to avoid leaking GDI selection state
@@ -277,10 +426,14 @@ pub(crate) fn handle_wm_drawitem(
             SelectObject(dis.hDC, prev_font);
         }
 
-        // Draw focus rectangle (inset by 3px; scale by DPI in future)
-        if (dis.itemState.0 & ODS_FOCUS.0) != 0 {
+        // Draw focus rectangle (inset by 3 DIPs) unless the style marks focus
+        // with its own border.
+        let has_focus_border =
+            source.is_some_and(|s| s.border_width_for(InteractionState::Focused) > 0);
+        if is_focused && !(interaction == InteractionState::Focused && has_focus_border) {
             let mut focus_rect = dis.rcItem;
-            let _ = InflateRect(&mut focus_rect, -3, -3);
+            let inset = scale.scale(FOCUS_RECT_INSET);
+            let _ = InflateRect(&mut focus_rect, -inset, -inset);
             let _ = DrawFocusRect(dis.hDC, &focus_rect);
         }
 
@@ -302,9 +455,7 @@ pub(crate) fn handle_wm_ctlcolorbtn(
 
     let result: PlatformResult<Option<LRESULT>> =
         internal_state.with_window_data_read(window_id, |window_data| {
-            if let Some(style_id) = window_data.get_style_for_control(control_id)
-                && let Some(style) = internal_state.get_parsed_style(style_id)
-            {
+            if let Some(style) = internal_state.parsed_style_for_control(window_data, control_id) {
                 if let Some(color) = &style.text_color {
                     unsafe { SetTextColor(hdc_button, color_to_colorref(color)) };
                 }
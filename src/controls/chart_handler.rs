@@ -8,13 +8,15 @@
  * Chart data is stored in GWLP_USERDATA as a heap-allocated `ChartWindowState`.
  * The data is replaced by `handle_set_chart_data_command` and freed on WM_DESTROY.
  *
- * Dark theme palette (Win32 COLORREF = 0x00BBGGRR):
- *   Background  #1E2228  → 0x0028_221E
- *   Gridlines   #3A3F47  → 0x0047_3F3A
+ * Background, grid and placeholder colors come from the `ChartPalette` held in
+ * the same state; it is taken from the current theme at creation and replaced
+ * by `apply_chart_palette` when a theme is applied.
  */
 
 use crate::app::Win32ApiInternalState;
+use crate::controls::styling_handler::color_to_colorref;
 use crate::error::{PlatformError, Result as PlatformResult};
+use crate::theme::ChartPalette;
 use crate::types::{ChartDataPacket, ControlId, WindowId};
 use crate::window_common::ControlKind;
 
@@ -34,15 +36,11 @@ use windows::Win32::{
 };
 use windows::core::{HSTRING, PCWSTR, w};
 
-// ── Dark theme colors ─────────────────────────────────────────────────────────
-
-const COLOR_BG: COLORREF = COLORREF(0x0028_221E); // #1E2228
-const COLOR_GRID: COLORREF = COLORREF(0x0047_3F3A); // #3A3F47
-
 // ── Per-window state stored in GWLP_USERDATA ─────────────────────────────────
 
 struct ChartWindowState {
     data: ChartDataPacket,
+    palette: ChartPalette,
 }
 
 impl Default for ChartWindowState {
@@ -53,6 +51,7 @@ impl Default for ChartWindowState {
                 week_labels: vec![],
                 is_loading: false,
             },
+            palette: ChartPalette::default(),
         }
     }
 }
@@ -159,6 +158,7 @@ unsafe fn paint_chart(hdc: windows::Win32::Graphics::Gdi::HDC, hwnd: HWND) {
         unsafe { &*state_ptr }
     };
     let lines = &state.data.lines;
+    let palette = &state.palette;
     let is_loading = state.data.is_loading;
 
     // Plot layout.
@@ -172,12 +172,12 @@ unsafe fn paint_chart(hdc: windows::Win32::Graphics::Gdi::HDC, hwnd: HWND) {
     let plot_h = (h - margin_top - margin_bottom).max(1);
 
     // 1. Fill dark background.
-    let bg_brush = unsafe { CreateSolidBrush(COLOR_BG) };
+    let bg_brush = unsafe { CreateSolidBrush(color_to_colorref(&palette.background)) };
     let _ = unsafe { FillRect(hdc, &rect, bg_brush) };
     let _ = unsafe { DeleteObject(bg_brush.into()) };
 
     // 2. Draw five dashed horizontal gridlines.
-    let grid_pen = unsafe { CreatePen(PS_DOT, 1, COLOR_GRID) };
+    let grid_pen = unsafe { CreatePen(PS_DOT, 1, color_to_colorref(&palette.grid)) };
     let old_pen = unsafe { SelectObject(hdc, grid_pen.into()) };
     for i in 0i32..=4 {
         let y = margin_top + plot_h * i / 4;
@@ -190,7 +190,7 @@ unsafe fn paint_chart(hdc: windows::Win32::Graphics::Gdi::HDC, hwnd: HWND) {
     // 3. Loading placeholder.
     if is_loading {
         unsafe { SetBkMode(hdc, BACKGROUND_MODE(1)) }; // TRANSPARENT = 1
-        let _ = unsafe { SetTextColor(hdc, COLORREF(0x0080_8080)) };
+        let _ = unsafe { SetTextColor(hdc, color_to_colorref(&palette.text)) };
         let msg: Vec<u16> = "Loading\u{2026}".encode_utf16().collect();
         let _ = unsafe { TextOutW(hdc, margin_left, margin_top + plot_h / 2 - 8, &msg) };
         return;
@@ -360,6 +360,7 @@ pub(crate) fn handle_create_chart_command(
         window_data.register_control_hwnd(control_id, hwnd_chart);
         Ok(())
     })?;
+    apply_chart_palette(hwnd_chart, internal_state.current_theme().chart.clone());
 
     log::debug!(
         "ChartHandler: chart {} created hwnd={hwnd_chart:?}",
@@ -391,3 +392,12 @@ pub(crate) fn handle_set_chart_data_command(
     }
     Ok(())
 }
+
+/// Replaces the chart's colors, e.g. when a theme is applied, and repaints it.
+pub(crate) fn apply_chart_palette(hwnd: HWND, palette: ChartPalette) {
+    unsafe {
+        let state = get_or_init_chart_state(hwnd);
+        (*state).palette = palette;
+        let _ = InvalidateRect(Some(hwnd), None, false);
+    }
+}
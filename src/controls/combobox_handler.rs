@@ -7,14 +7,13 @@
 use crate::app::Win32ApiInternalState;
 use crate::error::{PlatformError, Result as PlatformResult};
 use crate::types::{AppEvent, ControlId, WindowId};
-use crate::window_common::ControlKind;
+use crate::window_common::{self, ControlKind};
 
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
 use std::sync::Arc;
 use windows::Win32::{
     Foundation::{HWND, LPARAM, WPARAM},
-    Graphics::Gdi::{GetDC, GetDeviceCaps, LOGPIXELSY, ReleaseDC},
     UI::WindowsAndMessaging::{
         CreateWindowExW, DestroyWindow, GetWindowRect, HMENU, SendMessageW, WINDOW_EX_STYLE,
         WINDOW_STYLE, WS_BORDER, WS_CHILD, WS_VISIBLE, WS_VSCROLL,
@@ -38,7 +37,6 @@ const CB_GETITEMHEIGHT: u32 = 0x0154;
 const CB_SETMINVISIBLE: u32 = 0x1701;
 const CB_GETMINVISIBLE: u32 = 0x1702;
 const CB_ERR: isize = -1;
-const DEFAULT_DPI: i32 = 96;
 const FALLBACK_DROPDOWN_HEIGHT_PX: i32 = 260;
 const FALLBACK_MIN_VISIBLE_ITEMS: usize = 12;
 
@@ -149,9 +147,9 @@ pub(crate) fn handle_create_combobox_command(
         }
     };
 
-    // Enable dark mode — try_enable_dark_mode sets DarkMode_Explorer theme
+    // Apply the native color mode — DarkMode_Explorer (or Explorer under a light theme)
     // which gives the combo the same visual treatment as edit controls.
-    crate::window_common::try_enable_dark_mode(hwnd_combo);
+    crate::window_common::apply_native_color_mode(hwnd_combo);
 
     // Install a subclass that paints a uniform gray border, covering the
     // system-drawn 3D sunken edge that appears white in dark mode.
@@ -199,27 +197,13 @@ pub(crate) fn handle_create_combobox_command(
     })
 }
 
-fn dpi_for_window_or_default(hwnd: HWND) -> i32 {
-    let hdc = unsafe { GetDC(Some(hwnd)) };
-    if hdc.is_invalid() {
-        return DEFAULT_DPI;
-    }
-    let dpi = unsafe { GetDeviceCaps(Some(hdc), LOGPIXELSY) };
-    let _ = unsafe { ReleaseDC(Some(hwnd), hdc) };
-    if dpi > 0 { dpi } else { DEFAULT_DPI }
-}
-
-fn scale_by_dpi(px_at_96_dpi: i32, dpi: i32) -> i32 {
-    ((px_at_96_dpi.max(1) as i64 * dpi.max(DEFAULT_DPI) as i64) / DEFAULT_DPI as i64) as i32
-}
-
 pub(crate) fn fallback_min_dropdown_height_px() -> i32 {
     FALLBACK_DROPDOWN_HEIGHT_PX
 }
 
 pub(crate) fn compute_min_dropdown_height_px(hwnd_combo: HWND, base_height: i32) -> i32 {
-    let dpi = dpi_for_window_or_default(hwnd_combo);
-    let scaled_fallback = scale_by_dpi(FALLBACK_DROPDOWN_HEIGHT_PX, dpi);
+    let scaled_fallback =
+        window_common::dpi_scale_for_window(hwnd_combo).scale(FALLBACK_DROPDOWN_HEIGHT_PX);
     base_height.max(scaled_fallback)
 }
 
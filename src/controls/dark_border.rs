@@ -3,9 +3,15 @@
  * 3D sunken edge (2px: dark top-left, light bottom-right).
  *
  * Provides a subclass procedure that paints a uniform gray border after
- * WM_PAINT / WM_NCPAINT, and a helper to install it on any HWND.
+ * WM_PAINT / WM_NCPAINT, and a helper to install it on any HWND. The border
+ * color follows the current theme.
  */
 
+use crate::styling_primitives::Color;
+use crate::theme::ThemeColors;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
 use windows::Win32::{
     Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM},
     Graphics::Gdi::{CreateSolidBrush, DeleteObject, FrameRect, GetWindowDC, ReleaseDC},
@@ -15,8 +21,22 @@ use windows::Win32::{
     },
 };
 
-/// Gray border color matching the system dark-mode edit/treeview border.
-const DARK_BORDER_GRAY: COLORREF = COLORREF(0x60 | (0x60 << 8) | (0x60 << 16));
+/*
+ * Border color as a COLORREF value. GWLP_USERDATA already holds the previous
+ * window procedure, so the color is process-wide rather than per control; it
+ * starts as the dark theme border and follows `set_border_color` when a theme
+ * is applied.
+ */
+static BORDER_COLOR: AtomicU32 = AtomicU32::new(colorref_value(&ThemeColors::DARK.border));
+
+const fn colorref_value(color: &Color) -> u32 {
+    (color.r as u32) | ((color.g as u32) << 8) | ((color.b as u32) << 16)
+}
+
+/// Sets the border color painted from now on; callers repaint the affected windows.
+pub(crate) fn set_border_color(color: &Color) {
+    BORDER_COLOR.store(colorref_value(color), Ordering::Relaxed);
+}
 
 /// Paints a uniform 2px gray border over the full window rect, covering the
 /// system-drawn 3D sunken edge. Uses `GetWindowDC` so both client and
@@ -32,7 +52,7 @@ unsafe fn paint_dark_border(hwnd: HWND) {
             let w = wr.right - wr.left;
             let h = wr.bottom - wr.top;
             if w > 2 && h > 2 {
-                let brush = CreateSolidBrush(DARK_BORDER_GRAY);
+                let brush = CreateSolidBrush(COLORREF(BORDER_COLOR.load(Ordering::Relaxed)));
                 if !brush.0.is_null() {
                     // Outer 1px border
                     let outer = RECT {
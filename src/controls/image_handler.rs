@@ -0,0 +1,498 @@
+/*
+ * Custom-WndProc image control for CommanDuctUI, plus the GDI helpers every
+ * control uses to draw registered images.
+ *
+ * Images are decoded by the portable `crate::image` module when registered
+ * and kept in a registry shared by all windows. Controls render an image at
+ * the pixel size they need at their current DPI and alpha-blend it as a
+ * premultiplied 32 bit DIB. The image control scales its image to fit its
+ * client area, keeping the aspect ratio, and centers it.
+ *
+ * Per-instance state is stored in GWLP_USERDATA as a heap-allocated
+ * `ImageState`, matching `status_bar_handler`.
+ */
+
+use crate::app::Win32ApiInternalState;
+use crate::controls::styling_handler::{inset_by_padding, paint_styled_rect};
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::image::{self, ImageRegistry, RgbaImage};
+use crate::styling::Color;
+use crate::styling_primitives::InteractionState;
+use crate::styling_windows::ParsedControlStyle;
+use crate::types::{ControlId, ImageId, WindowId};
+use crate::window_common::{self, ControlKind};
+
+use std::ffi::c_void;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM},
+    Graphics::Gdi::{
+        AC_SRC_ALPHA, AC_SRC_OVER, AlphaBlend, BI_RGB, BITMAPINFO, BITMAPINFOHEADER, BLENDFUNCTION,
+        BeginPaint, CreateCompatibleDC, CreateDIBSection, DIB_RGB_COLORS, DeleteDC, DeleteObject,
+        EndPaint, HBITMAP, HDC, InvalidateRect, PAINTSTRUCT, SelectObject,
+    },
+    UI::WindowsAndMessaging::{
+        CS_HREDRAW, CS_VREDRAW, CreateWindowExW, DefWindowProcW, GWLP_USERDATA, GetClientRect,
+        GetWindowLongPtrW, HMENU, RegisterClassW, SetWindowLongPtrW, WINDOW_EX_STYLE, WM_DESTROY,
+        WM_ERASEBKGND, WM_PAINT, WNDCLASSW, WS_CHILD, WS_VISIBLE,
+    },
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+/// The image registry, shared by the platform state and every control that draws images.
+pub(crate) type SharedImageRegistry = Arc<RwLock<ImageRegistry>>;
+
+// ── Drawing helpers ───────────────────────────────────────────────────────────
+
+/// A top-down 32 bit DIB section holding `image` as premultiplied BGRA.
+pub(crate) fn create_premultiplied_bitmap(image: &RgbaImage) -> Option<HBITMAP> {
+    if image.width == 0 || image.height == 0 {
+        return None;
+    }
+    let info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: image.width as i32,
+            biHeight: -(image.height as i32), // negative: rows run top to bottom
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut bits: *mut c_void = std::ptr::null_mut();
+    let bitmap = match unsafe { CreateDIBSection(None, &info, DIB_RGB_COLORS, &mut bits, None, 0) }
+    {
+        Ok(bitmap) if !bits.is_null() => bitmap,
+        Ok(bitmap) => {
+            let _ = unsafe { DeleteObject(bitmap.into()) };
+            return None;
+        }
+        Err(err) => {
+            log::warn!(
+                "[Image] CreateDIBSection failed for a {}x{} image: {err:?}",
+                image.width,
+                image.height
+            );
+            return None;
+        }
+    };
+    let pixels = image.to_premultiplied_bgra();
+    unsafe { std::ptr::copy_nonoverlapping(pixels.as_ptr(), bits as *mut u8, pixels.len()) };
+    Some(bitmap)
+}
+
+/// Blends `image` over what is already painted, its top-left corner at (`x`, `y`).
+pub(crate) unsafe fn draw_rgba(hdc: HDC, image: &RgbaImage, x: i32, y: i32) {
+    let Some(bitmap) = create_premultiplied_bitmap(image) else {
+        return;
+    };
+    let (width, height) = (image.width as i32, image.height as i32);
+    unsafe {
+        let memory_dc = CreateCompatibleDC(Some(hdc));
+        let previous = SelectObject(memory_dc, bitmap.into());
+        let blend = BLENDFUNCTION {
+            BlendOp: AC_SRC_OVER as u8,
+            BlendFlags: 0,
+            SourceConstantAlpha: 255,
+            AlphaFormat: AC_SRC_ALPHA as u8,
+        };
+        let _ = AlphaBlend(
+            hdc, x, y, width, height, memory_dc, 0, 0, width, height, blend,
+        );
+        SelectObject(memory_dc, previous);
+        let _ = DeleteDC(memory_dc);
+        let _ = DeleteObject(bitmap.into());
+    }
+}
+
+/*
+ * Draws the registered image `image_id` scaled to fill `rect`. Returns false,
+ * drawing nothing, when the id is not registered so callers can fall back.
+ */
+pub(crate) unsafe fn draw_registered_image(
+    hdc: HDC,
+    registry: &RwLock<ImageRegistry>,
+    image_id: ImageId,
+    rect: &RECT,
+) -> bool {
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    if width <= 0 || height <= 0 {
+        return false;
+    }
+    let rendered = match registry.read() {
+        Ok(registry) => registry.render(image_id, width as u32, height as u32),
+        Err(e) => {
+            log::error!("[Image] Failed to acquire read lock on the image registry: {e:?}");
+            None
+        }
+    };
+    match rendered {
+        Some(rendered) => {
+            unsafe { draw_rgba(hdc, &rendered, rect.left, rect.top) };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether `image_id` is registered; descriptors may name images that are not.
+pub(crate) fn is_registered(registry: &RwLock<ImageRegistry>, image_id: ImageId) -> bool {
+    registry
+        .read()
+        .map(|registry| registry.contains(image_id))
+        .unwrap_or(false)
+}
+
+// ── ImageState ────────────────────────────────────────────────────────────────
+
+/// Per-instance heap-allocated state stored in GWLP_USERDATA.
+struct ImageState {
+    registry: SharedImageRegistry,
+    image: Option<ImageId>,
+    /// Fills the area the image leaves free; the theme's window background.
+    background: Color,
+    /// Style applied with `ApplyStyleToControl`: background, border and padding.
+    style: Option<Arc<ParsedControlStyle>>,
+}
+
+// ── Window class ──────────────────────────────────────────────────────────────
+
+const IMAGE_CLASS_NAME: PCWSTR = w!("CommanductUI_Image");
+static IMAGE_CLASS_REGISTERED: OnceLock<()> = OnceLock::new();
+
+fn register_image_class(h_instance: windows::Win32::Foundation::HINSTANCE) {
+    IMAGE_CLASS_REGISTERED.get_or_init(|| unsafe {
+        let wc = WNDCLASSW {
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(image_wnd_proc),
+            hInstance: h_instance,
+            hbrBackground: windows::Win32::Graphics::Gdi::HBRUSH(std::ptr::null_mut()),
+            lpszClassName: IMAGE_CLASS_NAME,
+            hCursor: windows::Win32::UI::WindowsAndMessaging::LoadCursorW(
+                None,
+                windows::Win32::UI::WindowsAndMessaging::IDC_ARROW,
+            )
+            .unwrap_or_default(),
+            ..Default::default()
+        };
+        let _ = RegisterClassW(&wc);
+    });
+}
+
+unsafe fn state_ptr(hwnd: HWND) -> *mut ImageState {
+    unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut ImageState }
+}
+
+/// A rect of `size` centered in `area`.
+fn centered_rect(area: &RECT, (width, height): (i32, i32)) -> RECT {
+    let left = area.left + (area.right - area.left - width) / 2;
+    let top = area.top + (area.bottom - area.top - height) / 2;
+    RECT {
+        left,
+        top,
+        right: left + width,
+        bottom: top + height,
+    }
+}
+
+// ── WndProc ───────────────────────────────────────────────────────────────────
+
+unsafe extern "system" fn image_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) };
+    }
+    match msg {
+        WM_ERASEBKGND => {
+            // Suppress default erase — WM_PAINT fills everything, prevents flicker.
+            LRESULT(1)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = unsafe { BeginPaint(hwnd, &mut ps) };
+            if !hdc.is_invalid() {
+                unsafe { paint_image(hwnd, hdc, &*state) };
+            }
+            let _ = unsafe { EndPaint(hwnd, &ps) };
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            let _ = unsafe { Box::from_raw(state) };
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0) };
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+// ── Paint ─────────────────────────────────────────────────────────────────────
+
+unsafe fn paint_image(hwnd: HWND, hdc: HDC, state: &ImageState) {
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    if client.right <= client.left || client.bottom <= client.top {
+        return;
+    }
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let source = state.style.as_ref().map(|s| &s.source);
+    let background = source
+        .and_then(|s| s.background_color.as_ref())
+        .unwrap_or(&state.background);
+    let (border_color, border_width, corner_radius) = source.map_or((None, 0, 0), |s| {
+        (
+            s.border_color.as_ref(),
+            s.border_width_for(InteractionState::Normal),
+            s.corner_radius.unwrap_or(0).max(0),
+        )
+    });
+    unsafe {
+        paint_styled_rect(
+            hdc,
+            &client,
+            Some(background),
+            border_color,
+            border_width,
+            corner_radius,
+            scale,
+        )
+    };
+
+    let Some(image_id) = state.image else {
+        return;
+    };
+    let Some((natural_width, natural_height)) = state
+        .registry
+        .read()
+        .ok()
+        .and_then(|registry| registry.natural_size(image_id))
+    else {
+        return;
+    };
+    let area = inset_by_padding(&client, source.and_then(|s| s.padding), scale);
+    let size = image::fit_within(
+        natural_width,
+        natural_height,
+        area.right - area.left,
+        area.bottom - area.top,
+    );
+    let target = centered_rect(&area, size);
+    unsafe { draw_registered_image(hdc, &state.registry, image_id, &target) };
+}
+
+// ── Command handlers ──────────────────────────────────────────────────────────
+
+/// Creates an image control as a child of `parent_control_id` (or main window if None).
+/// Follows the 4-phase read-kind-create-hwnd-write pattern from `status_bar_handler`.
+pub(crate) fn handle_create_image_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    parent_control_id: Option<ControlId>,
+    control_id: ControlId,
+    image_id: Option<ImageId>,
+) -> PlatformResult<()> {
+    log::debug!(
+        "[Image] handle_create_image_command WinID={window_id:?} ControlID={} ParentID={:?} image={image_id:?}",
+        control_id.raw(),
+        parent_control_id.map(|id| id.raw()),
+    );
+    let registry = internal_state.image_registry();
+    if let Some(image_id) = image_id {
+        registry
+            .read()
+            .map_err(|_| {
+                PlatformError::OperationFailed("RwLock poisoned on image registry".to_string())
+            })?
+            .require(image_id)?;
+    }
+
+    // Phase 1: Read-lock — duplicate check + get parent HWND.
+    let parent_hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "Image {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        let hwnd_parent = match parent_control_id {
+            Some(id) => window_data.get_control_hwnd(id).ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "[Image] Parent control {} not found in WinID {window_id:?}",
+                    id.raw()
+                ))
+            })?,
+            None => window_data.get_hwnd(),
+        };
+        if hwnd_parent.is_invalid() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[Image] Parent HWND invalid WinID={window_id:?}"
+            )));
+        }
+        Ok(hwnd_parent)
+    })?;
+
+    let h_instance = internal_state.h_instance();
+    register_image_class(h_instance);
+
+    // Phase 2: Write-lock — register the control kind.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "[Image] Race: Image {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.register_control_kind(control_id, ControlKind::Image);
+        Ok(())
+    })?;
+
+    // Phase 3: Create native HWND outside any lock.
+    let hwnd_image = unsafe {
+        match CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            IMAGE_CLASS_NAME,
+            &HSTRING::from(""),
+            WS_CHILD | WS_VISIBLE,
+            0,
+            0,
+            10,
+            10,
+            Some(parent_hwnd),
+            Some(HMENU(control_id.raw() as *mut _)),
+            Some(h_instance),
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(err) => {
+                let _ = internal_state.with_window_data_write(window_id, |window_data| {
+                    window_data.unregister_control_kind(control_id);
+                    Ok(())
+                });
+                return Err(err.into());
+            }
+        }
+    };
+
+    // Initialise GWLP_USERDATA with per-control state.
+    let state = Box::new(ImageState {
+        registry,
+        image: image_id,
+        background: internal_state
+            .current_theme()
+            .colors
+            .window_background
+            .clone(),
+        style: None,
+    });
+    unsafe {
+        SetWindowLongPtrW(hwnd_image, GWLP_USERDATA, Box::into_raw(state) as isize);
+    }
+
+    // Phase 4: Write-lock — store the HWND.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        window_data.register_control_hwnd(control_id, hwnd_image);
+        Ok(())
+    })?;
+
+    log::debug!(
+        "[Image] Created image control {} hwnd={hwnd_image:?}",
+        control_id.raw()
+    );
+    Ok(())
+}
+
+pub(crate) fn handle_set_image_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    image_id: Option<ImageId>,
+) -> PlatformResult<()> {
+    let hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.get_control_kind(control_id) != Some(ControlKind::Image) {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[Image] SetImage: control {} is not an image in window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.get_control_hwnd(control_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!(
+                "[Image] SetImage: control {} not found in window {window_id:?}",
+                control_id.raw()
+            ))
+        })
+    })?;
+    if let Some(image_id) = image_id
+        && !is_registered(&internal_state.image_registry(), image_id)
+    {
+        return Err(PlatformError::OperationFailed(format!(
+            "Image {image_id:?} has not been registered"
+        )));
+    }
+    unsafe {
+        let state = state_ptr(hwnd);
+        if state.is_null() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[Image] SetImage: control {} has no state",
+                control_id.raw()
+            )));
+        }
+        (*state).image = image_id;
+        let _ = InvalidateRect(Some(hwnd), None, false);
+    }
+    Ok(())
+}
+
+/// Replaces the background color and repaints; used when a theme is applied.
+pub(crate) fn apply_image_background(hwnd: HWND, background: Color) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).background = background;
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+/// Stores a style applied with `ApplyStyleToControl` and repaints.
+pub(crate) fn apply_control_style(hwnd: HWND, style: Arc<ParsedControlStyle>) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).style = Some(style);
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-ImageV1] The fitted image is centered in the control.
+    fn centered_rect_splits_the_free_space() {
+        // Arrange
+        let area = RECT {
+            left: 10,
+            top: 20,
+            right: 110,
+            bottom: 70,
+        };
+
+        // Act
+        let rect = centered_rect(&area, (50, 50));
+
+        // Assert
+        assert_eq!(
+            (rect.left, rect.top, rect.right, rect.bottom),
+            (35, 20, 85, 70)
+        );
+    }
+}
@@ -7,7 +7,7 @@
 
 use crate::PlatformResult;
 use crate::app::Win32ApiInternalState;
-use crate::styling::Color;
+use crate::styling::{Color, ParsedControlStyle};
 use crate::types::{ControlId, WindowId};
 use std::sync::Arc;
 use windows::Win32::{
@@ -46,28 +46,36 @@ pub(crate) fn handle_wm_ctlcoloredit(
 
     let result: PlatformResult<Option<LRESULT>> =
         internal_state.with_window_data_read(window_id, |window_data| {
-            if let Some(style_id) = window_data.get_style_for_control(control_id)
-                && let Some(style) = internal_state.get_parsed_style(style_id)
-            {
-                // Apply text color from the style, if defined.
-                if let Some(color) = &style.text_color {
-                    unsafe { SetTextColor(hdc_edit, color_to_colorref(color)) };
-                }
-                // Apply background color from the style, if defined.
-                if let Some(color) = &style.background_color {
-                    unsafe { SetBkColor(hdc_edit, color_to_colorref(color)) };
-                }
-                unsafe {
-                    SetBkMode(hdc_edit, OPAQUE);
-                }
-                // Return the brush handle for the system to use.
-                if let Some(brush) = style.background_brush {
-                    return Ok(Some(LRESULT(brush.0 as isize)));
-                }
-            }
-            // No style found or style had no brush, default processing.
-            Ok(None)
+            Ok(internal_state
+                .parsed_style_for_control(window_data, control_id)
+                .and_then(|style| apply_edit_colors(hdc_edit, &style)))
         });
 
     result.ok().flatten()
 }
+
+/*
+ * Sets an EDIT control's text and background colors from `style` and returns
+ * its background brush, or `None` for default processing when it has none.
+ * Numeric inputs reuse it to paint with `StyleId::DefaultInputError`.
+ */
+pub(crate) fn apply_edit_colors(
+    hdc_edit: windows::Win32::Graphics::Gdi::HDC,
+    style: &ParsedControlStyle,
+) -> Option<LRESULT> {
+    // Apply text color from the style, if defined.
+    if let Some(color) = &style.text_color {
+        unsafe { SetTextColor(hdc_edit, color_to_colorref(color)) };
+    }
+    // Apply background color from the style, if defined.
+    if let Some(color) = &style.background_color {
+        unsafe { SetBkColor(hdc_edit, color_to_colorref(color)) };
+    }
+    unsafe {
+        SetBkMode(hdc_edit, OPAQUE);
+    }
+    // Return the brush handle for the system to use.
+    style
+        .background_brush
+        .map(|brush| LRESULT(brush.0 as isize))
+}
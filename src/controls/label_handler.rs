@@ -265,9 +265,7 @@ pub(crate) fn handle_wm_ctlcolorstatic(
     let style_result: PlatformResult<Option<LRESULT>> =
         internal_state.with_window_data_read(window_id, |window_data| {
             // --- New Styling System Logic ---
-            if let Some(style_id) = window_data.get_style_for_control(control_id)
-                && let Some(style) = internal_state.get_parsed_style(style_id)
-            {
+            if let Some(style) = internal_state.parsed_style_for_control(window_data, control_id) {
                 // A style is defined for this control. Handle it completely and then return.
                 // Do not fall through to the legacy logic.
 
@@ -323,11 +321,7 @@ mod tests {
     #[test]
     // [CDU-Styling-CustomDrawV1] Converting colors to COLORREF preserves RGB ordering for custom draw paths.
     fn color_conversion_matches_bgr_layout() {
-        let color = Color {
-            r: 0x11,
-            g: 0x22,
-            b: 0x33,
-        };
+        let color = Color::rgb(0x11, 0x22, 0x33);
         let converted = color_to_colorref(&color);
         assert_eq!(converted.0, 0x0033_2211);
     }
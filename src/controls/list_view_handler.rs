@@ -0,0 +1,839 @@
+/*
+ * Encapsulates Win32-specific operations for ListView controls: a report-view
+ * SysListView32 in owner-data mode. Cell texts are never inserted into the
+ * native control; it asks for the visible ones through LVN_GETDISPINFOW, which
+ * is answered from the rows of `SetListViewRows` or, in virtual mode, from the
+ * application's `ListViewDataProvider`. Header clicks on sortable columns,
+ * selection changes and activation are reported as `AppEvent`s.
+ * [CDU-Control-ListViewV1]
+ */
+
+use crate::app::Win32ApiInternalState;
+use crate::controls::styling_handler;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::styling::StyleId;
+use crate::styling_windows::ParsedControlStyle;
+use crate::types::{AppEvent, ColumnAlignment, ControlId, ListViewColumn, SortDirection, WindowId};
+use crate::window_common::{self, ControlKind};
+
+use std::sync::Arc;
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    Graphics::Gdi::InvalidateRect,
+    UI::Controls::{
+        CDDS_ITEMPREPAINT, CDDS_PREPAINT, CDIS_FOCUS, CDIS_SELECTED, CDRF_DODEFAULT, CDRF_NEWFONT,
+        CDRF_NOTIFYITEMDRAW, HDF_SORTDOWN, HDF_SORTUP, HDI_FORMAT, HDITEMW,
+        LIST_VIEW_ITEM_STATE_FLAGS, LVCF_FMT, LVCF_TEXT, LVCF_WIDTH, LVCFMT_CENTER, LVCFMT_LEFT,
+        LVCFMT_RIGHT, LVCOLUMNW, LVCOLUMNW_FORMAT, LVIF_STATE, LVIF_TEXT, LVIS_FOCUSED,
+        LVIS_SELECTED, LVITEMW, NMITEMACTIVATE, NMLISTVIEW, NMLVCUSTOMDRAW, NMLVDISPINFOW,
+    },
+    UI::WindowsAndMessaging::{
+        CreateWindowExW, DestroyWindow, HMENU, SendMessageW, WINDOW_EX_STYLE, WINDOW_STYLE,
+        WS_BORDER, WS_CHILD, WS_TABSTOP, WS_VISIBLE,
+    },
+};
+use windows::core::{PCWSTR, PWSTR};
+
+const WC_LISTVIEW: PCWSTR = windows::core::w!("SysListView32");
+
+// ListView styles
+const LVS_REPORT: u32 = 0x0001;
+const LVS_SINGLESEL: u32 = 0x0004;
+const LVS_SHOWSELALWAYS: u32 = 0x0008;
+const LVS_OWNERDATA: u32 = 0x1000;
+const LVS_EX_FULLROWSELECT: isize = 0x0020;
+const LVS_EX_DOUBLEBUFFER: isize = 0x0001_0000;
+
+// ListView messages
+const LVM_FIRST: u32 = 0x1000;
+const LVM_SETBKCOLOR: u32 = LVM_FIRST + 1;
+const LVM_GETNEXTITEM: u32 = LVM_FIRST + 12;
+const LVM_ENSUREVISIBLE: u32 = LVM_FIRST + 19;
+const LVM_SETCOLUMNWIDTH: u32 = LVM_FIRST + 30;
+const LVM_GETHEADER: u32 = LVM_FIRST + 31;
+const LVM_SETTEXTCOLOR: u32 = LVM_FIRST + 36;
+const LVM_SETTEXTBKCOLOR: u32 = LVM_FIRST + 38;
+const LVM_SETITEMSTATE: u32 = LVM_FIRST + 43;
+const LVM_GETITEMSTATE: u32 = LVM_FIRST + 44;
+const LVM_SETITEMCOUNT: u32 = LVM_FIRST + 47;
+const LVM_SETEXTENDEDLISTVIEWSTYLE: u32 = LVM_FIRST + 54;
+const LVM_INSERTCOLUMNW: u32 = LVM_FIRST + 97;
+const LVNI_SELECTED: isize = 0x0002;
+const LVSICF_NOSCROLL: isize = 0x0002;
+// Header messages
+const HDM_FIRST: u32 = 0x1200;
+const HDM_GETITEMW: u32 = HDM_FIRST + 11;
+const HDM_SETITEMW: u32 = HDM_FIRST + 12;
+
+// ListView notifications (LVN_FIRST is -100)
+const LVN_FIRST: u32 = 0u32.wrapping_sub(100);
+pub(crate) const LVN_ITEMCHANGED: u32 = LVN_FIRST.wrapping_sub(1);
+pub(crate) const LVN_COLUMNCLICK: u32 = LVN_FIRST.wrapping_sub(8);
+pub(crate) const LVN_ITEMACTIVATE: u32 = LVN_FIRST.wrapping_sub(14);
+pub(crate) const LVN_GETDISPINFOW: u32 = LVN_FIRST.wrapping_sub(77);
+
+/*
+ * Per-control state kept in `NativeWindowData`. `rows` is `None` in virtual
+ * mode, where the cell texts come from the `ListViewDataProvider`.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ListViewState {
+    columns: Vec<ListViewColumn>,
+    rows: Option<Vec<Vec<String>>>,
+    sort: Option<(usize, SortDirection)>,
+    // The last selection reported to (or set by) the application, so programmatic
+    // selection and repeated LVN_ITEMCHANGED notifications raise no events.
+    reported_selection: Option<usize>,
+}
+
+impl ListViewState {
+    fn new(columns: Vec<ListViewColumn>) -> Self {
+        Self {
+            columns,
+            rows: Some(Vec::new()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn columns(&self) -> &[ListViewColumn] {
+        &self.columns
+    }
+
+    fn stored_cell(&self, row: usize, column: usize) -> Option<Option<String>> {
+        self.rows
+            .as_ref()
+            .map(|rows| rows.get(row).and_then(|cells| cells.get(column)).cloned())
+    }
+
+    // True if `row` differs from the last reported selection.
+    fn report_selection(&mut self, row: Option<usize>) -> bool {
+        if self.reported_selection == row {
+            return false;
+        }
+        self.reported_selection = row;
+        true
+    }
+}
+
+/// The direction a header click asks for: flipped on the sorted column, otherwise ascending.
+fn requested_direction(sort: Option<(usize, SortDirection)>, column: usize) -> SortDirection {
+    match sort {
+        Some((sorted, direction)) if sorted == column => direction.reversed(),
+        _ => SortDirection::Ascending,
+    }
+}
+
+fn column_format(alignment: ColumnAlignment) -> LVCOLUMNW_FORMAT {
+    match alignment {
+        ColumnAlignment::Left => LVCFMT_LEFT,
+        ColumnAlignment::Center => LVCFMT_CENTER,
+        ColumnAlignment::Right => LVCFMT_RIGHT,
+    }
+}
+
+// UTF-16 text cut to fit a buffer of `capacity` units, including the terminator.
+fn truncated_utf16(text: &str, capacity: usize) -> Vec<u16> {
+    let mut utf16: Vec<u16> = text
+        .encode_utf16()
+        .take(capacity.saturating_sub(1))
+        .collect();
+    utf16.push(0);
+    utf16
+}
+
+fn list_view_hwnd(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    operation: &str,
+) -> PlatformResult<HWND> {
+    internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.get_control_kind(control_id) != Some(ControlKind::ListView) {
+            log::warn!(
+                "ListViewHandler: Control ID {} is not a ListView for {operation} in WinID {window_id:?}",
+                control_id.raw()
+            );
+            return Err(PlatformError::InvalidHandle(format!(
+                "Control ID {} is not a ListView for {operation}",
+                control_id.raw()
+            )));
+        }
+        window_data.get_control_hwnd(control_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!(
+                "Control ID {} not found for {operation}",
+                control_id.raw()
+            ))
+        })
+    })
+}
+
+/*
+ * Creates a native report-view ListView with the given columns and registers
+ * it. Uses read/write/no-lock/write phase pattern for robustness.
+ */
+pub(crate) fn handle_create_list_view_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    parent_control_id: Option<ControlId>,
+    control_id: ControlId,
+    columns: Vec<ListViewColumn>,
+) -> PlatformResult<()> {
+    log::debug!(
+        "ListViewHandler: handle_create_list_view_command for WinID {window_id:?}, ParentID {:?}, ControlID {}, {} columns",
+        parent_control_id.as_ref().map(|id| id.raw()),
+        control_id.raw(),
+        columns.len()
+    );
+
+    // Phase 1: Read-only pre-checks and get parent HWND
+    let hwnd_parent_for_creation =
+        internal_state.with_window_data_read(window_id, |window_data| {
+            if window_data.has_control(control_id) {
+                log::warn!(
+                    "ListViewHandler: ListView with ID {} already exists for window {window_id:?}.",
+                    control_id.raw()
+                );
+                return Err(PlatformError::OperationFailed(format!(
+                    "ListView with ID {} already exists for window {window_id:?}",
+                    control_id.raw()
+                )));
+            }
+
+            let hwnd_parent = match parent_control_id {
+                Some(id) => window_data.get_control_hwnd(id).ok_or_else(|| {
+                    log::warn!(
+                        "ListViewHandler: Parent control with ID {} not found for CreateListView in WinID {window_id:?}",
+                        id.raw()
+                    );
+                    PlatformError::InvalidHandle(format!(
+                        "Parent control with ID {} not found for CreateListView in WinID {window_id:?}",
+                        id.raw()
+                    ))
+                })?,
+                None => window_data.get_hwnd(),
+            };
+
+            if hwnd_parent.is_invalid() {
+                return Err(PlatformError::InvalidHandle(format!(
+                    "ListViewHandler: Parent HWND for CreateListView is invalid (WinID: {window_id:?}, ParentControlID: {:?})",
+                    parent_control_id.as_ref().map(|id| id.raw())
+                )));
+            }
+            Ok(hwnd_parent)
+        })?;
+
+    // Register ControlKind before creation
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "ListView with ID {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.register_control_kind(control_id, ControlKind::ListView);
+        Ok(())
+    })?;
+
+    // Phase 2: Create the native control without holding any locks
+    let h_instance = internal_state.h_instance();
+    let hwnd_list = unsafe {
+        match CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            WC_LISTVIEW,
+            None,
+            WS_CHILD
+                | WS_VISIBLE
+                | WS_TABSTOP
+                | WS_BORDER
+                | WINDOW_STYLE(LVS_REPORT | LVS_SINGLESEL | LVS_SHOWSELALWAYS | LVS_OWNERDATA),
+            0,
+            0,
+            10,
+            10, // Dummy position/size, layout rules will adjust
+            Some(hwnd_parent_for_creation),
+            Some(HMENU(control_id.raw() as *mut _)),
+            Some(h_instance),
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(err) => {
+                let _ = internal_state.with_window_data_write(window_id, |window_data| {
+                    window_data.unregister_control_kind(control_id);
+                    Ok(())
+                });
+                return Err(err.into());
+            }
+        }
+    };
+
+    window_common::apply_native_color_mode(hwnd_list);
+    unsafe {
+        SendMessageW(
+            hwnd_list,
+            LVM_SETEXTENDEDLISTVIEWSTYLE,
+            Some(WPARAM(
+                (LVS_EX_FULLROWSELECT | LVS_EX_DOUBLEBUFFER) as usize,
+            )),
+            Some(LPARAM(LVS_EX_FULLROWSELECT | LVS_EX_DOUBLEBUFFER)),
+        );
+    }
+    insert_columns(hwnd_list, &columns);
+
+    // Phase 3: Register the new HWND
+    internal_state.with_window_data_write(window_id, |window_data| {
+        // Re-check for race condition
+        if window_data.has_control(control_id) {
+            log::warn!(
+                "ListViewHandler: Control ID {} was created concurrently for window {window_id:?}. Destroying new HWND.",
+                control_id.raw()
+            );
+            let _ = unsafe { DestroyWindow(hwnd_list) };
+            return Err(PlatformError::OperationFailed(format!(
+                "Control ID {} created concurrently",
+                control_id.raw()
+            )));
+        }
+
+        window_data.register_control_hwnd(control_id, hwnd_list);
+        window_data.insert_list_view_state(control_id, ListViewState::new(columns));
+        log::debug!(
+            "ListViewHandler: Registered ListView with ControlID {} and HWND {hwnd_list:?}",
+            control_id.raw()
+        );
+        Ok(())
+    })
+}
+
+fn insert_columns(hwnd_list: HWND, columns: &[ListViewColumn]) {
+    let scale = window_common::dpi_scale_for_window(hwnd_list);
+    for (index, column) in columns.iter().enumerate() {
+        let mut title: Vec<u16> = column
+            .title
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let lv_column = LVCOLUMNW {
+            mask: LVCF_FMT | LVCF_WIDTH | LVCF_TEXT,
+            fmt: column_format(column.alignment),
+            cx: scale.scale(column.width),
+            pszText: PWSTR(title.as_mut_ptr()),
+            ..Default::default()
+        };
+        let result = unsafe {
+            SendMessageW(
+                hwnd_list,
+                LVM_INSERTCOLUMNW,
+                Some(WPARAM(index)),
+                Some(LPARAM(&lv_column as *const _ as isize)),
+            )
+        };
+        if result.0 < 0 {
+            log::warn!(
+                "ListViewHandler: LVM_INSERTCOLUMNW failed for column {index} ('{}')",
+                column.title
+            );
+        }
+    }
+}
+
+/// Re-applies the DIP column widths at the control's current DPI.
+pub(crate) fn apply_column_widths(hwnd_list: HWND, columns: &[ListViewColumn]) {
+    let scale = window_common::dpi_scale_for_window(hwnd_list);
+    for (index, column) in columns.iter().enumerate() {
+        unsafe {
+            SendMessageW(
+                hwnd_list,
+                LVM_SETCOLUMNWIDTH,
+                Some(WPARAM(index)),
+                Some(LPARAM(scale.scale(column.width) as isize)),
+            );
+        }
+    }
+}
+
+/*
+ * Replaces the rows shown by the ListView. The texts stay in the platform
+ * state and are handed to the control on demand; only the row count is
+ * passed to the native control.
+ */
+pub(crate) fn handle_set_list_view_rows(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    rows: Vec<Vec<String>>,
+) -> PlatformResult<()> {
+    let hwnd_list = list_view_hwnd(internal_state, window_id, control_id, "SetListViewRows")?;
+    let row_count = rows.len();
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if let Some(state) = window_data.list_view_state_mut(control_id) {
+            state.rows = Some(rows);
+        }
+        Ok(())
+    })?;
+    set_row_count(hwnd_list, row_count);
+    Ok(())
+}
+
+/*
+ * Switches the ListView to virtual data with `row_count` rows. The texts of
+ * visible cells are requested from the `ListViewDataProvider` while painting.
+ */
+pub(crate) fn handle_set_list_view_row_count(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    row_count: usize,
+) -> PlatformResult<()> {
+    let hwnd_list = list_view_hwnd(internal_state, window_id, control_id, "SetListViewRowCount")?;
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if let Some(state) = window_data.list_view_state_mut(control_id) {
+            state.rows = None;
+        }
+        Ok(())
+    })?;
+    set_row_count(hwnd_list, row_count);
+    Ok(())
+}
+
+fn set_row_count(hwnd_list: HWND, row_count: usize) {
+    unsafe {
+        SendMessageW(
+            hwnd_list,
+            LVM_SETITEMCOUNT,
+            Some(WPARAM(row_count)),
+            Some(LPARAM(LVSICF_NOSCROLL)),
+        );
+        // Rows keep their indices, so cached texts of visible rows must be re-requested.
+        let _ = InvalidateRect(Some(hwnd_list), None, false);
+    }
+}
+
+/*
+ * Shows the sort arrow on one column header and clears it on all others. The
+ * column is remembered so the next click on it requests the opposite direction.
+ */
+pub(crate) fn handle_set_list_view_sort_indicator(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    column: Option<usize>,
+    direction: SortDirection,
+) -> PlatformResult<()> {
+    let hwnd_list = list_view_hwnd(
+        internal_state,
+        window_id,
+        control_id,
+        "SetListViewSortIndicator",
+    )?;
+    let column_count = internal_state.with_window_data_write(window_id, |window_data| {
+        let state = window_data.list_view_state_mut(control_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!(
+                "No ListView state for Control ID {}",
+                control_id.raw()
+            ))
+        })?;
+        if let Some(column) = column
+            && column >= state.columns.len()
+        {
+            return Err(PlatformError::OperationFailed(format!(
+                "ListView {} has no column at index {column}",
+                control_id.raw()
+            )));
+        }
+        state.sort = column.map(|column| (column, direction));
+        Ok(state.columns.len())
+    })?;
+
+    let hwnd_header =
+        HWND(unsafe { SendMessageW(hwnd_list, LVM_GETHEADER, None, None) }.0 as *mut _);
+    if hwnd_header.is_invalid() {
+        log::warn!(
+            "ListViewHandler: LVM_GETHEADER returned no header for ControlID {}",
+            control_id.raw()
+        );
+        return Ok(());
+    }
+    for index in 0..column_count {
+        let mut item = HDITEMW {
+            mask: HDI_FORMAT,
+            ..Default::default()
+        };
+        unsafe {
+            SendMessageW(
+                hwnd_header,
+                HDM_GETITEMW,
+                Some(WPARAM(index)),
+                Some(LPARAM(&mut item as *mut _ as isize)),
+            );
+        }
+        item.fmt.0 &= !(HDF_SORTUP.0 | HDF_SORTDOWN.0);
+        if column == Some(index) {
+            item.fmt.0 |= match direction {
+                SortDirection::Ascending => HDF_SORTUP.0,
+                SortDirection::Descending => HDF_SORTDOWN.0,
+            };
+        }
+        unsafe {
+            SendMessageW(
+                hwnd_header,
+                HDM_SETITEMW,
+                Some(WPARAM(index)),
+                Some(LPARAM(&item as *const _ as isize)),
+            );
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Selects a row (or clears the selection with `None`) and scrolls it into
+ * view. The selection is recorded as already reported, so the resulting
+ * LVN_ITEMCHANGED raises no `ListViewSelectionChanged`.
+ */
+pub(crate) fn handle_set_list_view_selection(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    row: Option<usize>,
+) -> PlatformResult<()> {
+    let hwnd_list = list_view_hwnd(
+        internal_state,
+        window_id,
+        control_id,
+        "SetListViewSelection",
+    )?;
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if let Some(state) = window_data.list_view_state_mut(control_id) {
+            state.reported_selection = row;
+        }
+        Ok(())
+    })?;
+
+    let clear = LVITEMW {
+        stateMask: LIST_VIEW_ITEM_STATE_FLAGS(LVIS_SELECTED.0 | LVIS_FOCUSED.0),
+        ..Default::default()
+    };
+    unsafe {
+        // An index of -1 applies the state to all rows.
+        SendMessageW(
+            hwnd_list,
+            LVM_SETITEMSTATE,
+            Some(WPARAM(usize::MAX)),
+            Some(LPARAM(&clear as *const _ as isize)),
+        );
+    }
+    if let Some(row) = row {
+        let select = LVITEMW {
+            stateMask: LIST_VIEW_ITEM_STATE_FLAGS(LVIS_SELECTED.0 | LVIS_FOCUSED.0),
+            state: LIST_VIEW_ITEM_STATE_FLAGS(LVIS_SELECTED.0 | LVIS_FOCUSED.0),
+            ..Default::default()
+        };
+        unsafe {
+            SendMessageW(
+                hwnd_list,
+                LVM_SETITEMSTATE,
+                Some(WPARAM(row)),
+                Some(LPARAM(&select as *const _ as isize)),
+            );
+            SendMessageW(
+                hwnd_list,
+                LVM_ENSUREVISIBLE,
+                Some(WPARAM(row)),
+                Some(LPARAM(0)),
+            );
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Answers LVN_GETDISPINFOW by copying the cell text into the buffer supplied
+ * by the control. Stored rows are read under the window lock; the data
+ * provider is asked after the lock is released.
+ */
+pub(crate) fn handle_lvn_getdispinfo(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    lparam: LPARAM,
+) {
+    let info = unsafe { &mut *(lparam.0 as *mut NMLVDISPINFOW) };
+    if (info.item.mask & LVIF_TEXT).0 == 0
+        || info.item.pszText.is_null()
+        || info.item.cchTextMax <= 0
+        || info.item.iItem < 0
+        || info.item.iSubItem < 0
+    {
+        return;
+    }
+    let (row, column) = (info.item.iItem as usize, info.item.iSubItem as usize);
+
+    let stored = internal_state
+        .with_window_data_read(window_id, |window_data| {
+            Ok(window_data
+                .list_view_state(control_id)
+                .and_then(|state| state.stored_cell(row, column)))
+        })
+        .unwrap_or(None);
+    let text = match stored {
+        Some(cell) => cell.unwrap_or_default(),
+        None => provider_cell_text(internal_state, window_id, control_id, row, column),
+    };
+
+    let utf16 = truncated_utf16(&text, info.item.cchTextMax as usize);
+    unsafe {
+        std::ptr::copy_nonoverlapping(utf16.as_ptr(), info.item.pszText.0, utf16.len());
+    }
+}
+
+fn provider_cell_text(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    row: usize,
+    column: usize,
+) -> String {
+    let provider_opt = internal_state
+        .list_view_data_provider()
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|weak_provider| weak_provider.upgrade());
+
+    if let Some(provider_arc) = provider_opt
+        && let Ok(provider_guard) = provider_arc.lock()
+    {
+        return provider_guard.cell_text(window_id, control_id, row, column);
+    }
+
+    String::new()
+}
+
+/*
+ * Handles LVN_COLUMNCLICK. Only sortable columns raise `ListViewSortRequested`;
+ * the ListView does not reorder anything itself.
+ */
+pub(crate) fn handle_lvn_columnclick(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    lparam: LPARAM,
+) -> Option<AppEvent> {
+    let nmlv = unsafe { &*(lparam.0 as *const NMLISTVIEW) };
+    let column = usize::try_from(nmlv.iSubItem).ok()?;
+    let direction = internal_state
+        .with_window_data_read(window_id, |window_data| {
+            Ok(window_data.list_view_state(control_id).and_then(|state| {
+                state
+                    .columns
+                    .get(column)
+                    .filter(|c| c.sortable)
+                    .map(|_| requested_direction(state.sort, column))
+            }))
+        })
+        .unwrap_or(None)?;
+
+    log::debug!(
+        "ListViewHandler: LVN_COLUMNCLICK for ControlID {}, column {column}, requesting {direction:?}",
+        control_id.raw()
+    );
+    Some(AppEvent::ListViewSortRequested {
+        window_id,
+        control_id,
+        column,
+        direction,
+    })
+}
+
+/*
+ * Handles LVN_ITEMCHANGED. The notification fires once per row whose state
+ * changes, so the current selection is queried and only reported when it
+ * differs from the last one.
+ */
+pub(crate) fn handle_lvn_itemchanged(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    lparam: LPARAM,
+) -> Option<AppEvent> {
+    let nmlv = unsafe { &*(lparam.0 as *const NMLISTVIEW) };
+    if (nmlv.uChanged & LVIF_STATE).0 == 0 {
+        return None;
+    }
+    let raw = unsafe {
+        SendMessageW(
+            nmlv.hdr.hwndFrom,
+            LVM_GETNEXTITEM,
+            Some(WPARAM(usize::MAX)),
+            Some(LPARAM(LVNI_SELECTED)),
+        )
+    }
+    .0;
+    let row = usize::try_from(raw).ok();
+
+    let changed = internal_state
+        .with_window_data_write(window_id, |window_data| {
+            Ok(window_data
+                .list_view_state_mut(control_id)
+                .is_some_and(|state| state.report_selection(row)))
+        })
+        .unwrap_or(false);
+    changed.then_some(AppEvent::ListViewSelectionChanged {
+        window_id,
+        control_id,
+        row,
+    })
+}
+
+/// Handles LVN_ITEMACTIVATE (double-click or Enter on a row).
+pub(crate) fn handle_lvn_itemactivate(
+    window_id: WindowId,
+    control_id: ControlId,
+    lparam: LPARAM,
+) -> Option<AppEvent> {
+    let activate = unsafe { &*(lparam.0 as *const NMITEMACTIVATE) };
+    let row = usize::try_from(activate.iItem).ok()?;
+    Some(AppEvent::ListViewRowActivated {
+        window_id,
+        control_id,
+        row,
+    })
+}
+
+/*
+ * Paints selected rows with the `ListViewSelectedRow` style when it is
+ * defined, suppressing the native highlight. Other rows use the colors set by
+ * `apply_control_style`.
+ */
+pub(crate) fn handle_nm_customdraw(
+    internal_state: &Arc<Win32ApiInternalState>,
+    lparam: LPARAM,
+) -> LRESULT {
+    let nmlvcd = unsafe { &mut *(lparam.0 as *mut NMLVCUSTOMDRAW) };
+    match nmlvcd.nmcd.dwDrawStage {
+        CDDS_PREPAINT => LRESULT(CDRF_NOTIFYITEMDRAW as isize),
+        CDDS_ITEMPREPAINT => {
+            let Some(selection_style) =
+                internal_state.get_parsed_style(StyleId::ListViewSelectedRow)
+            else {
+                return LRESULT(CDRF_DODEFAULT as isize);
+            };
+            // Owner-data ListViews do not report selection in uItemState reliably.
+            let state = unsafe {
+                SendMessageW(
+                    nmlvcd.nmcd.hdr.hwndFrom,
+                    LVM_GETITEMSTATE,
+                    Some(WPARAM(nmlvcd.nmcd.dwItemSpec)),
+                    Some(LPARAM(LVIS_SELECTED.0 as isize)),
+                )
+            };
+            if state.0 as u32 & LVIS_SELECTED.0 == 0 {
+                return LRESULT(CDRF_DODEFAULT as isize);
+            }
+            if let Some(color) = &selection_style.text_color {
+                nmlvcd.clrText = styling_handler::color_to_colorref(color);
+            }
+            if let Some(color) = &selection_style.background_color {
+                nmlvcd.clrTextBk = styling_handler::color_to_colorref(color);
+            }
+            nmlvcd.nmcd.uItemState.0 &= !(CDIS_SELECTED.0 | CDIS_FOCUS.0);
+            LRESULT(CDRF_NEWFONT as isize)
+        }
+        _ => LRESULT(CDRF_DODEFAULT as isize),
+    }
+}
+
+/// Sets the native background and text colors from a resolved style.
+pub(crate) fn apply_control_style(hwnd_list: HWND, style: &ParsedControlStyle) {
+    unsafe {
+        if let Some(color) = &style.background_color {
+            let color_ref = styling_handler::color_to_colorref(color);
+            SendMessageW(
+                hwnd_list,
+                LVM_SETBKCOLOR,
+                None,
+                Some(LPARAM(color_ref.0 as isize)),
+            );
+            SendMessageW(
+                hwnd_list,
+                LVM_SETTEXTBKCOLOR,
+                None,
+                Some(LPARAM(color_ref.0 as isize)),
+            );
+        }
+        if let Some(color) = &style.text_color {
+            let color_ref = styling_handler::color_to_colorref(color);
+            SendMessageW(
+                hwnd_list,
+                LVM_SETTEXTCOLOR,
+                None,
+                Some(LPARAM(color_ref.0 as isize)),
+            );
+        }
+        let _ = InvalidateRect(Some(hwnd_list), None, true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-ListViewV1] Clicking the sorted column flips the direction; other columns start ascending.
+    fn header_click_flips_direction_only_on_sorted_column() {
+        // Arrange
+        let sort = Some((1, SortDirection::Ascending));
+
+        // Act
+        let same = requested_direction(sort, 1);
+        let other = requested_direction(sort, 0);
+        let unsorted = requested_direction(None, 1);
+
+        // Assert
+        assert_eq!(same, SortDirection::Descending);
+        assert_eq!(other, SortDirection::Ascending);
+        assert_eq!(unsorted, SortDirection::Ascending);
+    }
+
+    #[test]
+    fn truncated_utf16_fits_buffer_with_terminator() {
+        // Act
+        let fits = truncated_utf16("abc", 8);
+        let cut = truncated_utf16("abcdef", 4);
+
+        // Assert
+        assert_eq!(fits, vec![97, 98, 99, 0]);
+        assert_eq!(cut, vec![97, 98, 99, 0]);
+    }
+
+    #[test]
+    fn selection_is_reported_only_when_it_changes() {
+        // Arrange
+        let mut state = ListViewState::new(vec![ListViewColumn::new("Name")]);
+
+        // Act
+        let first = state.report_selection(Some(2));
+        let repeated = state.report_selection(Some(2));
+        let cleared = state.report_selection(None);
+
+        // Assert
+        assert!(first);
+        assert!(!repeated);
+        assert!(cleared);
+    }
+
+    #[test]
+    fn stored_cells_are_none_in_virtual_mode() {
+        // Arrange
+        let mut state = ListViewState::new(vec![ListViewColumn::new("Name")]);
+        state.rows = Some(vec![vec!["a".into()]]);
+        let stored = state.stored_cell(0, 0);
+
+        // Act
+        state.rows = None;
+
+        // Assert
+        assert_eq!(stored, Some(Some("a".to_string())));
+        assert_eq!(state.stored_cell(0, 0), None);
+    }
+}
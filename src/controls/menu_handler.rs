@@ -10,8 +10,8 @@
 use crate::{
     app::Win32ApiInternalState,
     error::{PlatformError, Result as PlatformResult},
-    types::{AppEvent, MenuItemConfig, WindowId},
-    window_common::{NativeWindowData, try_enable_dark_mode},
+    types::{AppEvent, ControlId, MenuItemConfig, WindowId},
+    window_common::{NativeWindowData, apply_native_color_mode},
 };
 
 use std::sync::Arc;
@@ -21,7 +21,8 @@ use windows::{
         Foundation::{GetLastError, HWND},
         UI::WindowsAndMessaging::{
             AppendMenuW, CreateMenu, CreatePopupMenu, DestroyMenu, DrawMenuBar, HMENU, MF_POPUP,
-            MF_STRING, SetMenu,
+            MF_STRING, SetForegroundWindow, SetMenu, TPM_NONOTIFY, TPM_RETURNCMD, TPM_RIGHTBUTTON,
+            TrackPopupMenuEx,
         },
     },
     core::HSTRING,
@@ -72,7 +73,7 @@ pub(crate) fn handle_create_main_menu_command(
             "SetMenu failed for main menu on WindowId {window_id:?}: {last_error:?}"
         )));
     }
-    try_enable_dark_mode(hwnd_owner);
+    apply_native_color_mode(hwnd_owner);
     unsafe {
         let _ = DrawMenuBar(hwnd_owner);
     }
@@ -81,6 +82,86 @@ pub(crate) fn handle_create_main_menu_command(
     Ok(())
 }
 
+/*
+ * Handles the `ShowContextMenu` command by building a popup menu and tracking
+ * it at the given screen point until the user chooses or dismisses it.
+ *
+ * The items register their actions like main menu items, so a choice is
+ * translated by `handle_wm_command_for_menu` into `MenuActionClicked`. The
+ * actions are forgotten again once the menu closes. [CDU-ContextMenusV1]
+ */
+pub(crate) fn handle_show_context_menu_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    menu_items: Vec<MenuItemConfig>,
+    screen_x: i32,
+    screen_y: i32,
+) -> PlatformResult<()> {
+    log::debug!("MenuHandler: showing context menu for WinID {window_id:?}");
+
+    let h_popup_menu = unsafe { CreatePopupMenu()? };
+
+    let built = internal_state.with_window_data_write(window_id, |window_data| {
+        let hwnd = window_data.get_hwnd();
+        if hwnd.is_invalid() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "HWND not valid for WindowId {window_id:?} when showing a context menu"
+            )));
+        }
+        if window_data.get_control_hwnd(control_id).is_none() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "ShowContextMenu: control {} not found in window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        let first_menu_id = window_data.get_next_menu_item_id_counter();
+        let appended = menu_items.iter().try_for_each(|item_config| unsafe {
+            add_menu_item_recursive_impl(h_popup_menu, item_config, window_data)
+        });
+        let menu_ids = first_menu_id..window_data.get_next_menu_item_id_counter();
+        if let Err(e) = appended {
+            window_data.unregister_menu_actions(menu_ids);
+            return Err(e);
+        }
+        Ok((hwnd, menu_ids))
+    });
+    let (hwnd_owner, menu_ids) = match built {
+        Ok(built) => built,
+        Err(e) => {
+            unsafe { DestroyMenu(h_popup_menu).unwrap_or_default() };
+            return Err(e);
+        }
+    };
+
+    // The menu only closes on a click elsewhere while its owner is in the foreground.
+    let _ = unsafe { SetForegroundWindow(hwnd_owner) };
+    let command_id = unsafe {
+        TrackPopupMenuEx(
+            h_popup_menu,
+            (TPM_RIGHTBUTTON | TPM_RETURNCMD | TPM_NONOTIFY).0,
+            screen_x,
+            screen_y,
+            hwnd_owner,
+            None,
+        )
+    };
+    unsafe { DestroyMenu(h_popup_menu).unwrap_or_default() };
+
+    // Zero means the menu was dismissed without a choice.
+    let event = (command_id.0 != 0)
+        .then(|| handle_wm_command_for_menu(window_id, command_id.0, hwnd_owner, internal_state))
+        .flatten();
+    internal_state.with_window_data_write(window_id, |window_data| {
+        window_data.unregister_menu_actions(menu_ids);
+        Ok(())
+    })?;
+    if let Some(event) = event {
+        internal_state.send_event(event);
+    }
+    Ok(())
+}
+
 /*
  * Internal helper for recursively adding menu items to a parent menu.
  *
@@ -238,6 +319,30 @@ mod tests {
         assert!(actions.contains(&REFRESH_FILE_LIST_ID));
     }
 
+    #[test]
+    // [CDU-ContextMenusV1] Actions of a closed context menu no longer map to commands.
+    fn test_unregister_menu_actions_forgets_only_the_given_ids() {
+        // Arrange
+        let (_state, _window_id, mut native_data) = setup_test_env();
+        let main_id = native_data.register_menu_action(LOAD_PROFILE_ID);
+        let first_context_id = native_data.get_next_menu_item_id_counter();
+        native_data.register_menu_action(SAVE_PROFILE_AS_ID);
+        native_data.register_menu_action(REFRESH_FILE_LIST_ID);
+        let context_ids = first_context_id..native_data.get_next_menu_item_id_counter();
+
+        // Act
+        native_data.unregister_menu_actions(context_ids.clone());
+
+        // Assert
+        assert_eq!(native_data.menu_action_count(), 1);
+        assert_eq!(native_data.get_menu_action(main_id), Some(LOAD_PROFILE_ID));
+        assert!(
+            context_ids
+                .into_iter()
+                .all(|id| native_data.get_menu_action(id).is_none())
+        );
+    }
+
     #[test]
     // [CDU-CmdEventPatternV1] Menu WM_COMMAND notifications are converted back into semantic `AppEvent::MenuActionClicked` values.
     fn test_handle_wm_command_for_menu_returns_event() {
@@ -0,0 +1,560 @@
+/*
+ * Numeric input control for CommanDuctUI: a right-aligned EDIT next to an
+ * up-down (spin) control, both children of a small container window that is
+ * the control's registered HWND. The container keeps the parsed value, steps
+ * it from the spin buttons, the Up/Down keys and the mouse wheel, and sends
+ * WM_APP_NUMERIC_VALUE_CHANGED to the root window whenever typed or stepped
+ * text parses to a new value. Text that does not parse marks the control
+ * invalid; the root then paints the EDIT with `StyleId::DefaultInputError`.
+ * Parsing and formatting live in the portable `crate::numeric` module.
+ *
+ * Per-instance state is stored in the container's GWLP_USERDATA as a
+ * heap-allocated `NumericInputState`, matching `slider_handler`.
+ */
+
+use crate::app::Win32ApiInternalState;
+use crate::controls::input_handler;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::measure;
+use crate::numeric::NumericSpec;
+use crate::styling_primitives::StyleId;
+use crate::types::{ControlId, WindowId};
+use crate::window_common::{
+    self, ControlKind, WM_APP_NUMERIC_VALUE_CHANGED, apply_native_color_mode,
+    read_edit_control_text,
+};
+
+use std::sync::{Arc, OnceLock};
+
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    Graphics::Gdi::{HDC, InvalidateRect},
+    UI::{
+        Controls::{NMHDR, NMUPDOWN, UDM_SETPOS32, UDM_SETRANGE32, UDN_DELTAPOS, UPDOWN_CLASSW},
+        Input::KeyboardAndMouse::{EnableWindow, SetFocus, VK_DOWN, VK_UP},
+        Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+        WindowsAndMessaging::{
+            CreateWindowExW, DefWindowProcW, EN_CHANGE, EN_KILLFOCUS, ES_AUTOHSCROLL, ES_RIGHT,
+            GET_ANCESTOR_FLAGS, GWLP_USERDATA, GetAncestor, GetParent, GetWindowLongPtrW, HMENU,
+            MoveWindow, RegisterClassW, SendMessageW, SetWindowLongPtrW, SetWindowTextW,
+            WINDOW_EX_STYLE, WINDOW_STYLE, WM_COMMAND, WM_CTLCOLOREDIT, WM_CTLCOLORSTATIC,
+            WM_DESTROY, WM_ENABLE, WM_ERASEBKGND, WM_GETFONT, WM_KEYDOWN, WM_MOUSEWHEEL,
+            WM_NCDESTROY, WM_NOTIFY, WM_SETFOCUS, WM_SETFONT, WM_SIZE, WNDCLASSW, WS_BORDER,
+            WS_CHILD, WS_CLIPCHILDREN, WS_EX_CONTROLPARENT, WS_TABSTOP, WS_VISIBLE,
+        },
+    },
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+// Identifies the keyboard/wheel subclass installed on the inner EDIT.
+const EDIT_SUBCLASS_ID: usize = 0x4E55;
+// One notch of a standard mouse wheel.
+const WHEEL_DELTA: i32 = 120;
+// The spin control keeps its own position in the middle of this range so it
+// can always report a step in both directions; see UDN_DELTAPOS below.
+const SPIN_RANGE: isize = 100;
+
+// ── NumericInputState ─────────────────────────────────────────────────────────
+
+/// Per-instance heap-allocated state stored in GWLP_USERDATA of the container.
+struct NumericInputState {
+    spec: NumericSpec,
+    /// The last valid value, reported to the application when it changed.
+    value: f64,
+    /// Whether the EDIT currently holds text that does not parse.
+    invalid: bool,
+    hwnd_edit: HWND,
+    hwnd_spin: HWND,
+    /// Set while the text is replaced by a command, which raises no event.
+    updating: bool,
+}
+
+// ── Window class ──────────────────────────────────────────────────────────────
+
+const NUMERIC_INPUT_CLASS_NAME: PCWSTR = w!("CommanductUI_NumericInput");
+static NUMERIC_INPUT_CLASS_REGISTERED: OnceLock<()> = OnceLock::new();
+
+fn register_numeric_input_class(h_instance: windows::Win32::Foundation::HINSTANCE) {
+    NUMERIC_INPUT_CLASS_REGISTERED.get_or_init(|| unsafe {
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(numeric_input_wnd_proc),
+            hInstance: h_instance,
+            hbrBackground: windows::Win32::Graphics::Gdi::HBRUSH(std::ptr::null_mut()),
+            lpszClassName: NUMERIC_INPUT_CLASS_NAME,
+            ..Default::default()
+        };
+        let _ = RegisterClassW(&wc);
+    });
+}
+
+unsafe fn state_ptr(hwnd: HWND) -> *mut NumericInputState {
+    unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut NumericInputState }
+}
+
+// ── Value handling ────────────────────────────────────────────────────────────
+
+/*
+ * Re-parses the EDIT text after a change. Repaints the EDIT when it turns
+ * valid or invalid, and tells the root window when a valid text holds a new
+ * value. Text set by `handle_set_numeric_value_command` is skipped.
+ */
+unsafe fn on_text_changed(hwnd: HWND, state: *mut NumericInputState) {
+    unsafe {
+        if (*state).updating {
+            return;
+        }
+        let text = read_edit_control_text((*state).hwnd_edit).unwrap_or_default();
+        let parsed = (*state).spec.parse(&text);
+        if parsed.is_none() != (*state).invalid {
+            (*state).invalid = parsed.is_none();
+            let _ = InvalidateRect(Some((*state).hwnd_edit), None, true);
+        }
+        if let Some(value) = parsed
+            && value != (*state).value
+        {
+            (*state).value = value;
+            let root = GetAncestor(hwnd, GET_ANCESTOR_FLAGS(2)); // GA_ROOT
+            if !root.is_invalid() {
+                let _ = SendMessageW(
+                    root,
+                    WM_APP_NUMERIC_VALUE_CHANGED,
+                    Some(WPARAM(hwnd.0 as usize)),
+                    Some(LPARAM(0)),
+                );
+            }
+        }
+    }
+}
+
+/*
+ * Steps from the typed value (or the last valid one while the text is
+ * invalid) and shows the result. Setting the text raises EN_CHANGE, which
+ * reports the new value through `on_text_changed`.
+ */
+unsafe fn step(state: *mut NumericInputState, steps: i32) {
+    unsafe {
+        let text = read_edit_control_text((*state).hwnd_edit).unwrap_or_default();
+        let base = (*state).spec.parse(&text).unwrap_or((*state).value);
+        let target = (*state).spec.step_by(base, steps);
+        let formatted = (*state).spec.format(target);
+        let _ = SetWindowTextW((*state).hwnd_edit, &HSTRING::from(formatted.as_str()));
+    }
+}
+
+// Replaces the text without raising an event.
+unsafe fn show_value(state: *mut NumericInputState, value: f64) {
+    unsafe {
+        let formatted = (*state).spec.format(value);
+        (*state).updating = true;
+        let _ = SetWindowTextW((*state).hwnd_edit, &HSTRING::from(formatted.as_str()));
+        (*state).updating = false;
+        (*state).value = (*state).spec.clamp(value);
+        if (*state).invalid {
+            (*state).invalid = false;
+            let _ = InvalidateRect(Some((*state).hwnd_edit), None, true);
+        }
+    }
+}
+
+// ── WndProcs ──────────────────────────────────────────────────────────────────
+
+unsafe extern "system" fn numeric_input_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) };
+    }
+    match msg {
+        WM_ERASEBKGND => {
+            // The EDIT and the spin control cover the whole client area.
+            LRESULT(1)
+        }
+        WM_SIZE => {
+            let width = (lparam.0 & 0xFFFF) as i32;
+            let height = ((lparam.0 >> 16) & 0xFFFF) as i32;
+            let spin_w = window_common::dpi_scale_for_window(hwnd)
+                .scale(measure::SPIN_BUTTONS_W)
+                .min(width);
+            unsafe {
+                let _ = MoveWindow((*state).hwnd_edit, 0, 0, width - spin_w, height, true);
+                let _ = MoveWindow((*state).hwnd_spin, width - spin_w, 0, spin_w, height, true);
+            }
+            LRESULT(0)
+        }
+        WM_SETFONT | WM_GETFONT => unsafe {
+            SendMessageW((*state).hwnd_edit, msg, Some(wparam), Some(lparam))
+        },
+        WM_SETFOCUS => {
+            let _ = unsafe { SetFocus(Some((*state).hwnd_edit)) };
+            LRESULT(0)
+        }
+        WM_ENABLE => {
+            let enabled = wparam.0 != 0;
+            unsafe {
+                let _ = EnableWindow((*state).hwnd_edit, enabled);
+                let _ = EnableWindow((*state).hwnd_spin, enabled);
+            }
+            LRESULT(0)
+        }
+        WM_COMMAND => {
+            // Handled here rather than forwarded, so the root does not start
+            // the debounce timer it uses for plain inputs.
+            let notification = ((wparam.0 >> 16) & 0xFFFF) as u32;
+            if notification == EN_CHANGE {
+                unsafe { on_text_changed(hwnd, state) };
+            } else if notification == EN_KILLFOCUS {
+                // Normalize valid text to the spec's format ("2.5ms" -> "2.50 ms").
+                let text =
+                    unsafe { read_edit_control_text((*state).hwnd_edit) }.unwrap_or_default();
+                if let Some(value) = unsafe { (*state).spec.parse(&text) } {
+                    unsafe { show_value(state, value) };
+                }
+            }
+            LRESULT(0)
+        }
+        WM_NOTIFY => {
+            let header = unsafe { &*(lparam.0 as *const NMHDR) };
+            if header.code == UDN_DELTAPOS {
+                let delta = unsafe { (*(lparam.0 as *const NMUPDOWN)).iDelta };
+                unsafe { step(state, delta.signum()) };
+                // Returning nonzero keeps the spin control's own position unchanged.
+                return LRESULT(1);
+            }
+            LRESULT(0)
+        }
+        WM_CTLCOLOREDIT | WM_CTLCOLORSTATIC => {
+            // Let the window procedure that styles plain inputs color the EDIT.
+            unsafe {
+                match GetParent(hwnd) {
+                    Ok(parent) if !parent.is_invalid() => {
+                        SendMessageW(parent, msg, Some(wparam), Some(lparam))
+                    }
+                    _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+                }
+            }
+        }
+        WM_DESTROY => {
+            let _ = unsafe { Box::from_raw(state) };
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0) };
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+/*
+ * Subclass of the inner EDIT: the Up and Down keys and the mouse wheel step
+ * the value. `ref_data` is the container HWND.
+ */
+unsafe extern "system" fn edit_subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _subclass_id: usize,
+    ref_data: usize,
+) -> LRESULT {
+    let container = HWND(ref_data as *mut std::ffi::c_void);
+    let state = unsafe { state_ptr(container) };
+    match msg {
+        WM_KEYDOWN if !state.is_null() => {
+            let vk = wparam.0 as u16;
+            if vk == VK_UP.0 || vk == VK_DOWN.0 {
+                unsafe { step(state, if vk == VK_UP.0 { 1 } else { -1 }) };
+                return LRESULT(0);
+            }
+        }
+        WM_MOUSEWHEEL if !state.is_null() => {
+            let delta = ((wparam.0 >> 16) & 0xFFFF) as u16 as i16 as i32;
+            let steps = delta / WHEEL_DELTA;
+            if steps != 0 {
+                unsafe { step(state, steps) };
+            }
+            return LRESULT(0);
+        }
+        WM_NCDESTROY => {
+            let _ =
+                unsafe { RemoveWindowSubclass(hwnd, Some(edit_subclass_proc), EDIT_SUBCLASS_ID) };
+        }
+        _ => {}
+    }
+    unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+}
+
+// ── Queries used by window_common ─────────────────────────────────────────────
+
+/// The last valid value of the numeric input whose container is `hwnd`.
+pub(crate) fn current_value(hwnd: HWND) -> Option<f64> {
+    let state = unsafe { state_ptr(hwnd) };
+    (!state.is_null()).then(|| unsafe { (*state).value })
+}
+
+/// The text currently shown in the EDIT, for measuring the control.
+pub(crate) fn displayed_text(hwnd: HWND) -> String {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return String::new();
+    }
+    read_edit_control_text(unsafe { (*state).hwnd_edit }).unwrap_or_default()
+}
+
+/*
+ * Handles WM_CTLCOLOREDIT/WM_CTLCOLORSTATIC for the inner EDIT. While the
+ * text is invalid it paints with `StyleId::DefaultInputError` when that style
+ * is defined; otherwise it uses the control's own style like a plain input.
+ */
+pub(crate) fn handle_wm_ctlcoloredit(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    hdc_edit: HDC,
+    hwnd_edit: HWND,
+) -> Option<LRESULT> {
+    let invalid = unsafe { GetParent(hwnd_edit) }
+        .ok()
+        .map(|container| unsafe { state_ptr(container) })
+        .is_some_and(|state| !state.is_null() && unsafe { (*state).invalid });
+    if invalid && let Some(style) = internal_state.get_parsed_style(StyleId::DefaultInputError) {
+        return input_handler::apply_edit_colors(hdc_edit, &style);
+    }
+    input_handler::handle_wm_ctlcoloredit(internal_state, window_id, hdc_edit, hwnd_edit)
+}
+
+// ── Command handlers ──────────────────────────────────────────────────────────
+
+/// Creates a NumericInput control as a child of `parent_control_id` (or main window if None).
+/// Follows the 4-phase read-kind-create-hwnd-write pattern from `slider_handler`.
+pub(crate) fn handle_create_numeric_input_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    parent_control_id: Option<ControlId>,
+    control_id: ControlId,
+    spec: NumericSpec,
+    value: f64,
+) -> PlatformResult<()> {
+    log::debug!(
+        "[NumericInput] handle_create_numeric_input_command WinID={window_id:?} ControlID={} ParentID={:?}",
+        control_id.raw(),
+        parent_control_id.map(|id| id.raw()),
+    );
+    spec.validate()?;
+
+    // Phase 1: Read-lock — duplicate check + get parent HWND.
+    let parent_hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "NumericInput {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        let hwnd_parent = match parent_control_id {
+            Some(id) => window_data.get_control_hwnd(id).ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "[NumericInput] Parent control {} not found in WinID {window_id:?}",
+                    id.raw()
+                ))
+            })?,
+            None => window_data.get_hwnd(),
+        };
+        if hwnd_parent.is_invalid() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[NumericInput] Parent HWND invalid WinID={window_id:?}"
+            )));
+        }
+        Ok(hwnd_parent)
+    })?;
+
+    let h_instance = internal_state.h_instance();
+    register_numeric_input_class(h_instance);
+
+    // Phase 2: Write-lock — register the control kind.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "[NumericInput] Race: NumericInput {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.register_control_kind(control_id, ControlKind::NumericInput);
+        Ok(())
+    })?;
+
+    // Phase 3: Create the container and its children outside any lock. The
+    // EDIT shares the control ID so WM_CTLCOLOREDIT resolves to this control.
+    let unregister = || {
+        let _ = internal_state.with_window_data_write(window_id, |window_data| {
+            window_data.unregister_control_kind(control_id);
+            Ok(())
+        });
+    };
+    let hwnd_container = unsafe {
+        match CreateWindowExW(
+            WS_EX_CONTROLPARENT,
+            NUMERIC_INPUT_CLASS_NAME,
+            &HSTRING::from(""),
+            WS_CHILD | WS_VISIBLE | WS_CLIPCHILDREN,
+            0,
+            0,
+            10,
+            10,
+            Some(parent_hwnd),
+            Some(HMENU(control_id.raw() as *mut _)),
+            Some(h_instance),
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(err) => {
+                unregister();
+                return Err(err.into());
+            }
+        }
+    };
+    let children = unsafe {
+        CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            windows::Win32::UI::Controls::WC_EDITW,
+            &HSTRING::from(spec.format(value).as_str()),
+            WS_CHILD
+                | WS_VISIBLE
+                | WS_TABSTOP
+                | WS_BORDER
+                | WINDOW_STYLE(ES_AUTOHSCROLL as u32)
+                | WINDOW_STYLE(ES_RIGHT as u32),
+            0,
+            0,
+            10,
+            10,
+            Some(hwnd_container),
+            Some(HMENU(control_id.raw() as *mut _)),
+            Some(h_instance),
+            None,
+        )
+        .and_then(|hwnd_edit| {
+            CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                UPDOWN_CLASSW,
+                &HSTRING::from(""),
+                WS_CHILD | WS_VISIBLE,
+                0,
+                0,
+                10,
+                10,
+                Some(hwnd_container),
+                None,
+                Some(h_instance),
+                None,
+            )
+            .map(|hwnd_spin| (hwnd_edit, hwnd_spin))
+        })
+    };
+    let (hwnd_edit, hwnd_spin) = match children {
+        Ok(children) => children,
+        Err(err) => {
+            unsafe {
+                let _ = windows::Win32::UI::WindowsAndMessaging::DestroyWindow(hwnd_container);
+            }
+            unregister();
+            return Err(err.into());
+        }
+    };
+
+    unsafe {
+        SendMessageW(
+            hwnd_spin,
+            UDM_SETRANGE32,
+            Some(WPARAM(-SPIN_RANGE as usize)),
+            Some(LPARAM(SPIN_RANGE)),
+        );
+        SendMessageW(hwnd_spin, UDM_SETPOS32, None, Some(LPARAM(0)));
+    }
+
+    // Initialise GWLP_USERDATA with per-control state, then hook the EDIT.
+    let state = Box::new(NumericInputState {
+        value: spec.clamp(value),
+        spec,
+        invalid: false,
+        hwnd_edit,
+        hwnd_spin,
+        updating: false,
+    });
+    unsafe {
+        SetWindowLongPtrW(hwnd_container, GWLP_USERDATA, Box::into_raw(state) as isize);
+        let _ = SetWindowSubclass(
+            hwnd_edit,
+            Some(edit_subclass_proc),
+            EDIT_SUBCLASS_ID,
+            hwnd_container.0 as usize,
+        );
+    }
+    if internal_state
+        .get_parsed_style(StyleId::MainWindowBackground)
+        .is_some()
+    {
+        apply_native_color_mode_to_children(hwnd_container);
+    }
+
+    // Phase 4: Write-lock — store the HWND.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        window_data.register_control_hwnd(control_id, hwnd_container);
+        Ok(())
+    })?;
+
+    log::debug!(
+        "[NumericInput] Created numeric input {} hwnd={hwnd_container:?}",
+        control_id.raw()
+    );
+    Ok(())
+}
+
+/// Shows the clamped `value` and clears any error state without notifying the application.
+pub(crate) fn handle_set_numeric_value_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    value: f64,
+) -> PlatformResult<()> {
+    let hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.get_control_kind(control_id) != Some(ControlKind::NumericInput) {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[NumericInput] SetNumericValue: control {} is not a numeric input in window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.get_control_hwnd(control_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!(
+                "[NumericInput] SetNumericValue: control {} not found in window {window_id:?}",
+                control_id.raw()
+            ))
+        })
+    })?;
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            show_value(state, value);
+        }
+    }
+    Ok(())
+}
+
+/// Applies the window's native color mode to the EDIT and spin control.
+pub(crate) fn apply_native_color_mode_to_children(hwnd: HWND) {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return;
+    }
+    unsafe {
+        apply_native_color_mode((*state).hwnd_edit);
+        apply_native_color_mode((*state).hwnd_spin);
+    }
+}
+
+/// Repaints the EDIT after its style changed; the container itself draws nothing.
+pub(crate) fn refresh(hwnd: HWND) {
+    let state = unsafe { state_ptr(hwnd) };
+    if !state.is_null() {
+        let _ = unsafe { InvalidateRect(Some((*state).hwnd_edit), None, true) };
+    }
+}
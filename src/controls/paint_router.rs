@@ -10,6 +10,8 @@ pub(crate) enum PaintRoute {
     Edit,
     ComboListBox,
     Button,
+    /// The EDIT inside a numeric input; paints the error style while invalid.
+    NumericInput,
     Default,
 }
 
@@ -44,6 +46,9 @@ pub(crate) fn resolve_paint_route(kind: ControlKind, msg: u32) -> PaintRoute {
             debug!("[Paint] ControlKind::CheckBox routed WM_CTLCOLORSTATIC to button styling");
             PaintRoute::Button
         }
+        (ControlKind::NumericInput, WM_CTLCOLOREDIT | WM_CTLCOLORSTATIC) => {
+            PaintRoute::NumericInput
+        }
         (ControlKind::Static, WM_CTLCOLORSTATIC) => PaintRoute::LabelStatic,
         (ControlKind::Static, WM_CTLCOLOREDIT) => {
             warn!("[Paint] ControlKind::Static received WM_CTLCOLOREDIT; using default route");
@@ -81,6 +86,20 @@ mod tests {
         );
     }
 
+    #[test]
+    // [CDU-Control-NumericInputV1] The inner EDIT of a numeric input gets its own paint route.
+    fn numeric_input_routes_edit_and_static_messages_to_numeric_input() {
+        assert_eq!(
+            resolve_paint_route(ControlKind::NumericInput, WM_CTLCOLOREDIT),
+            PaintRoute::NumericInput
+        );
+        assert_eq!(
+            resolve_paint_route(ControlKind::NumericInput, WM_CTLCOLORSTATIC),
+            PaintRoute::NumericInput,
+            "disabled EDIT controls paint through WM_CTLCOLORSTATIC"
+        );
+    }
+
     #[test]
     fn combobox_routes_listbox_message_to_combo_listbox() {
         assert_eq!(
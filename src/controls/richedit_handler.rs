@@ -2,7 +2,7 @@ use crate::app::Win32ApiInternalState;
 use crate::controls::styling_handler::color_to_colorref;
 use crate::error::{PlatformError, Result as PlatformResult};
 use crate::types::{ControlId, WindowId};
-use crate::window_common::{ControlKind, try_enable_dark_mode};
+use crate::window_common::{ControlKind, apply_native_color_mode};
 
 use std::sync::Arc;
 use windows::Win32::Foundation::{COLORREF, LPARAM, WPARAM};
@@ -90,7 +90,7 @@ pub(crate) fn handle_create_rich_edit_command(
         .get_parsed_style(crate::styling::StyleId::MainWindowBackground)
         .is_some()
     {
-        try_enable_dark_mode(hwnd_richedit);
+        apply_native_color_mode(hwnd_richedit);
     }
 
     internal_state.with_window_data_write(window_id, |window_data| {
@@ -182,9 +182,8 @@ pub(crate) fn handle_set_rich_edit_content_command(
         })?;
         // Capture the style's colors now so we can re-apply them after EM_STREAMIN,
         // which resets the background color set by EM_SETBKGNDCOLOR.
-        let colors = window_data
-            .get_style_for_control(control_id)
-            .and_then(|style_id| internal_state.get_parsed_style(style_id))
+        let colors = internal_state
+            .parsed_style_for_control(window_data, control_id)
             .map(|style| style_colors_for_rich_edit(&style));
         Ok((hwnd, colors))
     })?;
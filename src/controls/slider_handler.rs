@@ -0,0 +1,749 @@
+/*
+ * Custom-WndProc slider control for CommanDuctUI.
+ *
+ * Renders a track, the filled part up to the thumb, optional tick marks and a
+ * round thumb, fully owner-drawn. Dragging the thumb sends
+ * WM_APP_SLIDER_DRAGGING to the root window on every value change; releasing
+ * the mouse (or losing capture) sends WM_APP_SLIDER_COMMITTED with the final
+ * value. Arrow, Page, Home and End keys step the value and commit at once.
+ * All value/position math lives in the portable `crate::slider` module.
+ *
+ * Per-instance state is stored in GWLP_USERDATA as a heap-allocated
+ * `SliderState`, matching `toggle_switch_handler`.
+ */
+
+use crate::app::Win32ApiInternalState;
+use crate::controls::styling_handler::{inset_by_padding, paint_styled_rect};
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::slider::{SliderOrientation, SliderRange};
+use crate::styling_primitives::InteractionState;
+use crate::styling_windows::ParsedControlStyle;
+use crate::theme::SliderPalette;
+use crate::types::{ControlId, WindowId};
+use crate::window_common::{
+    self, ControlKind, WM_APP_SLIDER_COMMITTED, WM_APP_SLIDER_DRAGGING, apply_native_color_mode,
+};
+
+use std::sync::{Arc, OnceLock};
+
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM},
+    Graphics::Gdi::{BeginPaint, DrawFocusRect, EndPaint, HDC, InvalidateRect, PAINTSTRUCT},
+    UI::{
+        Input::KeyboardAndMouse::{
+            GetCapture, IsWindowEnabled, ReleaseCapture, SetCapture, SetFocus, TME_LEAVE,
+            TRACKMOUSEEVENT, TrackMouseEvent, VK_DOWN, VK_END, VK_HOME, VK_LEFT, VK_NEXT, VK_PRIOR,
+            VK_RIGHT, VK_UP,
+        },
+        WindowsAndMessaging::{
+            CS_HREDRAW, CS_VREDRAW, CreateWindowExW, DefWindowProcW, GET_ANCESTOR_FLAGS,
+            GWLP_USERDATA, GetAncestor, GetClientRect, GetWindowLongPtrW, HMENU, RegisterClassW,
+            SendMessageW, SetWindowLongPtrW, WINDOW_EX_STYLE, WM_CANCELMODE, WM_CAPTURECHANGED,
+            WM_DESTROY, WM_ERASEBKGND, WM_GETDLGCODE, WM_KEYDOWN, WM_KILLFOCUS, WM_LBUTTONDOWN,
+            WM_LBUTTONUP, WM_MOUSEMOVE, WM_PAINT, WM_SETFOCUS, WNDCLASSW, WS_CHILD, WS_TABSTOP,
+            WS_VISIBLE,
+        },
+    },
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+// WM_MOUSELEAVE is not exported by windows-rs; define the constant directly.
+const WM_MOUSELEAVE: u32 = 0x02A3;
+// Asks the dialog manager to leave the arrow keys to the control.
+const DLGC_WANTARROWS: isize = 0x0001;
+
+// ── SliderState ───────────────────────────────────────────────────────────────
+
+/// Per-instance heap-allocated state stored in GWLP_USERDATA.
+struct SliderState {
+    range: SliderRange,
+    orientation: SliderOrientation,
+    value: i32,
+    dragging: bool,
+    focused: bool,
+    hovered: bool,
+    tracking_mouse: bool,
+    palette: SliderPalette,
+    /// Style applied with `ApplyStyleToControl`; its state colors override the
+    /// palette's background, and it supplies border and padding.
+    style: Option<Arc<ParsedControlStyle>>,
+}
+
+impl SliderState {
+    fn new(range: SliderRange, orientation: SliderOrientation, value: i32) -> Self {
+        Self {
+            range,
+            orientation,
+            value: range.snap(value),
+            dragging: false,
+            focused: false,
+            hovered: false,
+            tracking_mouse: false,
+            palette: SliderPalette::default(),
+            style: None,
+        }
+    }
+
+    // Keyboard paging moves one tick interval, or a tenth of the range without ticks.
+    fn page_steps(&self) -> i32 {
+        let step = self.range.step.max(1);
+        let page = self.range.tick_interval.unwrap_or_else(|| {
+            let span = i64::from(self.range.max) - i64::from(self.range.min);
+            (span / 10).clamp(1, i64::from(i32::MAX)) as i32
+        });
+        (page / step).max(1)
+    }
+}
+
+// ── Window class ──────────────────────────────────────────────────────────────
+
+const SLIDER_CLASS_NAME: PCWSTR = w!("CommanductUI_Slider");
+static SLIDER_CLASS_REGISTERED: OnceLock<()> = OnceLock::new();
+
+fn register_slider_class(h_instance: windows::Win32::Foundation::HINSTANCE) {
+    SLIDER_CLASS_REGISTERED.get_or_init(|| unsafe {
+        let wc = WNDCLASSW {
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(slider_wnd_proc),
+            hInstance: h_instance,
+            hbrBackground: windows::Win32::Graphics::Gdi::HBRUSH(std::ptr::null_mut()),
+            lpszClassName: SLIDER_CLASS_NAME,
+            hCursor: windows::Win32::UI::WindowsAndMessaging::LoadCursorW(
+                None,
+                windows::Win32::UI::WindowsAndMessaging::IDC_HAND,
+            )
+            .unwrap_or_default(),
+            ..Default::default()
+        };
+        let _ = RegisterClassW(&wc);
+    });
+}
+
+// ── Geometry ──────────────────────────────────────────────────────────────────
+
+// Geometry constants in DIPs; scaled to the window DPI when painting and hit testing.
+const THUMB_D: i32 = 14; // thumb diameter
+const TRACK_THICKNESS: i32 = 4;
+const TICK_GAP: i32 = 2; // distance between thumb edge and tick marks
+const TICK_LENGTH: i32 = 4;
+
+/*
+ * The track in pixels: where the `min` end lies on the main axis, how long it
+ * is and the cross-axis center line. The track is inset by the thumb radius so
+ * the thumb stays inside the control at both ends.
+ */
+struct TrackGeometry {
+    start: i32,
+    length: i32,
+    center: i32,
+    vertical: bool,
+}
+
+impl TrackGeometry {
+    fn new(content: &RECT, orientation: SliderOrientation, thumb_d: i32) -> Self {
+        let radius = thumb_d / 2;
+        match orientation {
+            SliderOrientation::Horizontal => Self {
+                start: content.left + radius,
+                length: (content.right - content.left - thumb_d).max(0),
+                center: (content.top + content.bottom) / 2,
+                vertical: false,
+            },
+            SliderOrientation::Vertical => Self {
+                // `min` sits at the bottom.
+                start: content.bottom - radius,
+                length: (content.bottom - content.top - thumb_d).max(0),
+                center: (content.left + content.right) / 2,
+                vertical: true,
+            },
+        }
+    }
+
+    // Main-axis pixel of an offset along the track.
+    fn coordinate(&self, offset: i32) -> i32 {
+        if self.vertical {
+            self.start - offset
+        } else {
+            self.start + offset
+        }
+    }
+
+    // Offset along the track of a client-area point.
+    fn offset_of(&self, x: i32, y: i32) -> i32 {
+        if self.vertical {
+            self.start - y
+        } else {
+            x - self.start
+        }
+    }
+
+    // A rectangle spanning main-axis `a..b` and cross-axis `c..d`.
+    fn rect(&self, a: i32, b: i32, c: i32, d: i32) -> RECT {
+        let (a, b) = (a.min(b), a.max(b));
+        if self.vertical {
+            RECT {
+                left: c,
+                top: a,
+                right: d,
+                bottom: b,
+            }
+        } else {
+            RECT {
+                left: a,
+                top: c,
+                right: b,
+                bottom: d,
+            }
+        }
+    }
+}
+
+unsafe fn state_ptr(hwnd: HWND) -> *mut SliderState {
+    unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut SliderState }
+}
+
+unsafe fn track_geometry(hwnd: HWND, state: &SliderState) -> TrackGeometry {
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let padding = state.style.as_ref().and_then(|s| s.source.padding);
+    let content = inset_by_padding(&client, padding, scale);
+    TrackGeometry::new(&content, state.orientation, scale.scale(THUMB_D))
+}
+
+// ── Notifications ─────────────────────────────────────────────────────────────
+
+// Stores a new value, repaints and tells the root window with `msg`.
+unsafe fn set_value_and_notify(hwnd: HWND, state: *mut SliderState, value: i32, msg: u32) {
+    unsafe {
+        let changed = (*state).value != value;
+        (*state).value = value;
+        if changed {
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+        // Drags only report changes; a commit is always reported so the
+        // application sees the end of every gesture.
+        if changed || msg == WM_APP_SLIDER_COMMITTED {
+            let root = GetAncestor(hwnd, GET_ANCESTOR_FLAGS(2)); // GA_ROOT
+            if !root.is_invalid() {
+                let _ = SendMessageW(
+                    root,
+                    msg,
+                    Some(WPARAM(hwnd.0 as usize)),
+                    Some(LPARAM(value as isize)),
+                );
+            }
+        }
+    }
+}
+
+// Maps a mouse position (client coordinates in `lparam`) to a snapped value.
+unsafe fn value_at_point(hwnd: HWND, state: &SliderState, lparam: LPARAM) -> i32 {
+    let x = (lparam.0 & 0xFFFF) as u16 as i16 as i32;
+    let y = ((lparam.0 >> 16) & 0xFFFF) as u16 as i16 as i32;
+    let track = unsafe { track_geometry(hwnd, state) };
+    state
+        .range
+        .value_at(track.offset_of(x, y), track.length.max(1))
+}
+
+// ── WndProc ───────────────────────────────────────────────────────────────────
+
+unsafe extern "system" fn slider_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) };
+    }
+    match msg {
+        WM_ERASEBKGND => {
+            // Suppress default erase — WM_PAINT fills everything, prevents flicker.
+            LRESULT(1)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = unsafe { BeginPaint(hwnd, &mut ps) };
+            if !hdc.is_invalid() {
+                unsafe { paint_slider(hwnd, hdc, &*state) };
+            }
+            let _ = unsafe { EndPaint(hwnd, &ps) };
+            LRESULT(0)
+        }
+        WM_GETDLGCODE => LRESULT(DLGC_WANTARROWS),
+        WM_LBUTTONDOWN => {
+            // Jump to the clicked position and start dragging from there.
+            unsafe {
+                let _ = SetFocus(Some(hwnd));
+                SetCapture(hwnd);
+                (*state).dragging = true;
+                let value = value_at_point(hwnd, &*state, lparam);
+                set_value_and_notify(hwnd, state, value, WM_APP_SLIDER_DRAGGING);
+            }
+            LRESULT(0)
+        }
+        WM_MOUSEMOVE => {
+            unsafe {
+                if (*state).dragging && GetCapture() == hwnd {
+                    let value = value_at_point(hwnd, &*state, lparam);
+                    set_value_and_notify(hwnd, state, value, WM_APP_SLIDER_DRAGGING);
+                    return LRESULT(0);
+                }
+                if !(*state).tracking_mouse {
+                    let mut tme = TRACKMOUSEEVENT {
+                        cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                        dwFlags: TME_LEAVE,
+                        hwndTrack: hwnd,
+                        dwHoverTime: 0,
+                    };
+                    let _ = TrackMouseEvent(&mut tme);
+                    (*state).tracking_mouse = true;
+                }
+                if !(*state).hovered {
+                    (*state).hovered = true;
+                    let _ = InvalidateRect(Some(hwnd), None, false);
+                }
+            }
+            LRESULT(0)
+        }
+        WM_LBUTTONUP => {
+            // Releasing capture sends WM_CAPTURECHANGED, which commits.
+            unsafe {
+                if GetCapture() == hwnd {
+                    let _ = ReleaseCapture();
+                }
+            }
+            LRESULT(0)
+        }
+        WM_CAPTURECHANGED | WM_CANCELMODE => {
+            // The drag ends here whether the button was released or capture was
+            // taken away (Alt+Tab, a modal dialog); both commit the last value.
+            unsafe {
+                if (*state).dragging {
+                    (*state).dragging = false;
+                    let value = (*state).value;
+                    set_value_and_notify(hwnd, state, value, WM_APP_SLIDER_COMMITTED);
+                }
+                if msg == WM_CANCELMODE && GetCapture() == hwnd {
+                    let _ = ReleaseCapture();
+                }
+            }
+            LRESULT(0)
+        }
+        WM_KEYDOWN => {
+            let vk = wparam.0 as u16;
+            let current = unsafe { (*state).value };
+            let range = unsafe { (*state).range };
+            let page = unsafe { (*state).page_steps() };
+            let target = match vk {
+                v if v == VK_RIGHT.0 || v == VK_UP.0 => Some(range.step_by(current, 1)),
+                v if v == VK_LEFT.0 || v == VK_DOWN.0 => Some(range.step_by(current, -1)),
+                v if v == VK_PRIOR.0 => Some(range.step_by(current, page)),
+                v if v == VK_NEXT.0 => Some(range.step_by(current, -page)),
+                v if v == VK_HOME.0 => Some(range.min),
+                v if v == VK_END.0 => Some(range.max),
+                _ => None,
+            };
+            match target {
+                Some(value) => {
+                    if value != current && !unsafe { (*state).dragging } {
+                        unsafe {
+                            set_value_and_notify(hwnd, state, value, WM_APP_SLIDER_COMMITTED)
+                        };
+                    }
+                    LRESULT(0)
+                }
+                None => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+            }
+        }
+        WM_SETFOCUS | WM_KILLFOCUS => {
+            unsafe {
+                (*state).focused = msg == WM_SETFOCUS;
+                let _ = InvalidateRect(Some(hwnd), None, false);
+            }
+            LRESULT(0)
+        }
+        WM_MOUSELEAVE => {
+            unsafe {
+                (*state).tracking_mouse = false;
+                (*state).hovered = false;
+                let _ = InvalidateRect(Some(hwnd), None, false);
+            }
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            let _ = unsafe { Box::from_raw(state) };
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0) };
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+// ── Paint ─────────────────────────────────────────────────────────────────────
+
+unsafe fn paint_slider(hwnd: HWND, hdc: HDC, state: &SliderState) {
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    if client.right <= client.left || client.bottom <= client.top {
+        return;
+    }
+
+    // Resolve the applied style's colors for the current interaction state.
+    let source = state.style.as_ref().map(|s| &s.source);
+    let enabled = unsafe { IsWindowEnabled(hwnd) }.as_bool();
+    let interaction = if !enabled {
+        InteractionState::Disabled
+    } else if state.dragging {
+        InteractionState::Pressed
+    } else if state.hovered {
+        InteractionState::Hovered
+    } else if state.focused {
+        InteractionState::Focused
+    } else {
+        InteractionState::Normal
+    };
+    let colors = source
+        .map(|s| s.colors_for(interaction))
+        .unwrap_or_default();
+    let background = colors
+        .background_color
+        .as_ref()
+        .unwrap_or(&state.palette.background);
+    let border_width = source.map_or(0, |s| s.border_width_for(interaction));
+    let corner_radius = source.and_then(|s| s.corner_radius).unwrap_or(0).max(0);
+    let scale = window_common::dpi_scale_for_window(hwnd);
+
+    unsafe {
+        paint_styled_rect(
+            hdc,
+            &client,
+            Some(background),
+            colors.border_color.as_ref(),
+            border_width,
+            corner_radius,
+            scale,
+        )
+    };
+
+    let track = unsafe { track_geometry(hwnd, state) };
+    let thumb_d = scale.scale(THUMB_D);
+    let half_thickness = scale.scale(TRACK_THICKNESS) / 2;
+    let (cross_top, cross_bottom) = (
+        track.center - half_thickness,
+        track.center + half_thickness.max(1),
+    );
+    let thumb_at = track.coordinate(state.range.position_of(state.value, track.length));
+    let end = track.coordinate(track.length);
+
+    // Track, then the filled part from `min` to the thumb. Disabled sliders show no fill.
+    let track_rect = track.rect(track.start, end, cross_top, cross_bottom);
+    unsafe {
+        paint_styled_rect(
+            hdc,
+            &track_rect,
+            Some(&state.palette.track),
+            None,
+            0,
+            TRACK_THICKNESS / 2,
+            scale,
+        )
+    };
+    if enabled {
+        let fill_rect = track.rect(track.start, thumb_at, cross_top, cross_bottom);
+        unsafe {
+            paint_styled_rect(
+                hdc,
+                &fill_rect,
+                Some(&state.palette.fill),
+                None,
+                0,
+                TRACK_THICKNESS / 2,
+                scale,
+            )
+        };
+    }
+
+    // Tick marks below (horizontal) or right of (vertical) the thumb.
+    let tick_from = track.center + thumb_d / 2 + scale.scale(TICK_GAP);
+    let tick_to = tick_from + scale.scale(TICK_LENGTH);
+    let tick_width = scale.scale(1).max(1);
+    for tick in state.range.tick_values() {
+        let at = track.coordinate(state.range.position_of(tick, track.length));
+        let tick_rect = track.rect(at, at + tick_width, tick_from, tick_to);
+        unsafe {
+            paint_styled_rect(
+                hdc,
+                &tick_rect,
+                Some(&state.palette.tick),
+                None,
+                0,
+                0,
+                scale,
+            )
+        };
+    }
+
+    // Thumb, a circle centered on the value's position.
+    let thumb_rect = track.rect(
+        thumb_at - thumb_d / 2,
+        thumb_at - thumb_d / 2 + thumb_d,
+        track.center - thumb_d / 2,
+        track.center - thumb_d / 2 + thumb_d,
+    );
+    let thumb_color = if enabled {
+        &state.palette.thumb
+    } else {
+        &state.palette.track
+    };
+    unsafe {
+        paint_styled_rect(
+            hdc,
+            &thumb_rect,
+            Some(thumb_color),
+            None,
+            0,
+            THUMB_D / 2,
+            scale,
+        )
+    };
+
+    // Focus rect around the thumb, unless the style marks focus with a border.
+    let has_focus_border =
+        source.is_some_and(|s| s.border_width_for(InteractionState::Focused) > 0);
+    if state.focused && !(interaction == InteractionState::Focused && has_focus_border) {
+        let focus_rect = RECT {
+            left: thumb_rect.left - 2,
+            top: thumb_rect.top - 2,
+            right: thumb_rect.right + 2,
+            bottom: thumb_rect.bottom + 2,
+        };
+        let _ = unsafe { DrawFocusRect(hdc, &focus_rect) };
+    }
+}
+
+// ── Command handlers ──────────────────────────────────────────────────────────
+
+/// Creates a Slider control as a child of `parent_control_id` (or main window if None).
+/// Follows the 4-phase read-kind-create-hwnd-write pattern from `toggle_switch_handler`.
+pub(crate) fn handle_create_slider_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    parent_control_id: Option<ControlId>,
+    control_id: ControlId,
+    range: SliderRange,
+    orientation: SliderOrientation,
+    value: i32,
+) -> PlatformResult<()> {
+    log::debug!(
+        "[Slider] handle_create_slider_command WinID={window_id:?} ControlID={} ParentID={:?}",
+        control_id.raw(),
+        parent_control_id.map(|id| id.raw()),
+    );
+    range.validate()?;
+
+    // Phase 1: Read-lock — duplicate check + get parent HWND.
+    let parent_hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "Slider {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        let hwnd_parent = match parent_control_id {
+            Some(id) => window_data.get_control_hwnd(id).ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "[Slider] Parent control {} not found in WinID {window_id:?}",
+                    id.raw()
+                ))
+            })?,
+            None => window_data.get_hwnd(),
+        };
+        if hwnd_parent.is_invalid() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[Slider] Parent HWND invalid WinID={window_id:?}"
+            )));
+        }
+        Ok(hwnd_parent)
+    })?;
+
+    let h_instance = internal_state.h_instance();
+    register_slider_class(h_instance);
+
+    // Phase 2: Write-lock — register the control kind.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "[Slider] Race: Slider {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.register_control_kind(control_id, ControlKind::Slider);
+        Ok(())
+    })?;
+
+    // Phase 3: Create native HWND outside any lock.
+    let hwnd_slider = unsafe {
+        match CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            SLIDER_CLASS_NAME,
+            &HSTRING::from(""),
+            WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+            0,
+            0,
+            10,
+            10,
+            Some(parent_hwnd),
+            Some(HMENU(control_id.raw() as *mut _)),
+            Some(h_instance),
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(err) => {
+                let _ = internal_state.with_window_data_write(window_id, |window_data| {
+                    window_data.unregister_control_kind(control_id);
+                    Ok(())
+                });
+                return Err(err.into());
+            }
+        }
+    };
+
+    // Initialise GWLP_USERDATA with per-control state.
+    let mut state = Box::new(SliderState::new(range, orientation, value));
+    state.palette = internal_state.current_theme().slider.clone();
+    unsafe {
+        SetWindowLongPtrW(hwnd_slider, GWLP_USERDATA, Box::into_raw(state) as isize);
+    }
+    apply_native_color_mode(hwnd_slider);
+
+    // Phase 4: Write-lock — store the HWND.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        window_data.register_control_hwnd(control_id, hwnd_slider);
+        Ok(())
+    })?;
+
+    log::debug!(
+        "[Slider] Created slider {} hwnd={hwnd_slider:?}",
+        control_id.raw()
+    );
+    Ok(())
+}
+
+fn slider_hwnd(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    operation: &str,
+) -> PlatformResult<HWND> {
+    internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.get_control_kind(control_id) != Some(ControlKind::Slider) {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[Slider] {operation}: control {} is not a slider in window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.get_control_hwnd(control_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!(
+                "[Slider] {operation}: control {} not found in window {window_id:?}",
+                control_id.raw()
+            ))
+        })
+    })
+}
+
+/// Moves the thumb to the snapped `value` and repaints without notifying the application.
+pub(crate) fn handle_set_slider_value_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    value: i32,
+) -> PlatformResult<()> {
+    let hwnd = slider_hwnd(internal_state, window_id, control_id, "SetSliderValue")?;
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).value = (*state).range.snap(value);
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+    Ok(())
+}
+
+/// Pushes resolved palette colors into the control so WM_PAINT can render without
+/// accessing `Win32ApiInternalState`.
+pub(crate) fn handle_set_slider_style_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    palette: SliderPalette,
+) -> PlatformResult<()> {
+    let hwnd = slider_hwnd(internal_state, window_id, control_id, "SetSliderStyle")?;
+    apply_slider_palette(hwnd, palette);
+    Ok(())
+}
+
+/// Replaces the palette and repaints; also used when a theme is applied.
+pub(crate) fn apply_slider_palette(hwnd: HWND, palette: SliderPalette) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).palette = palette;
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+/// Stores a style applied with `ApplyStyleToControl` and repaints.
+pub(crate) fn apply_control_style(hwnd: HWND, style: Arc<ParsedControlStyle>) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).style = Some(style);
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-SliderV1] Vertical tracks put min at the bottom and map points back to offsets.
+    fn vertical_track_runs_bottom_to_top() {
+        // Arrange
+        let content = RECT {
+            left: 0,
+            top: 0,
+            right: 20,
+            bottom: 114,
+        };
+
+        // Act
+        let track = TrackGeometry::new(&content, SliderOrientation::Vertical, 14);
+
+        // Assert
+        assert_eq!((track.start, track.length, track.center), (107, 100, 10));
+        assert_eq!(track.coordinate(100), 7);
+        assert_eq!(track.offset_of(3, 57), 50);
+    }
+
+    #[test]
+    fn page_steps_follow_tick_interval_or_tenth_of_range() {
+        // Arrange
+        let ticked = SliderState::new(
+            SliderRange::new(0, 100).step(5).ticks(20),
+            SliderOrientation::Horizontal,
+            0,
+        );
+        let plain = SliderState::new(SliderRange::new(0, 100), SliderOrientation::Horizontal, 0);
+
+        // Act & Assert
+        assert_eq!(ticked.page_steps(), 4);
+        assert_eq!(plain.page_steps(), 10);
+    }
+}
@@ -7,6 +7,7 @@
 use crate::app::Win32ApiInternalState;
 use crate::controls::styling_handler::color_to_colorref;
 use crate::error::{PlatformError, Result as PlatformResult};
+use crate::theme::SplitterPalette;
 use crate::types::{ControlId, SplitterOrientation, WindowId};
 use crate::window_common::{ControlKind, WM_APP_SPLITTER_DRAG_ENDED, WM_APP_SPLITTER_DRAGGING};
 
@@ -37,24 +38,13 @@ use windows::core::{HSTRING, PCWSTR};
 
 const WC_SPLITTER: PCWSTR = windows::core::w!("CommanductUI_Splitter");
 
-// Colors for splitter states
-const COLOR_NORMAL: crate::styling::Color = crate::styling::Color {
-    r: 0x40,
-    g: 0x44,
-    b: 0x4B,
-};
-const COLOR_HOVER: crate::styling::Color = crate::styling::Color {
-    r: 0x55,
-    g: 0x5A,
-    b: 0x64,
-};
-
 /// Per-window-instance state stored in GWLP_USERDATA.
-/// Used by the splitter's custom WndProc to track hover/drag state.
+/// Used by the splitter's custom WndProc to track hover/drag state and its colors.
 #[derive(Debug, Default)]
 struct SplitterWndData {
     is_hovered: bool,
     is_tracking_mouse: bool,
+    palette: SplitterPalette,
 }
 
 /// Helper to get or create window data from GWLP_USERDATA.
@@ -186,9 +176,9 @@ unsafe extern "system" fn splitter_wnd_proc(
                 if !hdc.is_invalid() {
                     // Use hover color when hovered, normal color otherwise
                     let color = if (*data).is_hovered {
-                        &COLOR_HOVER
+                        &(*data).palette.hover
                     } else {
-                        &COLOR_NORMAL
+                        &(*data).palette.normal
                     };
                     let brush = CreateSolidBrush(color_to_colorref(color));
                     FillRect(hdc, &ps.rcPaint, brush);
@@ -341,6 +331,11 @@ pub(crate) fn handle_create_splitter_command(
         }
     };
 
+    apply_splitter_palette(
+        hwnd_splitter,
+        internal_state.current_theme().splitter.clone(),
+    );
+
     // Phase 3: Register the HWND and internal state
     internal_state.with_window_data_write(window_id, |window_data| {
         if window_data.has_control(control_id) {
@@ -370,3 +365,12 @@ pub(crate) fn handle_create_splitter_command(
 // Note: Drag state tracking is handled internally by the splitter's window procedure.
 // The splitter sends WM_APP_SPLITTER_DRAGGING and WM_APP_SPLITTER_DRAG_ENDED messages
 // to the parent window, which are then handled in window_common.rs to generate AppEvents.
+
+/// Replaces the splitter's colors, e.g. when a theme is applied, and repaints it.
+pub(crate) fn apply_splitter_palette(hwnd: HWND, palette: SplitterPalette) {
+    unsafe {
+        let data = get_wnd_data(hwnd);
+        (*data).palette = palette;
+        let _ = InvalidateRect(Some(hwnd), None, false);
+    }
+}
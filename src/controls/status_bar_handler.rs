@@ -0,0 +1,628 @@
+/*
+ * Custom-WndProc status bar control for CommanDuctUI.
+ *
+ * Paints a row of segments, each showing a text colored by its severity or,
+ * for progress segments, a progress bar. Segment widths come from the
+ * portable `crate::status_bar` layout, with `Auto` segments measured in the
+ * bar's font. A click (press and release over the same segment) sends
+ * WM_APP_STATUS_SEGMENT_CLICKED to the root window with the segment index.
+ *
+ * Per-instance state is stored in GWLP_USERDATA as a heap-allocated
+ * `StatusBarState`, matching `slider_handler`.
+ */
+
+use crate::app::Win32ApiInternalState;
+use crate::controls::styling_handler::{color_to_colorref, paint_styled_rect};
+use crate::dpi::DpiScale;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::status_bar::{self, SEGMENT_PADDING_X, SegmentContent, StatusSegment};
+use crate::styling::Color;
+use crate::styling_windows::ParsedControlStyle;
+use crate::theme::StatusBarPalette;
+use crate::types::{ControlId, MessageSeverity, WindowId};
+use crate::window_common::{self, ControlKind, WM_APP_STATUS_SEGMENT_CLICKED};
+
+use std::sync::{Arc, OnceLock};
+
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, RECT, SIZE, WPARAM},
+    Graphics::Gdi::{
+        BeginPaint, DEFAULT_GUI_FONT, DT_END_ELLIPSIS, DT_LEFT, DT_NOPREFIX, DT_SINGLELINE,
+        DT_VCENTER, DrawTextW, EndPaint, GetDC, GetStockObject, GetTextExtentPoint32W, HDC,
+        HGDIOBJ, InvalidateRect, PAINTSTRUCT, ReleaseDC, SelectObject, SetBkMode, SetTextColor,
+        TRANSPARENT,
+    },
+    UI::{
+        Input::KeyboardAndMouse::{GetCapture, ReleaseCapture, SetCapture},
+        WindowsAndMessaging::{
+            CS_HREDRAW, CS_VREDRAW, CreateWindowExW, DefWindowProcW, GET_ANCESTOR_FLAGS,
+            GWLP_USERDATA, GetAncestor, GetClientRect, GetWindowLongPtrW, HMENU, RegisterClassW,
+            SendMessageW, SetWindowLongPtrW, WINDOW_EX_STYLE, WM_CAPTURECHANGED, WM_DESTROY,
+            WM_ERASEBKGND, WM_GETFONT, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_PAINT, WNDCLASSW, WS_CHILD,
+            WS_VISIBLE,
+        },
+    },
+};
+use windows::core::{HSTRING, PCWSTR, w};
+
+// Geometry constants in DIPs; scaled to the window DPI when painting.
+const PROGRESS_BAR_HEIGHT: i32 = 10;
+const SEPARATOR_WIDTH: i32 = 1;
+const SEPARATOR_INSET_Y: i32 = 4; // separators stop short of the top and bottom edges
+
+// ── StatusBarState ────────────────────────────────────────────────────────────
+
+/// Per-instance heap-allocated state stored in GWLP_USERDATA.
+struct StatusBarState {
+    segments: Vec<StatusSegment>,
+    contents: Vec<SegmentContent>,
+    /// Segment under the mouse when the button went down.
+    pressed: Option<usize>,
+    palette: StatusBarPalette,
+    /// Style applied with `ApplyStyleToControl`; supplies the font and
+    /// overrides the palette's background and normal text color.
+    style: Option<Arc<ParsedControlStyle>>,
+}
+
+// ── Window class ──────────────────────────────────────────────────────────────
+
+const STATUS_BAR_CLASS_NAME: PCWSTR = w!("CommanductUI_StatusBar");
+static STATUS_BAR_CLASS_REGISTERED: OnceLock<()> = OnceLock::new();
+
+fn register_status_bar_class(h_instance: windows::Win32::Foundation::HINSTANCE) {
+    STATUS_BAR_CLASS_REGISTERED.get_or_init(|| unsafe {
+        let wc = WNDCLASSW {
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(status_bar_wnd_proc),
+            hInstance: h_instance,
+            hbrBackground: windows::Win32::Graphics::Gdi::HBRUSH(std::ptr::null_mut()),
+            lpszClassName: STATUS_BAR_CLASS_NAME,
+            hCursor: windows::Win32::UI::WindowsAndMessaging::LoadCursorW(
+                None,
+                windows::Win32::UI::WindowsAndMessaging::IDC_ARROW,
+            )
+            .unwrap_or_default(),
+            ..Default::default()
+        };
+        let _ = RegisterClassW(&wc);
+    });
+}
+
+unsafe fn state_ptr(hwnd: HWND) -> *mut StatusBarState {
+    unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut StatusBarState }
+}
+
+// ── Layout ────────────────────────────────────────────────────────────────────
+
+// The applied style's font at `scale`, or the stock GUI font.
+fn bar_font(state: &StatusBarState, scale: DpiScale) -> HGDIOBJ {
+    let stock_font: HGDIOBJ = unsafe { GetStockObject(DEFAULT_GUI_FONT) };
+    state
+        .style
+        .as_ref()
+        .and_then(|s| s.font_for_dpi(scale))
+        .filter(|hf| !hf.is_invalid())
+        .map_or(stock_font, Into::into)
+}
+
+// Segment widths for the client width; `hdc` must have the bar's font selected.
+unsafe fn segment_widths(
+    hdc: HDC,
+    state: &StatusBarState,
+    width: i32,
+    scale: DpiScale,
+) -> Vec<i32> {
+    let text_widths: Vec<i32> = state
+        .contents
+        .iter()
+        .map(|content| {
+            let wide: Vec<u16> = content.text.encode_utf16().collect();
+            let mut size = SIZE::default();
+            let _ = unsafe { GetTextExtentPoint32W(hdc, &wide, &mut size) };
+            size.cx
+        })
+        .collect();
+    status_bar::segment_widths(&state.segments, &text_widths, width, scale)
+}
+
+// Segment widths outside WM_PAINT, measured on a temporary DC.
+unsafe fn measure_segment_widths(hwnd: HWND, state: &StatusBarState) -> Vec<i32> {
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let hdc = unsafe { GetDC(Some(hwnd)) };
+    if hdc.is_invalid() {
+        return Vec::new();
+    }
+    let old_font = unsafe { SelectObject(hdc, bar_font(state, scale)) };
+    let widths = unsafe { segment_widths(hdc, state, client.right - client.left, scale) };
+    unsafe {
+        SelectObject(hdc, old_font);
+        ReleaseDC(Some(hwnd), hdc);
+    }
+    widths
+}
+
+// The segment under the client x coordinate in `lparam`.
+unsafe fn segment_at_point(hwnd: HWND, state: &StatusBarState, lparam: LPARAM) -> Option<usize> {
+    let x = (lparam.0 & 0xFFFF) as u16 as i16 as i32;
+    let widths = unsafe { measure_segment_widths(hwnd, state) };
+    status_bar::segment_at(&widths, x)
+}
+
+/// Text color of a segment: warnings and errors use the palette, the rest `normal`.
+fn severity_color<'a>(
+    palette: &'a StatusBarPalette,
+    normal: &'a Color,
+    severity: MessageSeverity,
+) -> &'a Color {
+    match severity {
+        MessageSeverity::Warning => &palette.warning,
+        MessageSeverity::Error => &palette.error,
+        MessageSeverity::None | MessageSeverity::Information => normal,
+    }
+}
+
+/// Right edge of the filled part of a progress bar spanning `left..right`.
+fn progress_fill_right(left: i32, right: i32, percent: u32) -> i32 {
+    let width = i64::from((right - left).max(0));
+    left + (width * i64::from(percent.min(100)) / 100) as i32
+}
+
+// ── WndProc ───────────────────────────────────────────────────────────────────
+
+unsafe extern "system" fn status_bar_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) };
+    }
+    match msg {
+        WM_ERASEBKGND => {
+            // Suppress default erase — WM_PAINT fills everything, prevents flicker.
+            LRESULT(1)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = unsafe { BeginPaint(hwnd, &mut ps) };
+            if !hdc.is_invalid() {
+                unsafe { paint_status_bar(hwnd, hdc, &*state) };
+            }
+            let _ = unsafe { EndPaint(hwnd, &ps) };
+            LRESULT(0)
+        }
+        // Lets measurement code size the bar in the font it paints with.
+        WM_GETFONT => {
+            let scale = window_common::dpi_scale_for_window(hwnd);
+            let font = unsafe { &*state }
+                .style
+                .as_ref()
+                .and_then(|s| s.font_for_dpi(scale));
+            LRESULT(font.map_or(0, |font| font.0 as isize))
+        }
+        WM_LBUTTONDOWN => {
+            unsafe {
+                (*state).pressed = segment_at_point(hwnd, &*state, lparam);
+                if (*state).pressed.is_some() {
+                    SetCapture(hwnd);
+                }
+            }
+            LRESULT(0)
+        }
+        WM_LBUTTONUP => {
+            unsafe {
+                let pressed = (*state).pressed.take();
+                if GetCapture() == hwnd {
+                    let _ = ReleaseCapture();
+                }
+                // Like a button, releasing over another segment cancels the click.
+                if let Some(segment) = pressed
+                    && segment_at_point(hwnd, &*state, lparam) == Some(segment)
+                {
+                    let root = GetAncestor(hwnd, GET_ANCESTOR_FLAGS(2)); // GA_ROOT
+                    if !root.is_invalid() {
+                        let _ = SendMessageW(
+                            root,
+                            WM_APP_STATUS_SEGMENT_CLICKED,
+                            Some(WPARAM(hwnd.0 as usize)),
+                            Some(LPARAM(segment as isize)),
+                        );
+                    }
+                }
+            }
+            LRESULT(0)
+        }
+        WM_CAPTURECHANGED => {
+            unsafe { (*state).pressed = None };
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            let _ = unsafe { Box::from_raw(state) };
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0) };
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+// ── Paint ─────────────────────────────────────────────────────────────────────
+
+unsafe fn paint_status_bar(hwnd: HWND, hdc: HDC, state: &StatusBarState) {
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    if client.right <= client.left || client.bottom <= client.top {
+        return;
+    }
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let source = state.style.as_ref().map(|s| &s.source);
+    let background = source
+        .and_then(|s| s.background_color.as_ref())
+        .unwrap_or(&state.palette.background);
+    let normal_text = source
+        .and_then(|s| s.text_color.as_ref())
+        .unwrap_or(&state.palette.text);
+
+    unsafe { paint_styled_rect(hdc, &client, Some(background), None, 0, 0, scale) };
+
+    let old_font = unsafe { SelectObject(hdc, bar_font(state, scale)) };
+    unsafe { SetBkMode(hdc, TRANSPARENT) };
+    let widths = unsafe { segment_widths(hdc, state, client.right - client.left, scale) };
+    let padding = scale.scale(SEGMENT_PADDING_X);
+    let separator_inset = scale.scale(SEPARATOR_INSET_Y);
+    let mut left = client.left;
+
+    for (index, (&width, content)) in widths.iter().zip(&state.contents).enumerate() {
+        let right = left + width;
+        let inner = RECT {
+            left: left + padding,
+            top: client.top,
+            right: (right - padding).max(left + padding),
+            bottom: client.bottom,
+        };
+        match content.progress {
+            Some(percent) if state.segments[index].progress => {
+                let bar_height = scale.scale(PROGRESS_BAR_HEIGHT);
+                let top = (client.top + client.bottom - bar_height) / 2;
+                let track = RECT {
+                    top,
+                    bottom: top + bar_height,
+                    ..inner
+                };
+                let fill = RECT {
+                    right: progress_fill_right(track.left, track.right, percent),
+                    ..track
+                };
+                unsafe {
+                    paint_styled_rect(
+                        hdc,
+                        &track,
+                        Some(&state.palette.progress_track),
+                        None,
+                        0,
+                        0,
+                        scale,
+                    );
+                    paint_styled_rect(
+                        hdc,
+                        &fill,
+                        Some(&state.palette.progress_fill),
+                        None,
+                        0,
+                        0,
+                        scale,
+                    );
+                }
+            }
+            _ if !content.text.is_empty() => {
+                let color = severity_color(&state.palette, normal_text, content.severity);
+                let mut wide: Vec<u16> = content.text.encode_utf16().collect();
+                let mut text_rect = inner;
+                unsafe {
+                    let _ = SetTextColor(hdc, color_to_colorref(color));
+                    DrawTextW(
+                        hdc,
+                        &mut wide,
+                        &mut text_rect,
+                        DT_LEFT | DT_VCENTER | DT_SINGLELINE | DT_END_ELLIPSIS | DT_NOPREFIX,
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        // Separator at the right edge of every segment but the last.
+        if index + 1 < widths.len() && width > 0 {
+            let separator = RECT {
+                left: right - scale.scale(SEPARATOR_WIDTH).max(1),
+                top: client.top + separator_inset,
+                right,
+                bottom: client.bottom - separator_inset,
+            };
+            unsafe {
+                paint_styled_rect(
+                    hdc,
+                    &separator,
+                    Some(&state.palette.separator),
+                    None,
+                    0,
+                    0,
+                    scale,
+                )
+            };
+        }
+        left = right;
+    }
+    unsafe { SelectObject(hdc, old_font) };
+}
+
+// ── Command handlers ──────────────────────────────────────────────────────────
+
+/// Creates a StatusBar control as a child of `parent_control_id` (or main window if None).
+/// Follows the 4-phase read-kind-create-hwnd-write pattern from `slider_handler`.
+pub(crate) fn handle_create_status_bar_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    parent_control_id: Option<ControlId>,
+    control_id: ControlId,
+    segments: Vec<StatusSegment>,
+) -> PlatformResult<()> {
+    log::debug!(
+        "[StatusBar] handle_create_status_bar_command WinID={window_id:?} ControlID={} ParentID={:?} segments={}",
+        control_id.raw(),
+        parent_control_id.map(|id| id.raw()),
+        segments.len(),
+    );
+    status_bar::validate_segments(&segments)?;
+
+    // Phase 1: Read-lock — duplicate check + get parent HWND.
+    let parent_hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "StatusBar {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        let hwnd_parent = match parent_control_id {
+            Some(id) => window_data.get_control_hwnd(id).ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "[StatusBar] Parent control {} not found in WinID {window_id:?}",
+                    id.raw()
+                ))
+            })?,
+            None => window_data.get_hwnd(),
+        };
+        if hwnd_parent.is_invalid() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[StatusBar] Parent HWND invalid WinID={window_id:?}"
+            )));
+        }
+        Ok(hwnd_parent)
+    })?;
+
+    let h_instance = internal_state.h_instance();
+    register_status_bar_class(h_instance);
+
+    // Phase 2: Write-lock — register the control kind.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "[StatusBar] Race: StatusBar {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.register_control_kind(control_id, ControlKind::StatusBar);
+        Ok(())
+    })?;
+
+    // Phase 3: Create native HWND outside any lock.
+    let hwnd_bar = unsafe {
+        match CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            STATUS_BAR_CLASS_NAME,
+            &HSTRING::from(""),
+            WS_CHILD | WS_VISIBLE,
+            0,
+            0,
+            10,
+            10,
+            Some(parent_hwnd),
+            Some(HMENU(control_id.raw() as *mut _)),
+            Some(h_instance),
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(err) => {
+                let _ = internal_state.with_window_data_write(window_id, |window_data| {
+                    window_data.unregister_control_kind(control_id);
+                    Ok(())
+                });
+                return Err(err.into());
+            }
+        }
+    };
+
+    // Initialise GWLP_USERDATA with per-control state.
+    let state = Box::new(StatusBarState {
+        contents: vec![SegmentContent::default(); segments.len()],
+        segments,
+        pressed: None,
+        palette: internal_state.current_theme().status_bar.clone(),
+        style: None,
+    });
+    unsafe {
+        SetWindowLongPtrW(hwnd_bar, GWLP_USERDATA, Box::into_raw(state) as isize);
+    }
+
+    // Phase 4: Write-lock — store the HWND.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        window_data.register_control_hwnd(control_id, hwnd_bar);
+        Ok(())
+    })?;
+
+    log::debug!(
+        "[StatusBar] Created status bar {} hwnd={hwnd_bar:?}",
+        control_id.raw()
+    );
+    Ok(())
+}
+
+/*
+ * Runs `update` on one segment's content and repaints. Unknown controls fail
+ * with `InvalidHandle`, segment indexes out of range with `OperationFailed`.
+ */
+fn update_segment(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    segment: usize,
+    operation: &str,
+    update: impl FnOnce(&StatusSegment, &mut SegmentContent) -> PlatformResult<()>,
+) -> PlatformResult<()> {
+    let hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.get_control_kind(control_id) != Some(ControlKind::StatusBar) {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[StatusBar] {operation}: control {} is not a status bar in window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.get_control_hwnd(control_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!(
+                "[StatusBar] {operation}: control {} not found in window {window_id:?}",
+                control_id.raw()
+            ))
+        })
+    })?;
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return Err(PlatformError::InvalidHandle(format!(
+            "[StatusBar] {operation}: control {} has no state",
+            control_id.raw()
+        )));
+    }
+    let state = unsafe { &mut *state };
+    let count = state.segments.len();
+    match (state.segments.get(segment), state.contents.get_mut(segment)) {
+        (Some(spec), Some(content)) => update(spec, content)?,
+        _ => {
+            return Err(PlatformError::OperationFailed(format!(
+                "[StatusBar] {operation}: status bar {} has {count} segments, no segment {segment}",
+                control_id.raw()
+            )));
+        }
+    }
+    let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
+    Ok(())
+}
+
+pub(crate) fn handle_set_status_segment_text_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    segment: usize,
+    text: String,
+    severity: MessageSeverity,
+) -> PlatformResult<()> {
+    update_segment(
+        internal_state,
+        window_id,
+        control_id,
+        segment,
+        "SetStatusSegmentText",
+        |_, content| {
+            content.text = text;
+            content.severity = severity;
+            Ok(())
+        },
+    )
+}
+
+pub(crate) fn handle_set_status_segment_progress_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    segment: usize,
+    progress: Option<u32>,
+) -> PlatformResult<()> {
+    update_segment(
+        internal_state,
+        window_id,
+        control_id,
+        segment,
+        "SetStatusSegmentProgress",
+        |spec, content| {
+            if !spec.progress {
+                return Err(PlatformError::OperationFailed(format!(
+                    "[StatusBar] Segment {segment} of status bar {} is not a progress segment",
+                    control_id.raw()
+                )));
+            }
+            content.progress = progress.map(|percent| percent.min(100));
+            Ok(())
+        },
+    )
+}
+
+/// Replaces the palette and repaints; used when a theme is applied.
+pub(crate) fn apply_status_bar_palette(hwnd: HWND, palette: StatusBarPalette) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).palette = palette;
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+/// Stores a style applied with `ApplyStyleToControl` and repaints.
+pub(crate) fn apply_control_style(hwnd: HWND, style: Arc<ParsedControlStyle>) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).style = Some(style);
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-StatusBarV1] Warnings and errors use the palette; other severities the normal text color.
+    fn severity_picks_the_text_color() {
+        // Arrange
+        let palette = StatusBarPalette::default();
+        let normal = Color::rgb(1, 2, 3);
+
+        // Act
+        let colors: Vec<&Color> = [
+            MessageSeverity::None,
+            MessageSeverity::Information,
+            MessageSeverity::Warning,
+            MessageSeverity::Error,
+        ]
+        .into_iter()
+        .map(|severity| severity_color(&palette, &normal, severity))
+        .collect();
+
+        // Assert
+        assert_eq!(
+            colors,
+            vec![&normal, &normal, &palette.warning, &palette.error]
+        );
+    }
+
+    #[test]
+    fn progress_fill_is_proportional_and_capped() {
+        // Act & Assert
+        assert_eq!(progress_fill_right(10, 110, 0), 10);
+        assert_eq!(progress_fill_right(10, 110, 40), 50);
+        assert_eq!(progress_fill_right(10, 110, 250), 110);
+        assert_eq!(progress_fill_right(10, 5, 50), 10);
+    }
+}
@@ -6,12 +6,19 @@
  * TODO: Should we deprecate this module?
  */
 
+use crate::dpi::DpiScale;
 use crate::styling::Color;
-use windows::Win32::Foundation::COLORREF;
+use crate::styling_primitives::FontDescription;
+use windows::Win32::Foundation::{COLORREF, RECT};
+use windows::Win32::Graphics::Gdi::{
+    CreatePen, CreateSolidBrush, DeleteObject, GetStockObject, HDC, HGDIOBJ, NULL_BRUSH, NULL_PEN,
+    PS_INSIDEFRAME, Rectangle, RoundRect, SelectObject,
+};
 
 /*
  * Creates a Win32 COLORREF from the platform-agnostic `Color` struct.
  * Win32 expects colors in BGR format, so this function handles the conversion.
+ * COLORREF has no alpha; translucent colors are flattened with `Color::over` first.
  */
 pub(crate) fn color_to_colorref(color: &Color) -> COLORREF {
     COLORREF((color.r as u32) | ((color.g as u32) << 8) | ((color.b as u32) << 16))
@@ -22,9 +29,96 @@ pub(crate) fn color_to_colorref(color: &Color) -> COLORREF {
  * Used for retrieving system colors and converting them to our Color type.
  */
 pub(crate) fn colorref_to_color(cr: COLORREF) -> Color {
-    Color {
-        r: (cr.0 & 0xFF) as u8,
-        g: ((cr.0 >> 8) & 0xFF) as u8,
-        b: ((cr.0 >> 16) & 0xFF) as u8,
+    Color::rgb(
+        (cr.0 & 0xFF) as u8,
+        ((cr.0 >> 8) & 0xFF) as u8,
+        ((cr.0 >> 16) & 0xFF) as u8,
+    )
+}
+
+/*
+ * The `CreateFontW` italic, underline and strike-out arguments for a font
+ * description; unset decorations are off.
+ */
+pub(crate) fn font_decoration_flags(font: &FontDescription) -> (u32, u32, u32) {
+    let flag = |value: Option<bool>| u32::from(value.unwrap_or(false));
+    (
+        flag(font.italic),
+        flag(font.underline),
+        flag(font.strikethrough),
+    )
+}
+
+/*
+ * Shrinks `rect` by a style's (top, right, bottom, left) padding, given in
+ * DIPs and scaled to the pixels of `scale`.
+ */
+pub(crate) fn inset_by_padding(
+    rect: &RECT,
+    padding: Option<(i32, i32, i32, i32)>,
+    scale: DpiScale,
+) -> RECT {
+    let (top, right, bottom, left) = padding.unwrap_or((0, 0, 0, 0));
+    let (top, right, bottom, left) = (
+        scale.scale(top),
+        scale.scale(right),
+        scale.scale(bottom),
+        scale.scale(left),
+    );
+    RECT {
+        left: rect.left + left,
+        top: rect.top + top,
+        right: (rect.right - right).max(rect.left + left),
+        bottom: (rect.bottom - bottom).max(rect.top + top),
+    }
+}
+
+/*
+ * Fills `rect` with `fill` and strokes a `border_width` border inside it in
+ * `border`, with rounded corners when `corner_radius` is positive. Used by the
+ * owner-draw paths so buttons, tab bars and toggle switches draw style borders
+ * and radii the same way. Either the fill or the border may be omitted.
+ * `border_width` and `corner_radius` are DIPs, scaled by `scale`.
+ */
+pub(crate) unsafe fn paint_styled_rect(
+    hdc: HDC,
+    rect: &RECT,
+    fill: Option<&Color>,
+    border: Option<&Color>,
+    border_width: i32,
+    corner_radius: i32,
+    scale: DpiScale,
+) {
+    let border_width = scale.scale(border_width);
+    let corner_radius = scale.scale(corner_radius);
+    unsafe {
+        let brush = fill.map(|color| CreateSolidBrush(color_to_colorref(color)));
+        let pen = border
+            .filter(|_| border_width > 0)
+            .map(|color| CreatePen(PS_INSIDEFRAME, border_width, color_to_colorref(color)));
+        if brush.is_none() && pen.is_none() {
+            return;
+        }
+        let brush_obj: HGDIOBJ = brush.map_or_else(|| GetStockObject(NULL_BRUSH), Into::into);
+        let pen_obj: HGDIOBJ = pen.map_or_else(|| GetStockObject(NULL_PEN), Into::into);
+        let old_brush = SelectObject(hdc, brush_obj);
+        let old_pen = SelectObject(hdc, pen_obj);
+        // Without a pen GDI leaves out the right and bottom edge; include them.
+        let extra = if pen.is_none() { 1 } else { 0 };
+        let (right, bottom) = (rect.right + extra, rect.bottom + extra);
+        if corner_radius > 0 {
+            let diameter = corner_radius * 2;
+            let _ = RoundRect(hdc, rect.left, rect.top, right, bottom, diameter, diameter);
+        } else {
+            let _ = Rectangle(hdc, rect.left, rect.top, right, bottom);
+        }
+        SelectObject(hdc, old_pen);
+        SelectObject(hdc, old_brush);
+        if let Some(brush) = brush {
+            let _ = DeleteObject(brush.into());
+        }
+        if let Some(pen) = pen {
+            let _ = DeleteObject(pen.into());
+        }
     }
 }
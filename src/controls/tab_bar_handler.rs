@@ -1,21 +1,34 @@
 /*
  * Custom-WndProc tab bar control for CommanDuctUI.
  *
- * Renders a row of text tabs with a colored bottom accent line on the active tab
- * and a subtle hover highlight.  Sends WM_APP_TAB_SELECTED to the parent window
+ * Renders a row of text tabs, each optionally led by a registered image, with a
+ * colored bottom accent line on the active tab and a subtle hover highlight.  Sends WM_APP_TAB_SELECTED to the parent window
  * when the user clicks a tab.
  *
+ * Each tab is a tool of the tab bar's tooltip. A tab without a tooltip of its
+ * own asks the root window with WM_APP_TAB_TOOLTIP_REQUESTED, which consults
+ * the UiStateProvider. [CDU-TooltipsV1]
+ *
  * Per-instance state is stored in GWLP_USERDATA as a heap-allocated
  * `TabBarState`, matching the pattern used by `chart_handler` and `splitter_handler`.
  */
 
 use crate::app::Win32ApiInternalState;
-use crate::controls::styling_handler::color_to_colorref;
+use crate::controls::image_handler::{self, SharedImageRegistry};
+use crate::controls::styling_handler::{
+    color_to_colorref, font_decoration_flags, inset_by_padding, paint_styled_rect,
+};
+use crate::controls::tooltip_handler;
+use crate::dpi::DpiScale;
 use crate::error::{PlatformError, Result as PlatformResult};
+use crate::image::{IMAGE_TEXT_GAP, SMALL_IMAGE_SIZE};
 use crate::styling::Color;
-use crate::styling_primitives::FontDescription;
-use crate::types::{ControlId, WindowId};
-use crate::window_common::{ControlKind, WM_APP_TAB_SELECTED};
+use crate::styling_primitives::{FontDescription, InteractionState};
+use crate::styling_windows::ParsedControlStyle;
+use crate::theme::TabBarColors;
+use crate::tooltip::TooltipOptions;
+use crate::types::{ControlId, ImageId, WindowId};
+use crate::window_common::{self, ControlKind, WM_APP_TAB_SELECTED, WM_APP_TAB_TOOLTIP_REQUESTED};
 
 use std::sync::{Arc, OnceLock};
 
@@ -24,49 +37,32 @@ use windows::Win32::{
     Graphics::Gdi::{
         BeginPaint, CLIP_DEFAULT_PRECIS, CreateFontW, CreateSolidBrush, DEFAULT_CHARSET,
         DEFAULT_GUI_FONT, DEFAULT_QUALITY, DeleteObject, EndPaint, FF_DONTCARE, FW_BOLD, FW_NORMAL,
-        FillRect, GetDC, GetDeviceCaps, GetStockObject, GetTextExtentPoint32W, HDC, HFONT, HGDIOBJ,
-        InvalidateRect, LOGPIXELSY, OUT_DEFAULT_PRECIS, PAINTSTRUCT, ReleaseDC, SelectObject,
-        SetBkMode, SetTextColor, TRANSPARENT, TextOutW,
+        FillRect, GetStockObject, GetTextExtentPoint32W, HDC, HFONT, HGDIOBJ, InvalidateRect,
+        OUT_DEFAULT_PRECIS, PAINTSTRUCT, SelectObject, SetBkMode, SetTextColor, TRANSPARENT,
+        TextOutW,
     },
-    System::WindowsProgramming::MulDiv,
     UI::{
+        Controls::{
+            NMHDR, TTF_SUBCLASS, TTM_ADDTOOLW, TTM_DELTOOLW, TTN_GETDISPINFOW, TTTOOLINFOW,
+        },
         Input::KeyboardAndMouse::{TME_LEAVE, TRACKMOUSEEVENT, TrackMouseEvent},
         WindowsAndMessaging::{
-            CS_HREDRAW, CS_VREDRAW, CreateWindowExW, DefWindowProcW, GET_ANCESTOR_FLAGS,
-            GWLP_USERDATA, GetAncestor, GetClientRect, GetWindowLongPtrW, HMENU, RegisterClassW,
-            SendMessageW, SetWindowLongPtrW, WINDOW_EX_STYLE, WM_DESTROY, WM_ERASEBKGND,
-            WM_LBUTTONDOWN, WM_MOUSEMOVE, WM_PAINT, WM_SIZE, WNDCLASSW, WS_CHILD, WS_VISIBLE,
+            CS_HREDRAW, CS_VREDRAW, CreateWindowExW, DefWindowProcW, DestroyWindow,
+            GET_ANCESTOR_FLAGS, GWLP_USERDATA, GetAncestor, GetClientRect, GetWindowLongPtrW,
+            HMENU, RegisterClassW, SendMessageW, SetWindowLongPtrW, WINDOW_EX_STYLE, WM_DESTROY,
+            WM_ERASEBKGND, WM_LBUTTONDOWN, WM_MOUSEMOVE, WM_NOTIFY, WM_PAINT, WM_SIZE, WNDCLASSW,
+            WS_CHILD, WS_VISIBLE,
         },
     },
 };
-use windows::core::{HSTRING, PCWSTR, w};
+use windows::core::{HSTRING, PCWSTR, PWSTR, w};
 
 // WM_MOUSELEAVE is not exported by windows-rs; define the constant directly.
 const WM_MOUSELEAVE: u32 = 0x02A3;
 
-// ── Default palette ───────────────────────────────────────────────────────────
-
-fn default_background() -> Color {
-    Color {
-        r: 0x2E,
-        g: 0x32,
-        b: 0x39,
-    }
-}
-fn default_text() -> Color {
-    Color {
-        r: 0xE0,
-        g: 0xE5,
-        b: 0xEC,
-    }
-}
-fn default_accent() -> Color {
-    Color {
-        r: 0x00,
-        g: 0x80,
-        b: 0xFF,
-    }
-}
+// LPSTR_TEXTCALLBACKW is not exported by windows-rs either: a tool whose text
+// is this sentinel asks for it with TTN_GETDISPINFOW.
+const LPSTR_TEXTCALLBACKW: PWSTR = PWSTR(-1isize as *mut u16);
 
 // ── TabBarPalette ─────────────────────────────────────────────────────────────
 
@@ -88,29 +84,9 @@ impl TabBarPalette {
     /// the primary colors.  The derivation is deterministic so it is
     /// independently testable.
     pub(crate) fn new(background: Color, text: Color, accent: Color) -> Self {
-        // text_inactive = 40% text color + 60% background color
-        let blend = |a: u8, b: u8| -> u8 {
-            let a16 = a as u16;
-            let b16 = b as u16;
-            ((a16 * 40 + b16 * 60) / 100) as u8
-        };
-        let text_inactive = Color {
-            r: blend(text.r, background.r),
-            g: blend(text.g, background.g),
-            b: blend(text.b, background.b),
-        };
-
-        // hover_fill = background + 6% white overlay
-        let overlay = |base: u8| -> u8 {
-            let base16 = base as u16;
-            let extra = (255u16 * 6) / 100; // ~15
-            (base16 + extra).min(255) as u8
-        };
-        let hover_fill = Color {
-            r: overlay(background.r),
-            g: overlay(background.g),
-            b: overlay(background.b),
-        };
+        let text_inactive = background.mix_percent(&text, 40);
+        // 6% of white, about 15 per channel.
+        let hover_fill = background.offset(15);
 
         Self {
             background,
@@ -122,9 +98,15 @@ impl TabBarPalette {
     }
 }
 
+impl TabBarPalette {
+    pub(crate) fn from_colors(colors: TabBarColors) -> Self {
+        Self::new(colors.background, colors.text, colors.accent)
+    }
+}
+
 impl Default for TabBarPalette {
     fn default() -> Self {
-        Self::new(default_background(), default_text(), default_accent())
+        Self::from_colors(TabBarColors::default())
     }
 }
 
@@ -133,6 +115,9 @@ impl Default for TabBarPalette {
 /// Per-instance heap-allocated state stored in GWLP_USERDATA.
 struct TabBarState {
     items: Vec<String>,
+    /// Image of each tab by index; tabs past the end have none.
+    images: Vec<Option<ImageId>>,
+    registry: SharedImageRegistry,
     selected_index: usize,
     hover_index: Option<usize>,
     tracking_mouse: bool,
@@ -140,26 +125,74 @@ struct TabBarState {
     item_rects: Vec<RECT>,
     palette: TabBarPalette,
     /// Optional style-driven font; if None the control uses DEFAULT_GUI_FONT.
-    font: Option<HFONT>,
+    font_desc: Option<FontDescription>,
+    /// `font_desc` created for the DPI the tab bar was last painted at.
+    font: Option<(DpiScale, HFONT)>,
+    /// Style applied with `ApplyStyleToControl`: hovered/pressed colors for
+    /// hovered/active tabs, border, corner radius, padding and fallback font.
+    style: Option<Arc<ParsedControlStyle>>,
+    /// Tooltip of each tab by index; tabs without one ask the UiStateProvider.
+    tooltips: Vec<Option<String>>,
+    /// Tooltip control with one tool per tab, laid out at the painted rects.
+    hwnd_tooltip: Option<HWND>,
+    /// Number of tools currently added to `hwnd_tooltip`.
+    tool_count: usize,
+    /// Text of the tip being shown; the tooltip reads it after TTN_GETDISPINFOW returns.
+    tooltip_text: Vec<u16>,
+    /// The UiStateProvider's answer to the last WM_APP_TAB_TOOLTIP_REQUESTED.
+    provided_tooltip: Option<String>,
 }
 
 impl TabBarState {
     fn new(items: Vec<String>) -> Self {
         Self {
             items,
+            images: Vec::new(),
+            registry: SharedImageRegistry::default(),
             selected_index: 0,
             hover_index: None,
             tracking_mouse: false,
             item_rects: Vec::new(),
             palette: TabBarPalette::default(),
+            font_desc: None,
             font: None,
+            style: None,
+            tooltips: Vec::new(),
+            hwnd_tooltip: None,
+            tool_count: 0,
+            tooltip_text: Vec::new(),
+            provided_tooltip: None,
         }
     }
 }
 
-impl Drop for TabBarState {
-    fn drop(&mut self) {
-        if let Some(hfont) = self.font.take().filter(|f| !f.is_invalid()) {
+impl TabBarState {
+    /*
+     * The SetTabBarStyle font at `scale`, re-created when the tab bar has
+     * moved to a monitor with a different DPI since it was last painted.
+     */
+    fn font_for_dpi(&mut self, scale: DpiScale) -> Option<HFONT> {
+        if let Some((cached_scale, hfont)) = self.font
+            && cached_scale == scale
+        {
+            return Some(hfont);
+        }
+        self.release_font();
+        let font_desc = self.font_desc.as_ref()?;
+        match create_hfont(font_desc, scale) {
+            Ok(hfont) => {
+                self.font = Some((scale, hfont));
+                Some(hfont)
+            }
+            Err(e) => {
+                log::warn!("[TabBar] font creation failed: {e:?}; using default");
+                None
+            }
+        }
+    }
+
+    fn release_font(&mut self) {
+        if let Some((_, hfont)) = self.font.take().filter(|(_, f)| !f.is_invalid()) {
             unsafe {
                 let _ = DeleteObject(hfont.into());
             }
@@ -167,6 +200,15 @@ impl Drop for TabBarState {
     }
 }
 
+impl Drop for TabBarState {
+    fn drop(&mut self) {
+        self.release_font();
+        if let Some(hwnd_tooltip) = self.hwnd_tooltip.take() {
+            let _ = unsafe { DestroyWindow(hwnd_tooltip) };
+        }
+    }
+}
+
 /// Gets or lazily allocates state from GWLP_USERDATA (like chart_handler).
 unsafe fn get_or_init_state(hwnd: HWND) -> *mut TabBarState {
     unsafe {
@@ -276,6 +318,14 @@ unsafe extern "system" fn tab_bar_wnd_proc(
             }
             LRESULT(0)
         }
+        WM_NOTIFY => {
+            let nmhdr = lparam.0 as *const NMHDR;
+            if !nmhdr.is_null() && unsafe { (*nmhdr).code } == TTN_GETDISPINFOW {
+                unsafe { answer_tab_tooltip(hwnd, (*nmhdr).idFrom, lparam) };
+                return LRESULT(0);
+            }
+            unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+        }
         WM_MOUSELEAVE => {
             unsafe {
                 let state = get_or_init_state(hwnd);
@@ -299,6 +349,92 @@ unsafe extern "system" fn tab_bar_wnd_proc(
     }
 }
 
+// ── Tooltips ──────────────────────────────────────────────────────────────────
+
+/*
+ * Answers TTN_GETDISPINFOW for the tab at `index`: its own tooltip or, when
+ * it has none, the one the root window obtains from the UiStateProvider.
+ * No reference into the state is held while the root window is asked.
+ */
+unsafe fn answer_tab_tooltip(hwnd: HWND, index: usize, lparam: LPARAM) {
+    let state = unsafe { get_or_init_state(hwnd) };
+    let own = unsafe {
+        let tooltips = &(*state).tooltips;
+        tooltips.get(index).cloned().flatten()
+    };
+    let text = crate::tooltip::item_tooltip(own.as_deref(), || {
+        unsafe { (*state).provided_tooltip = None };
+        let root = unsafe { GetAncestor(hwnd, GET_ANCESTOR_FLAGS(2)) }; // GA_ROOT
+        if !root.is_invalid() {
+            unsafe {
+                SendMessageW(
+                    root,
+                    WM_APP_TAB_TOOLTIP_REQUESTED,
+                    Some(WPARAM(hwnd.0 as usize)),
+                    Some(LPARAM(index as isize)),
+                )
+            };
+        }
+        unsafe { (*state).provided_tooltip.take() }
+    });
+    unsafe {
+        tooltip_handler::answer_get_disp_info(lparam, &mut (*state).tooltip_text, text.as_deref())
+    };
+}
+
+/// Stores the UiStateProvider's tooltip while the tab bar waits for it.
+pub(crate) fn set_provided_tooltip(hwnd: HWND, text: Option<String>) {
+    unsafe { (*get_or_init_state(hwnd)).provided_tooltip = text };
+}
+
+/// The tooltip showing the tabs' tips.
+pub(crate) fn tooltip_of(hwnd: HWND) -> Option<HWND> {
+    let ptr = unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) } as *mut TabBarState;
+    if ptr.is_null() {
+        return None;
+    }
+    unsafe { (*ptr).hwnd_tooltip }
+}
+
+// Replaces the tooltip's tools with one per tab, at the rects just painted.
+fn sync_tooltip_tools(hwnd: HWND, state: &mut TabBarState) {
+    let Some(hwnd_tooltip) = state.hwnd_tooltip else {
+        return;
+    };
+    let tool = |index: usize, rect: RECT| TTTOOLINFOW {
+        cbSize: std::mem::size_of::<TTTOOLINFOW>() as u32,
+        uFlags: TTF_SUBCLASS,
+        hwnd,
+        uId: index,
+        rect,
+        lpszText: LPSTR_TEXTCALLBACKW,
+        ..Default::default()
+    };
+    for index in 0..state.tool_count {
+        let info = tool(index, RECT::default());
+        unsafe {
+            SendMessageW(
+                hwnd_tooltip,
+                TTM_DELTOOLW,
+                Some(WPARAM(0)),
+                Some(LPARAM(&info as *const TTTOOLINFOW as isize)),
+            )
+        };
+    }
+    for (index, rect) in state.item_rects.iter().enumerate() {
+        let info = tool(index, *rect);
+        unsafe {
+            SendMessageW(
+                hwnd_tooltip,
+                TTM_ADDTOOLW,
+                Some(WPARAM(0)),
+                Some(LPARAM(&info as *const TTTOOLINFOW as isize)),
+            )
+        };
+    }
+    state.tool_count = state.item_rects.len();
+}
+
 // ── Hit-test helper ───────────────────────────────────────────────────────────
 
 fn hit_test(rects: &[RECT], x: i32, y: i32) -> Option<usize> {
@@ -330,36 +466,74 @@ unsafe fn paint_tab_bar(hwnd: HWND, hdc: HDC) {
         return;
     }
 
-    // Select font (saves the old one for restoration).
+    // Select font (saves the old one for restoration): SetTabBarStyle's font,
+    // then the applied style's font, then the stock GUI font.
     let stock_font: HGDIOBJ = unsafe { GetStockObject(DEFAULT_GUI_FONT) };
-    let font_hgdiobj: HGDIOBJ = if let Some(hf) = state.font {
-        if hf.is_invalid() {
-            stock_font
-        } else {
-            hf.into()
-        }
-    } else {
-        stock_font
-    };
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let font_hgdiobj: HGDIOBJ = state
+        .font_for_dpi(scale)
+        .or_else(|| state.style.as_ref().and_then(|s| s.font_for_dpi(scale)))
+        .filter(|hf| !hf.is_invalid())
+        .map_or(stock_font, Into::into);
     let old_font = unsafe { SelectObject(hdc, font_hgdiobj) };
 
-    // Fill background.
-    let bg_cr = color_to_colorref(&state.palette.background);
-    let bg_brush = unsafe { CreateSolidBrush(bg_cr) };
-    let _ = unsafe { FillRect(hdc, &client, bg_brush) };
-    let _ = unsafe { DeleteObject(bg_brush.into()) };
+    let source = state.style.as_ref().map(|s| &s.source);
+
+    // Fill background and draw the style's border, if any.
+    let (border_color, border_width, corner_radius) = source.map_or((None, 0, 0), |s| {
+        (
+            s.border_color.as_ref(),
+            s.border_width_for(InteractionState::Normal),
+            s.corner_radius.unwrap_or(0).max(0),
+        )
+    });
+    unsafe {
+        paint_styled_rect(
+            hdc,
+            &client,
+            Some(&state.palette.background),
+            border_color,
+            border_width,
+            corner_radius,
+            scale,
+        )
+    };
 
     // Compute tab rects using text extents.
-    let h_pad = 16i32; // pixels of horizontal padding on each side of the label
+    // DIPs of horizontal padding on each side of the label; a style's padding overrides it.
+    let h_pad = scale.scale(
+        source
+            .and_then(|s| s.padding)
+            .map_or(16i32, |(_, right, _, left)| left.max(right).max(0)),
+    );
+    let hover_colors = source.map(|s| s.colors_for(InteractionState::Hovered));
+    let active_colors = source.map(|s| s.colors_for(InteractionState::Pressed));
     let mut x_cursor = 0i32;
     let mut new_rects: Vec<RECT> = Vec::with_capacity(state.items.len());
     let mut tab_widths: Vec<i32> = Vec::with_capacity(state.items.len());
 
-    for label in &state.items {
+    // [CDU-ImagesV1] Tabs with a registered image reserve room for it before the label.
+    let image_size = scale.scale(SMALL_IMAGE_SIZE);
+    let image_widths: Vec<i32> =
+        (0..state.items.len())
+            .map(|i| {
+                let registered =
+                    state.images.get(i).copied().flatten().filter(|&image_id| {
+                        image_handler::is_registered(&state.registry, image_id)
+                    });
+                match (registered, state.items[i].is_empty()) {
+                    (None, _) => 0,
+                    (Some(_), true) => image_size,
+                    (Some(_), false) => image_size + scale.scale(IMAGE_TEXT_GAP),
+                }
+            })
+            .collect();
+
+    for (label, image_width) in state.items.iter().zip(&image_widths) {
         let wide: Vec<u16> = label.encode_utf16().collect();
         let mut sz = SIZE::default();
         let _ = unsafe { GetTextExtentPoint32W(hdc, &wide, &mut sz) };
-        tab_widths.push(sz.cx + h_pad * 2);
+        tab_widths.push(image_width + sz.cx + h_pad * 2);
     }
     for &tw in &tab_widths {
         new_rects.push(RECT {
@@ -370,15 +544,33 @@ unsafe fn paint_tab_bar(hwnd: HWND, hdc: HDC) {
         });
         x_cursor += tw;
     }
-    state.item_rects = new_rects.clone();
+    let tools_changed = state.item_rects != new_rects || state.tool_count != new_rects.len();
 
     // Draw tabs.
     unsafe { SetBkMode(hdc, TRANSPARENT) };
-    let accent_h = 3i32;
+    let accent_h = scale.scale(3);
 
     for (i, (tab_rect, label)) in new_rects.iter().zip(state.items.iter()).enumerate() {
         // Hover highlight for non-active hovered tab.
-        if state.hover_index == Some(i) && i != state.selected_index {
+        // Only a style's own hover/pressed overrides replace the palette colors.
+        let is_hovered = state.hover_index == Some(i) && i != state.selected_index;
+        let style_colors = if i == state.selected_index {
+            active_colors
+                .as_ref()
+                .filter(|_| source.is_some_and(|s| s.pressed.is_some() || s.hover.is_some()))
+        } else if is_hovered {
+            hover_colors
+                .as_ref()
+                .filter(|_| source.is_some_and(|s| s.hover.is_some()))
+        } else {
+            None
+        };
+
+        if let Some(fill) = style_colors.and_then(|c| c.background_color.as_ref()) {
+            let fill_brush = unsafe { CreateSolidBrush(color_to_colorref(fill)) };
+            let _ = unsafe { FillRect(hdc, tab_rect, fill_brush) };
+            let _ = unsafe { DeleteObject(fill_brush.into()) };
+        } else if is_hovered {
             let hover_cr = color_to_colorref(&state.palette.hover_fill);
             let hover_brush = unsafe { CreateSolidBrush(hover_cr) };
             let _ = unsafe { FillRect(hdc, tab_rect, hover_brush) };
@@ -386,19 +578,36 @@ unsafe fn paint_tab_bar(hwnd: HWND, hdc: HDC) {
         }
 
         // Text color: bright for active, dimmed for inactive.
-        let txt_cr = if i == state.selected_index {
-            color_to_colorref(&state.palette.text_active)
-        } else {
-            color_to_colorref(&state.palette.text_inactive)
+        let txt_cr = match style_colors.and_then(|c| c.text_color.as_ref()) {
+            Some(color) => color_to_colorref(color),
+            None if i == state.selected_index => color_to_colorref(&state.palette.text_active),
+            None => color_to_colorref(&state.palette.text_inactive),
         };
         let _ = unsafe { SetTextColor(hdc, txt_cr) };
 
-        // Center text horizontally and vertically in the tab rect.
+        // Center text horizontally and vertically in the padded tab rect.
+        let text_rect = inset_by_padding(tab_rect, source.and_then(|s| s.padding), scale);
         let wide: Vec<u16> = label.encode_utf16().collect();
         let mut sz = SIZE::default();
         let _ = unsafe { GetTextExtentPoint32W(hdc, &wide, &mut sz) };
-        let text_x = tab_rect.left + (tab_rect.right - tab_rect.left - sz.cx) / 2;
-        let text_y = (h - sz.cy) / 2;
+        let content_x =
+            tab_rect.left + (tab_rect.right - tab_rect.left - image_widths[i] - sz.cx) / 2;
+        if let Some(image_id) = state.images.get(i).copied().flatten()
+            && image_widths[i] > 0
+        {
+            let image_top = text_rect.top + (text_rect.bottom - text_rect.top - image_size) / 2;
+            let image_rect = RECT {
+                left: content_x,
+                top: image_top,
+                right: content_x + image_size,
+                bottom: image_top + image_size,
+            };
+            unsafe {
+                image_handler::draw_registered_image(hdc, &state.registry, image_id, &image_rect)
+            };
+        }
+        let text_x = content_x + image_widths[i];
+        let text_y = text_rect.top + (text_rect.bottom - text_rect.top - sz.cy) / 2;
         let _ = unsafe { TextOutW(hdc, text_x, text_y, &wide) };
     }
 
@@ -410,7 +619,12 @@ unsafe fn paint_tab_bar(hwnd: HWND, hdc: HDC) {
             right: active_rect.right,
             bottom: h,
         };
-        let accent_cr = color_to_colorref(&state.palette.accent);
+        // A pressed-state border color recolors the accent line of the active tab.
+        let accent = active_colors
+            .as_ref()
+            .and_then(|c| c.border_color.as_ref())
+            .unwrap_or(&state.palette.accent);
+        let accent_cr = color_to_colorref(accent);
         let accent_brush = unsafe { CreateSolidBrush(accent_cr) };
         let _ = unsafe { FillRect(hdc, &accent_rect, accent_brush) };
         let _ = unsafe { DeleteObject(accent_brush.into()) };
@@ -418,25 +632,19 @@ unsafe fn paint_tab_bar(hwnd: HWND, hdc: HDC) {
 
     // Restore font.
     unsafe { SelectObject(hdc, old_font) };
+
+    // Re-register the tab tools only after the style is no longer borrowed.
+    if tools_changed {
+        state.item_rects = new_rects;
+        sync_tooltip_tools(hwnd, state);
+    }
 }
 
 // ── Font creation helper ──────────────────────────────────────────────────────
 
-/// Creates an HFONT from a `FontDescription`.  Returns `Ok(None)` if `font_desc`
-/// is `None`.  Follows the same pattern as `Win32ApiInternalState::define_style`.
-fn create_hfont(font_desc: &FontDescription) -> PlatformResult<HFONT> {
-    let hdc_screen = unsafe { GetDC(None) };
-    if hdc_screen.is_invalid() {
-        return Err(PlatformError::OperationFailed(
-            "TabBar: could not acquire screen DC for font creation".into(),
-        ));
-    }
-    let logical_height = if let Some(pt) = font_desc.size {
-        -unsafe { MulDiv(pt, GetDeviceCaps(Some(hdc_screen), LOGPIXELSY), 72) }
-    } else {
-        0
-    };
-    unsafe { ReleaseDC(None, hdc_screen) };
+/// Creates an HFONT from a `FontDescription` at the pixel size for `scale`.
+fn create_hfont(font_desc: &FontDescription, scale: DpiScale) -> PlatformResult<HFONT> {
+    let logical_height = font_desc.size.map_or(0, |pt| -scale.font_pixel_size(pt));
 
     use crate::styling_primitives::FontWeight;
     let weight = match font_desc.weight {
@@ -445,6 +653,7 @@ fn create_hfont(font_desc: &FontDescription) -> PlatformResult<HFONT> {
     };
     let name = font_desc.name.as_deref().unwrap_or("MS Shell Dlg 2");
     let name_str = HSTRING::from(name);
+    let (italic, underline, strike_out) = font_decoration_flags(font_desc);
     let hfont = unsafe {
         CreateFontW(
             logical_height,
@@ -452,9 +661,9 @@ fn create_hfont(font_desc: &FontDescription) -> PlatformResult<HFONT> {
             0,
             0,
             weight,
-            0,
-            0,
-            0,
+            italic,
+            underline,
+            strike_out,
             DEFAULT_CHARSET,
             OUT_DEFAULT_PRECIS,
             CLIP_DEFAULT_PRECIS,
@@ -481,6 +690,8 @@ pub(crate) fn handle_create_tab_bar_command(
     control_id: ControlId,
     parent_control_id: Option<ControlId>,
     items: Vec<String>,
+    images: Vec<Option<ImageId>>,
+    tooltips: Vec<Option<String>>,
 ) -> PlatformResult<()> {
     log::debug!(
         "[TabBar] handle_create_tab_bar_command WinID={window_id:?} ControlID={} ParentID={:?}",
@@ -560,7 +771,21 @@ pub(crate) fn handle_create_tab_bar_command(
     };
 
     // Initialise GWLP_USERDATA with items.
-    let state = Box::new(TabBarState::new(items));
+    let theme = internal_state.current_theme();
+    let mut state = Box::new(TabBarState::new(items));
+    state.images = images;
+    state.tooltips = tooltips;
+    state.registry = internal_state.image_registry();
+    state.palette = TabBarPalette::from_colors(theme.resolved_tab_bar_colors());
+    state.hwnd_tooltip =
+        tooltip_handler::create_tooltip_window(hwnd_tab_bar, h_instance, &theme.tooltip);
+    if let Some(hwnd_tooltip) = state.hwnd_tooltip {
+        tooltip_handler::apply_tooltip_options(
+            hwnd_tooltip,
+            &TooltipOptions::default().multiline(),
+            window_common::dpi_scale_for_window(hwnd_tab_bar),
+        );
+    }
     unsafe {
         SetWindowLongPtrW(hwnd_tab_bar, GWLP_USERDATA, Box::into_raw(state) as isize);
     }
@@ -578,12 +803,14 @@ pub(crate) fn handle_create_tab_bar_command(
     Ok(())
 }
 
-/// Replaces all tab labels and triggers a repaint.
+/// Replaces all tab labels, images and tooltips and triggers a repaint.
 pub(crate) fn handle_set_tab_bar_items(
     internal_state: &Arc<Win32ApiInternalState>,
     window_id: WindowId,
     control_id: ControlId,
     items: Vec<String>,
+    images: Vec<Option<ImageId>>,
+    tooltips: Vec<Option<String>>,
 ) -> PlatformResult<()> {
     let hwnd = internal_state.with_window_data_read(window_id, |window_data| {
         window_data.get_control_hwnd(control_id).ok_or_else(|| {
@@ -596,6 +823,8 @@ pub(crate) fn handle_set_tab_bar_items(
     unsafe {
         let state = get_or_init_state(hwnd);
         (*state).items = items;
+        (*state).images = images;
+        (*state).tooltips = tooltips;
         (*state).selected_index = 0;
         (*state).item_rects.clear();
         let _ = InvalidateRect(Some(hwnd), None, false);
@@ -649,30 +878,51 @@ pub(crate) fn handle_set_tab_bar_style(
         })
     })?;
 
-    let new_font: Option<HFONT> = match &font_desc {
-        Some(fd) => match create_hfont(fd) {
-            Ok(hf) => Some(hf),
-            Err(e) => {
-                log::warn!("[TabBar] SetTabBarStyle: font creation failed: {e:?}; using default");
-                None
-            }
-        },
-        None => None,
-    };
-
     unsafe {
         let state = get_or_init_state(hwnd);
-        // Drop old font if any.
-        if let Some(old_font) = (*state).font.take().filter(|f| !f.is_invalid()) {
-            let _ = DeleteObject(old_font.into());
-        }
+        // The font itself is created on the next paint, at the tab bar's DPI.
+        (*state).release_font();
         (*state).palette = TabBarPalette::new(background_color, text_color, accent_color);
-        (*state).font = new_font;
+        (*state).font_desc = font_desc;
         let _ = InvalidateRect(Some(hwnd), None, false);
     }
     Ok(())
 }
 
+/// Replaces the palette (keeping any style-driven font), e.g. when a theme is applied.
+pub(crate) fn apply_tab_bar_palette(hwnd: HWND, palette: TabBarPalette) {
+    unsafe {
+        let state = get_or_init_state(hwnd);
+        (*state).palette = palette;
+        let _ = InvalidateRect(Some(hwnd), None, false);
+    }
+}
+
+/*
+ * Stores a style applied with `ApplyStyleToControl`. Its base colors replace
+ * the palette's background and text; the remaining fields are read by
+ * `paint_tab_bar`. The style's font is owned by the style registry.
+ */
+pub(crate) fn apply_control_style(hwnd: HWND, style: Arc<ParsedControlStyle>) {
+    unsafe {
+        let state = &mut *get_or_init_state(hwnd);
+        if style.background_color.is_some() || style.text_color.is_some() {
+            let palette = &state.palette;
+            let background = style
+                .background_color
+                .clone()
+                .unwrap_or_else(|| palette.background.clone());
+            let text = style
+                .text_color
+                .clone()
+                .unwrap_or_else(|| palette.text_active.clone());
+            state.palette = TabBarPalette::new(background, text, palette.accent.clone());
+        }
+        state.style = Some(style);
+        let _ = InvalidateRect(Some(hwnd), None, false);
+    }
+}
+
 // ── Unit tests ─────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -681,21 +931,9 @@ mod tests {
 
     #[test]
     fn tab_bar_palette_derives_text_inactive_and_hover_fill() {
-        let bg = Color {
-            r: 0x2E,
-            g: 0x32,
-            b: 0x39,
-        };
-        let text = Color {
-            r: 0xE0,
-            g: 0xE5,
-            b: 0xEC,
-        };
-        let accent = Color {
-            r: 0x00,
-            g: 0x80,
-            b: 0xFF,
-        };
+        let bg = Color::rgb(0x2E, 0x32, 0x39);
+        let text = Color::rgb(0xE0, 0xE5, 0xEC);
+        let accent = Color::rgb(0x00, 0x80, 0xFF);
         let palette = TabBarPalette::new(bg.clone(), text.clone(), accent.clone());
 
         // text_inactive = 40% text + 60% background
@@ -11,11 +11,15 @@
  */
 
 use crate::app::Win32ApiInternalState;
-use crate::controls::styling_handler::color_to_colorref;
+use crate::controls::styling_handler::{color_to_colorref, inset_by_padding, paint_styled_rect};
 use crate::error::{PlatformError, Result as PlatformResult};
-use crate::styling::Color;
+use crate::styling_primitives::InteractionState;
+use crate::styling_windows::ParsedControlStyle;
+use crate::theme::ToggleSwitchPalette;
 use crate::types::{ControlId, WindowId};
-use crate::window_common::{ControlKind, WM_APP_TOGGLE_SWITCH_CLICKED, try_enable_dark_mode};
+use crate::window_common::{
+    self, ControlKind, WM_APP_TOGGLE_SWITCH_CLICKED, apply_native_color_mode,
+};
 
 use std::sync::{Arc, OnceLock};
 
@@ -23,64 +27,26 @@ use windows::Win32::{
     Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM},
     Graphics::Gdi::{
         BeginPaint, CreatePen, CreateSolidBrush, DEFAULT_GUI_FONT, DeleteObject, DrawFocusRect,
-        Ellipse, EndPaint, FillRect, GetStockObject, HDC, HGDIOBJ, InvalidateRect, PAINTSTRUCT,
-        PS_SOLID, RoundRect, SelectObject, SetBkMode, SetTextColor, TRANSPARENT, TextOutW,
+        Ellipse, EndPaint, GetStockObject, HDC, HGDIOBJ, InvalidateRect, PAINTSTRUCT, PS_SOLID,
+        RoundRect, SelectObject, SetBkMode, SetTextColor, TRANSPARENT, TextOutW,
     },
     UI::{
-        Input::KeyboardAndMouse::{VK_RETURN, VK_SPACE},
+        Input::KeyboardAndMouse::{
+            IsWindowEnabled, TME_LEAVE, TRACKMOUSEEVENT, TrackMouseEvent, VK_RETURN, VK_SPACE,
+        },
         WindowsAndMessaging::{
             CS_HREDRAW, CS_VREDRAW, CreateWindowExW, DefWindowProcW, GET_ANCESTOR_FLAGS,
             GWLP_USERDATA, GetAncestor, GetClientRect, GetWindowLongPtrW, HMENU, RegisterClassW,
             SendMessageW, SetWindowLongPtrW, WINDOW_EX_STYLE, WM_DESTROY, WM_ERASEBKGND,
-            WM_KEYDOWN, WM_KILLFOCUS, WM_LBUTTONUP, WM_PAINT, WM_SETFOCUS, WNDCLASSW, WS_CHILD,
-            WS_TABSTOP, WS_VISIBLE,
+            WM_KEYDOWN, WM_KILLFOCUS, WM_LBUTTONUP, WM_MOUSEMOVE, WM_PAINT, WM_SETFOCUS, WNDCLASSW,
+            WS_CHILD, WS_TABSTOP, WS_VISIBLE,
         },
     },
 };
 use windows::core::{HSTRING, PCWSTR, w};
 
-// ── Palette ───────────────────────────────────────────────────────────────────
-
-#[derive(Debug, Clone)]
-pub(crate) struct ToggleSwitchPalette {
-    pub background: Color,
-    pub pill_off: Color,
-    pub pill_on: Color,
-    pub knob: Color,
-    pub text: Color,
-}
-
-impl Default for ToggleSwitchPalette {
-    fn default() -> Self {
-        Self {
-            background: Color {
-                r: 0x2B,
-                g: 0x2B,
-                b: 0x2B,
-            },
-            pill_off: Color {
-                r: 0x4B,
-                g: 0x4F,
-                b: 0x57,
-            },
-            pill_on: Color {
-                r: 0x00,
-                g: 0x80,
-                b: 0xFF,
-            },
-            knob: Color {
-                r: 0xF0,
-                g: 0xF0,
-                b: 0xF0,
-            },
-            text: Color {
-                r: 0xCC,
-                g: 0xCC,
-                b: 0xCC,
-            },
-        }
-    }
-}
+// WM_MOUSELEAVE is not exported by windows-rs; define the constant directly.
+const WM_MOUSELEAVE: u32 = 0x02A3;
 
 // ── ToggleSwitchState ─────────────────────────────────────────────────────────
 
@@ -88,8 +54,13 @@ impl Default for ToggleSwitchPalette {
 struct ToggleSwitchState {
     checked: bool,
     focused: bool,
+    hovered: bool,
+    tracking_mouse: bool,
     label: String,
     palette: ToggleSwitchPalette,
+    /// Style applied with `ApplyStyleToControl`; its state colors override the
+    /// palette's background and text, and it supplies border, padding and font.
+    style: Option<Arc<ParsedControlStyle>>,
     // No event_tx — events are delivered via WM_APP_TOGGLE_SWITCH_CLICKED
     // to the root window WndProc, consistent with tab_bar and splitter patterns.
 }
@@ -99,8 +70,11 @@ impl ToggleSwitchState {
         Self {
             checked,
             focused: false,
+            hovered: false,
+            tracking_mouse: false,
             label,
             palette: ToggleSwitchPalette::default(),
+            style: None,
         }
     }
 }
@@ -131,7 +105,7 @@ fn register_toggle_switch_class(h_instance: windows::Win32::Foundation::HINSTANC
 
 // ── WndProc ───────────────────────────────────────────────────────────────────
 
-// Pill geometry constants (logical pixels).
+// Pill geometry constants in DIPs; scaled to the window DPI when painting.
 const PILL_W: i32 = 28;
 const PILL_H: i32 = 16;
 const KNOB_D: i32 = 12; // knob diameter
@@ -227,6 +201,41 @@ unsafe extern "system" fn toggle_switch_wnd_proc(
             }
             LRESULT(0)
         }
+        WM_MOUSEMOVE => {
+            unsafe {
+                let ptr = GetWindowLongPtrW(hwnd, GWLP_USERDATA);
+                if ptr != 0 {
+                    let state = ptr as *mut ToggleSwitchState;
+                    if !(*state).tracking_mouse {
+                        let mut tme = TRACKMOUSEEVENT {
+                            cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                            dwFlags: TME_LEAVE,
+                            hwndTrack: hwnd,
+                            dwHoverTime: 0,
+                        };
+                        let _ = TrackMouseEvent(&mut tme);
+                        (*state).tracking_mouse = true;
+                    }
+                    if !(*state).hovered {
+                        (*state).hovered = true;
+                        let _ = InvalidateRect(Some(hwnd), None, false);
+                    }
+                }
+            }
+            LRESULT(0)
+        }
+        WM_MOUSELEAVE => {
+            unsafe {
+                let ptr = GetWindowLongPtrW(hwnd, GWLP_USERDATA);
+                if ptr != 0 {
+                    let state = ptr as *mut ToggleSwitchState;
+                    (*state).tracking_mouse = false;
+                    (*state).hovered = false;
+                    let _ = InvalidateRect(Some(hwnd), None, false);
+                }
+            }
+            LRESULT(0)
+        }
         WM_DESTROY => {
             let ptr = unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) };
             if ptr != 0 {
@@ -256,21 +265,65 @@ unsafe fn paint_toggle_switch(hwnd: HWND, hdc: HDC) {
         return;
     }
 
-    // Fill background.
-    let bg_cr = color_to_colorref(&state.palette.background);
-    let bg_brush = unsafe { CreateSolidBrush(bg_cr) };
-    let _ = unsafe { FillRect(hdc, &client, bg_brush) };
-    let _ = unsafe { DeleteObject(bg_brush.into()) };
+    // Resolve the applied style's colors for the current interaction state.
+    let source = state.style.as_ref().map(|s| &s.source);
+    let interaction = if !unsafe { IsWindowEnabled(hwnd) }.as_bool() {
+        InteractionState::Disabled
+    } else if state.hovered {
+        InteractionState::Hovered
+    } else if state.focused {
+        InteractionState::Focused
+    } else {
+        InteractionState::Normal
+    };
+    let colors = source
+        .map(|s| s.colors_for(interaction))
+        .unwrap_or_default();
+    let background = colors
+        .background_color
+        .as_ref()
+        .unwrap_or(&state.palette.background);
+    let border_width = source.map_or(0, |s| s.border_width_for(interaction));
+    let corner_radius = source.and_then(|s| s.corner_radius).unwrap_or(0).max(0);
+
+    // The pill geometry constants are DIPs.
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let pill_w = scale.scale(PILL_W);
+    let pill_h = scale.scale(PILL_H);
+    let knob_d = scale.scale(KNOB_D);
+
+    // Fill background and draw the style's border, if any.
+    unsafe {
+        paint_styled_rect(
+            hdc,
+            &client,
+            Some(background),
+            colors.border_color.as_ref(),
+            border_width,
+            corner_radius,
+            scale,
+        )
+    };
 
-    // Compute pill position (centered vertically).
-    let pill_top = (h - PILL_H) / 2;
-    let pill_bottom = pill_top + PILL_H;
-    let pill_left = PILL_MARGIN_LEFT;
-    let pill_right = pill_left + PILL_W;
+    // Compute pill position (centered vertically); a style's padding replaces the left margin.
+    let content = inset_by_padding(&client, source.and_then(|s| s.padding), scale);
+    let pill_top = content.top + (content.bottom - content.top - pill_h) / 2;
+    let pill_bottom = pill_top + pill_h;
+    let pill_left = if source.is_some_and(|s| s.padding.is_some()) {
+        content.left
+    } else {
+        scale.scale(PILL_MARGIN_LEFT)
+    };
+    let pill_right = pill_left + pill_w;
 
     // Draw pill using RoundRect (corner radius = PILL_H for fully rounded ends).
+    // A style's pressed background replaces the "on" color.
+    let pill_on = source
+        .and_then(|s| s.pressed.as_ref())
+        .and_then(|p| p.background_color.as_ref())
+        .unwrap_or(&state.palette.pill_on);
     let pill_color = if state.checked {
-        color_to_colorref(&state.palette.pill_on)
+        color_to_colorref(pill_on)
     } else {
         color_to_colorref(&state.palette.pill_off)
     };
@@ -278,7 +331,7 @@ unsafe fn paint_toggle_switch(hwnd: HWND, hdc: HDC) {
     let null_pen: HGDIOBJ = unsafe { GetStockObject(windows::Win32::Graphics::Gdi::NULL_PEN) };
     let old_pen = unsafe { SelectObject(hdc, null_pen) };
     let old_brush = unsafe { SelectObject(hdc, pill_brush.into()) };
-    let corner = PILL_H; // diameter = height → fully rounded ends
+    let corner = pill_h; // diameter = height → fully rounded ends
     let _ = unsafe {
         RoundRect(
             hdc,
@@ -295,14 +348,14 @@ unsafe fn paint_toggle_switch(hwnd: HWND, hdc: HDC) {
     let _ = unsafe { DeleteObject(pill_brush.into()) };
 
     // Draw knob (circle, centered vertically inside pill).
-    let knob_margin = (PILL_H - KNOB_D) / 2;
+    let knob_margin = (pill_h - knob_d) / 2;
     let (knob_left, knob_right) = if state.checked {
-        (pill_right - knob_margin - KNOB_D, pill_right - knob_margin)
+        (pill_right - knob_margin - knob_d, pill_right - knob_margin)
     } else {
-        (pill_left + knob_margin, pill_left + knob_margin + KNOB_D)
+        (pill_left + knob_margin, pill_left + knob_margin + knob_d)
     };
     let knob_top = pill_top + knob_margin;
-    let knob_bottom = knob_top + KNOB_D;
+    let knob_bottom = knob_top + knob_d;
 
     let knob_cr = color_to_colorref(&state.palette.knob);
     let knob_brush = unsafe { CreateSolidBrush(knob_cr) };
@@ -316,27 +369,35 @@ unsafe fn paint_toggle_switch(hwnd: HWND, hdc: HDC) {
     let _ = unsafe { DeleteObject(knob_pen.into()) };
 
     // Draw label text to the right of the pill.
-    let text_x = pill_right + PILL_LABEL_GAP;
+    let text_x = pill_right + scale.scale(PILL_LABEL_GAP);
     let label_wide: Vec<u16> = state.label.encode_utf16().collect();
     if !label_wide.is_empty() {
         unsafe { SetBkMode(hdc, TRANSPARENT) };
-        let text_cr = color_to_colorref(&state.palette.text);
-        let _ = unsafe { SetTextColor(hdc, text_cr) };
+        let text = colors.text_color.as_ref().unwrap_or(&state.palette.text);
+        let _ = unsafe { SetTextColor(hdc, color_to_colorref(text)) };
 
         let stock_font: HGDIOBJ = unsafe { GetStockObject(DEFAULT_GUI_FONT) };
-        let old_font = unsafe { SelectObject(hdc, stock_font) };
+        let font: HGDIOBJ = state
+            .style
+            .as_ref()
+            .and_then(|s| s.font_for_dpi(scale))
+            .filter(|hf| !hf.is_invalid())
+            .map_or(stock_font, Into::into);
+        let old_font = unsafe { SelectObject(hdc, font) };
 
         let mut sz = windows::Win32::Foundation::SIZE::default();
         let _ = unsafe {
             windows::Win32::Graphics::Gdi::GetTextExtentPoint32W(hdc, &label_wide, &mut sz)
         };
-        let text_y = (h - sz.cy) / 2;
+        let text_y = content.top + (content.bottom - content.top - sz.cy) / 2;
         let _ = unsafe { TextOutW(hdc, text_x, text_y, &label_wide) };
         unsafe { SelectObject(hdc, old_font) };
     }
 
-    // Draw focus rect around pill when focused.
-    if state.focused {
+    // Draw focus rect around pill when focused, unless the style marks focus with a border.
+    let has_focus_border =
+        source.is_some_and(|s| s.border_width_for(InteractionState::Focused) > 0);
+    if state.focused && !(interaction == InteractionState::Focused && has_focus_border) {
         let focus_rect = RECT {
             left: pill_left - 2,
             top: pill_top - 2,
@@ -437,13 +498,14 @@ pub(crate) fn handle_create_toggle_switch_command(
     };
 
     // Initialise GWLP_USERDATA with per-control state.
-    let state = Box::new(ToggleSwitchState::new(label, checked));
+    let mut state = Box::new(ToggleSwitchState::new(label, checked));
+    state.palette = internal_state.current_theme().toggle_switch.clone();
     unsafe {
         SetWindowLongPtrW(hwnd_toggle, GWLP_USERDATA, Box::into_raw(state) as isize);
     }
 
     // Enable dark mode title-bar treatment (toggle owns its own painting via WM_PAINT).
-    try_enable_dark_mode(hwnd_toggle);
+    apply_native_color_mode(hwnd_toggle);
 
     // Phase 4: Write-lock — store the HWND.
     internal_state.with_window_data_write(window_id, |window_data| {
@@ -500,6 +562,12 @@ pub(crate) fn handle_set_toggle_switch_style_command(
             ))
         })
     })?;
+    apply_toggle_switch_palette(hwnd, palette);
+    Ok(())
+}
+
+/// Replaces the palette and repaints; also used when a theme is applied.
+pub(crate) fn apply_toggle_switch_palette(hwnd: HWND, palette: ToggleSwitchPalette) {
     unsafe {
         let ptr = GetWindowLongPtrW(hwnd, GWLP_USERDATA);
         if ptr != 0 {
@@ -508,5 +576,16 @@ pub(crate) fn handle_set_toggle_switch_style_command(
             let _ = InvalidateRect(Some(hwnd), None, false);
         }
     }
-    Ok(())
+}
+
+/// Stores a style applied with `ApplyStyleToControl` and repaints.
+pub(crate) fn apply_control_style(hwnd: HWND, style: Arc<ParsedControlStyle>) {
+    unsafe {
+        let ptr = GetWindowLongPtrW(hwnd, GWLP_USERDATA);
+        if ptr != 0 {
+            let state = ptr as *mut ToggleSwitchState;
+            (*state).style = Some(style);
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
 }
@@ -0,0 +1,882 @@
+/*
+ * Custom-WndProc toolbar control for CommanDuctUI.
+ *
+ * Paints a row of `ToolbarItem`s laid out by the portable `crate::toolbar`
+ * module: buttons with an optional registered image or icon glyph (drawn in
+ * the Segoe MDL2 Assets symbol font), text and dropdown arrow, separators and
+ * empty spacers. Buttons highlight on hover and press; toggles keep the
+ * checked fill. A click (press and release over the same enabled button)
+ * flips a toggle and sends WM_APP_TOOLBAR_ITEM_CLICKED to the root window,
+ * which turns it into the button's `MenuActionClicked`. Item tooltips are
+ * tools of a native tooltip control, colored by the theme, whose rectangles
+ * follow the layout.
+ *
+ * Per-instance state is stored in GWLP_USERDATA as a heap-allocated
+ * `ToolbarState`, matching `slider_handler`.
+ */
+
+use crate::app::Win32ApiInternalState;
+use crate::controls::image_handler::{self, SharedImageRegistry};
+use crate::controls::styling_handler::{color_to_colorref, paint_styled_rect};
+use crate::controls::tooltip_handler;
+use crate::dpi::DpiScale;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::styling_windows::ParsedControlStyle;
+use crate::theme::{ToolbarPalette, TooltipPalette};
+use crate::toolbar::{
+    self, BUTTON_PADDING_X, DROPDOWN_ARROW_WIDTH, ICON_SIZE, ICON_TEXT_GAP, ToolbarItem,
+    ToolbarItemKind,
+};
+use crate::types::{ControlId, MenuActionId, WindowId};
+use crate::window_common::{self, ControlKind, WM_APP_TOOLBAR_ITEM_CLICKED};
+
+use std::sync::{Arc, OnceLock};
+
+use windows::Win32::{
+    Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, RECT, SIZE, WPARAM},
+    Graphics::Gdi::{
+        BeginPaint, CLIP_DEFAULT_PRECIS, CreateFontW, DEFAULT_CHARSET, DEFAULT_GUI_FONT,
+        DEFAULT_QUALITY, DRAW_TEXT_FORMAT, DT_CENTER, DT_LEFT, DT_NOPREFIX, DT_SINGLELINE,
+        DT_VCENTER, DeleteObject, DrawTextW, EndPaint, FF_DONTCARE, FW_NORMAL, GetDC,
+        GetStockObject, GetTextExtentPoint32W, HDC, HFONT, HGDIOBJ, InvalidateRect,
+        OUT_DEFAULT_PRECIS, PAINTSTRUCT, ReleaseDC, SelectObject, SetBkMode, SetTextColor,
+        TRANSPARENT,
+    },
+    UI::{
+        Controls::{TTF_SUBCLASS, TTM_ADDTOOLW, TTM_NEWTOOLRECTW, TTTOOLINFOW},
+        Input::KeyboardAndMouse::{
+            GetCapture, ReleaseCapture, SetCapture, TME_LEAVE, TRACKMOUSEEVENT, TrackMouseEvent,
+        },
+        WindowsAndMessaging::{
+            CS_HREDRAW, CS_VREDRAW, CreateWindowExW, DefWindowProcW, DestroyWindow,
+            GET_ANCESTOR_FLAGS, GWLP_USERDATA, GetAncestor, GetClientRect, GetWindowLongPtrW,
+            HMENU, RegisterClassW, SendMessageW, SetWindowLongPtrW, WINDOW_EX_STYLE, WM_CANCELMODE,
+            WM_CAPTURECHANGED, WM_DESTROY, WM_ERASEBKGND, WM_GETFONT, WM_LBUTTONDOWN, WM_LBUTTONUP,
+            WM_MOUSEMOVE, WM_PAINT, WM_SIZE, WNDCLASSW, WS_CHILD, WS_VISIBLE,
+        },
+    },
+};
+use windows::core::{HSTRING, PCWSTR, PWSTR, w};
+
+// WM_MOUSELEAVE is not exported by windows-rs; define the constant directly.
+const WM_MOUSELEAVE: u32 = 0x02A3;
+
+// Geometry constants in DIPs; scaled to the window DPI when painting.
+const BUTTON_MARGIN: i32 = 2; // gap between a button's fill and its layout slot
+const BUTTON_CORNER_RADIUS: i32 = 3;
+const SEPARATOR_INSET_Y: i32 = 5;
+
+// Symbol font for icon glyphs and the dropdown arrow (ChevronDown).
+const ICON_FONT_NAME: PCWSTR = w!("Segoe MDL2 Assets");
+const DROPDOWN_GLYPH: char = '\u{E70D}';
+
+// ── ToolbarState ──────────────────────────────────────────────────────────────
+
+/// Per-instance heap-allocated state stored in GWLP_USERDATA.
+struct ToolbarState {
+    items: Vec<ToolbarItem>,
+    /// Button under the mouse, if any.
+    hot: Option<usize>,
+    /// Button the mouse went down on; clicked if released over it.
+    pressed: Option<usize>,
+    tracking_mouse: bool,
+    palette: ToolbarPalette,
+    /// Style applied with `ApplyStyleToControl`; supplies the text font and
+    /// overrides the palette's background and text color.
+    style: Option<Arc<ParsedControlStyle>>,
+    /// Icon font at the DPI it was created for; deleted with the toolbar.
+    icon_font: Option<(u32, HFONT)>,
+    /// Native tooltip control holding one tool per item with a tooltip.
+    hwnd_tooltip: Option<HWND>,
+    registry: SharedImageRegistry,
+}
+
+// ── Window class ──────────────────────────────────────────────────────────────
+
+const TOOLBAR_CLASS_NAME: PCWSTR = w!("CommanductUI_Toolbar");
+static TOOLBAR_CLASS_REGISTERED: OnceLock<()> = OnceLock::new();
+
+fn register_toolbar_class(h_instance: HINSTANCE) {
+    TOOLBAR_CLASS_REGISTERED.get_or_init(|| unsafe {
+        let wc = WNDCLASSW {
+            style: CS_HREDRAW | CS_VREDRAW,
+            lpfnWndProc: Some(toolbar_wnd_proc),
+            hInstance: h_instance,
+            hbrBackground: windows::Win32::Graphics::Gdi::HBRUSH(std::ptr::null_mut()),
+            lpszClassName: TOOLBAR_CLASS_NAME,
+            hCursor: windows::Win32::UI::WindowsAndMessaging::LoadCursorW(
+                None,
+                windows::Win32::UI::WindowsAndMessaging::IDC_ARROW,
+            )
+            .unwrap_or_default(),
+            ..Default::default()
+        };
+        let _ = RegisterClassW(&wc);
+    });
+}
+
+unsafe fn state_ptr(hwnd: HWND) -> *mut ToolbarState {
+    unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut ToolbarState }
+}
+
+// ── Fonts and layout ──────────────────────────────────────────────────────────
+
+// The applied style's font at `scale`, or the stock GUI font.
+fn text_font(state: &ToolbarState, scale: DpiScale) -> HGDIOBJ {
+    let stock_font: HGDIOBJ = unsafe { GetStockObject(DEFAULT_GUI_FONT) };
+    state
+        .style
+        .as_ref()
+        .and_then(|s| s.font_for_dpi(scale))
+        .filter(|hf| !hf.is_invalid())
+        .map_or(stock_font, Into::into)
+}
+
+// The symbol font sized to the icon box at `scale`, created on first use per DPI.
+fn icon_font(state: &mut ToolbarState, scale: DpiScale) -> Option<HFONT> {
+    if let Some((dpi, font)) = state.icon_font
+        && dpi == scale.dpi
+    {
+        return Some(font);
+    }
+    let font = unsafe {
+        CreateFontW(
+            -scale.scale(ICON_SIZE),
+            0,
+            0,
+            0,
+            FW_NORMAL.0 as i32,
+            0,
+            0,
+            0,
+            DEFAULT_CHARSET,
+            OUT_DEFAULT_PRECIS,
+            CLIP_DEFAULT_PRECIS,
+            DEFAULT_QUALITY,
+            FF_DONTCARE.0 as u32,
+            ICON_FONT_NAME,
+        )
+    };
+    if font.is_invalid() {
+        log::warn!(
+            "[Toolbar] Could not create the icon font at {} DPI",
+            scale.dpi
+        );
+        return None;
+    }
+    if let Some((_, old)) = state.icon_font.replace((scale.dpi, font)) {
+        let _ = unsafe { DeleteObject(old.into()) };
+    }
+    Some(font)
+}
+
+// Item widths for the client width; `hdc` must have the text font selected.
+unsafe fn item_widths(hdc: HDC, state: &ToolbarState, width: i32, scale: DpiScale) -> Vec<i32> {
+    let text_widths: Vec<i32> = state
+        .items
+        .iter()
+        .map(|item| {
+            let wide: Vec<u16> = item.text.encode_utf16().collect();
+            let mut size = SIZE::default();
+            let _ = unsafe { GetTextExtentPoint32W(hdc, &wide, &mut size) };
+            size.cx
+        })
+        .collect();
+    toolbar::item_widths(&state.items, &text_widths, width, scale)
+}
+
+// Item widths outside WM_PAINT, measured on a temporary DC.
+unsafe fn measure_item_widths(hwnd: HWND, state: &ToolbarState) -> Vec<i32> {
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let hdc = unsafe { GetDC(Some(hwnd)) };
+    if hdc.is_invalid() {
+        return Vec::new();
+    }
+    let old_font = unsafe { SelectObject(hdc, text_font(state, scale)) };
+    let widths = unsafe { item_widths(hdc, state, client.right - client.left, scale) };
+    unsafe {
+        SelectObject(hdc, old_font);
+        ReleaseDC(Some(hwnd), hdc);
+    }
+    widths
+}
+
+// The button under the client x coordinate in `lparam`.
+unsafe fn button_at_point(hwnd: HWND, state: &ToolbarState, lparam: LPARAM) -> Option<usize> {
+    let x = (lparam.0 & 0xFFFF) as u16 as i16 as i32;
+    let widths = unsafe { measure_item_widths(hwnd, state) };
+    toolbar::button_at(&state.items, &widths, x)
+}
+
+// The items' rectangles, left to right, given their widths.
+fn item_rects(client: &RECT, widths: &[i32]) -> Vec<RECT> {
+    let mut left = client.left;
+    widths
+        .iter()
+        .map(|&width| {
+            let rect = RECT {
+                left,
+                top: client.top,
+                right: left + width,
+                bottom: client.bottom,
+            };
+            left += width;
+            rect
+        })
+        .collect()
+}
+
+// ── Tooltips ──────────────────────────────────────────────────────────────────
+
+fn tool_info(hwnd_bar: HWND, index: usize, rect: RECT) -> TTTOOLINFOW {
+    TTTOOLINFOW {
+        cbSize: std::mem::size_of::<TTTOOLINFOW>() as u32,
+        uFlags: TTF_SUBCLASS,
+        hwnd: hwnd_bar,
+        uId: index,
+        rect,
+        ..Default::default()
+    }
+}
+
+// Creates the tooltip control with one tool per item that has a tooltip.
+unsafe fn create_tooltips(
+    hwnd_bar: HWND,
+    items: &[ToolbarItem],
+    h_instance: HINSTANCE,
+    palette: &TooltipPalette,
+) -> Option<HWND> {
+    if items.iter().all(|item| item.tooltip.is_none()) {
+        return None;
+    }
+    let hwnd_tooltip = tooltip_handler::create_tooltip_window(hwnd_bar, h_instance, palette)?;
+    for (index, item) in items.iter().enumerate() {
+        let Some(tooltip) = &item.tooltip else {
+            continue;
+        };
+        // The tooltip control copies the text when the tool is added.
+        let mut text = tooltip_handler::wide(tooltip);
+        let mut info = tool_info(hwnd_bar, index, RECT::default());
+        info.lpszText = PWSTR(text.as_mut_ptr());
+        unsafe {
+            SendMessageW(
+                hwnd_tooltip,
+                TTM_ADDTOOLW,
+                Some(WPARAM(0)),
+                Some(LPARAM(&info as *const TTTOOLINFOW as isize)),
+            )
+        };
+    }
+    Some(hwnd_tooltip)
+}
+
+// Moves each tool to its item's current rectangle.
+unsafe fn update_tool_rects(hwnd: HWND, state: &ToolbarState) {
+    let Some(hwnd_tooltip) = state.hwnd_tooltip else {
+        return;
+    };
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    let widths = unsafe { measure_item_widths(hwnd, state) };
+    for (index, rect) in item_rects(&client, &widths).into_iter().enumerate() {
+        if state.items[index].tooltip.is_none() {
+            continue;
+        }
+        let info = tool_info(hwnd, index, rect);
+        unsafe {
+            SendMessageW(
+                hwnd_tooltip,
+                TTM_NEWTOOLRECTW,
+                Some(WPARAM(0)),
+                Some(LPARAM(&info as *const TTTOOLINFOW as isize)),
+            )
+        };
+    }
+}
+
+// ── WndProc ───────────────────────────────────────────────────────────────────
+
+unsafe extern "system" fn toolbar_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) };
+    }
+    match msg {
+        WM_ERASEBKGND => {
+            // Suppress default erase — WM_PAINT fills everything, prevents flicker.
+            LRESULT(1)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = unsafe { BeginPaint(hwnd, &mut ps) };
+            if !hdc.is_invalid() {
+                unsafe { paint_toolbar(hwnd, hdc, &mut *state) };
+            }
+            let _ = unsafe { EndPaint(hwnd, &ps) };
+            LRESULT(0)
+        }
+        WM_SIZE => {
+            unsafe { update_tool_rects(hwnd, &*state) };
+            LRESULT(0)
+        }
+        // Lets measurement code size the toolbar in the font it paints with.
+        WM_GETFONT => {
+            let scale = window_common::dpi_scale_for_window(hwnd);
+            let font = unsafe { &*state }
+                .style
+                .as_ref()
+                .and_then(|s| s.font_for_dpi(scale));
+            LRESULT(font.map_or(0, |font| font.0 as isize))
+        }
+        WM_MOUSEMOVE => {
+            unsafe {
+                if !(*state).tracking_mouse {
+                    let mut tme = TRACKMOUSEEVENT {
+                        cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                        dwFlags: TME_LEAVE,
+                        hwndTrack: hwnd,
+                        dwHoverTime: 0,
+                    };
+                    let _ = TrackMouseEvent(&mut tme);
+                    (*state).tracking_mouse = true;
+                }
+                let hot = button_at_point(hwnd, &*state, lparam);
+                if hot != (*state).hot {
+                    (*state).hot = hot;
+                    let _ = InvalidateRect(Some(hwnd), None, false);
+                }
+            }
+            LRESULT(0)
+        }
+        WM_MOUSELEAVE => {
+            unsafe {
+                (*state).tracking_mouse = false;
+                (*state).hot = None;
+                let _ = InvalidateRect(Some(hwnd), None, false);
+            }
+            LRESULT(0)
+        }
+        WM_LBUTTONDOWN => {
+            unsafe {
+                let pressed = button_at_point(hwnd, &*state, lparam)
+                    .filter(|&index| (&(*state).items)[index].enabled);
+                (*state).pressed = pressed;
+                if pressed.is_some() {
+                    SetCapture(hwnd);
+                    let _ = InvalidateRect(Some(hwnd), None, false);
+                }
+            }
+            LRESULT(0)
+        }
+        WM_LBUTTONUP => {
+            unsafe {
+                let pressed = (*state).pressed.take();
+                if GetCapture() == hwnd {
+                    let _ = ReleaseCapture();
+                }
+                // Like a button, releasing over another item cancels the click.
+                if let Some(index) = pressed
+                    && button_at_point(hwnd, &*state, lparam) == Some(index)
+                    && (&(*state).items)[index].enabled
+                {
+                    let item = &mut (&mut (*state).items)[index];
+                    if item.kind == ToolbarItemKind::Toggle {
+                        item.checked = !item.checked;
+                    }
+                    let root = GetAncestor(hwnd, GET_ANCESTOR_FLAGS(2)); // GA_ROOT
+                    if !root.is_invalid() {
+                        let _ = SendMessageW(
+                            root,
+                            WM_APP_TOOLBAR_ITEM_CLICKED,
+                            Some(WPARAM(hwnd.0 as usize)),
+                            Some(LPARAM(index as isize)),
+                        );
+                    }
+                }
+                let _ = InvalidateRect(Some(hwnd), None, false);
+            }
+            LRESULT(0)
+        }
+        WM_CAPTURECHANGED | WM_CANCELMODE => {
+            unsafe {
+                if (*state).pressed.take().is_some() {
+                    let _ = InvalidateRect(Some(hwnd), None, false);
+                }
+                if msg == WM_CANCELMODE && GetCapture() == hwnd {
+                    let _ = ReleaseCapture();
+                }
+            }
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            let state = unsafe { Box::from_raw(state) };
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0) };
+            if let Some((_, font)) = state.icon_font {
+                let _ = unsafe { DeleteObject(font.into()) };
+            }
+            // The tooltip is owned by the top-level window, so it outlives the toolbar.
+            if let Some(hwnd_tooltip) = state.hwnd_tooltip {
+                let _ = unsafe { DestroyWindow(hwnd_tooltip) };
+            }
+            LRESULT(0)
+        }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+// ── Paint ─────────────────────────────────────────────────────────────────────
+
+// Draws `text` into `rect` with the font and flags given, leaving the DC's font as it was.
+unsafe fn draw_text(hdc: HDC, font: HGDIOBJ, text: &str, rect: RECT, flags: DRAW_TEXT_FORMAT) {
+    let mut wide: Vec<u16> = text.encode_utf16().collect();
+    let mut rect = rect;
+    unsafe {
+        let old_font = SelectObject(hdc, font);
+        DrawTextW(hdc, &mut wide, &mut rect, flags);
+        SelectObject(hdc, old_font);
+    }
+}
+
+unsafe fn paint_toolbar(hwnd: HWND, hdc: HDC, state: &mut ToolbarState) {
+    let mut client = RECT::default();
+    let _ = unsafe { GetClientRect(hwnd, &mut client) };
+    if client.right <= client.left || client.bottom <= client.top {
+        return;
+    }
+    let scale = window_common::dpi_scale_for_window(hwnd);
+    let font = text_font(state, scale);
+    let icons: HGDIOBJ = icon_font(state, scale).map_or(font, Into::into);
+    let state = &*state;
+    let source = state.style.as_ref().map(|s| &s.source);
+    let background = source
+        .and_then(|s| s.background_color.as_ref())
+        .unwrap_or(&state.palette.background);
+    let normal_text = source
+        .and_then(|s| s.text_color.as_ref())
+        .unwrap_or(&state.palette.text);
+
+    unsafe { paint_styled_rect(hdc, &client, Some(background), None, 0, 0, scale) };
+
+    let old_font = unsafe { SelectObject(hdc, font) };
+    unsafe { SetBkMode(hdc, TRANSPARENT) };
+    let widths = unsafe { item_widths(hdc, state, client.right - client.left, scale) };
+    let margin = scale.scale(BUTTON_MARGIN);
+    let single_line = DT_SINGLELINE | DT_VCENTER | DT_NOPREFIX;
+
+    for (index, (item, slot)) in state
+        .items
+        .iter()
+        .zip(item_rects(&client, &widths))
+        .enumerate()
+    {
+        if slot.right <= slot.left {
+            continue;
+        }
+        match item.kind {
+            ToolbarItemKind::Spacer => {}
+            ToolbarItemKind::Separator => {
+                let center = (slot.left + slot.right) / 2;
+                let line = RECT {
+                    left: center,
+                    top: slot.top + scale.scale(SEPARATOR_INSET_Y),
+                    right: center + scale.scale(1).max(1),
+                    bottom: slot.bottom - scale.scale(SEPARATOR_INSET_Y),
+                };
+                unsafe {
+                    paint_styled_rect(
+                        hdc,
+                        &line,
+                        Some(&state.palette.separator),
+                        None,
+                        0,
+                        0,
+                        scale,
+                    )
+                };
+            }
+            ToolbarItemKind::Push | ToolbarItemKind::Toggle | ToolbarItemKind::Dropdown => {
+                let face = RECT {
+                    left: slot.left + margin,
+                    top: slot.top + margin,
+                    right: slot.right - margin,
+                    bottom: slot.bottom - margin,
+                };
+                let fill = if !item.enabled {
+                    None
+                } else if state.pressed == Some(index) && state.hot == Some(index) {
+                    Some(&state.palette.pressed)
+                } else if item.checked {
+                    Some(&state.palette.checked)
+                } else if state.hot == Some(index) {
+                    Some(&state.palette.hover)
+                } else {
+                    None
+                };
+                if fill.is_some() {
+                    unsafe {
+                        paint_styled_rect(hdc, &face, fill, None, 0, BUTTON_CORNER_RADIUS, scale)
+                    };
+                }
+
+                let color = if item.enabled {
+                    normal_text
+                } else {
+                    &state.palette.disabled_text
+                };
+                let _ = unsafe { SetTextColor(hdc, color_to_colorref(color)) };
+                let mut left = slot.left + scale.scale(BUTTON_PADDING_X);
+                if item.has_icon() {
+                    let icon_size = scale.scale(ICON_SIZE);
+                    let icon_rect = RECT {
+                        left,
+                        right: left + icon_size,
+                        ..slot
+                    };
+                    // [CDU-ImagesV1] A registered image replaces the glyph.
+                    let image_top = (slot.top + slot.bottom - icon_size) / 2;
+                    let image_rect = RECT {
+                        top: image_top,
+                        bottom: image_top + icon_size,
+                        ..icon_rect
+                    };
+                    let drew_image = item.image.is_some_and(|image_id| unsafe {
+                        image_handler::draw_registered_image(
+                            hdc,
+                            &state.registry,
+                            image_id,
+                            &image_rect,
+                        )
+                    });
+                    if let (false, Some(glyph)) = (drew_image, item.icon) {
+                        unsafe {
+                            draw_text(
+                                hdc,
+                                icons,
+                                &glyph.to_string(),
+                                icon_rect,
+                                DT_CENTER | single_line,
+                            )
+                        };
+                    }
+                    left = icon_rect.right + scale.scale(ICON_TEXT_GAP);
+                }
+                let arrow_width = if item.kind == ToolbarItemKind::Dropdown {
+                    scale.scale(DROPDOWN_ARROW_WIDTH)
+                } else {
+                    0
+                };
+                let content_right = slot.right - scale.scale(BUTTON_PADDING_X);
+                if !item.text.is_empty() {
+                    let text_rect = RECT {
+                        left,
+                        right: content_right - arrow_width,
+                        ..slot
+                    };
+                    unsafe { draw_text(hdc, font, &item.text, text_rect, DT_LEFT | single_line) };
+                }
+                if arrow_width > 0 {
+                    // The chevron is drawn at half the icon size.
+                    let arrow_rect = RECT {
+                        left: content_right - arrow_width,
+                        right: content_right,
+                        top: slot.top + (slot.bottom - slot.top) / 4,
+                        bottom: slot.bottom - (slot.bottom - slot.top) / 4,
+                    };
+                    unsafe {
+                        draw_text(
+                            hdc,
+                            icons,
+                            &DROPDOWN_GLYPH.to_string(),
+                            arrow_rect,
+                            DT_CENTER | single_line,
+                        )
+                    };
+                }
+            }
+        }
+    }
+    unsafe { SelectObject(hdc, old_font) };
+}
+
+// ── Command handlers ──────────────────────────────────────────────────────────
+
+/// Creates a Toolbar control as a child of `parent_control_id` (or main window if None).
+/// Follows the 4-phase read-kind-create-hwnd-write pattern from `slider_handler`.
+pub(crate) fn handle_create_toolbar_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    parent_control_id: Option<ControlId>,
+    control_id: ControlId,
+    items: Vec<ToolbarItem>,
+) -> PlatformResult<()> {
+    log::debug!(
+        "[Toolbar] handle_create_toolbar_command WinID={window_id:?} ControlID={} ParentID={:?} items={}",
+        control_id.raw(),
+        parent_control_id.map(|id| id.raw()),
+        items.len(),
+    );
+    toolbar::validate_items(&items)?;
+
+    // Phase 1: Read-lock — duplicate check + get parent HWND.
+    let parent_hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "Toolbar {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        let hwnd_parent = match parent_control_id {
+            Some(id) => window_data.get_control_hwnd(id).ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "[Toolbar] Parent control {} not found in WinID {window_id:?}",
+                    id.raw()
+                ))
+            })?,
+            None => window_data.get_hwnd(),
+        };
+        if hwnd_parent.is_invalid() {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[Toolbar] Parent HWND invalid WinID={window_id:?}"
+            )));
+        }
+        Ok(hwnd_parent)
+    })?;
+
+    let h_instance = internal_state.h_instance();
+    register_toolbar_class(h_instance);
+
+    // Phase 2: Write-lock — register the control kind.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        if window_data.has_control(control_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "[Toolbar] Race: Toolbar {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.register_control_kind(control_id, ControlKind::Toolbar);
+        Ok(())
+    })?;
+
+    // Phase 3: Create native HWND outside any lock.
+    let hwnd_bar = unsafe {
+        match CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            TOOLBAR_CLASS_NAME,
+            &HSTRING::from(""),
+            WS_CHILD | WS_VISIBLE,
+            0,
+            0,
+            10,
+            10,
+            Some(parent_hwnd),
+            Some(HMENU(control_id.raw() as *mut _)),
+            Some(h_instance),
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(err) => {
+                let _ = internal_state.with_window_data_write(window_id, |window_data| {
+                    window_data.unregister_control_kind(control_id);
+                    Ok(())
+                });
+                return Err(err.into());
+            }
+        }
+    };
+
+    // Initialise GWLP_USERDATA with per-control state.
+    let theme = internal_state.current_theme();
+    let hwnd_tooltip = unsafe { create_tooltips(hwnd_bar, &items, h_instance, &theme.tooltip) };
+    let state = Box::new(ToolbarState {
+        items,
+        hot: None,
+        pressed: None,
+        tracking_mouse: false,
+        palette: theme.toolbar.clone(),
+        style: None,
+        icon_font: None,
+        hwnd_tooltip,
+        registry: internal_state.image_registry(),
+    });
+    unsafe {
+        SetWindowLongPtrW(hwnd_bar, GWLP_USERDATA, Box::into_raw(state) as isize);
+    }
+
+    // Phase 4: Write-lock — store the HWND.
+    internal_state.with_window_data_write(window_id, |window_data| {
+        window_data.register_control_hwnd(control_id, hwnd_bar);
+        Ok(())
+    })?;
+
+    log::debug!(
+        "[Toolbar] Created toolbar {} hwnd={hwnd_bar:?}",
+        control_id.raw()
+    );
+    Ok(())
+}
+
+/*
+ * Runs `update` on every button with `action_id` (only toggles if
+ * `toggles_only`) and repaints. Unknown controls fail with `InvalidHandle`, a
+ * toolbar without a matching button with `OperationFailed`.
+ */
+fn update_buttons(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    action_id: MenuActionId,
+    toggles_only: bool,
+    operation: &str,
+    update: impl Fn(&mut ToolbarItem),
+) -> PlatformResult<()> {
+    let hwnd = internal_state.with_window_data_read(window_id, |window_data| {
+        if window_data.get_control_kind(control_id) != Some(ControlKind::Toolbar) {
+            return Err(PlatformError::InvalidHandle(format!(
+                "[Toolbar] {operation}: control {} is not a toolbar in window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        window_data.get_control_hwnd(control_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!(
+                "[Toolbar] {operation}: control {} not found in window {window_id:?}",
+                control_id.raw()
+            ))
+        })
+    })?;
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return Err(PlatformError::InvalidHandle(format!(
+            "[Toolbar] {operation}: control {} has no state",
+            control_id.raw()
+        )));
+    }
+    let state = unsafe { &mut *state };
+    let mut matched = false;
+    for item in state.items.iter_mut().filter(|item| {
+        item.action == Some(action_id) && (!toggles_only || item.kind == ToolbarItemKind::Toggle)
+    }) {
+        update(item);
+        matched = true;
+    }
+    if !matched {
+        let kind = if toggles_only {
+            "toggle button"
+        } else {
+            "button"
+        };
+        return Err(PlatformError::OperationFailed(format!(
+            "[Toolbar] {operation}: toolbar {} has no {kind} for {action_id:?}",
+            control_id.raw()
+        )));
+    }
+    let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
+    Ok(())
+}
+
+pub(crate) fn handle_set_toolbar_item_enabled_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    action_id: MenuActionId,
+    enabled: bool,
+) -> PlatformResult<()> {
+    update_buttons(
+        internal_state,
+        window_id,
+        control_id,
+        action_id,
+        false,
+        "SetToolbarItemEnabled",
+        |item| item.enabled = enabled,
+    )
+}
+
+pub(crate) fn handle_set_toolbar_item_checked_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    action_id: MenuActionId,
+    checked: bool,
+) -> PlatformResult<()> {
+    update_buttons(
+        internal_state,
+        window_id,
+        control_id,
+        action_id,
+        true,
+        "SetToolbarItemChecked",
+        |item| item.checked = checked,
+    )
+}
+
+/// The action of the item at `index`, read when a click reaches the root window.
+pub(crate) fn action_at(hwnd: HWND, index: usize) -> Option<MenuActionId> {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return None;
+    }
+    unsafe { &*state }.items.get(index)?.action
+}
+
+/// The tooltip holding the buttons' tips, if any button has one.
+pub(crate) fn tooltip_of(hwnd: HWND) -> Option<HWND> {
+    let state = unsafe { state_ptr(hwnd) };
+    if state.is_null() {
+        return None;
+    }
+    unsafe { &*state }.hwnd_tooltip
+}
+
+/// Replaces the palette and repaints; used when a theme is applied.
+pub(crate) fn apply_toolbar_palette(hwnd: HWND, palette: ToolbarPalette) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).palette = palette;
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+/// Stores a style applied with `ApplyStyleToControl`; its font changes the layout, so tools move too.
+pub(crate) fn apply_control_style(hwnd: HWND, style: Arc<ParsedControlStyle>) {
+    unsafe {
+        let state = state_ptr(hwnd);
+        if !state.is_null() {
+            (*state).style = Some(style);
+            update_tool_rects(hwnd, &*state);
+            let _ = InvalidateRect(Some(hwnd), None, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn item_rects_follow_the_widths() {
+        // Arrange
+        let client = RECT {
+            left: 0,
+            top: 0,
+            right: 100,
+            bottom: 30,
+        };
+
+        // Act
+        let rects = item_rects(&client, &[20, 0, 30]);
+
+        // Assert
+        let edges: Vec<(i32, i32)> = rects.iter().map(|r| (r.left, r.right)).collect();
+        assert_eq!(edges, vec![(0, 20), (20, 20), (20, 50)]);
+        assert!(rects.iter().all(|r| r.top == 0 && r.bottom == 30));
+    }
+}
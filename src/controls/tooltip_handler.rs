@@ -0,0 +1,269 @@
+/*
+ * Native tooltips for CommanDuctUI.
+ *
+ * `SetControlTooltip` gives a control its own tooltip window, owned by the
+ * control's top-level window, so every control keeps its own wrapping and
+ * delays. The tree view's item tips and the tab bar's and toolbar's tips go
+ * through the same helpers, so every tooltip takes its colors from the
+ * theme's `TooltipPalette`. Visual styles are switched off on each tooltip
+ * because themed tooltips ignore custom colors.
+ * [CDU-TooltipsV1]
+ */
+
+use crate::app::Win32ApiInternalState;
+use crate::controls::styling_handler::color_to_colorref;
+use crate::dpi::DpiScale;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::theme::TooltipPalette;
+use crate::tooltip::{self, MULTILINE_MAX_WIDTH, PERSISTENT_DISPLAY_MS, TooltipOptions};
+use crate::types::{ControlId, WindowId};
+use crate::window_common;
+
+use std::sync::Arc;
+
+use windows::Win32::{
+    Foundation::{HINSTANCE, HWND, LPARAM, WPARAM},
+    UI::{
+        Controls::{
+            NMTTDISPINFOW, SetWindowTheme, TOOLTIPS_CLASSW, TTDT_AUTOMATIC, TTDT_AUTOPOP,
+            TTDT_INITIAL, TTF_IDISHWND, TTF_SUBCLASS, TTM_ADDTOOLW, TTM_SETDELAYTIME,
+            TTM_SETMAXTIPWIDTH, TTM_SETTIPBKCOLOR, TTM_SETTIPTEXTCOLOR, TTM_UPDATETIPTEXTW,
+            TTS_ALWAYSTIP, TTS_NOPREFIX, TTTOOLINFOW,
+        },
+        WindowsAndMessaging::{
+            CW_USEDEFAULT, CreateWindowExW, DestroyWindow, SendMessageW, WINDOW_STYLE,
+            WS_EX_TOPMOST, WS_POPUP,
+        },
+    },
+};
+use windows::core::{PCWSTR, PWSTR, w};
+
+/// `text` as UTF-16 with a terminating NUL.
+pub(crate) fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/*
+ * Creates a tooltip window for tools of `hwnd_owner`, colored with `palette`.
+ * A popup's owner is always a top-level window, so the tooltip lives until
+ * that window is destroyed unless it is destroyed earlier.
+ */
+pub(crate) fn create_tooltip_window(
+    hwnd_owner: HWND,
+    h_instance: HINSTANCE,
+    palette: &TooltipPalette,
+) -> Option<HWND> {
+    let created = unsafe {
+        CreateWindowExW(
+            WS_EX_TOPMOST,
+            TOOLTIPS_CLASSW,
+            PCWSTR::null(),
+            WS_POPUP | WINDOW_STYLE(TTS_ALWAYSTIP | TTS_NOPREFIX),
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            Some(hwnd_owner),
+            None,
+            Some(h_instance),
+            None,
+        )
+    };
+    match created {
+        Ok(hwnd_tooltip) => {
+            adopt_tooltip(hwnd_tooltip, palette);
+            Some(hwnd_tooltip)
+        }
+        Err(err) => {
+            log::warn!("[Tooltip] Could not create a tooltip for HWND {hwnd_owner:?}: {err:?}");
+            None
+        }
+    }
+}
+
+/// Switches off visual styles on a tooltip, also one a control created itself, and colors it.
+pub(crate) fn adopt_tooltip(hwnd_tooltip: HWND, palette: &TooltipPalette) {
+    if let Err(err) = unsafe { SetWindowTheme(hwnd_tooltip, w!(""), w!("")) } {
+        log::debug!("[Tooltip] SetWindowTheme failed for HWND {hwnd_tooltip:?}: {err:?}");
+    }
+    apply_tooltip_palette(hwnd_tooltip, palette);
+}
+
+/// Recolors a tooltip; used on creation and when a theme is applied.
+pub(crate) fn apply_tooltip_palette(hwnd_tooltip: HWND, palette: &TooltipPalette) {
+    let background = color_to_colorref(&palette.background);
+    let text = color_to_colorref(&palette.text);
+    unsafe {
+        SendMessageW(
+            hwnd_tooltip,
+            TTM_SETTIPBKCOLOR,
+            Some(WPARAM(background.0 as usize)),
+            Some(LPARAM(0)),
+        );
+        SendMessageW(
+            hwnd_tooltip,
+            TTM_SETTIPTEXTCOLOR,
+            Some(WPARAM(text.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+}
+
+/*
+ * Applies wrapping and delays. Multi-line tips wrap at `MULTILINE_MAX_WIDTH`
+ * DIPs; the tooltip only honors line breaks once it has a maximum width.
+ * Delays start from the defaults, so options set earlier do not linger.
+ */
+pub(crate) fn apply_tooltip_options(hwnd_tooltip: HWND, options: &TooltipOptions, scale: DpiScale) {
+    let max_width = if options.multiline {
+        scale.scale(MULTILINE_MAX_WIDTH) as isize
+    } else {
+        -1
+    };
+    let set_delay = |which: u32, milliseconds: isize| unsafe {
+        SendMessageW(
+            hwnd_tooltip,
+            TTM_SETDELAYTIME,
+            Some(WPARAM(which as usize)),
+            Some(LPARAM(milliseconds)),
+        );
+    };
+    unsafe {
+        SendMessageW(
+            hwnd_tooltip,
+            TTM_SETMAXTIPWIDTH,
+            Some(WPARAM(0)),
+            Some(LPARAM(max_width)),
+        );
+    }
+    set_delay(TTDT_AUTOMATIC, -1);
+    if let Some(delay) = options.initial_delay_ms {
+        set_delay(TTDT_INITIAL, delay.min(PERSISTENT_DISPLAY_MS) as isize);
+    }
+    if let Some(duration) = options.display_ms() {
+        set_delay(TTDT_AUTOPOP, duration as isize);
+    }
+}
+
+/*
+ * Answers TTN_GETDISPINFOW with `text`; `None` shows no tip. The tooltip
+ * reads the text after the notification returns, so it is kept in `buffer`,
+ * which the control owns.
+ */
+pub(crate) unsafe fn answer_get_disp_info(
+    lparam: LPARAM,
+    buffer: &mut Vec<u16>,
+    text: Option<&str>,
+) {
+    *buffer = wide(text.unwrap_or(""));
+    let info = lparam.0 as *mut NMTTDISPINFOW;
+    if !info.is_null() {
+        unsafe { (*info).lpszText = PWSTR(buffer.as_mut_ptr()) };
+    }
+}
+
+// The tool covering a whole control; the tooltip subclasses it to see the pointer.
+fn control_tool(hwnd_control: HWND, text: &mut [u16]) -> TTTOOLINFOW {
+    TTTOOLINFOW {
+        cbSize: std::mem::size_of::<TTTOOLINFOW>() as u32,
+        uFlags: TTF_IDISHWND | TTF_SUBCLASS,
+        hwnd: hwnd_control,
+        uId: hwnd_control.0 as usize,
+        lpszText: PWSTR(text.as_mut_ptr()),
+        ..Default::default()
+    }
+}
+
+/*
+ * Attaches `text` to a control, replacing its earlier tooltip; text that is
+ * blank removes the tooltip. Each control has its own tooltip window, so its
+ * options do not affect other controls.
+ */
+pub(crate) fn handle_set_control_tooltip_command(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    control_id: ControlId,
+    text: String,
+    options: TooltipOptions,
+) -> PlatformResult<()> {
+    let (hwnd_window, hwnd_control, existing) =
+        internal_state.with_window_data_read(window_id, |window_data| {
+            let hwnd_control = window_data.get_control_hwnd(control_id).ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "[Tooltip] SetControlTooltip: control {} not found in window {window_id:?}",
+                    control_id.raw()
+                ))
+            })?;
+            Ok((
+                window_data.get_hwnd(),
+                hwnd_control,
+                window_data.get_control_tooltip(control_id),
+            ))
+        })?;
+
+    let Some(shown) = tooltip::displayed_text(&text, &options) else {
+        if existing.is_some() {
+            let removed = internal_state.with_window_data_write(window_id, |window_data| {
+                Ok(window_data.take_control_tooltip(control_id))
+            })?;
+            if let Some(hwnd_tooltip) = removed {
+                let _ = unsafe { DestroyWindow(hwnd_tooltip) };
+            }
+        }
+        return Ok(());
+    };
+
+    // The tooltip copies the text when the tool is added or updated.
+    let mut text_buffer = wide(&shown);
+    let info = control_tool(hwnd_control, &mut text_buffer);
+    let hwnd_tooltip = match existing {
+        Some(hwnd_tooltip) => {
+            unsafe {
+                SendMessageW(
+                    hwnd_tooltip,
+                    TTM_UPDATETIPTEXTW,
+                    Some(WPARAM(0)),
+                    Some(LPARAM(&info as *const TTTOOLINFOW as isize)),
+                )
+            };
+            hwnd_tooltip
+        }
+        None => {
+            let palette = internal_state.current_theme().tooltip.clone();
+            let hwnd_tooltip =
+                create_tooltip_window(hwnd_window, internal_state.h_instance(), &palette)
+                    .ok_or_else(|| {
+                        PlatformError::OperationFailed(format!(
+                            "[Tooltip] Could not create the tooltip of control {}",
+                            control_id.raw()
+                        ))
+                    })?;
+            let added = unsafe {
+                SendMessageW(
+                    hwnd_tooltip,
+                    TTM_ADDTOOLW,
+                    Some(WPARAM(0)),
+                    Some(LPARAM(&info as *const TTTOOLINFOW as isize)),
+                )
+            };
+            if added.0 == 0 {
+                let _ = unsafe { DestroyWindow(hwnd_tooltip) };
+                return Err(PlatformError::OperationFailed(format!(
+                    "[Tooltip] Could not add control {} to its tooltip",
+                    control_id.raw()
+                )));
+            }
+            internal_state.with_window_data_write(window_id, |window_data| {
+                window_data.set_control_tooltip(control_id, hwnd_tooltip);
+                Ok(())
+            })?;
+            hwnd_tooltip
+        }
+    };
+    apply_tooltip_options(
+        hwnd_tooltip,
+        &options,
+        window_common::dpi_scale_for_window(hwnd_control),
+    );
+    Ok(())
+}
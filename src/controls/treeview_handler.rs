@@ -10,14 +10,20 @@
  * to specific TreeView details.
  */
 use crate::app::Win32ApiInternalState;
+use crate::controls::image_handler;
 use crate::controls::styling_handler;
+use crate::controls::tooltip_handler;
+use crate::dpi::DpiScale;
 use crate::error::{PlatformError, Result as PlatformResult};
+use crate::image::{ImageRegistry, SMALL_IMAGE_SIZE};
 use crate::styling::StyleId;
 use crate::styling_primitives::Color;
+use crate::tooltip::{self, TooltipOptions};
 use crate::types::{
-    AppEvent, CheckState, ControlId, TreeItemDescriptor, TreeItemId, TreeItemMarkerKind, WindowId,
+    AppEvent, CheckState, ControlId, ImageId, TreeItemDescriptor, TreeItemId, TreeItemMarkerKind,
+    WindowId,
 };
-use crate::window_common::{ControlKind, try_enable_dark_mode};
+use crate::window_common::{self, ControlKind, apply_native_color_mode};
 
 use windows::{
     Win32::{
@@ -28,13 +34,16 @@ use windows::{
         },
         UI::Controls::{
             CDDS_ITEMPOSTPAINT, CDDS_ITEMPREPAINT, CDDS_PREPAINT, CDIS_FOCUS, CDIS_SELECTED,
-            CDRF_DODEFAULT, CDRF_NEWFONT, CDRF_NOTIFYITEMDRAW, CDRF_NOTIFYPOSTPAINT, HTREEITEM,
-            NMHDR, NMTVCUSTOMDRAW, TVGN_CARET, TVHITTESTINFO, TVHT_ONITEMLABEL,
-            TVHT_ONITEMSTATEICON, TVI_LAST, TVIF_CHILDREN, TVIF_PARAM, TVIF_STATE, TVIF_TEXT,
-            TVINSERTSTRUCTW, TVINSERTSTRUCTW_0, TVIS_STATEIMAGEMASK, TVITEMEXW, TVITEMEXW_CHILDREN,
-            TVM_DELETEITEM, TVM_GETITEMRECT, TVM_GETITEMW, TVM_HITTEST, TVM_INSERTITEMW,
-            TVM_SELECTITEM, TVM_SETITEMW, TVS_CHECKBOXES, TVS_HASBUTTONS, TVS_HASLINES,
-            TVS_LINESATROOT, TVS_SHOWSELALWAYS, WC_TREEVIEWW,
+            CDRF_DODEFAULT, CDRF_NEWFONT, CDRF_NOTIFYITEMDRAW, CDRF_NOTIFYPOSTPAINT, HIMAGELIST,
+            HTREEITEM, ILC_COLOR32, ImageList_Add, ImageList_Create, ImageList_Destroy, NMHDR,
+            NMTVCUSTOMDRAW, NMTVGETINFOTIPW, TVGN_CARET, TVHITTESTINFO, TVHT_ONITEMLABEL,
+            TVHT_ONITEMSTATEICON, TVI_LAST, TVIF_CHILDREN, TVIF_IMAGE, TVIF_PARAM,
+            TVIF_SELECTEDIMAGE, TVIF_STATE, TVIF_TEXT, TVINSERTSTRUCTW, TVINSERTSTRUCTW_0,
+            TVIS_STATEIMAGEMASK, TVITEMEXW, TVITEMEXW_CHILDREN, TVM_DELETEITEM, TVM_GETITEMRECT,
+            TVM_GETITEMW, TVM_GETNEXTITEM, TVM_GETTOOLTIPS, TVM_HITTEST, TVM_INSERTITEMW,
+            TVM_SELECTITEM, TVM_SETIMAGELIST, TVM_SETITEMW, TVS_CHECKBOXES, TVS_HASBUTTONS,
+            TVS_HASLINES, TVS_INFOTIP, TVS_LINESATROOT, TVS_SHOWSELALWAYS, TVSIL_NORMAL,
+            WC_TREEVIEWW,
         },
         UI::WindowsAndMessaging::*,
     },
@@ -56,15 +65,15 @@ use std::sync::Arc;
  *     windows::Win32::Foundation::COLORREF(0x00FF0000); // BGR format for Blue
  */
 
+// Marker and selection-accent geometry in DIPs; scaled to the tree view's DPI when drawn.
 const MARKER_DIAMETER: i32 = 6;
 const MARKER_LEFT_OFFSET: i32 = 12;
 const SELECTION_ACCENT_WIDTH: i32 = 3;
 const MARKER_BORDER: i32 = 1;
-const MARKER_OUTER_COLOR: Color = Color {
-    r: 255,
-    g: 255,
-    b: 255,
-};
+const MARKER_OUTER_COLOR: Color = Color::rgb(255, 255, 255);
+
+// Image index of items without an image once the tree view has an image list.
+const I_IMAGENONE: i32 = -2;
 
 /*
  * Holds internal state specific to a TreeView control instance.
@@ -76,6 +85,12 @@ pub(crate) struct TreeViewInternalState {
     pub(crate) item_id_to_htreeitem: HashMap<TreeItemId, HTREEITEM>,
     pub(crate) htreeitem_to_item_id: HashMap<isize, TreeItemId>,
     pub(crate) style_overrides: HashMap<TreeItemId, StyleId>,
+    // Normal image list holding the item images of the last population, and
+    // each image's index in it. [CDU-ImagesV1]
+    image_list: Option<HIMAGELIST>,
+    image_indices: HashMap<ImageId, i32>,
+    // Tooltips given by the descriptors; other items ask the UiStateProvider. [CDU-TooltipsV1]
+    tooltips: HashMap<TreeItemId, String>,
 }
 
 impl TreeViewInternalState {
@@ -84,6 +99,60 @@ impl TreeViewInternalState {
             item_id_to_htreeitem: HashMap::new(),
             htreeitem_to_item_id: HashMap::new(),
             style_overrides: HashMap::new(),
+            image_list: None,
+            image_indices: HashMap::new(),
+            tooltips: HashMap::new(),
+        }
+    }
+
+    /*
+     * Replaces the tree view's image list with one holding every registered
+     * image the descriptors use, rendered at `size` pixels. Without any such
+     * image the list is removed, so items take no room for an icon.
+     */
+    fn rebuild_image_list(
+        &mut self,
+        hwnd_treeview: HWND,
+        registry: &ImageRegistry,
+        items: &[TreeItemDescriptor],
+        size: i32,
+    ) {
+        let mut image_ids = Vec::new();
+        collect_registered_images(items, registry, &mut image_ids);
+        self.image_indices.clear();
+        let new_list = if image_ids.is_empty() {
+            None
+        } else {
+            let list =
+                unsafe { ImageList_Create(size, size, ILC_COLOR32, image_ids.len() as i32, 0) };
+            (!list.is_invalid()).then_some(list)
+        };
+        if let Some(list) = new_list {
+            for image_id in image_ids {
+                let Some(bitmap) = registry
+                    .render(image_id, size as u32, size as u32)
+                    .as_ref()
+                    .and_then(image_handler::create_premultiplied_bitmap)
+                else {
+                    continue;
+                };
+                let index = unsafe { ImageList_Add(list, bitmap, None) };
+                let _ = unsafe { DeleteObject(bitmap.into()) };
+                if index >= 0 {
+                    self.image_indices.insert(image_id, index);
+                }
+            }
+        }
+        unsafe {
+            SendMessageW(
+                hwnd_treeview,
+                TVM_SETIMAGELIST,
+                Some(WPARAM(TVSIL_NORMAL as usize)),
+                Some(LPARAM(new_list.map_or(0, |list| list.0))),
+            );
+        }
+        if let Some(old_list) = std::mem::replace(&mut self.image_list, new_list) {
+            let _ = unsafe { ImageList_Destroy(Some(old_list)) };
         }
     }
 
@@ -104,6 +173,7 @@ impl TreeViewInternalState {
         self.item_id_to_htreeitem.clear();
         self.htreeitem_to_item_id.clear();
         self.style_overrides.clear();
+        self.tooltips.clear();
         log::debug!("TreeViewInternalState::clear_items_impl completed for HWND {hwnd_treeview:?}");
     }
 
@@ -129,8 +199,21 @@ impl TreeViewInternalState {
             CheckState::Unchecked => 1,
         };
 
+        // Once the tree view has an image list, items without an image must say so.
+        let image = match item_desc.image.and_then(|id| self.image_indices.get(&id)) {
+            Some(&index) => index,
+            None => I_IMAGENONE,
+        };
+        let image_mask = if self.image_list.is_some() {
+            TVIF_IMAGE | TVIF_SELECTEDIMAGE
+        } else {
+            Default::default()
+        };
+
         let tv_item = TVITEMEXW {
-            mask: TVIF_TEXT | TVIF_PARAM | TVIF_CHILDREN,
+            mask: TVIF_TEXT | TVIF_PARAM | TVIF_CHILDREN | image_mask,
+            iImage: image,
+            iSelectedImage: image,
             hItem: HTREEITEM::default(), // Will be filled by the system if successful
             pszText: PWSTR(text_buffer.as_mut_ptr()),
             cchTextMax: text_buffer.len() as i32,
@@ -173,6 +256,9 @@ impl TreeViewInternalState {
         if let Some(style_id) = item_desc.style_override {
             self.style_overrides.insert(item_desc.id, style_id);
         }
+        if let Some(tooltip) = &item_desc.tooltip {
+            self.tooltips.insert(item_desc.id, tooltip.clone());
+        }
 
         // Explicitly set the state after insertion. This ensures the built-in
         // state image list for checkboxes is attached before we request a
@@ -209,6 +295,31 @@ impl TreeViewInternalState {
     }
 }
 
+impl Drop for TreeViewInternalState {
+    fn drop(&mut self) {
+        if let Some(list) = self.image_list.take() {
+            let _ = unsafe { ImageList_Destroy(Some(list)) };
+        }
+    }
+}
+
+// Registered images used by `items` and their descendants, each once, in first-use order.
+fn collect_registered_images(
+    items: &[TreeItemDescriptor],
+    registry: &ImageRegistry,
+    image_ids: &mut Vec<ImageId>,
+) {
+    for item in items {
+        if let Some(image_id) = item.image
+            && registry.contains(image_id)
+            && !image_ids.contains(&image_id)
+        {
+            image_ids.push(image_id);
+        }
+        collect_registered_images(&item.children, registry, image_ids);
+    }
+}
+
 /*
  * Handles the creation of a native TreeView control.
  * This function uses a read-create-write pattern to minimize lock contention.
@@ -274,7 +385,12 @@ pub(crate) fn handle_create_treeview_command(
     // Phase 2: Create the window without holding a lock.
     let h_instance_for_creation = internal_state.h_instance();
     let tvs_style = WINDOW_STYLE(
-        TVS_HASLINES | TVS_LINESATROOT | TVS_HASBUTTONS | TVS_SHOWSELALWAYS | TVS_CHECKBOXES,
+        TVS_HASLINES
+            | TVS_LINESATROOT
+            | TVS_HASBUTTONS
+            | TVS_SHOWSELALWAYS
+            | TVS_CHECKBOXES
+            | TVS_INFOTIP,
     );
     let combined_style = WS_CHILD | WS_VISIBLE | WS_BORDER | tvs_style;
     let hwnd_tv = unsafe {
@@ -306,7 +422,16 @@ pub(crate) fn handle_create_treeview_command(
         .get_parsed_style(StyleId::MainWindowBackground)
         .is_some()
     {
-        try_enable_dark_mode(hwnd_tv);
+        apply_native_color_mode(hwnd_tv);
+    }
+    // Item tooltips keep their line breaks. [CDU-TooltipsV1]
+    if let Some(hwnd_tooltip) = tooltip_of(hwnd_tv) {
+        tooltip_handler::adopt_tooltip(hwnd_tooltip, &internal_state.current_theme().tooltip);
+        tooltip_handler::apply_tooltip_options(
+            hwnd_tooltip,
+            &TooltipOptions::default().multiline(),
+            window_common::dpi_scale_for_window(hwnd_tv),
+        );
     }
 
     // Phase 3: Acquire write lock to update NativeWindowData.
@@ -353,11 +478,19 @@ pub(crate) fn populate_treeview(
         control_id.raw()
     );
 
+    let registry = internal_state.image_registry();
     internal_state.with_treeview_state_mut(window_id, control_id, |hwnd_treeview, tv_state| {
         log::debug!(
             "TreeViewHandler: Populating TreeView (HWND {hwnd_treeview:?}). Clearing existing items."
         );
         tv_state.clear_items_impl(hwnd_treeview);
+        let image_size = window_common::dpi_scale_for_window(hwnd_treeview).scale(SMALL_IMAGE_SIZE);
+        match registry.read() {
+            Ok(registry) => {
+                tv_state.rebuild_image_list(hwnd_treeview, &registry, &items, image_size)
+            }
+            Err(e) => log::error!("Failed to acquire read lock on the image registry: {e:?}"),
+        }
 
         for item_desc in items {
             tv_state.add_item_recursive_impl(hwnd_treeview, HTREEITEM(0), &item_desc)?;
@@ -887,36 +1020,12 @@ fn tree_item_marker_for_display(
 fn tree_item_marker_color(marker: TreeItemMarkerKind) -> Option<Color> {
     match marker {
         TreeItemMarkerKind::None => None,
-        TreeItemMarkerKind::Blue => Some(Color {
-            r: 33,
-            g: 150,
-            b: 243,
-        }), // Material Blue 500
-        TreeItemMarkerKind::Green => Some(Color {
-            r: 46,
-            g: 204,
-            b: 113,
-        }), // Material Green 400
-        TreeItemMarkerKind::Yellow => Some(Color {
-            r: 255,
-            g: 193,
-            b: 7,
-        }), // Amber 500
-        TreeItemMarkerKind::Red => Some(Color {
-            r: 244,
-            g: 67,
-            b: 54,
-        }), // Material Red 500
-        TreeItemMarkerKind::Purple => Some(Color {
-            r: 156,
-            g: 39,
-            b: 176,
-        }), // Material Purple 500
-        TreeItemMarkerKind::Gray => Some(Color {
-            r: 117,
-            g: 117,
-            b: 117,
-        }), // Gray 600
+        TreeItemMarkerKind::Blue => Some(Color::rgb(33, 150, 243)), // Material Blue 500
+        TreeItemMarkerKind::Green => Some(Color::rgb(46, 204, 113)), // Material Green 400
+        TreeItemMarkerKind::Yellow => Some(Color::rgb(255, 193, 7)), // Amber 500
+        TreeItemMarkerKind::Red => Some(Color::rgb(244, 67, 54)),   // Material Red 500
+        TreeItemMarkerKind::Purple => Some(Color::rgb(156, 39, 176)), // Material Purple 500
+        TreeItemMarkerKind::Gray => Some(Color::rgb(117, 117, 117)), // Gray 600
     }
 }
 
@@ -938,11 +1047,14 @@ fn draw_tree_item_marker(hdc: HDC, hwnd_treeview: HWND, h_item_native: HTREEITEM
         return;
     }
 
+    let scale = window_common::dpi_scale_for_window(hwnd_treeview);
+    let diameter = scale.scale(MARKER_DIAMETER);
+    let border = scale.scale(MARKER_BORDER);
     let height = item_rect.bottom - item_rect.top;
-    let top = item_rect.top + (height - MARKER_DIAMETER) / 2;
-    let left = item_rect.left + MARKER_LEFT_OFFSET;
-    let right = left + MARKER_DIAMETER;
-    let bottom = top + MARKER_DIAMETER;
+    let top = item_rect.top + (height - diameter) / 2;
+    let left = item_rect.left + scale.scale(MARKER_LEFT_OFFSET);
+    let right = left + diameter;
+    let bottom = top + diameter;
 
     let outer_color_ref = styling_handler::color_to_colorref(&MARKER_OUTER_COLOR);
     let outer_brush = unsafe { CreateSolidBrush(outer_color_ref) };
@@ -963,10 +1075,10 @@ fn draw_tree_item_marker(hdc: HDC, hwnd_treeview: HWND, h_item_native: HTREEITEM
 
     unsafe {
         let previous_brush = SelectObject(hdc, HGDIOBJ(inner_brush.0));
-        let inner_left = left + MARKER_BORDER;
-        let inner_top = top + MARKER_BORDER;
-        let inner_right = right - MARKER_BORDER;
-        let inner_bottom = bottom - MARKER_BORDER;
+        let inner_left = left + border;
+        let inner_top = top + border;
+        let inner_right = right - border;
+        let inner_bottom = bottom - border;
         if inner_right > inner_left && inner_bottom > inner_top {
             let _ = Ellipse(hdc, inner_left, inner_top, inner_right, inner_bottom);
         }
@@ -1029,7 +1141,7 @@ fn treeview_tail_fill_rect(item_draw_rect: RECT, client_rect: RECT) -> Option<RE
     })
 }
 
-fn treeview_selection_accent_rect(item_draw_rect: RECT) -> Option<RECT> {
+fn treeview_selection_accent_rect(item_draw_rect: RECT, scale: DpiScale) -> Option<RECT> {
     if item_draw_rect.bottom <= item_draw_rect.top {
         return None;
     }
@@ -1037,7 +1149,7 @@ fn treeview_selection_accent_rect(item_draw_rect: RECT) -> Option<RECT> {
     Some(RECT {
         left: 0,
         top: item_draw_rect.top,
-        right: SELECTION_ACCENT_WIDTH,
+        right: scale.scale(SELECTION_ACCENT_WIDTH),
         bottom: item_draw_rect.bottom,
     })
 }
@@ -1081,20 +1193,21 @@ pub(crate) fn handle_nm_customdraw(
             let marker_kind = tree_item_marker_for_display(internal_state, window_id, tree_item_id);
 
             // Gather base style colors
-            let base_style_id = internal_state
-                .with_window_data_read(window_id, |window_data| {
-                    Ok(window_data.get_style_for_control(control_id_of_treeview))
-                })
-                .unwrap_or(None);
+            let base_style =
+                internal_state
+                    .with_window_data_read(window_id, |window_data| {
+                        Ok(internal_state
+                            .parsed_style_for_control(window_data, control_id_of_treeview))
+                    })
+                    .unwrap_or(None);
 
             let mut selected_font: Option<HFONT> = None;
-            let (base_text, base_bg, base_font) = base_style_id
-                .and_then(|sid| internal_state.get_parsed_style(sid))
+            let (base_text, base_bg, base_font) = base_style
                 .map(|s| {
                     (
                         s.text_color.clone(),
                         s.background_color.clone(),
-                        s.font_handle,
+                        s.font_for_window(nmtvcd.nmcd.hdr.hwndFrom),
                     )
                 })
                 .unwrap_or((None, None, None));
@@ -1117,7 +1230,7 @@ pub(crate) fn handle_nm_customdraw(
                     (
                         s.text_color.clone(),
                         s.background_color.clone(),
-                        s.font_handle,
+                        s.font_for_window(nmtvcd.nmcd.hdr.hwndFrom),
                     )
                 })
                 .unwrap_or((None, None, None));
@@ -1244,7 +1357,7 @@ pub(crate) fn handle_nm_customdraw(
                 .and_then(|style_id| {
                     internal_state
                         .get_parsed_style(style_id)
-                        .and_then(|style| style.font_handle)
+                        .and_then(|style| style.font_for_window(hwnd_treeview))
                 })
                 .is_some()
                 || is_item_new_for_display(internal_state, window_id, tree_item_id);
@@ -1292,7 +1405,10 @@ pub(crate) fn handle_nm_customdraw(
                 && let Some(accent_style) =
                     internal_state.get_parsed_style(StyleId::TreeViewSelectionAccent)
                 && let Some(accent_color) = accent_style.background_color.as_ref()
-                && let Some(accent_rect) = treeview_selection_accent_rect(nmtvcd.nmcd.rc)
+                && let Some(accent_rect) = treeview_selection_accent_rect(
+                    nmtvcd.nmcd.rc,
+                    window_common::dpi_scale_for_window(hwnd_treeview),
+                )
             {
                 let accent_brush =
                     unsafe { CreateSolidBrush(styling_handler::color_to_colorref(accent_color)) };
@@ -1394,6 +1510,143 @@ pub(crate) fn handle_wm_app_treeview_checkbox_clicked(
     }
 }
 
+/// The tooltip the tree view created for its item tips.
+pub(crate) fn tooltip_of(hwnd_treeview: HWND) -> Option<HWND> {
+    let result = unsafe { SendMessageW(hwnd_treeview, TVM_GETTOOLTIPS, None, None) };
+    (result.0 != 0).then_some(HWND(result.0 as *mut c_void))
+}
+
+/*
+ * Handles TVN_GETINFOTIPW by filling in the item's tooltip: its descriptor's
+ * text, or else the UiStateProvider's. The provider is asked without holding
+ * the window data lock. An item without either shows no tip.
+ * [CDU-TooltipsV1]
+ */
+pub(crate) fn handle_tvn_getinfotip(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    lparam: LPARAM,
+) {
+    let info_ptr = lparam.0 as *mut NMTVGETINFOTIPW;
+    if info_ptr.is_null() {
+        return;
+    }
+    let info = unsafe { &mut *info_ptr };
+    if info.pszText.is_null() || info.cchTextMax <= 0 {
+        return;
+    }
+    let item_id = TreeItemId(info.lParam.0 as u64);
+    let own = internal_state
+        .with_window_data_read(window_id, |window_data| {
+            Ok(window_data
+                .get_treeview_state()
+                .and_then(|tv_state| tv_state.tooltips.get(&item_id).cloned()))
+        })
+        .unwrap_or_else(|err| {
+            log::error!("TreeView tooltip lookup failed for WinID {window_id:?}: {err:?}");
+            None
+        });
+    let text = tooltip::item_tooltip(own.as_deref(), || {
+        let provider = internal_state
+            .ui_state_provider()
+            .lock()
+            .ok()?
+            .as_ref()
+            .and_then(|weak_handler| weak_handler.upgrade())?;
+        let guard = provider.lock().ok()?;
+        guard.tree_item_tooltip(window_id, item_id)
+    })
+    .unwrap_or_default();
+
+    // The buffer belongs to the tree view; longer text is cut to fit.
+    let capacity = info.cchTextMax as usize - 1;
+    let units: Vec<u16> = text.encode_utf16().take(capacity).collect();
+    unsafe {
+        std::ptr::copy_nonoverlapping(units.as_ptr(), info.pszText.0, units.len());
+        *info.pszText.0.add(units.len()) = 0;
+    }
+}
+
+/*
+ * The tree item a context menu is for, with the client point (physical
+ * pixels) to report: for a right click the item under `click`, if any; from
+ * the keyboard the selected item and the lower-left corner of its label, or
+ * the control's origin when nothing is selected. [CDU-ContextMenusV1]
+ */
+pub(crate) fn context_menu_target(
+    internal_state: &Arc<Win32ApiInternalState>,
+    window_id: WindowId,
+    hwnd_treeview: HWND,
+    click: Option<POINT>,
+) -> (Option<TreeItemId>, POINT) {
+    let (h_item, anchor) = match click {
+        Some(pt) => {
+            let mut hit_info = TVHITTESTINFO {
+                pt,
+                ..Default::default()
+            };
+            let h_item = unsafe {
+                SendMessageW(
+                    hwnd_treeview,
+                    TVM_HITTEST,
+                    Some(WPARAM(0)),
+                    Some(LPARAM(&mut hit_info as *mut _ as isize)),
+                )
+            };
+            (HTREEITEM(h_item.0), pt)
+        }
+        None => {
+            let h_item = HTREEITEM(
+                unsafe {
+                    SendMessageW(
+                        hwnd_treeview,
+                        TVM_GETNEXTITEM,
+                        Some(WPARAM(TVGN_CARET as usize)),
+                        Some(LPARAM(0)),
+                    )
+                }
+                .0,
+            );
+            // TVM_GETITEMRECT takes the item in the RECT it fills.
+            let mut label_rect = RECT::default();
+            unsafe { *((&mut label_rect as *mut RECT) as *mut HTREEITEM) = h_item };
+            let has_rect = h_item.0 != 0
+                && unsafe {
+                    SendMessageW(
+                        hwnd_treeview,
+                        TVM_GETITEMRECT,
+                        Some(WPARAM(1)), // TRUE for the label only
+                        Some(LPARAM(&mut label_rect as *mut _ as isize)),
+                    )
+                }
+                .0 != 0;
+            let anchor = if has_rect {
+                POINT {
+                    x: label_rect.left,
+                    y: label_rect.bottom,
+                }
+            } else {
+                POINT::default()
+            };
+            (h_item, anchor)
+        }
+    };
+    if h_item.0 == 0 {
+        return (None, anchor);
+    }
+    let item_id = internal_state
+        .with_window_data_read(window_id, |window_data| {
+            Ok(window_data
+                .get_treeview_state()
+                .and_then(|tv_state| tv_state.htreeitem_to_item_id.get(&h_item.0).copied()))
+        })
+        .unwrap_or_else(|err| {
+            log::error!("TreeView context menu lookup failed for WinID {window_id:?}: {err:?}");
+            None
+        });
+    (item_id, anchor)
+}
+
 /*
  * Handles general NM_CLICK notifications for a TreeView.
  * This function's primary purpose is to detect clicks on a TreeView item's state
@@ -1507,7 +1760,7 @@ mod tests {
     use super::*;
 
     fn c(r: u8, g: u8, b: u8) -> Color {
-        Color { r, g, b }
+        Color::rgb(r, g, b)
     }
 
     #[test]
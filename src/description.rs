@@ -0,0 +1,519 @@
+/*
+ * Declarative UI descriptions. A TOML document lists a window's title, styles,
+ * main menu and control tree; `UiDescription::compile` validates it and emits
+ * the `PlatformCommand`s an application would otherwise build by hand, ready
+ * to pass to `main_event_loop` as `initial_commands_to_execute`.
+ *
+ * ```toml
+ * title = "Example"
+ *
+ * [[style]]
+ * id = "HeaderLabel"
+ * font = { size = 12, weight = "Bold" }
+ *
+ * [[menu]]
+ * text = "&File"
+ * children = [{ text = "E&xit", action = 1 }]
+ *
+ * [[control]]
+ * id = 100
+ * spec = { kind = "Panel" }
+ * layout = { dock = "Top", fixed_size = 30 }
+ *
+ * [[control]]
+ * id = 101
+ * parent = 100
+ * spec = { kind = "Button", text = "Go" }
+ * style = "DefaultButton"
+ * layout = { dock = "Left", fixed_size = 80 }
+ * ```
+ *
+ * Each control's `spec` names its kind and the fields of that kind. Controls
+ * reference their parent by id and may be listed in any order; they are
+ * created parents first, through `builder::WindowBuilder`. Values use the
+ * serde form of the public types (see the `serialization` module), e.g.
+ * `dock = { ProportionalFill = { weight = 1.0, axis = "Horizontal" } }`.
+ * Unknown parents, duplicate ids, parent cycles, invalid layouts such as two
+ * `Fill` siblings and misspelled keys are rejected.
+ * [CDU-DeclarativeUiV1]
+ */
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::de::{Deserializer, Error as _, IgnoredAny};
+
+use crate::builder::{ControlBuilder, ControlSpec, WindowBuilder};
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::layout::Rect;
+use crate::styling_primitives::{ControlStyle, StyleId};
+use crate::types::{
+    ContainerLayout, ControlId, DockStyle, LayoutRule, MenuItemConfig, PlatformCommand,
+    SizeConstraints, SizeMode, WindowId,
+};
+
+/// A window's declarative description: everything below its title bar.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UiDescription {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default, rename = "style")]
+    pub styles: Vec<StyleDescription>,
+    #[serde(default)]
+    pub menu: Vec<MenuItemConfig>,
+    #[serde(default, rename = "control")]
+    pub controls: Vec<ControlDescription>,
+}
+
+/// A `DefineStyle` entry: the style id plus the `ControlStyle` fields inline.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StyleDescription {
+    pub id: StyleId,
+    #[serde(flatten)]
+    pub style: ControlStyle,
+    // Keys `ControlStyle` does not have; flattening would otherwise drop them.
+    #[serde(flatten)]
+    extra: BTreeMap<String, IgnoredAny>,
+}
+
+/*
+ * One control. The `spec` table's `kind` selects the control type and its
+ * specific fields (see `builder::ControlSpec`); the optional `layout` becomes
+ * the control's `LayoutRule`, `style` is applied with `ApplyStyleToControl`
+ * after creation and `tooltip` is attached with `SetControlTooltip`.
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ControlDescription {
+    pub id: ControlId,
+    #[serde(default)]
+    pub parent: Option<ControlId>,
+    #[serde(default)]
+    pub style: Option<StyleId>,
+    #[serde(default)]
+    pub tooltip: Option<String>,
+    #[serde(default)]
+    pub layout: Option<LayoutDescription>,
+    #[serde(deserialize_with = "deserialize_spec")]
+    pub spec: ControlSpec,
+}
+
+/// The `LayoutRule` fields of a control; the id and parent come from the control.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutDescription {
+    pub dock: DockStyle,
+    /// Docking order among siblings; defaults to the declaration order.
+    #[serde(default)]
+    pub order: Option<u32>,
+    #[serde(default)]
+    pub fixed_size: Option<i32>,
+    #[serde(default)]
+    pub size_mode: SizeMode,
+    #[serde(default)]
+    pub margin: (i32, i32, i32, i32),
+    #[serde(default)]
+    pub constraints: SizeConstraints,
+    #[serde(default)]
+    pub absolute_bounds: Option<Rect>,
+    #[serde(default)]
+    pub preferred_size: Option<(i32, i32)>,
+    #[serde(default)]
+    pub container: Option<ContainerLayout>,
+}
+
+impl UiDescription {
+    /// Parses a TOML description. Syntax errors report their line and column.
+    /// Misspelled keys are rejected rather than ignored.
+    pub fn from_toml_str(source: &str) -> PlatformResult<Self> {
+        let description: Self = toml::from_str(source).map_err(|e| {
+            PlatformError::OperationFailed(format!("UI description could not be parsed: {e}"))
+        })?;
+        description.reject_unknown_keys()?;
+        Ok(description)
+    }
+
+    pub fn load(path: &Path) -> PlatformResult<Self> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            PlatformError::OperationFailed(format!(
+                "Failed to read UI description {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_toml_str(&source).map_err(|e| match e {
+            PlatformError::OperationFailed(msg) => {
+                PlatformError::OperationFailed(format!("{}: {msg}", path.display()))
+            }
+            other => other,
+        })
+    }
+
+    /*
+     * Validates the description and returns the commands that build it in
+     * `window_id`: title, style definitions, menu, control creation (parents
+     * first), initial control state and styles, and finally one
+     * `DefineLayout` with every control's rule.
+     */
+    pub fn compile(&self, window_id: WindowId) -> PlatformResult<Vec<PlatformCommand>> {
+        let mut builder = WindowBuilder::new(window_id).menu(self.menu.clone());
+        if let Some(title) = &self.title {
+            builder = builder.title(title.clone());
+        }
+        for style in &self.styles {
+            builder = builder.define_style(style.id, style.style.clone());
+        }
+        for control in &self.controls {
+            let node = control.to_builder();
+            builder = match control.parent {
+                Some(parent) => builder.child_of(parent, node),
+                None => builder.child(node),
+            };
+        }
+        Ok(builder.build()?.into_commands())
+    }
+
+    fn reject_unknown_keys(&self) -> PlatformResult<()> {
+        let unknown = |entry: String, key: &str| {
+            PlatformError::OperationFailed(format!(
+                "UI description could not be parsed: {entry} has unknown key `{key}`"
+            ))
+        };
+        for style in &self.styles {
+            if let Some(key) = style.extra.keys().next() {
+                return Err(unknown(format!("style {:?}", style.id), key));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ControlDescription {
+    fn to_builder(&self) -> ControlBuilder {
+        let mut node = ControlBuilder::new(self.id, self.spec.clone());
+        if let Some(style_id) = self.style {
+            node = node.style(style_id);
+        }
+        if let Some(text) = &self.tooltip {
+            node = node.tooltip(text.clone());
+        }
+        if let Some(layout) = &self.layout {
+            node = node.layout(LayoutRule {
+                fixed_size: layout.fixed_size,
+                size_mode: layout.size_mode,
+                margin: layout.margin,
+                constraints: layout.constraints,
+                absolute_bounds: layout.absolute_bounds,
+                preferred_size: layout.preferred_size,
+                container: layout.container.clone(),
+                ..LayoutRule::new(self.id, layout.dock)
+            });
+            if let Some(order) = layout.order {
+                node = node.order(order);
+            }
+        }
+        node
+    }
+}
+
+/*
+ * `ControlSpec` denies unknown fields, but serde skips that check for unit
+ * variants, so a kind without fields must not come with other keys.
+ */
+fn deserialize_spec<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ControlSpec, D::Error> {
+    let table = toml::Table::deserialize(deserializer)?;
+    let stray_key = table.keys().find(|key| *key != "kind").cloned();
+    let spec = ControlSpec::deserialize(toml::Value::Table(table)).map_err(D::Error::custom)?;
+    match stray_key {
+        Some(key) if !has_fields(&spec) => Err(D::Error::custom(format!(
+            "unknown field `{key}`, {spec:?} takes no fields"
+        ))),
+        _ => Ok(spec),
+    }
+}
+
+fn has_fields(spec: &ControlSpec) -> bool {
+    match spec {
+        ControlSpec::Panel
+        | ControlSpec::RichEdit
+        | ControlSpec::TreeView
+        | ControlSpec::Chart
+        | ControlSpec::ProgressBar => false,
+        ControlSpec::Button { .. }
+        | ControlSpec::Label { .. }
+        | ControlSpec::Input { .. }
+        | ControlSpec::Splitter { .. }
+        | ControlSpec::ComboBox { .. }
+        | ControlSpec::RadioButton { .. }
+        | ControlSpec::CheckBox { .. }
+        | ControlSpec::TabBar { .. }
+        | ControlSpec::ToggleSwitch { .. }
+        | ControlSpec::ListView { .. }
+        | ControlSpec::Slider { .. }
+        | ControlSpec::NumericInput { .. }
+        | ControlSpec::StatusBar { .. }
+        | ControlSpec::Toolbar { .. }
+        | ControlSpec::Image { .. } => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessPlatform;
+    use crate::types::{ColumnAlignment, ListViewColumn, WindowConfig};
+
+    const SAMPLE: &str = r#"
+title = "Described"
+
+[[style]]
+id = "HeaderLabel"
+font = { size = 12, weight = "Bold" }
+text_color = { r = 10, g = 20, b = 30 }
+
+[[menu]]
+text = "&File"
+children = [{ text = "E&xit", action = 1 }]
+
+[[control]]
+id = 101
+parent = 100
+spec = { kind = "Button", text = "Go" }
+style = "DefaultButton"
+layout = { dock = "Left", fixed_size = 80 }
+
+[[control]]
+id = 100
+spec = { kind = "Panel" }
+layout = { dock = "Top", fixed_size = 30, margin = [2, 2, 2, 2] }
+
+[[control]]
+id = 102
+parent = 100
+spec = { kind = "ComboBox", items = ["One", "Two"], selected_index = 1 }
+layout = { dock = "Fill" }
+
+[[control]]
+id = 103
+spec = { kind = "Label", text = "Status", class = "StatusBar" }
+layout = { dock = "Fill" }
+"#;
+
+    fn setup() -> (HeadlessPlatform, WindowId) {
+        let mut platform = HeadlessPlatform::new();
+        let window_id = platform
+            .create_window(WindowConfig {
+                title: "Untitled",
+                width: 400,
+                height: 300,
+            })
+            .unwrap();
+        (platform, window_id)
+    }
+
+    fn compile(source: &str) -> PlatformResult<Vec<PlatformCommand>> {
+        UiDescription::from_toml_str(source)?.compile(WindowId::new(1))
+    }
+
+    #[test]
+    // [CDU-DeclarativeUiV1] A description compiles to commands that build the described window.
+    fn description_compiles_to_commands_that_build_the_window() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let description = UiDescription::from_toml_str(SAMPLE).unwrap();
+
+        // Act
+        let commands = description.compile(window_id).unwrap();
+        for command in commands {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Assert
+        let window = platform.window(window_id).unwrap();
+        assert_eq!(window.title, "Described");
+        assert_eq!(window.menu_items[0].children[0].text, "E&xit");
+        assert_eq!(window.children(None), vec![ControlId(100), ControlId(103)]);
+        assert_eq!(
+            window.children(Some(ControlId(100))),
+            vec![ControlId(101), ControlId(102)]
+        );
+        let combo = window.control(ControlId(102)).unwrap();
+        assert_eq!(combo.items, vec!["One".to_string(), "Two".to_string()]);
+        assert_eq!(combo.selected_index, Some(1));
+        assert_eq!(
+            window.control(ControlId(101)).unwrap().style_id,
+            Some(StyleId::DefaultButton)
+        );
+        assert_eq!(
+            window.control(ControlId(100)).unwrap().rect,
+            Some(Rect::new(2, 2, 398, 32))
+        );
+        assert!(platform.defined_style(StyleId::HeaderLabel).is_some());
+    }
+
+    #[test]
+    // [CDU-DeclarativeUiV1] Duplicate ids and unknown parents are rejected before any command is emitted.
+    fn duplicate_ids_and_unknown_parents_are_rejected() {
+        // Arrange
+        let duplicate = "[[control]]\nid = 1\nspec = { kind = \"Panel\" }\n\n[[control]]\nid = 1\nspec = { kind = \"TreeView\" }\n";
+        let orphan =
+            "[[control]]\nid = 2\nparent = 9\nspec = { kind = \"Button\", text = \"Go\" }\n";
+        let cycle = "[[control]]\nid = 3\nparent = 4\nspec = { kind = \"Panel\" }\n\n[[control]]\nid = 4\nparent = 3\nspec = { kind = \"Panel\" }\n";
+
+        // Act
+        let results = [compile(duplicate), compile(orphan), compile(cycle)];
+
+        // Assert
+        let messages: Vec<String> = results
+            .into_iter()
+            .map(|r| r.unwrap_err().to_string())
+            .collect();
+        assert!(messages[0].contains("control 1 is declared more than once"));
+        assert!(messages[1].contains("control 2 names unknown parent 9"));
+        assert!(messages[2].contains("is its own ancestor"));
+    }
+
+    #[test]
+    // [CDU-DeclarativeUiV1] Layout validation applies, e.g. to competing Fill siblings.
+    fn multiple_fill_children_are_rejected() {
+        // Arrange
+        let source = r#"
+[[control]]
+id = 1
+spec = { kind = "TreeView" }
+layout = { dock = "Fill" }
+
+[[control]]
+id = 2
+spec = { kind = "RichEdit" }
+layout = { dock = "Fill" }
+"#;
+
+        // Act
+        let result = compile(source);
+
+        // Assert
+        assert!(matches!(result, Err(PlatformError::OperationFailed(msg)) if msg.contains("Fill")));
+    }
+
+    #[test]
+    // [CDU-Styling-CustomIdsV1] Descriptions define and apply application style ids.
+    fn custom_style_ids_can_be_defined_and_applied() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let source = r#"
+[[style]]
+id = { Custom = 5 }
+text_color = { r = 1, g = 2, b = 3 }
+
+[[control]]
+id = 1
+spec = { kind = "Label", text = "Note" }
+style = { Custom = 5 }
+"#;
+
+        // Act
+        let commands = UiDescription::from_toml_str(source)
+            .unwrap()
+            .compile(window_id)
+            .unwrap();
+        for command in commands {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Assert
+        assert!(platform.defined_style(StyleId::Custom(5)).is_some());
+        assert_eq!(
+            platform
+                .window(window_id)
+                .unwrap()
+                .control(ControlId(1))
+                .unwrap()
+                .style_id,
+            Some(StyleId::Custom(5))
+        );
+    }
+
+    #[test]
+    fn parse_errors_report_their_location() {
+        // Arrange
+        let source = "[[control]]\nspec = { kind = \"Panel\" }\nid = \"not a number\"\n";
+
+        // Act
+        let result = UiDescription::from_toml_str(source);
+
+        // Assert
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("line 3"), "{message}");
+    }
+
+    #[test]
+    // [CDU-DeclarativeUiV1] Misspelled keys are rejected instead of silently ignored.
+    fn misspelled_keys_are_rejected() {
+        // Arrange
+        let control = "[[control]]\nid = 1\nspec = { kind = \"Panel\" }\n\n[[control]]\nid = 2\nprent = 1\nspec = { kind = \"Button\", text = \"Go\" }\n";
+        let spec = "[[control]]\nid = 1\nspec = { kind = \"Button\", txt = \"Go\" }\n";
+        let unit_spec = "[[control]]\nid = 1\nspec = { kind = \"Panel\", txt = \"Go\" }\n";
+        let style = "[[style]]\nid = \"HeaderLabel\"\ntxt_color = { r = 1, g = 2, b = 3 }\n";
+        let tab_bar =
+            "[[control]]\nid = 1\nspec = { kind = \"TabBar\", items = [\"A\"], images = [1] }\n";
+
+        // Act
+        let control_result = UiDescription::from_toml_str(control);
+        let spec_result = UiDescription::from_toml_str(spec);
+        let unit_spec_result = UiDescription::from_toml_str(unit_spec);
+        let style_result = UiDescription::from_toml_str(style);
+        let tab_bar_result = UiDescription::from_toml_str(tab_bar);
+
+        // Assert
+        let message = control_result.unwrap_err().to_string();
+        assert!(message.contains("unknown field `prent`"), "{message}");
+        assert!(message.contains("line 7"), "{message}");
+        let message = spec_result.unwrap_err().to_string();
+        assert!(message.contains("unknown field `txt`"), "{message}");
+        let message = unit_spec_result.unwrap_err().to_string();
+        assert!(message.contains("unknown field `txt`"), "{message}");
+        let message = style_result.unwrap_err().to_string();
+        assert!(
+            message.contains("HeaderLabel has unknown key `txt_color`"),
+            "{message}"
+        );
+        assert!(tab_bar_result.is_ok());
+    }
+
+    #[test]
+    // [CDU-Control-ListViewV1] ListView columns are described with defaults for width, alignment and sorting.
+    fn list_view_columns_are_described() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let source = r#"
+[[control]]
+id = 1
+[control.spec]
+kind = "ListView"
+columns = [{ title = "Name", sortable = true }, { title = "Size", width = 60, alignment = "Right" }]
+"#;
+
+        // Act
+        let commands = UiDescription::from_toml_str(source)
+            .unwrap()
+            .compile(window_id)
+            .unwrap();
+        for command in commands {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Assert
+        let control = platform.control(window_id, ControlId(1)).unwrap();
+        assert_eq!(
+            control.columns,
+            vec![
+                ListViewColumn::new("Name").sortable(),
+                ListViewColumn::new("Size")
+                    .width(60)
+                    .alignment(ColumnAlignment::Right),
+            ]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+/*
+ * Per-monitor DPI scaling. Layout rules, window sizes, the sizes and
+ * positions reported in `AppEvent`s and the geometry of the owner-drawn
+ * controls are expressed in device-independent pixels (DIPs): one DIP is one
+ * pixel at 96 DPI. A `DpiScale` converts DIPs to the physical pixels of the
+ * monitor a window is on, so the same rules give proportionally larger
+ * controls on a 150% display. Font sizes are points, which follow the DPI by
+ * definition (`font_pixel_size`).
+ *
+ * Backends keep one `DpiScale` per window. When a window moves to a monitor
+ * with a different DPI they re-run its layout, re-create the fonts of its
+ * controls and raise `AppEvent::DpiChanged`.
+ * [CDU-DpiAwarenessV1]
+ */
+
+use crate::layout::Rect;
+use crate::types::{ContainerLayout, GridTrack, LayoutRule, SizeConstraints};
+
+/// The DPI at which one DIP is one pixel.
+pub const DEFAULT_DPI: u32 = 96;
+
+const POINTS_PER_INCH: u32 = 72;
+
+/// The scale of one window: its monitor's DPI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DpiScale {
+    pub dpi: u32,
+}
+
+impl Default for DpiScale {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl DpiScale {
+    pub const DEFAULT: DpiScale = DpiScale { dpi: DEFAULT_DPI };
+
+    /// A DPI of zero, as returned by a failed native query, means 96.
+    pub const fn new(dpi: u32) -> Self {
+        Self {
+            dpi: if dpi == 0 { DEFAULT_DPI } else { dpi },
+        }
+    }
+
+    /// The scale factor, e.g. 1.5 at 144 DPI.
+    pub fn factor(&self) -> f64 {
+        self.dpi as f64 / DEFAULT_DPI as f64
+    }
+
+    /// DIPs to pixels, rounded to the nearest pixel.
+    pub fn scale(&self, dips: i32) -> i32 {
+        mul_div_round(dips, self.dpi, DEFAULT_DPI)
+    }
+
+    /// Pixels to DIPs, rounded to the nearest DIP.
+    pub fn unscale(&self, pixels: i32) -> i32 {
+        mul_div_round(pixels, DEFAULT_DPI, self.dpi)
+    }
+
+    pub fn scale_size(&self, (width, height): (i32, i32)) -> (i32, i32) {
+        (self.scale(width), self.scale(height))
+    }
+
+    /// Scales every edge, so adjacent rectangles stay adjacent.
+    pub fn scale_rect(&self, rect: Rect) -> Rect {
+        Rect::new(
+            self.scale(rect.left),
+            self.scale(rect.top),
+            self.scale(rect.right),
+            self.scale(rect.bottom),
+        )
+    }
+
+    /// The pixel height of a font of `point_size` points.
+    pub fn font_pixel_size(&self, point_size: i32) -> i32 {
+        mul_div_round(point_size, self.dpi, POINTS_PER_INCH)
+    }
+
+    /*
+     * Converts a rule from DIPs to pixels: fixed size, margins, constraints,
+     * absolute bounds, preferred size, fixed grid tracks and container
+     * spacing. Weights, orders and grid positions are unit-free and kept.
+     */
+    pub fn scale_rule(&self, rule: &LayoutRule) -> LayoutRule {
+        let scale_opt = |value: Option<i32>| value.map(|v| self.scale(v));
+        let (top, right, bottom, left) = rule.margin;
+        LayoutRule {
+            fixed_size: scale_opt(rule.fixed_size),
+            margin: (
+                self.scale(top),
+                self.scale(right),
+                self.scale(bottom),
+                self.scale(left),
+            ),
+            constraints: SizeConstraints {
+                min_width: scale_opt(rule.constraints.min_width),
+                max_width: scale_opt(rule.constraints.max_width),
+                min_height: scale_opt(rule.constraints.min_height),
+                max_height: scale_opt(rule.constraints.max_height),
+            },
+            absolute_bounds: rule.absolute_bounds.map(|r| self.scale_rect(r)),
+            preferred_size: rule.preferred_size.map(|s| self.scale_size(s)),
+            container: rule.container.as_ref().map(|c| self.scale_container(c)),
+            ..rule.clone()
+        }
+    }
+
+    pub fn scale_rules(&self, rules: &[LayoutRule]) -> Vec<LayoutRule> {
+        rules.iter().map(|rule| self.scale_rule(rule)).collect()
+    }
+
+    fn scale_container(&self, container: &ContainerLayout) -> ContainerLayout {
+        match container {
+            ContainerLayout::Grid(grid) => {
+                let tracks = |tracks: &[GridTrack]| {
+                    tracks
+                        .iter()
+                        .map(|track| match *track {
+                            GridTrack::Fixed(size) => GridTrack::Fixed(self.scale(size)),
+                            other => other,
+                        })
+                        .collect()
+                };
+                let mut grid = grid.clone();
+                grid.rows = tracks(&grid.rows);
+                grid.columns = tracks(&grid.columns);
+                grid.row_spacing = self.scale(grid.row_spacing);
+                grid.column_spacing = self.scale(grid.column_spacing);
+                ContainerLayout::Grid(grid)
+            }
+            ContainerLayout::Flow(flow) => {
+                let mut flow = *flow;
+                flow.spacing = self.scale(flow.spacing);
+                flow.line_spacing = self.scale(flow.line_spacing);
+                ContainerLayout::Flow(flow)
+            }
+        }
+    }
+}
+
+// `value * numerator / denominator`, rounding halves away from zero like Win32 `MulDiv`.
+fn mul_div_round(value: i32, numerator: u32, denominator: u32) -> i32 {
+    let product = value as i64 * numerator as i64;
+    let denominator = denominator as i64;
+    let rounded = if product >= 0 {
+        (product + denominator / 2) / denominator
+    } else {
+        (product - denominator / 2) / denominator
+    };
+    rounded.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ControlId, DockStyle, FlowLayout, GridDefinition, LayoutAxis};
+
+    #[test]
+    // [CDU-DpiAwarenessV1] DIPs scale to pixels and back with rounding, and points follow the DPI.
+    fn dips_scale_to_pixels_and_back() {
+        // Arrange
+        let scale = DpiScale::new(144);
+
+        // Act
+        let scaled = [scale.scale(10), scale.scale(3), scale.scale(-3)];
+        let unscaled = scale.unscale(scale.scale(75));
+
+        // Assert
+        assert_eq!(scaled, [15, 5, -5]);
+        assert_eq!(unscaled, 75);
+        assert_eq!(scale.font_pixel_size(9), 18);
+        assert_eq!(DpiScale::new(0), DpiScale::DEFAULT);
+        assert_eq!(DpiScale::DEFAULT.scale(i32::MAX), i32::MAX);
+        assert!((scale.factor() - 1.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    // [CDU-DpiAwarenessV1] Every length of a layout rule is scaled; weights and positions are not.
+    fn scale_rule_scales_every_length() {
+        // Arrange
+        let rule = LayoutRule {
+            fixed_size: Some(20),
+            margin: (2, 4, 6, 8),
+            constraints: SizeConstraints {
+                min_width: Some(100),
+                ..SizeConstraints::default()
+            },
+            preferred_size: Some((50, 10)),
+            container: Some(ContainerLayout::Grid(GridDefinition {
+                rows: vec![GridTrack::Fixed(30), GridTrack::Star(1.0)],
+                columns: vec![GridTrack::Auto],
+                row_spacing: 4,
+                column_spacing: 0,
+            })),
+            ..LayoutRule::new(
+                ControlId(1),
+                DockStyle::ProportionalFill {
+                    weight: 2.0,
+                    axis: LayoutAxis::Horizontal,
+                },
+            )
+        };
+        let flow = LayoutRule {
+            container: Some(ContainerLayout::Flow(FlowLayout {
+                spacing: 6,
+                ..FlowLayout::default()
+            })),
+            ..LayoutRule::new(ControlId(2), DockStyle::Fill)
+        };
+
+        // Act
+        let scaled = DpiScale::new(192).scale_rules(&[rule.clone(), flow]);
+
+        // Assert
+        assert_eq!(scaled[0].fixed_size, Some(40));
+        assert_eq!(scaled[0].margin, (4, 8, 12, 16));
+        assert_eq!(scaled[0].constraints.min_width, Some(200));
+        assert_eq!(scaled[0].constraints.max_width, None);
+        assert_eq!(scaled[0].preferred_size, Some((100, 20)));
+        assert_eq!(scaled[0].dock_style, rule.dock_style);
+        let Some(ContainerLayout::Grid(grid)) = &scaled[0].container else {
+            panic!("grid container expected");
+        };
+        assert_eq!(grid.rows, vec![GridTrack::Fixed(60), GridTrack::Star(1.0)]);
+        assert_eq!(grid.row_spacing, 8);
+        let Some(ContainerLayout::Flow(flow)) = &scaled[1].container else {
+            panic!("flow container expected");
+        };
+        assert_eq!(flow.spacing, 12);
+    }
+}
@@ -0,0 +1,4069 @@
+/*
+ * Provides `HeadlessPlatform`, a portable in-memory implementation of the
+ * platform layer. It consumes the same `PlatformCommand`s as the Win32 backend
+ * and keeps a virtual control tree (windows, logical ControlIds, parents, text,
+ * enabled/check state, tree items, combo entries, tab selection, slider
+ * values, status bar segments, toolbar items, images, tooltips) that tests
+ * can inspect on any operating system. Registered images are decoded exactly as
+ * on Windows, so invalid image bytes fail here too.
+ *
+ * The headless backend drives the `PlatformEventHandler::try_dequeue_command`
+ * loop like `PlatformInterface::main_event_loop`, but instead of pumping OS
+ * messages it delivers `AppEvent`s injected by the test. Injected user events
+ * update the virtual tree first, mirroring how a native control changes state
+ * before Windows notifies the application. Errors follow the Win32 backend:
+ * unknown windows and controls surface as `InvalidHandle`, duplicate creations
+ * as `OperationFailed`.
+ *
+ * `SizeMode::Auto` rules are measured with `ApproximateTextMetrics` unless the
+ * test installs its own `TextMetrics` through `with_text_metrics`. Windows
+ * start at 96 DPI; injecting `AppEvent::DpiChanged` moves one to another DPI.
+ * Window sizes, layout rules and measurements are in DIPs and control
+ * rectangles in pixels at the window's DPI, as on a native window.
+ *
+ * Modal dialogs are not simulated. `Show*Dialog` and `ShowMessageBox` commands
+ * are recorded in `shown_dialogs()` and the test injects the matching
+ * `*DialogCompleted` event to play the user's answer.
+ */
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, Weak};
+
+use crate::dpi::DpiScale;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::image::{self, ImageRegistry};
+use crate::layout::{self, Rect};
+use crate::measure::{self, ApproximateTextMetrics, MeasureKind, TextMetrics};
+use crate::numeric::NumericSpec;
+#[cfg(feature = "recording")]
+use crate::recording::{RecordedSession, SessionEntry, SessionRecorder};
+use crate::slider::{SliderOrientation, SliderRange};
+use crate::status_bar::{self, SegmentContent, StatusSegment};
+use crate::styling_primitives::{ControlStyle, StyleId, resolve_style};
+use crate::theme::Theme;
+use crate::toolbar::{self, ToolbarItem, ToolbarItemKind};
+use crate::tooltip::{self, TooltipOptions};
+use crate::types::{
+    AppEvent, ChartDataPacket, ControlId, ImageId, LayoutRule, ListViewColumn,
+    ListViewDataProvider, MenuActionId, MenuItemConfig, MessageSeverity, PlatformCommand,
+    PlatformEventHandler, SizeMode, SortDirection, TreeItemDescriptor, TreeItemId,
+    TreeItemMarkerKind, UiStateProvider, WindowConfig, WindowId,
+};
+
+/// The kind of a virtual control, matching the `Create*` command that produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeadlessControlKind {
+    Button,
+    Panel,
+    Label,
+    Input,
+    RichEdit,
+    TreeView,
+    Chart,
+    ProgressBar,
+    Splitter,
+    ComboBox,
+    RadioButton,
+    CheckBox,
+    TabBar,
+    ToggleSwitch,
+    ListView,
+    Slider,
+    NumericInput,
+    StatusBar,
+    Toolbar,
+    Image,
+}
+
+/// The observable state of one virtual control.
+#[derive(Debug, Clone)]
+pub struct HeadlessControl {
+    pub kind: HeadlessControlKind,
+    pub parent_control_id: Option<ControlId>,
+    /// Caption, label, input text or RTF content depending on the kind.
+    pub text: String,
+    pub enabled: bool,
+    /// Check state of check boxes, radio buttons and toggle switches.
+    pub checked: bool,
+    /// Severity of the last `UpdateLabelText`.
+    pub severity: MessageSeverity,
+    pub tree_items: Vec<TreeItemDescriptor>,
+    pub selected_tree_item: Option<TreeItemId>,
+    /// ComboBox entries or TabBar labels.
+    pub items: Vec<String>,
+    /// ComboBox selection, active TabBar index or selected ListView row.
+    pub selected_index: Option<usize>,
+    pub columns: Vec<ListViewColumn>,
+    /// ListView rows from `SetListViewRows`; empty in virtual mode.
+    pub rows: Vec<Vec<String>>,
+    pub row_count: usize,
+    /// Set by `SetListViewRowCount`; cell texts then come from the `ListViewDataProvider`.
+    pub virtual_rows: bool,
+    pub sort_indicator: Option<(usize, SortDirection)>,
+    pub progress_range: (u32, u32),
+    pub progress_position: u32,
+    /// Range and orientation of a slider; `None` for every other kind.
+    pub slider_range: Option<SliderRange>,
+    pub slider_orientation: SliderOrientation,
+    /// Current slider value, always snapped to `slider_range`.
+    pub value: i32,
+    /// Spec of a numeric input; `None` for every other kind. Its `text` is the shown text.
+    pub numeric_spec: Option<NumericSpec>,
+    /// Last valid value of a numeric input.
+    pub numeric_value: f64,
+    /// Set while a numeric input's text does not parse; the control then
+    /// renders with `StyleId::DefaultInputError` (see `effective_style`).
+    pub input_invalid: bool,
+    /// Segments of a status bar as created, and what each one shows.
+    pub status_segments: Vec<StatusSegment>,
+    pub segment_contents: Vec<SegmentContent>,
+    /// Items of a toolbar, with their current enabled and checked state.
+    pub toolbar_items: Vec<ToolbarItem>,
+    /// Image shown by an image control or next to a button's text.
+    pub image: Option<ImageId>,
+    /// Images of TabBar tabs by index.
+    pub item_images: Vec<Option<ImageId>>,
+    /// Text shown on hover, as displayed, with the options it was set with.
+    pub tooltip: Option<String>,
+    pub tooltip_options: TooltipOptions,
+    /// Tooltips given to TabBar tabs by index; tabs without one ask the `UiStateProvider`.
+    pub item_tooltips: Vec<Option<String>>,
+    /// Last scroll position as (vertical, horizontal) percentages.
+    pub scroll_position: (u32, u32),
+    pub chart_data: Option<ChartDataPacket>,
+    pub style_id: Option<StyleId>,
+    /// Parent-relative rectangle in pixels from the last layout pass, if a rule covers the control.
+    pub rect: Option<Rect>,
+    radio_group_start: bool,
+}
+
+impl HeadlessControl {
+    fn new(kind: HeadlessControlKind, parent_control_id: Option<ControlId>, text: String) -> Self {
+        Self {
+            kind,
+            parent_control_id,
+            text,
+            enabled: true,
+            checked: false,
+            severity: MessageSeverity::None,
+            tree_items: Vec::new(),
+            selected_tree_item: None,
+            items: Vec::new(),
+            selected_index: None,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            row_count: 0,
+            virtual_rows: false,
+            sort_indicator: None,
+            progress_range: (0, 100),
+            progress_position: 0,
+            slider_range: None,
+            slider_orientation: SliderOrientation::default(),
+            value: 0,
+            numeric_spec: None,
+            numeric_value: 0.0,
+            input_invalid: false,
+            status_segments: Vec::new(),
+            segment_contents: Vec::new(),
+            toolbar_items: Vec::new(),
+            image: None,
+            item_images: Vec::new(),
+            tooltip: None,
+            tooltip_options: TooltipOptions::default(),
+            item_tooltips: Vec::new(),
+            scroll_position: (0, 0),
+            chart_data: None,
+            style_id: None,
+            rect: None,
+            radio_group_start: false,
+        }
+    }
+
+    /// Looks up a tree item anywhere in the hierarchy of a TreeView.
+    pub fn find_tree_item(&self, item_id: TreeItemId) -> Option<&TreeItemDescriptor> {
+        find_tree_item(&self.tree_items, item_id)
+    }
+
+    /// Intrinsic size from the control's text and the font of its effective
+    /// style (see `HeadlessWindow::effective_style`), for kinds that have one.
+    pub fn preferred_size(
+        &self,
+        style: Option<&ControlStyle>,
+        metrics: &dyn TextMetrics,
+    ) -> Option<(i32, i32)> {
+        let kind = match self.kind {
+            HeadlessControlKind::Button => MeasureKind::Button,
+            HeadlessControlKind::Label => MeasureKind::Label,
+            HeadlessControlKind::Input => MeasureKind::Input,
+            HeadlessControlKind::NumericInput => MeasureKind::NumericInput,
+            HeadlessControlKind::CheckBox => MeasureKind::CheckBox,
+            HeadlessControlKind::RadioButton => MeasureKind::RadioButton,
+            HeadlessControlKind::ToggleSwitch => MeasureKind::ToggleSwitch,
+            HeadlessControlKind::ComboBox => MeasureKind::ComboBox,
+            HeadlessControlKind::ProgressBar => MeasureKind::ProgressBar,
+            HeadlessControlKind::StatusBar => MeasureKind::StatusBar,
+            HeadlessControlKind::Toolbar => MeasureKind::Toolbar,
+            _ => return None,
+        };
+        let text = if kind == MeasureKind::ComboBox {
+            self.items.join("\n")
+        } else {
+            self.text.clone()
+        };
+        let font = style
+            .and_then(|style| style.font.clone())
+            .unwrap_or_default();
+        let size = measure::preferred_size(kind, &text, &font, metrics, DpiScale::DEFAULT);
+        // Buttons with an image grow by the image and the gap before the text.
+        if kind == MeasureKind::Button && self.image.is_some() {
+            return Some(image::with_leading_image(size, DpiScale::DEFAULT));
+        }
+        Some(size)
+    }
+}
+
+/// The observable state of one virtual top-level window.
+#[derive(Debug, Clone)]
+pub struct HeadlessWindow {
+    pub title: String,
+    /// Client size in DIPs.
+    pub width: i32,
+    pub height: i32,
+    pub dpi_scale: DpiScale,
+    pub visible: bool,
+    pub menu_items: Vec<MenuItemConfig>,
+    /// Items of the open context menu, until one is chosen or the menu is dismissed.
+    pub context_menu: Option<Vec<MenuItemConfig>>,
+    pub layout_rules: Option<Vec<LayoutRule>>,
+    pub ui_setup_complete: bool,
+    controls: HashMap<ControlId, HeadlessControl>,
+    // Creation order, used for radio-button grouping like the Win32 z-order.
+    creation_order: Vec<ControlId>,
+}
+
+impl HeadlessWindow {
+    pub fn control(&self, control_id: ControlId) -> Option<&HeadlessControl> {
+        self.controls.get(&control_id)
+    }
+
+    /// Returns the direct children of `parent` (`None` = client area) in creation order.
+    pub fn children(&self, parent: Option<ControlId>) -> Vec<ControlId> {
+        self.creation_order
+            .iter()
+            .copied()
+            .filter(|id| self.controls[id].parent_control_id == parent)
+            .collect()
+    }
+
+    pub fn control_count(&self) -> usize {
+        self.controls.len()
+    }
+
+    /*
+     * The style a control renders with: its applied style resolved through its
+     * base chain, with the font and colors it leaves unset inherited from the
+     * nearest styled ancestors. Controls without an applied style have none.
+     * Numeric inputs with invalid text use `StyleId::DefaultInputError` instead.
+     * [CDU-Styling-InheritanceV1]
+     */
+    pub fn effective_style(
+        &self,
+        control_id: ControlId,
+        styles: &HashMap<StyleId, ControlStyle>,
+    ) -> Option<ControlStyle> {
+        let resolved = |style_id| resolve_style(style_id, styles).ok().flatten();
+        let control = self.controls.get(&control_id)?;
+        let style_id = if control.input_invalid {
+            StyleId::DefaultInputError
+        } else {
+            control.style_id?
+        };
+        let mut style = resolved(style_id)?;
+        let mut parent = control.parent_control_id;
+        while let Some(ancestor) = parent.and_then(|id| self.controls.get(&id)) {
+            if let Some(ancestor_style) = ancestor.style_id.and_then(resolved) {
+                style = style.inherit_from_parent(&ancestor_style);
+            }
+            parent = ancestor.parent_control_id;
+        }
+        Some(style)
+    }
+
+    // Re-runs the portable layout engine, like WM_SIZE does for native windows.
+    // `SizeMode::Auto` rules get their `preferred_size` measured first, in
+    // DIPs; the rules are then scaled to the window's DPI.
+    fn relayout(&mut self, styles: &HashMap<StyleId, ControlStyle>, metrics: &dyn TextMetrics) {
+        let Some(rules) = &self.layout_rules else {
+            return;
+        };
+        let rules: Vec<LayoutRule> = rules
+            .iter()
+            .map(|rule| {
+                let measured = (rule.size_mode == SizeMode::Auto)
+                    .then(|| self.controls.get(&rule.control_id))
+                    .flatten()
+                    .and_then(|control| {
+                        let style = self.effective_style(rule.control_id, styles);
+                        control.preferred_size(style.as_ref(), metrics)
+                    });
+                LayoutRule {
+                    preferred_size: measured.or(rule.preferred_size),
+                    ..rule.clone()
+                }
+            })
+            .collect();
+        let scale = self.dpi_scale;
+        let rects = layout::compute_layout(
+            scale.scale(self.width),
+            scale.scale(self.height),
+            &scale.scale_rules(&rules),
+        );
+        for (control_id, control) in &mut self.controls {
+            control.rect = rects.get(control_id).copied();
+        }
+    }
+
+    fn has_menu_action(&self, action_id: MenuActionId) -> bool {
+        // Toolbar buttons and an open context menu raise the same event, but
+        // only while they can be clicked.
+        menu_contains(&self.menu_items, action_id)
+            || self
+                .context_menu
+                .as_deref()
+                .is_some_and(|items| menu_contains(items, action_id))
+            || self.controls.values().any(|control| {
+                control.enabled
+                    && control
+                        .toolbar_items
+                        .iter()
+                        .any(|item| item.enabled && item.action == Some(action_id))
+            })
+    }
+
+    fn flip_toolbar_toggles(&mut self, action_id: MenuActionId) {
+        let toggles = self
+            .controls
+            .values_mut()
+            .filter(|control| control.enabled)
+            .flat_map(|control| control.toolbar_items.iter_mut())
+            .filter(|item| {
+                item.enabled
+                    && item.kind == ToolbarItemKind::Toggle
+                    && item.action == Some(action_id)
+            });
+        for item in toggles {
+            item.checked = !item.checked;
+        }
+    }
+}
+
+/*
+ * The in-memory platform backend. Construct it with `new`, create windows with
+ * `create_window`, then either call `main_event_loop` with the application's
+ * handler or feed commands directly through `execute_command`.
+ */
+pub struct HeadlessPlatform {
+    next_window_id: usize,
+    windows: HashMap<WindowId, HeadlessWindow>,
+    defined_styles: HashMap<StyleId, ControlStyle>,
+    theme: Theme,
+    images: ImageRegistry,
+    event_handler: Option<Weak<Mutex<dyn PlatformEventHandler>>>,
+    ui_state_provider: Option<Weak<Mutex<dyn UiStateProvider>>>,
+    list_view_data_provider: Option<Weak<Mutex<dyn ListViewDataProvider>>>,
+    pending_events: VecDeque<AppEvent>,
+    shown_dialogs: Vec<PlatformCommand>,
+    command_errors: Vec<PlatformError>,
+    is_quitting: bool,
+    quit_notified: bool,
+    text_metrics: Box<dyn TextMetrics>,
+    #[cfg(feature = "recording")]
+    recorder: Option<Arc<SessionRecorder>>,
+}
+
+impl Default for HeadlessPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadlessPlatform {
+    pub fn new() -> Self {
+        Self {
+            next_window_id: 1,
+            windows: HashMap::new(),
+            defined_styles: HashMap::new(),
+            theme: Theme::dark(),
+            images: ImageRegistry::default(),
+            event_handler: None,
+            ui_state_provider: None,
+            list_view_data_provider: None,
+            pending_events: VecDeque::new(),
+            shown_dialogs: Vec::new(),
+            command_errors: Vec::new(),
+            is_quitting: false,
+            quit_notified: false,
+            text_metrics: Box::new(ApproximateTextMetrics::default()),
+            #[cfg(feature = "recording")]
+            recorder: None,
+        }
+    }
+
+    /// Replaces the text measurement used for `SizeMode::Auto` layout rules.
+    pub fn with_text_metrics(mut self, metrics: impl TextMetrics + 'static) -> Self {
+        self.text_metrics = Box::new(metrics);
+        self
+    }
+
+    /// Records windows, executed commands and delivered events until set to `None`.
+    #[cfg(feature = "recording")]
+    pub fn set_session_recorder(&mut self, recorder: Option<Arc<SessionRecorder>>) {
+        self.recorder = recorder;
+    }
+
+    /*
+     * Replays a recorded session into this backend: windows are created,
+     * commands executed, and user events and typed numeric text re-applied to
+     * the virtual tree in recorded order. Events are not delivered to any handler, and events the
+     * commands raise are dropped because the session already contains the ones
+     * that were delivered. Command failures land in `command_errors`, as they
+     * would in the run loop.
+     */
+    #[cfg(feature = "recording")]
+    pub fn replay(&mut self, session: &RecordedSession) {
+        for recorded in &session.entries {
+            match &recorded.entry {
+                SessionEntry::WindowCreated {
+                    window_id,
+                    title,
+                    width,
+                    height,
+                } => {
+                    let config = WindowConfig {
+                        title,
+                        width: *width,
+                        height: *height,
+                    };
+                    match self.create_window(config) {
+                        Ok(created) if created != *window_id => log::warn!(
+                            "Headless: Replay created {created:?} for recorded {window_id:?}; later entries may not match"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => self.command_errors.push(e),
+                    }
+                }
+                SessionEntry::Command(command) => {
+                    if let Err(e) = self.execute_command(command.as_ref().clone()) {
+                        self.command_errors.push(e);
+                    }
+                }
+                SessionEntry::Event(event) => {
+                    if let Err(e) = self.apply_user_event(event) {
+                        log::debug!("Headless: Replayed event {event:?} not applied: {e:?}");
+                    }
+                }
+                SessionEntry::NumericTextTyped {
+                    window_id,
+                    control_id,
+                    text,
+                } => {
+                    if let Err(e) = self.apply_numeric_text(*window_id, *control_id, text) {
+                        log::debug!("Headless: Replayed numeric text {text:?} not applied: {e:?}");
+                    }
+                }
+            }
+            self.pending_events.clear();
+        }
+    }
+
+    /// Creates a hidden virtual window, like `PlatformInterface::create_window`.
+    pub fn create_window(&mut self, config: WindowConfig) -> PlatformResult<WindowId> {
+        let window_id = WindowId(self.next_window_id);
+        self.next_window_id += 1;
+        #[cfg(feature = "recording")]
+        if let Some(recorder) = &self.recorder {
+            recorder.record_window_created(window_id, &config);
+        }
+        self.windows.insert(
+            window_id,
+            HeadlessWindow {
+                title: config.title.to_string(),
+                width: config.width,
+                height: config.height,
+                dpi_scale: DpiScale::DEFAULT,
+                visible: false,
+                menu_items: Vec::new(),
+                context_menu: None,
+                layout_rules: None,
+                ui_setup_complete: false,
+                controls: HashMap::new(),
+                creation_order: Vec::new(),
+            },
+        );
+        log::debug!("Headless: Created window {window_id:?}");
+        Ok(window_id)
+    }
+
+    pub fn window(&self, window_id: WindowId) -> Option<&HeadlessWindow> {
+        self.windows.get(&window_id)
+    }
+
+    pub fn control(&self, window_id: WindowId, control_id: ControlId) -> Option<&HeadlessControl> {
+        self.windows.get(&window_id)?.control(control_id)
+    }
+
+    /// The style as defined, before its `base` chain is merged in.
+    pub fn defined_style(&self, style_id: StyleId) -> Option<&ControlStyle> {
+        self.defined_styles.get(&style_id)
+    }
+
+    /// The defined style merged over its `base` chain.
+    pub fn resolved_style(&self, style_id: StyleId) -> Option<ControlStyle> {
+        resolve_style(style_id, &self.defined_styles).ok().flatten()
+    }
+
+    /// See `HeadlessWindow::effective_style`.
+    pub fn effective_style(
+        &self,
+        window_id: WindowId,
+        control_id: ControlId,
+    ) -> Option<ControlStyle> {
+        self.windows
+            .get(&window_id)?
+            .effective_style(control_id, &self.defined_styles)
+    }
+
+    /*
+     * Pixel widths of a status bar's segments at its laid-out width, with
+     * `Auto` segments measured by the platform's `TextMetrics` in the font of
+     * the bar's effective style. Empty until a layout pass covers the bar.
+     */
+    pub fn status_segment_widths(
+        &self,
+        window_id: WindowId,
+        control_id: ControlId,
+    ) -> PlatformResult<Vec<i32>> {
+        let window = self.windows.get(&window_id).ok_or_else(|| {
+            PlatformError::InvalidHandle(format!("WindowId {window_id:?} not found"))
+        })?;
+        let control = window
+            .control(control_id)
+            .filter(|control| control.kind == HeadlessControlKind::StatusBar)
+            .ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "Control ID {} is not a status bar in WinID {window_id:?}",
+                    control_id.raw()
+                ))
+            })?;
+        let Some(rect) = control.rect else {
+            return Ok(Vec::new());
+        };
+        let font = window
+            .effective_style(control_id, &self.defined_styles)
+            .and_then(|style| style.font)
+            .unwrap_or_default();
+        let text_widths: Vec<i32> = control
+            .segment_contents
+            .iter()
+            .map(|content| {
+                let (width, _) = self.text_metrics.measure_text(&content.text, &font);
+                window.dpi_scale.scale(width)
+            })
+            .collect();
+        Ok(status_bar::segment_widths(
+            &control.status_segments,
+            &text_widths,
+            rect.width(),
+            window.dpi_scale,
+        ))
+    }
+
+    /// The theme last applied with `ApplyTheme`; dark until then.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Images registered with `RegisterImage`, decoded.
+    pub fn images(&self) -> &ImageRegistry {
+        &self.images
+    }
+
+    /// Dialog and message box commands received so far, in order.
+    pub fn shown_dialogs(&self) -> &[PlatformCommand] {
+        &self.shown_dialogs
+    }
+
+    /// Errors from commands dequeued from the event handler. The Win32 loop only logs these.
+    pub fn command_errors(&self) -> &[PlatformError] {
+        &self.command_errors
+    }
+
+    pub fn is_quitting(&self) -> bool {
+        self.is_quitting
+    }
+
+    /// Asks the attached `UiStateProvider` which marker a tree item would be painted with.
+    pub fn tree_item_marker(&self, window_id: WindowId, item_id: TreeItemId) -> TreeItemMarkerKind {
+        self.ui_state_provider
+            .as_ref()
+            .and_then(Weak::upgrade)
+            .and_then(|provider| {
+                provider
+                    .lock()
+                    .ok()
+                    .map(|guard| guard.tree_item_marker(window_id, item_id))
+            })
+            .unwrap_or(TreeItemMarkerKind::None)
+    }
+
+    /*
+     * The tooltip a tree item would show: its descriptor's, else the attached
+     * `UiStateProvider`'s. `None` if the item does not exist or has none.
+     */
+    pub fn tree_item_tooltip(
+        &self,
+        window_id: WindowId,
+        control_id: ControlId,
+        item_id: TreeItemId,
+    ) -> Option<String> {
+        let item = self
+            .control(window_id, control_id)?
+            .find_tree_item(item_id)?;
+        tooltip::item_tooltip(item.tooltip.as_deref(), || {
+            let provider = self.ui_state_provider.as_ref()?.upgrade()?;
+            let guard = provider.lock().ok()?;
+            guard.tree_item_tooltip(window_id, item_id)
+        })
+    }
+
+    /// The tooltip a TabBar tab would show, resolved like `tree_item_tooltip`.
+    pub fn tab_tooltip(
+        &self,
+        window_id: WindowId,
+        control_id: ControlId,
+        index: usize,
+    ) -> Option<String> {
+        let control = self.control(window_id, control_id)?;
+        if control.kind != HeadlessControlKind::TabBar || index >= control.items.len() {
+            return None;
+        }
+        let own = control.item_tooltips.get(index).cloned().flatten();
+        tooltip::item_tooltip(own.as_deref(), || {
+            let provider = self.ui_state_provider.as_ref()?.upgrade()?;
+            let guard = provider.lock().ok()?;
+            guard.tab_tooltip(window_id, control_id, index)
+        })
+    }
+
+    /// Mirrors `PlatformInterface::set_list_view_data_provider`.
+    pub fn set_list_view_data_provider(&mut self, provider: Arc<Mutex<dyn ListViewDataProvider>>) {
+        self.list_view_data_provider = Some(Arc::downgrade(&provider));
+    }
+
+    /*
+     * The text a ListView would paint in a cell: from the stored rows, or from
+     * the attached `ListViewDataProvider` in virtual mode. `None` if the
+     * control, row or column does not exist.
+     */
+    pub fn list_view_cell(
+        &self,
+        window_id: WindowId,
+        control_id: ControlId,
+        row: usize,
+        column: usize,
+    ) -> Option<String> {
+        let control = self.control(window_id, control_id)?;
+        if control.kind != HeadlessControlKind::ListView
+            || row >= control.row_count
+            || column >= control.columns.len()
+        {
+            return None;
+        }
+        if !control.virtual_rows {
+            return Some(control.rows[row].get(column).cloned().unwrap_or_default());
+        }
+        let provider = self.list_view_data_provider.as_ref()?.upgrade()?;
+        let guard = provider.lock().ok()?;
+        Some(guard.cell_text(window_id, control_id, row, column))
+    }
+
+    /*
+     * Mirrors `PlatformInterface::main_event_loop`: attaches the handler and
+     * provider, executes the initial commands (halting on the first error), then
+     * runs until no commands or events are pending. Unlike the Win32 loop this
+     * returns when idle so the test can inject the next event.
+     */
+    pub fn main_event_loop(
+        &mut self,
+        event_handler: Arc<Mutex<dyn PlatformEventHandler>>,
+        ui_state_provider: Arc<Mutex<dyn UiStateProvider>>,
+        initial_commands: Vec<PlatformCommand>,
+    ) -> PlatformResult<()> {
+        self.event_handler = Some(Arc::downgrade(&event_handler));
+        self.ui_state_provider = Some(Arc::downgrade(&ui_state_provider));
+
+        for command in initial_commands {
+            if let Err(e) = self.execute_command(command) {
+                log::error!("Headless: Error executing initial UI command: {e:?}. Halting.");
+                return Err(e);
+            }
+        }
+        self.run_until_idle()
+    }
+
+    /*
+     * Delivers a user-originated event. The virtual tree is updated first (e.g.
+     * a `CheckBoxToggled` flips the stored check state), then the event is
+     * handed to the application and the resulting commands are executed.
+     * Events that reference unknown or disabled controls are rejected, since
+     * the native backend could never produce them.
+     */
+    pub fn inject_event(&mut self, event: AppEvent) -> PlatformResult<()> {
+        self.ensure_running()?;
+        self.apply_user_event(&event)?;
+        self.pending_events.push_back(event);
+        self.run_until_idle()
+    }
+
+    /*
+     * Plays the user typing `text` into a numeric input. The text is kept as
+     * typed; while it does not parse, the control is marked `input_invalid`.
+     * Valid text that changes the value is delivered as `NumericValueChanged`,
+     * exactly as `inject_event` would.
+     */
+    pub fn type_numeric_text(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        text: &str,
+    ) -> PlatformResult<()> {
+        self.ensure_running()?;
+        let event = self.apply_numeric_text(window_id, control_id, text)?;
+        #[cfg(feature = "recording")]
+        if let Some(recorder) = &self.recorder {
+            recorder.record_numeric_text(window_id, control_id, text);
+        }
+        self.pending_events.extend(event);
+        self.run_until_idle()
+    }
+
+    // Stores typed numeric text; returns the event to raise when it changes the value.
+    fn apply_numeric_text(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        text: &str,
+    ) -> PlatformResult<Option<AppEvent>> {
+        let control =
+            self.user_control_mut(window_id, control_id, HeadlessControlKind::NumericInput)?;
+        let parsed = control
+            .numeric_spec
+            .as_ref()
+            .and_then(|spec| spec.parse(text));
+        control.text = text.to_string();
+        control.input_invalid = parsed.is_none();
+        let changed = parsed.filter(|&value| value != control.numeric_value);
+        Ok(changed.map(|value| {
+            control.numeric_value = value;
+            AppEvent::NumericValueChanged {
+                window_id,
+                control_id,
+                value,
+            }
+        }))
+    }
+
+    /*
+     * Plays the user clicking the toolbar button with `action_id` by injecting
+     * `MenuActionClicked`, which also flips a toggle button's checked state.
+     * Disabled buttons and toolbars reject the click.
+     */
+    pub fn click_toolbar_item(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        action_id: MenuActionId,
+    ) -> PlatformResult<()> {
+        self.ensure_running()?;
+        let control = self.user_control_mut(window_id, control_id, HeadlessControlKind::Toolbar)?;
+        let item = control
+            .toolbar_items
+            .iter()
+            .find(|item| item.action == Some(action_id))
+            .ok_or_else(|| {
+                PlatformError::OperationFailed(format!(
+                    "Toolbar {} has no button for {action_id:?}",
+                    control_id.raw()
+                ))
+            })?;
+        if !item.enabled {
+            return Err(PlatformError::OperationFailed(format!(
+                "Toolbar button for {action_id:?} is disabled and cannot be clicked"
+            )));
+        }
+        self.inject_event(AppEvent::MenuActionClicked { action_id })
+    }
+
+    /*
+     * Plays the user choosing the item with `action_id` from the context menu
+     * opened by `ShowContextMenu` by injecting `MenuActionClicked`, which also
+     * closes the menu. Without an open menu holding that action the choice is
+     * rejected and the menu stays open.
+     */
+    pub fn choose_context_menu_item(
+        &mut self,
+        window_id: WindowId,
+        action_id: MenuActionId,
+    ) -> PlatformResult<()> {
+        self.ensure_running()?;
+        let window = self.window_mut(window_id)?;
+        let Some(items) = &window.context_menu else {
+            return Err(PlatformError::OperationFailed(format!(
+                "No context menu is open in WinID {window_id:?}"
+            )));
+        };
+        if !menu_contains(items, action_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "The context menu in WinID {window_id:?} has no item for {action_id:?}"
+            )));
+        }
+        self.inject_event(AppEvent::MenuActionClicked { action_id })
+    }
+
+    /// Plays the user closing the open context menu without choosing; no event is raised.
+    pub fn dismiss_context_menu(&mut self, window_id: WindowId) -> PlatformResult<()> {
+        self.window_mut(window_id)?.context_menu = None;
+        Ok(())
+    }
+
+    /// Drains application commands and pending events until both are exhausted or a quit is requested.
+    pub fn run_until_idle(&mut self) -> PlatformResult<()> {
+        let Some(handler) = self.event_handler.as_ref().and_then(Weak::upgrade) else {
+            log::warn!("Headless: run_until_idle without an attached event handler.");
+            return Ok(());
+        };
+
+        loop {
+            loop {
+                let command = match handler.lock() {
+                    Ok(mut guard) => guard.try_dequeue_command(),
+                    Err(_) => {
+                        return Err(PlatformError::OperationFailed(
+                            "Event handler mutex poisoned".into(),
+                        ));
+                    }
+                };
+                let Some(command) = command else { break };
+                if let Err(e) = self.execute_command(command) {
+                    log::error!("Headless: Error executing command from queue: {e:?}");
+                    self.command_errors.push(e);
+                }
+            }
+
+            if self.is_quitting {
+                break;
+            }
+            let Some(event) = self.pending_events.pop_front() else {
+                break;
+            };
+            log::trace!("Headless: Delivering event {event:?}");
+            #[cfg(feature = "recording")]
+            if let Some(recorder) = &self.recorder {
+                recorder.record_event(&event);
+            }
+            match handler.lock() {
+                Ok(mut guard) => guard.handle_event(event),
+                Err(_) => {
+                    return Err(PlatformError::OperationFailed(
+                        "Event handler mutex poisoned".into(),
+                    ));
+                }
+            }
+        }
+
+        if self.is_quitting && !self.quit_notified {
+            self.quit_notified = true;
+            if let Ok(mut guard) = handler.lock() {
+                guard.on_quit();
+            }
+        }
+        Ok(())
+    }
+
+    // ── Command execution ────────────────────────────────────────────────────
+
+    /// Executes a single command against the virtual tree.
+    pub fn execute_command(&mut self, command: PlatformCommand) -> PlatformResult<()> {
+        log::trace!("Headless: Executing command: {command:?}");
+        #[cfg(feature = "recording")]
+        if let Some(recorder) = &self.recorder {
+            recorder.record_command(&command);
+        }
+        use HeadlessControlKind as Kind;
+        match command {
+            PlatformCommand::SetWindowTitle { window_id, title } => {
+                self.window_mut(window_id)?.title = title;
+            }
+            PlatformCommand::ShowWindow { window_id } => {
+                self.window_mut(window_id)?.visible = true;
+            }
+            PlatformCommand::CloseWindow { window_id } => {
+                self.window_mut(window_id)?;
+                self.windows.remove(&window_id);
+                self.pending_events
+                    .push_back(AppEvent::WindowDestroyed { window_id });
+            }
+            PlatformCommand::PopulateTreeView {
+                window_id,
+                control_id,
+                items,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TreeView,
+                    "PopulateTreeView",
+                )?;
+                control.tree_items = items;
+                control.selected_tree_item = None;
+            }
+            PlatformCommand::UpdateTreeItemVisualState {
+                window_id,
+                control_id,
+                item_id,
+                new_state,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TreeView,
+                    "UpdateTreeItemVisualState",
+                )?;
+                tree_item_mut(&mut control.tree_items, item_id)?.state = new_state;
+            }
+            PlatformCommand::UpdateTreeItemText {
+                window_id,
+                control_id,
+                item_id,
+                text,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TreeView,
+                    "UpdateTreeItemText",
+                )?;
+                tree_item_mut(&mut control.tree_items, item_id)?.text = text;
+            }
+            PlatformCommand::RedrawTreeItem {
+                window_id,
+                control_id,
+                item_id,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TreeView,
+                    "RedrawTreeItem",
+                )?;
+                tree_item_mut(&mut control.tree_items, item_id)?;
+            }
+            PlatformCommand::SetTreeViewSelection {
+                window_id,
+                control_id,
+                item_id,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TreeView,
+                    "SetTreeViewSelection",
+                )?;
+                tree_item_mut(&mut control.tree_items, item_id)?;
+                control.selected_tree_item = Some(item_id);
+            }
+            PlatformCommand::ExpandVisibleTreeItems {
+                window_id,
+                control_id,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TreeView,
+                    "ExpandVisibleTreeItems",
+                )?;
+            }
+            PlatformCommand::ExpandAllTreeItems {
+                window_id,
+                control_id,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TreeView,
+                    "ExpandAllTreeItems",
+                )?;
+            }
+            PlatformCommand::ShowSaveFileDialog { window_id, .. }
+            | PlatformCommand::ShowOpenFileDialog { window_id, .. }
+            | PlatformCommand::ShowProfileSelectionDialog { window_id, .. }
+            | PlatformCommand::ShowInputDialog { window_id, .. }
+            | PlatformCommand::ShowExcludePatternsDialog { window_id, .. }
+            | PlatformCommand::ShowMessageBox { window_id, .. }
+            | PlatformCommand::ShowFolderPickerDialog { window_id, .. } => {
+                self.window_mut(window_id)?;
+                self.shown_dialogs.push(command);
+            }
+            PlatformCommand::SetControlEnabled {
+                window_id,
+                control_id,
+                enabled,
+            } => {
+                self.control_mut(window_id, control_id, "SetControlEnabled")?
+                    .enabled = enabled;
+            }
+            PlatformCommand::QuitApplication => {
+                log::debug!("Headless: QuitApplication received.");
+                self.is_quitting = true;
+            }
+            PlatformCommand::CreateMainMenu {
+                window_id,
+                menu_items,
+            } => {
+                self.window_mut(window_id)?.menu_items = menu_items;
+            }
+            PlatformCommand::SignalMainWindowUISetupComplete { window_id } => {
+                self.window_mut(window_id)?.ui_setup_complete = true;
+                self.pending_events
+                    .push_back(AppEvent::MainWindowUISetupComplete { window_id });
+            }
+            PlatformCommand::DefineLayout { window_id, rules } => {
+                let window = self.window_mut(window_id)?;
+                layout::validate_layout_rules(&rules)?;
+                window.layout_rules = Some(rules);
+                self.relayout_window(window_id);
+            }
+            PlatformCommand::CreateButton {
+                window_id,
+                parent_control_id,
+                control_id,
+                text,
+                image,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Button,
+                    text,
+                )?
+                .image = image;
+            }
+            PlatformCommand::CreateTreeView {
+                window_id,
+                parent_control_id,
+                control_id,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::TreeView,
+                    String::new(),
+                )?;
+            }
+            PlatformCommand::CreatePanel {
+                window_id,
+                parent_control_id,
+                control_id,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Panel,
+                    String::new(),
+                )?;
+            }
+            PlatformCommand::CreateLabel {
+                window_id,
+                parent_control_id,
+                control_id,
+                initial_text,
+                class: _,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Label,
+                    initial_text,
+                )?;
+            }
+            PlatformCommand::CreateInput {
+                window_id,
+                parent_control_id,
+                control_id,
+                initial_text,
+                ..
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Input,
+                    initial_text,
+                )?;
+            }
+            PlatformCommand::CreateRichEdit {
+                window_id,
+                parent_control_id,
+                control_id,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::RichEdit,
+                    String::new(),
+                )?;
+            }
+            PlatformCommand::CreateChart {
+                window_id,
+                parent_control_id,
+                control_id,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Chart,
+                    String::new(),
+                )?;
+            }
+            PlatformCommand::CreateProgressBar {
+                window_id,
+                parent_control_id,
+                control_id,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::ProgressBar,
+                    String::new(),
+                )?;
+            }
+            PlatformCommand::CreateSplitter {
+                window_id,
+                parent_control_id,
+                control_id,
+                orientation: _,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Splitter,
+                    String::new(),
+                )?;
+            }
+            PlatformCommand::CreateComboBox {
+                window_id,
+                parent_control_id,
+                control_id,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::ComboBox,
+                    String::new(),
+                )?;
+            }
+            PlatformCommand::CreateRadioButton {
+                window_id,
+                parent_control_id,
+                control_id,
+                text,
+                group_start,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::RadioButton,
+                    text,
+                )?
+                .radio_group_start = group_start;
+            }
+            PlatformCommand::CreateCheckBox {
+                window_id,
+                parent_control_id,
+                control_id,
+                text,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::CheckBox,
+                    text,
+                )?;
+            }
+            PlatformCommand::CreateTabBar {
+                window_id,
+                control_id,
+                parent_control_id,
+                items,
+                images,
+                tooltips,
+            } => {
+                let control = self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::TabBar,
+                    String::new(),
+                )?;
+                control.items = items;
+                control.item_images = images;
+                control.item_tooltips = tooltips;
+                control.selected_index = Some(0);
+            }
+            PlatformCommand::CreateListView {
+                window_id,
+                parent_control_id,
+                control_id,
+                columns,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::ListView,
+                    String::new(),
+                )?
+                .columns = columns;
+            }
+            PlatformCommand::CreateSlider {
+                window_id,
+                parent_control_id,
+                control_id,
+                range,
+                orientation,
+                value,
+            } => {
+                range.validate()?;
+                let control = self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Slider,
+                    String::new(),
+                )?;
+                control.slider_range = Some(range);
+                control.slider_orientation = orientation;
+                control.value = range.snap(value);
+            }
+            PlatformCommand::CreateNumericInput {
+                window_id,
+                parent_control_id,
+                control_id,
+                spec,
+                value,
+            } => {
+                spec.validate()?;
+                let control = self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::NumericInput,
+                    spec.format(value),
+                )?;
+                control.numeric_value = spec.clamp(value);
+                control.numeric_spec = Some(spec);
+            }
+            PlatformCommand::CreateStatusBar {
+                window_id,
+                parent_control_id,
+                control_id,
+                segments,
+            } => {
+                status_bar::validate_segments(&segments)?;
+                let control = self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::StatusBar,
+                    String::new(),
+                )?;
+                control.segment_contents = vec![SegmentContent::default(); segments.len()];
+                control.status_segments = segments;
+            }
+            PlatformCommand::CreateToolbar {
+                window_id,
+                parent_control_id,
+                control_id,
+                items,
+            } => {
+                toolbar::validate_items(&items)?;
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Toolbar,
+                    String::new(),
+                )?
+                .toolbar_items = items;
+            }
+            PlatformCommand::RegisterImage { image_id, bytes } => {
+                self.images.register(image_id, &bytes)?;
+            }
+            PlatformCommand::CreateImage {
+                window_id,
+                parent_control_id,
+                control_id,
+                image_id,
+            } => {
+                if let Some(image_id) = image_id {
+                    self.images.require(image_id)?;
+                }
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::Image,
+                    String::new(),
+                )?
+                .image = image_id;
+            }
+            PlatformCommand::SetImage {
+                window_id,
+                control_id,
+                image_id,
+            } => {
+                if let Some(image_id) = image_id {
+                    self.images.require(image_id)?;
+                }
+                self.control_of_kind_mut(window_id, control_id, Kind::Image, "SetImage")?
+                    .image = image_id;
+            }
+            PlatformCommand::SetControlTooltip {
+                window_id,
+                control_id,
+                text,
+                options,
+            } => {
+                let control = self.control_mut(window_id, control_id, "SetControlTooltip")?;
+                control.tooltip = tooltip::displayed_text(&text, &options);
+                control.tooltip_options = options;
+            }
+            PlatformCommand::ShowContextMenu {
+                window_id,
+                control_id,
+                menu_items,
+                ..
+            } => {
+                self.control_mut(window_id, control_id, "ShowContextMenu")?;
+                // A new menu replaces one still open, as only one popup menu can be tracked.
+                self.window_mut(window_id)?.context_menu = Some(menu_items);
+            }
+            PlatformCommand::CreateToggleSwitch {
+                window_id,
+                parent_control_id,
+                control_id,
+                label,
+                checked,
+            } => {
+                self.create_control(
+                    window_id,
+                    parent_control_id,
+                    control_id,
+                    Kind::ToggleSwitch,
+                    label,
+                )?
+                .checked = checked;
+            }
+            PlatformCommand::UpdateLabelText {
+                window_id,
+                control_id,
+                text,
+                severity,
+            } => {
+                let control = self.control_mut(window_id, control_id, "UpdateLabelText")?;
+                control.text = text;
+                control.severity = severity;
+                self.relayout_window(window_id);
+            }
+            PlatformCommand::SetChartData {
+                window_id,
+                control_id,
+                data,
+            } => {
+                self.control_of_kind_mut(window_id, control_id, Kind::Chart, "SetChartData")?
+                    .chart_data = Some(data);
+            }
+            PlatformCommand::SetProgressBarRange {
+                window_id,
+                control_id,
+                min,
+                max,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ProgressBar,
+                    "SetProgressBarRange",
+                )?
+                .progress_range = (min, max);
+            }
+            PlatformCommand::SetProgressBarPosition {
+                window_id,
+                control_id,
+                position,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ProgressBar,
+                    "SetProgressBarPosition",
+                )?
+                .progress_position = position;
+            }
+            PlatformCommand::SetControlText {
+                window_id,
+                control_id,
+                text,
+            } => {
+                self.control_mut(window_id, control_id, "SetControlText")?
+                    .text = text;
+                self.relayout_window(window_id);
+            }
+            PlatformCommand::SetInputText {
+                window_id,
+                control_id,
+                text,
+            } => {
+                self.control_mut(window_id, control_id, "SetInputText")?
+                    .text = text;
+                self.relayout_window(window_id);
+            }
+            PlatformCommand::SetViewerContent {
+                window_id,
+                control_id,
+                text,
+            } => {
+                self.control_mut(window_id, control_id, "SetViewerContent")?
+                    .text = text;
+            }
+            PlatformCommand::SetRichEditContent {
+                window_id,
+                control_id,
+                rtf_text,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::RichEdit,
+                    "SetRichEditContent",
+                )?
+                .text = rtf_text;
+            }
+            PlatformCommand::SetScrollPosition {
+                window_id,
+                control_id,
+                vertical_pos,
+                horizontal_pos,
+            } => {
+                self.control_mut(window_id, control_id, "SetScrollPosition")?
+                    .scroll_position = (vertical_pos, horizontal_pos);
+            }
+            PlatformCommand::SetComboBoxItems {
+                window_id,
+                control_id,
+                items,
+            } => {
+                // No implicit selection, matching CB_RESETCONTENT + CB_ADDSTRING.
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ComboBox,
+                    "SetComboBoxItems",
+                )?;
+                control.items = items;
+                control.selected_index = None;
+                self.relayout_window(window_id);
+            }
+            PlatformCommand::SetComboBoxSelection {
+                window_id,
+                control_id,
+                selected_index,
+            } => {
+                // CB_SETCURSEL with an out-of-range index clears the selection.
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ComboBox,
+                    "SetComboBoxSelection",
+                )?;
+                control.selected_index = selected_index.filter(|&i| i < control.items.len());
+            }
+            PlatformCommand::SetRadioButtonChecked {
+                window_id,
+                control_id,
+                checked,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::RadioButton,
+                    "SetRadioButtonChecked",
+                )?
+                .checked = checked;
+            }
+            PlatformCommand::SetCheckBoxChecked {
+                window_id,
+                control_id,
+                checked,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::CheckBox,
+                    "SetCheckBoxChecked",
+                )?
+                .checked = checked;
+            }
+            PlatformCommand::SetTabBarItems {
+                window_id,
+                control_id,
+                items,
+                images,
+                tooltips,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TabBar,
+                    "SetTabBarItems",
+                )?;
+                control.items = items;
+                control.item_images = images;
+                control.item_tooltips = tooltips;
+                control.selected_index = Some(0);
+            }
+            PlatformCommand::SetTabBarSelection {
+                window_id,
+                control_id,
+                selected_index,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::TabBar,
+                    "SetTabBarSelection",
+                )?;
+                control.selected_index =
+                    Some(selected_index.min(control.items.len().saturating_sub(1)));
+            }
+            PlatformCommand::SetTabBarStyle {
+                window_id,
+                control_id,
+                ..
+            } => {
+                self.control_of_kind_mut(window_id, control_id, Kind::TabBar, "SetTabBarStyle")?;
+            }
+            PlatformCommand::DefineStyle { style_id, style } => {
+                // A base chain that leads back to this style is rejected and
+                // the previous definition kept, like the Win32 backend.
+                let previous = self.defined_styles.insert(style_id, style);
+                if let Err(err) = resolve_style(style_id, &self.defined_styles) {
+                    match previous {
+                        Some(previous) => self.defined_styles.insert(style_id, previous),
+                        None => self.defined_styles.remove(&style_id),
+                    };
+                    return Err(err);
+                }
+                let window_ids: Vec<WindowId> = self.windows.keys().copied().collect();
+                for window_id in window_ids {
+                    self.relayout_window(window_id);
+                }
+            }
+            PlatformCommand::ApplyTheme { theme } => {
+                self.defined_styles
+                    .extend(theme.styles.iter().map(|(id, style)| (*id, style.clone())));
+                self.theme = theme;
+                let window_ids: Vec<WindowId> = self.windows.keys().copied().collect();
+                for window_id in window_ids {
+                    self.relayout_window(window_id);
+                }
+            }
+            PlatformCommand::ApplyStyleToControl {
+                window_id,
+                control_id,
+                style_id,
+            } => {
+                self.control_mut(window_id, control_id, "ApplyStyleToControl")?
+                    .style_id = Some(style_id);
+                self.relayout_window(window_id);
+            }
+            PlatformCommand::SetToggleSwitchState {
+                window_id,
+                control_id,
+                checked,
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ToggleSwitch,
+                    "SetToggleSwitchState",
+                )?
+                .checked = checked;
+            }
+            PlatformCommand::SetToggleSwitchStyle {
+                window_id,
+                control_id,
+                ..
+            } => {
+                self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ToggleSwitch,
+                    "SetToggleSwitchStyle",
+                )?;
+            }
+            PlatformCommand::SetSliderValue {
+                window_id,
+                control_id,
+                value,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::Slider,
+                    "SetSliderValue",
+                )?;
+                if let Some(range) = control.slider_range {
+                    control.value = range.snap(value);
+                }
+            }
+            PlatformCommand::SetNumericValue {
+                window_id,
+                control_id,
+                value,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::NumericInput,
+                    "SetNumericValue",
+                )?;
+                if let Some(spec) = &control.numeric_spec {
+                    control.numeric_value = spec.clamp(value);
+                    control.text = spec.format(value);
+                    control.input_invalid = false;
+                }
+                self.relayout_window(window_id);
+            }
+            PlatformCommand::SetStatusSegmentText {
+                window_id,
+                control_id,
+                segment,
+                text,
+                severity,
+            } => {
+                let content = self.status_segment_mut(
+                    window_id,
+                    control_id,
+                    segment,
+                    "SetStatusSegmentText",
+                )?;
+                content.text = text;
+                content.severity = severity;
+            }
+            PlatformCommand::SetStatusSegmentProgress {
+                window_id,
+                control_id,
+                segment,
+                progress,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::StatusBar,
+                    "SetStatusSegmentProgress",
+                )?;
+                if !control
+                    .status_segments
+                    .get(segment)
+                    .is_some_and(|spec| spec.progress)
+                {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "Segment {segment} of status bar {} is not a progress segment",
+                        control_id.raw()
+                    )));
+                }
+                control.segment_contents[segment].progress = progress.map(|p| p.min(100));
+            }
+            PlatformCommand::SetToolbarItemEnabled {
+                window_id,
+                control_id,
+                action_id,
+                enabled,
+            } => {
+                for item in self.toolbar_buttons_mut(
+                    window_id,
+                    control_id,
+                    action_id,
+                    false,
+                    "SetToolbarItemEnabled",
+                )? {
+                    item.enabled = enabled;
+                }
+            }
+            PlatformCommand::SetToolbarItemChecked {
+                window_id,
+                control_id,
+                action_id,
+                checked,
+            } => {
+                for item in self.toolbar_buttons_mut(
+                    window_id,
+                    control_id,
+                    action_id,
+                    true,
+                    "SetToolbarItemChecked",
+                )? {
+                    item.checked = checked;
+                }
+            }
+            PlatformCommand::SetSliderStyle {
+                window_id,
+                control_id,
+                ..
+            } => {
+                self.control_of_kind_mut(window_id, control_id, Kind::Slider, "SetSliderStyle")?;
+            }
+            PlatformCommand::SetListViewRows {
+                window_id,
+                control_id,
+                rows,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ListView,
+                    "SetListViewRows",
+                )?;
+                control.row_count = rows.len();
+                control.rows = rows;
+                control.virtual_rows = false;
+                control.selected_index = control
+                    .selected_index
+                    .filter(|&row| row < control.row_count);
+            }
+            PlatformCommand::SetListViewRowCount {
+                window_id,
+                control_id,
+                row_count,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ListView,
+                    "SetListViewRowCount",
+                )?;
+                control.rows.clear();
+                control.row_count = row_count;
+                control.virtual_rows = true;
+                control.selected_index = control.selected_index.filter(|&row| row < row_count);
+            }
+            PlatformCommand::SetListViewSortIndicator {
+                window_id,
+                control_id,
+                column,
+                direction,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ListView,
+                    "SetListViewSortIndicator",
+                )?;
+                if let Some(column) = column
+                    && column >= control.columns.len()
+                {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "ListView {} has no column at index {column}",
+                        control_id.raw()
+                    )));
+                }
+                control.sort_indicator = column.map(|column| (column, direction));
+            }
+            PlatformCommand::SetListViewSelection {
+                window_id,
+                control_id,
+                row,
+            } => {
+                let control = self.control_of_kind_mut(
+                    window_id,
+                    control_id,
+                    Kind::ListView,
+                    "SetListViewSelection",
+                )?;
+                if let Some(row) = row
+                    && row >= control.row_count
+                {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "ListView {} has no row at index {row}",
+                        control_id.raw()
+                    )));
+                }
+                control.selected_index = row;
+            }
+        }
+        Ok(())
+    }
+
+    fn relayout_window(&mut self, window_id: WindowId) {
+        if let Some(window) = self.windows.get_mut(&window_id) {
+            window.relayout(&self.defined_styles, self.text_metrics.as_ref());
+        }
+    }
+
+    fn window_mut(&mut self, window_id: WindowId) -> PlatformResult<&mut HeadlessWindow> {
+        self.windows.get_mut(&window_id).ok_or_else(|| {
+            log::warn!("Headless: Attempted to access non-existent WindowId {window_id:?}");
+            PlatformError::InvalidHandle(format!("WindowId {window_id:?} not found"))
+        })
+    }
+
+    fn control_mut(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        operation: &str,
+    ) -> PlatformResult<&mut HeadlessControl> {
+        self.window_mut(window_id)?
+            .controls
+            .get_mut(&control_id)
+            .ok_or_else(|| {
+                PlatformError::InvalidHandle(format!(
+                    "Control ID {} not found for {operation} in WinID {window_id:?}",
+                    control_id.raw()
+                ))
+            })
+    }
+
+    fn control_of_kind_mut(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        kind: HeadlessControlKind,
+        operation: &str,
+    ) -> PlatformResult<&mut HeadlessControl> {
+        let control = self.control_mut(window_id, control_id, operation)?;
+        if control.kind != kind {
+            return Err(PlatformError::InvalidHandle(format!(
+                "Control ID {} is a {:?}, not a {kind:?}, for {operation} in WinID {window_id:?}",
+                control_id.raw(),
+                control.kind
+            )));
+        }
+        Ok(control)
+    }
+
+    /*
+     * Registers a new virtual control. Mirrors the Win32 creation checks:
+     * duplicate logical IDs fail with `OperationFailed`, a missing parent with
+     * `InvalidHandle`. [CDU-IdempotentCommandsV1]
+     */
+    fn create_control(
+        &mut self,
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        control_id: ControlId,
+        kind: HeadlessControlKind,
+        text: String,
+    ) -> PlatformResult<&mut HeadlessControl> {
+        let window = self.window_mut(window_id)?;
+        if window.controls.contains_key(&control_id) {
+            log::warn!(
+                "Headless: {kind:?} with ID {} already exists for window {window_id:?}",
+                control_id.raw()
+            );
+            return Err(PlatformError::OperationFailed(format!(
+                "{kind:?} with ID {} already exists for window {window_id:?}",
+                control_id.raw()
+            )));
+        }
+        if let Some(parent_id) = parent_control_id
+            && !window.controls.contains_key(&parent_id)
+        {
+            log::warn!(
+                "Headless: Parent control with ID {} not found for Create{kind:?} in WinID {window_id:?}",
+                parent_id.raw()
+            );
+            return Err(PlatformError::InvalidHandle(format!(
+                "Parent control with ID {} not found for Create{kind:?} in WinID {window_id:?}",
+                parent_id.raw()
+            )));
+        }
+        window.creation_order.push(control_id);
+        window.controls.insert(
+            control_id,
+            HeadlessControl::new(kind, parent_control_id, text),
+        );
+        self.relayout_window(window_id);
+        self.control_mut(window_id, control_id, "create")
+    }
+
+    // ── Injected user events ─────────────────────────────────────────────────
+
+    // A status bar segment's content; segment indexes out of range fail with `OperationFailed`.
+    fn status_segment_mut(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        segment: usize,
+        operation: &str,
+    ) -> PlatformResult<&mut SegmentContent> {
+        let control = self.control_of_kind_mut(
+            window_id,
+            control_id,
+            HeadlessControlKind::StatusBar,
+            operation,
+        )?;
+        let count = control.segment_contents.len();
+        control.segment_contents.get_mut(segment).ok_or_else(|| {
+            PlatformError::OperationFailed(format!(
+                "Status bar {} has {count} segments, no segment {segment}, for {operation}",
+                control_id.raw()
+            ))
+        })
+    }
+
+    /*
+     * The buttons of a toolbar raising `action_id` (only toggles if
+     * `toggles_only`); a toolbar without any fails with `OperationFailed`.
+     */
+    fn toolbar_buttons_mut(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        action_id: MenuActionId,
+        toggles_only: bool,
+        operation: &str,
+    ) -> PlatformResult<Vec<&mut ToolbarItem>> {
+        let control = self.control_of_kind_mut(
+            window_id,
+            control_id,
+            HeadlessControlKind::Toolbar,
+            operation,
+        )?;
+        let buttons: Vec<&mut ToolbarItem> = control
+            .toolbar_items
+            .iter_mut()
+            .filter(|item| {
+                item.action == Some(action_id)
+                    && (!toggles_only || item.kind == ToolbarItemKind::Toggle)
+            })
+            .collect();
+        if buttons.is_empty() {
+            let kind = if toggles_only {
+                "toggle button"
+            } else {
+                "button"
+            };
+            return Err(PlatformError::OperationFailed(format!(
+                "Toolbar {} has no {kind} for {action_id:?}, for {operation}",
+                control_id.raw()
+            )));
+        }
+        Ok(buttons)
+    }
+
+    // User input stops once the application has quit.
+    fn ensure_running(&self) -> PlatformResult<()> {
+        if self.is_quitting {
+            return Err(PlatformError::OperationFailed(
+                "Headless platform has quit; no further events are delivered".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn user_control_mut(
+        &mut self,
+        window_id: WindowId,
+        control_id: ControlId,
+        kind: HeadlessControlKind,
+    ) -> PlatformResult<&mut HeadlessControl> {
+        let control = self.control_of_kind_mut(window_id, control_id, kind, "injected event")?;
+        require_enabled(control, window_id, control_id)?;
+        Ok(control)
+    }
+
+    fn apply_user_event(&mut self, event: &AppEvent) -> PlatformResult<()> {
+        use HeadlessControlKind as Kind;
+        match *event {
+            AppEvent::WindowCloseRequestedByUser { window_id }
+            | AppEvent::MainWindowUISetupComplete { window_id }
+            | AppEvent::FileSaveDialogCompleted { window_id, .. }
+            | AppEvent::FileOpenProfileDialogCompleted { window_id, .. }
+            | AppEvent::ProfileSelectionDialogCompleted { window_id, .. }
+            | AppEvent::GenericInputDialogCompleted { window_id, .. }
+            | AppEvent::ExcludePatternsDialogCompleted { window_id, .. }
+            | AppEvent::FolderPickerDialogCompleted { window_id, .. } => {
+                self.window_mut(window_id)?;
+            }
+            AppEvent::WindowResized {
+                window_id,
+                width,
+                height,
+            } => {
+                let window = self.window_mut(window_id)?;
+                window.width = width;
+                window.height = height;
+                self.relayout_window(window_id);
+            }
+            AppEvent::WindowDestroyed { window_id } => {
+                self.window_mut(window_id)?;
+                self.windows.remove(&window_id);
+            }
+            AppEvent::DpiChanged { window_id, dpi } => {
+                self.window_mut(window_id)?.dpi_scale = DpiScale::new(dpi);
+                self.relayout_window(window_id);
+            }
+            AppEvent::TreeViewItemToggledByUser {
+                window_id,
+                item_id,
+                new_state,
+            } => {
+                let control = self.tree_item_owner_mut(window_id, item_id)?;
+                tree_item_mut(&mut control.tree_items, item_id)?.state = new_state;
+            }
+            AppEvent::TreeViewItemSelectionChanged { window_id, item_id } => {
+                self.tree_item_owner_mut(window_id, item_id)?
+                    .selected_tree_item = Some(item_id);
+            }
+            AppEvent::ButtonClicked {
+                window_id,
+                control_id,
+            } => {
+                self.user_control_mut(window_id, control_id, Kind::Button)?;
+            }
+            AppEvent::MenuActionClicked { action_id } => {
+                if !self.windows.values().any(|w| w.has_menu_action(action_id)) {
+                    return Err(PlatformError::InvalidHandle(format!(
+                        "MenuActionId {action_id:?} is not registered in any menu or enabled toolbar button"
+                    )));
+                }
+                // Clickable toolbar toggles with the action flip, as a click on them would,
+                // and an open context menu holding the action closes.
+                for window in self.windows.values_mut() {
+                    window.flip_toolbar_toggles(action_id);
+                    if window
+                        .context_menu
+                        .as_deref()
+                        .is_some_and(|items| menu_contains(items, action_id))
+                    {
+                        window.context_menu = None;
+                    }
+                }
+            }
+            AppEvent::ControlScrolled {
+                window_id,
+                control_id,
+                vertical_pos,
+                horizontal_pos,
+            } => {
+                self.control_mut(window_id, control_id, "injected event")?
+                    .scroll_position = (vertical_pos, horizontal_pos);
+            }
+            AppEvent::InputTextChanged {
+                window_id,
+                control_id,
+                ref text,
+            } => {
+                self.user_control_mut(window_id, control_id, Kind::Input)?
+                    .text
+                    .clone_from(text);
+            }
+            AppEvent::SplitterDragging {
+                window_id,
+                control_id,
+                ..
+            }
+            | AppEvent::SplitterDragEnded {
+                window_id,
+                control_id,
+                ..
+            } => {
+                self.user_control_mut(window_id, control_id, Kind::Splitter)?;
+            }
+            AppEvent::ComboBoxSelectionChanged {
+                window_id,
+                control_id,
+                selected_index,
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::ComboBox)?;
+                if let Some(index) = selected_index
+                    && index >= control.items.len()
+                {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "ComboBox {} has no item at index {index}",
+                        control_id.raw()
+                    )));
+                }
+                control.selected_index = selected_index;
+            }
+            AppEvent::RadioButtonSelected {
+                window_id,
+                control_id,
+            } => {
+                self.user_control_mut(window_id, control_id, Kind::RadioButton)?;
+                let window = self.window_mut(window_id)?;
+                for id in radio_group_of(window, control_id) {
+                    if let Some(radio) = window.controls.get_mut(&id) {
+                        radio.checked = id == control_id;
+                    }
+                }
+            }
+            AppEvent::CheckBoxToggled {
+                window_id,
+                control_id,
+                checked,
+            } => {
+                self.user_control_mut(window_id, control_id, Kind::CheckBox)?
+                    .checked = checked;
+            }
+            AppEvent::TabBarSelectionChanged {
+                window_id,
+                control_id,
+                selected_index,
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::TabBar)?;
+                if selected_index >= control.items.len() {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "TabBar {} has no tab at index {selected_index}",
+                        control_id.raw()
+                    )));
+                }
+                control.selected_index = Some(selected_index);
+            }
+            AppEvent::ToggleSwitchToggled {
+                window_id,
+                control_id,
+                checked,
+            } => {
+                self.user_control_mut(window_id, control_id, Kind::ToggleSwitch)?
+                    .checked = checked;
+            }
+            AppEvent::ListViewSortRequested {
+                window_id,
+                control_id,
+                column,
+                ..
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::ListView)?;
+                if !control.columns.get(column).is_some_and(|c| c.sortable) {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "ListView {} has no sortable column at index {column}",
+                        control_id.raw()
+                    )));
+                }
+            }
+            AppEvent::ListViewSelectionChanged {
+                window_id,
+                control_id,
+                row,
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::ListView)?;
+                if let Some(row) = row
+                    && row >= control.row_count
+                {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "ListView {} has no row at index {row}",
+                        control_id.raw()
+                    )));
+                }
+                control.selected_index = row;
+            }
+            AppEvent::ListViewRowActivated {
+                window_id,
+                control_id,
+                row,
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::ListView)?;
+                if row >= control.row_count {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "ListView {} has no row at index {row}",
+                        control_id.raw()
+                    )));
+                }
+            }
+            AppEvent::SliderValueChanged {
+                window_id,
+                control_id,
+                value,
+                ..
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::Slider)?;
+                if let Some(range) = control.slider_range
+                    && range.snap(value) != value
+                {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "Slider {} cannot take the value {value}",
+                        control_id.raw()
+                    )));
+                }
+                control.value = value;
+            }
+            AppEvent::NumericValueChanged {
+                window_id,
+                control_id,
+                value,
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::NumericInput)?;
+                if let Some(spec) = &control.numeric_spec {
+                    if spec.clamp(value) != value {
+                        return Err(PlatformError::OperationFailed(format!(
+                            "Numeric input {} cannot take the value {value}",
+                            control_id.raw()
+                        )));
+                    }
+                    // Typed text that already reads as the value is kept as typed.
+                    if spec.parse(&control.text) != Some(value) {
+                        control.text = spec.format(value);
+                    }
+                }
+                control.numeric_value = value;
+                control.input_invalid = false;
+            }
+            AppEvent::StatusSegmentClicked {
+                window_id,
+                control_id,
+                segment,
+            } => {
+                let control = self.user_control_mut(window_id, control_id, Kind::StatusBar)?;
+                if segment >= control.status_segments.len() {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "Status bar {} has no segment {segment}",
+                        control_id.raw()
+                    )));
+                }
+            }
+            AppEvent::ContextMenuRequested {
+                window_id,
+                control_id,
+                item_id,
+                ..
+            } => {
+                // Any control can ask for a menu; only tree views hit an item.
+                let control = self.control_mut(window_id, control_id, "injected event")?;
+                require_enabled(control, window_id, control_id)?;
+                if let Some(item_id) = item_id
+                    && (control.kind != Kind::TreeView || control.find_tree_item(item_id).is_none())
+                {
+                    return Err(PlatformError::InvalidHandle(format!(
+                        "TreeItemId {item_id:?} not found in control {}",
+                        control_id.raw()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Tree events carry no ControlId, so find the TreeView that owns the item.
+    fn tree_item_owner_mut(
+        &mut self,
+        window_id: WindowId,
+        item_id: TreeItemId,
+    ) -> PlatformResult<&mut HeadlessControl> {
+        self.window_mut(window_id)?
+            .controls
+            .values_mut()
+            .find(|control| {
+                control.kind == HeadlessControlKind::TreeView
+                    && find_tree_item(&control.tree_items, item_id).is_some()
+            })
+            .ok_or_else(|| {
+                PlatformError::InvalidHandle(format!("TreeItemId {item_id:?} not found"))
+            })
+    }
+}
+
+fn require_enabled(
+    control: &HeadlessControl,
+    window_id: WindowId,
+    control_id: ControlId,
+) -> PlatformResult<()> {
+    if control.enabled {
+        return Ok(());
+    }
+    Err(PlatformError::OperationFailed(format!(
+        "Control ID {} in WinID {window_id:?} is disabled and cannot raise user events",
+        control_id.raw()
+    )))
+}
+
+fn menu_contains(items: &[MenuItemConfig], action_id: MenuActionId) -> bool {
+    items
+        .iter()
+        .any(|item| item.action == Some(action_id) || menu_contains(&item.children, action_id))
+}
+
+fn find_tree_item(
+    items: &[TreeItemDescriptor],
+    item_id: TreeItemId,
+) -> Option<&TreeItemDescriptor> {
+    items.iter().find_map(|item| {
+        if item.id == item_id {
+            Some(item)
+        } else {
+            find_tree_item(&item.children, item_id)
+        }
+    })
+}
+
+fn tree_item_mut(
+    items: &mut [TreeItemDescriptor],
+    item_id: TreeItemId,
+) -> PlatformResult<&mut TreeItemDescriptor> {
+    fn find(
+        items: &mut [TreeItemDescriptor],
+        item_id: TreeItemId,
+    ) -> Option<&mut TreeItemDescriptor> {
+        for item in items {
+            if item.id == item_id {
+                return Some(item);
+            }
+            if let Some(found) = find(&mut item.children, item_id) {
+                return Some(found);
+            }
+        }
+        None
+    }
+    find(items, item_id)
+        .ok_or_else(|| PlatformError::InvalidHandle(format!("TreeItemId {item_id:?} not found")))
+}
+
+/*
+ * Returns the radio buttons sharing a group with `control_id`. Like WS_GROUP,
+ * a group runs in creation order from a `group_start` button up to the next
+ * one, among radio buttons of the same parent.
+ */
+fn radio_group_of(window: &HeadlessWindow, control_id: ControlId) -> Vec<ControlId> {
+    let parent = window.controls[&control_id].parent_control_id;
+    let mut groups: Vec<Vec<ControlId>> = Vec::new();
+    for id in window.children(parent) {
+        let control = &window.controls[&id];
+        if control.kind != HeadlessControlKind::RadioButton {
+            continue;
+        }
+        if control.radio_group_start || groups.is_empty() {
+            groups.push(Vec::new());
+        }
+        if let Some(group) = groups.last_mut() {
+            group.push(id);
+        }
+    }
+    groups
+        .into_iter()
+        .find(|group| group.contains(&control_id))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styling_primitives::Color;
+    use crate::types::{CheckState, DockStyle};
+
+    const BUTTON: ControlId = ControlId::new(1);
+    const PANEL: ControlId = ControlId::new(2);
+    const TREE: ControlId = ControlId::new(3);
+    const CHECK: ControlId = ControlId::new(4);
+
+    #[derive(Default)]
+    struct ScriptedHandler {
+        commands: VecDeque<PlatformCommand>,
+        received: Vec<AppEvent>,
+        quit_called: bool,
+        reply_to_click: Option<PlatformCommand>,
+    }
+
+    impl PlatformEventHandler for ScriptedHandler {
+        fn handle_event(&mut self, event: AppEvent) {
+            if matches!(event, AppEvent::ButtonClicked { .. })
+                && let Some(reply) = self.reply_to_click.take()
+            {
+                self.commands.push_back(reply);
+            }
+            self.received.push(event);
+        }
+
+        fn on_quit(&mut self) {
+            self.quit_called = true;
+        }
+
+        fn try_dequeue_command(&mut self) -> Option<PlatformCommand> {
+            self.commands.pop_front()
+        }
+    }
+
+    struct NoMarkers;
+
+    impl UiStateProvider for NoMarkers {
+        fn is_tree_item_new(&self, _window_id: WindowId, _item_id: TreeItemId) -> bool {
+            false
+        }
+    }
+
+    fn setup() -> (HeadlessPlatform, WindowId) {
+        let mut platform = HeadlessPlatform::new();
+        let window_id = platform
+            .create_window(WindowConfig {
+                title: "Test",
+                width: 800,
+                height: 600,
+            })
+            .unwrap();
+        (platform, window_id)
+    }
+
+    fn tree_item(id: u64, children: Vec<TreeItemDescriptor>) -> TreeItemDescriptor {
+        TreeItemDescriptor {
+            id: TreeItemId(id),
+            text: format!("item {id}"),
+            is_folder: !children.is_empty(),
+            state: CheckState::Unchecked,
+            children,
+            style_override: None,
+            image: None,
+            tooltip: None,
+        }
+    }
+
+    #[test]
+    // [CDU-ControlLogicalIdsV1] Controls are tracked by logical ID under their parent.
+    fn create_controls_builds_virtual_tree() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::CreatePanel {
+                window_id,
+                parent_control_id: None,
+                control_id: PANEL,
+            })
+            .unwrap();
+        platform
+            .execute_command(PlatformCommand::CreateButton {
+                window_id,
+                parent_control_id: Some(PANEL),
+                control_id: BUTTON,
+                text: "Go".into(),
+                image: None,
+            })
+            .unwrap();
+
+        // Assert
+        let window = platform.window(window_id).unwrap();
+        assert_eq!(window.children(None), vec![PANEL]);
+        assert_eq!(window.children(Some(PANEL)), vec![BUTTON]);
+        let button = window.control(BUTTON).unwrap();
+        assert_eq!(button.kind, HeadlessControlKind::Button);
+        assert_eq!(button.text, "Go");
+        assert!(button.enabled);
+    }
+
+    #[test]
+    // [CDU-IdempotentCommandsV1] Duplicate logical IDs fail like the Win32 backend.
+    fn duplicate_control_creation_fails() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let create = PlatformCommand::CreateCheckBox {
+            window_id,
+            parent_control_id: None,
+            control_id: CHECK,
+            text: "Opt".into(),
+        };
+        platform.execute_command(create.clone()).unwrap();
+
+        // Act
+        let result = platform.execute_command(create);
+
+        // Assert
+        match result {
+            Err(PlatformError::OperationFailed(msg)) => assert!(msg.contains("already exists")),
+            other => panic!("expected OperationFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    // [CDU-Tech-ErrorHandlingV1] Missing parents, controls and windows surface as InvalidHandle.
+    fn unknown_ids_return_invalid_handle() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+
+        // Act
+        let missing_parent = platform.execute_command(PlatformCommand::CreateButton {
+            window_id,
+            parent_control_id: Some(PANEL),
+            control_id: BUTTON,
+            text: "Go".into(),
+            image: None,
+        });
+        let missing_control = platform.execute_command(PlatformCommand::SetControlText {
+            window_id,
+            control_id: BUTTON,
+            text: "x".into(),
+        });
+        let missing_window = platform.execute_command(PlatformCommand::ShowWindow {
+            window_id: WindowId::new(99),
+        });
+
+        // Assert
+        assert!(matches!(
+            missing_parent,
+            Err(PlatformError::InvalidHandle(_))
+        ));
+        assert!(matches!(
+            missing_control,
+            Err(PlatformError::InvalidHandle(_))
+        ));
+        assert!(matches!(
+            missing_window,
+            Err(PlatformError::InvalidHandle(_))
+        ));
+        assert_eq!(platform.window(window_id).unwrap().control_count(), 0);
+    }
+
+    #[test]
+    fn kind_specific_command_rejects_other_kinds() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        platform
+            .execute_command(PlatformCommand::CreateButton {
+                window_id,
+                parent_control_id: None,
+                control_id: BUTTON,
+                text: "Go".into(),
+                image: None,
+            })
+            .unwrap();
+
+        // Act
+        let result = platform.execute_command(PlatformCommand::PopulateTreeView {
+            window_id,
+            control_id: BUTTON,
+            items: vec![],
+        });
+
+        // Assert
+        assert!(matches!(result, Err(PlatformError::InvalidHandle(_))));
+    }
+
+    #[test]
+    // [CDU-TreeView-ItemStateV1] Nested tree item state and text follow update commands.
+    fn tree_item_updates_reach_nested_items() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        platform
+            .execute_command(PlatformCommand::CreateTreeView {
+                window_id,
+                parent_control_id: None,
+                control_id: TREE,
+            })
+            .unwrap();
+        platform
+            .execute_command(PlatformCommand::PopulateTreeView {
+                window_id,
+                control_id: TREE,
+                items: vec![tree_item(1, vec![tree_item(2, vec![])])],
+            })
+            .unwrap();
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::UpdateTreeItemVisualState {
+                window_id,
+                control_id: TREE,
+                item_id: TreeItemId(2),
+                new_state: CheckState::Checked,
+            })
+            .unwrap();
+        let missing = platform.execute_command(PlatformCommand::SetTreeViewSelection {
+            window_id,
+            control_id: TREE,
+            item_id: TreeItemId(7),
+        });
+
+        // Assert
+        let tree = platform.control(window_id, TREE).unwrap();
+        assert_eq!(
+            tree.find_tree_item(TreeItemId(2)).unwrap().state,
+            CheckState::Checked
+        );
+        assert!(matches!(missing, Err(PlatformError::InvalidHandle(_))));
+    }
+
+    #[test]
+    fn combo_and_tab_selection_follow_win32_rules() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let combo = ControlId::new(10);
+        let tabs = ControlId::new(11);
+        let commands = vec![
+            PlatformCommand::CreateComboBox {
+                window_id,
+                parent_control_id: None,
+                control_id: combo,
+            },
+            PlatformCommand::SetComboBoxItems {
+                window_id,
+                control_id: combo,
+                items: vec!["a".into(), "b".into()],
+            },
+            PlatformCommand::SetComboBoxSelection {
+                window_id,
+                control_id: combo,
+                selected_index: Some(5),
+            },
+            PlatformCommand::CreateTabBar {
+                window_id,
+                control_id: tabs,
+                parent_control_id: None,
+                items: vec!["One".into(), "Two".into()],
+                images: Vec::new(),
+                tooltips: Vec::new(),
+            },
+            PlatformCommand::SetTabBarSelection {
+                window_id,
+                control_id: tabs,
+                selected_index: 9,
+            },
+        ];
+
+        // Act
+        for command in commands {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Assert
+        assert_eq!(
+            platform.control(window_id, combo).unwrap().selected_index,
+            None
+        );
+        assert_eq!(
+            platform.control(window_id, tabs).unwrap().selected_index,
+            Some(1)
+        );
+    }
+
+    #[test]
+    // [CDU-CmdEventPatternV1] Injected events reach the handler and its commands are executed.
+    fn injected_click_drives_command_loop() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let handler = Arc::new(Mutex::new(ScriptedHandler {
+            reply_to_click: Some(PlatformCommand::SetControlText {
+                window_id,
+                control_id: BUTTON,
+                text: "Clicked".into(),
+            }),
+            ..Default::default()
+        }));
+        platform
+            .main_event_loop(
+                handler.clone(),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![
+                    PlatformCommand::CreateButton {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: BUTTON,
+                        text: "Go".into(),
+                        image: None,
+                    },
+                    PlatformCommand::SignalMainWindowUISetupComplete { window_id },
+                ],
+            )
+            .unwrap();
+
+        // Act
+        platform
+            .inject_event(AppEvent::ButtonClicked {
+                window_id,
+                control_id: BUTTON,
+            })
+            .unwrap();
+
+        // Assert
+        let received = &handler.lock().unwrap().received;
+        assert!(matches!(
+            received[0],
+            AppEvent::MainWindowUISetupComplete { .. }
+        ));
+        assert!(matches!(received[1], AppEvent::ButtonClicked { .. }));
+        assert_eq!(platform.control(window_id, BUTTON).unwrap().text, "Clicked");
+    }
+
+    #[test]
+    fn injected_events_update_state_and_respect_enabled() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        platform
+            .main_event_loop(
+                handler.clone(),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![PlatformCommand::CreateCheckBox {
+                    window_id,
+                    parent_control_id: None,
+                    control_id: CHECK,
+                    text: "Opt".into(),
+                }],
+            )
+            .unwrap();
+
+        // Act
+        platform
+            .inject_event(AppEvent::CheckBoxToggled {
+                window_id,
+                control_id: CHECK,
+                checked: true,
+            })
+            .unwrap();
+        platform
+            .execute_command(PlatformCommand::SetControlEnabled {
+                window_id,
+                control_id: CHECK,
+                enabled: false,
+            })
+            .unwrap();
+        let disabled = platform.inject_event(AppEvent::CheckBoxToggled {
+            window_id,
+            control_id: CHECK,
+            checked: false,
+        });
+
+        // Assert
+        assert!(platform.control(window_id, CHECK).unwrap().checked);
+        assert!(matches!(disabled, Err(PlatformError::OperationFailed(_))));
+        assert_eq!(handler.lock().unwrap().received.len(), 1);
+    }
+
+    #[test]
+    fn radio_selection_unchecks_group_siblings_only() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let ids = [20, 21, 22].map(ControlId::new);
+        for (i, id) in ids.iter().enumerate() {
+            platform
+                .execute_command(PlatformCommand::CreateRadioButton {
+                    window_id,
+                    parent_control_id: None,
+                    control_id: *id,
+                    text: format!("r{i}"),
+                    group_start: i != 1,
+                })
+                .unwrap();
+            platform
+                .execute_command(PlatformCommand::SetRadioButtonChecked {
+                    window_id,
+                    control_id: *id,
+                    checked: true,
+                })
+                .unwrap();
+        }
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        platform
+            .main_event_loop(handler, Arc::new(Mutex::new(NoMarkers)), vec![])
+            .unwrap();
+
+        // Act
+        platform
+            .inject_event(AppEvent::RadioButtonSelected {
+                window_id,
+                control_id: ids[1],
+            })
+            .unwrap();
+
+        // Assert
+        let checked: Vec<bool> = ids
+            .iter()
+            .map(|id| platform.control(window_id, *id).unwrap().checked)
+            .collect();
+        assert_eq!(checked, vec![false, true, true]);
+    }
+
+    #[test]
+    // [CDU-AppQuitV1][CDU-WindowLifecycleEventsV1] Closing emits WindowDestroyed and quitting calls on_quit once.
+    fn close_and_quit_follow_lifecycle() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+
+        // Act
+        platform
+            .main_event_loop(
+                handler.clone(),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![PlatformCommand::CloseWindow { window_id }],
+            )
+            .unwrap();
+        handler
+            .lock()
+            .unwrap()
+            .commands
+            .push_back(PlatformCommand::QuitApplication);
+        platform.run_until_idle().unwrap();
+        let after_quit = platform.inject_event(AppEvent::MenuActionClicked {
+            action_id: MenuActionId(1),
+        });
+
+        // Assert
+        let guard = handler.lock().unwrap();
+        assert!(matches!(
+            guard.received[0],
+            AppEvent::WindowDestroyed { .. }
+        ));
+        assert!(guard.quit_called);
+        assert!(platform.window(window_id).is_none());
+        assert!(after_quit.is_err());
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] DefineLayout is validated and re-applied on resize.
+    fn layout_rules_position_controls_and_follow_resize() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        platform
+            .execute_command(PlatformCommand::CreatePanel {
+                window_id,
+                parent_control_id: None,
+                control_id: PANEL,
+            })
+            .unwrap();
+        let invalid = platform.execute_command(PlatformCommand::DefineLayout {
+            window_id,
+            rules: vec![LayoutRule::new(PANEL, DockStyle::Top)],
+        });
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::DefineLayout {
+                window_id,
+                rules: vec![LayoutRule::new(PANEL, DockStyle::Fill)],
+            })
+            .unwrap();
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        platform
+            .main_event_loop(handler, Arc::new(Mutex::new(NoMarkers)), vec![])
+            .unwrap();
+        platform
+            .inject_event(AppEvent::WindowResized {
+                window_id,
+                width: 300,
+                height: 200,
+            })
+            .unwrap();
+
+        // Assert
+        assert!(matches!(invalid, Err(PlatformError::OperationFailed(_))));
+        assert_eq!(
+            platform.control(window_id, PANEL).unwrap().rect,
+            Some(Rect::from_size(300, 200))
+        );
+    }
+
+    // Ten pixels per character and twenty per line, regardless of font.
+    struct FixedMetrics;
+
+    impl TextMetrics for FixedMetrics {
+        fn measure_text(&self, text: &str, _font: &crate::FontDescription) -> (i32, i32) {
+            let lines: Vec<&str> = text.split('\n').collect();
+            let widest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+            (widest as i32 * 10, lines.len() as i32 * 20)
+        }
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] Auto-sized docked labels follow their text without a fixed_size.
+    fn auto_sized_label_is_remeasured_when_text_changes() {
+        // Arrange
+        let mut platform = HeadlessPlatform::new().with_text_metrics(FixedMetrics);
+        let window_id = platform
+            .create_window(WindowConfig {
+                title: "Test",
+                width: 200,
+                height: 100,
+            })
+            .unwrap();
+        let label = ControlId::new(5);
+        for command in [
+            PlatformCommand::CreateLabel {
+                window_id,
+                parent_control_id: None,
+                control_id: label,
+                initial_text: "Status".into(),
+                class: crate::types::LabelClass::Default,
+            },
+            PlatformCommand::CreatePanel {
+                window_id,
+                parent_control_id: None,
+                control_id: PANEL,
+            },
+            PlatformCommand::DefineLayout {
+                window_id,
+                rules: vec![
+                    LayoutRule {
+                        size_mode: SizeMode::Auto,
+                        ..LayoutRule::new(label, DockStyle::Top)
+                    },
+                    LayoutRule {
+                        order: 1,
+                        ..LayoutRule::new(PANEL, DockStyle::Fill)
+                    },
+                ],
+            },
+        ] {
+            platform.execute_command(command).unwrap();
+        }
+        let single_line = platform.control(window_id, label).unwrap().rect;
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::UpdateLabelText {
+                window_id,
+                control_id: label,
+                text: "Status\nDetails".into(),
+                severity: MessageSeverity::Information,
+            })
+            .unwrap();
+
+        // Assert
+        assert_eq!(single_line, Some(Rect::new(0, 0, 200, 20)));
+        assert_eq!(
+            platform.control(window_id, label).unwrap().rect,
+            Some(Rect::new(0, 0, 200, 40))
+        );
+        assert_eq!(
+            platform.control(window_id, PANEL).unwrap().rect,
+            Some(Rect::new(0, 40, 200, 100))
+        );
+    }
+
+    #[test]
+    // [CDU-DpiAwarenessV1] A DPI change rescales fixed sizes, margins and measured sizes.
+    fn dpi_change_rescales_layout() {
+        // Arrange
+        let mut platform = HeadlessPlatform::new().with_text_metrics(FixedMetrics);
+        let window_id = platform
+            .create_window(WindowConfig {
+                title: "Test",
+                width: 200,
+                height: 100,
+            })
+            .unwrap();
+        let label = ControlId::new(5);
+        for command in [
+            PlatformCommand::CreatePanel {
+                window_id,
+                parent_control_id: None,
+                control_id: PANEL,
+            },
+            PlatformCommand::CreateLabel {
+                window_id,
+                parent_control_id: None,
+                control_id: label,
+                initial_text: "Status".into(),
+                class: crate::types::LabelClass::Default,
+            },
+            PlatformCommand::DefineLayout {
+                window_id,
+                rules: vec![
+                    LayoutRule {
+                        fixed_size: Some(30),
+                        margin: (2, 2, 2, 2),
+                        ..LayoutRule::new(PANEL, DockStyle::Top)
+                    },
+                    LayoutRule {
+                        order: 1,
+                        size_mode: SizeMode::Auto,
+                        ..LayoutRule::new(label, DockStyle::Top)
+                    },
+                ],
+            },
+        ] {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Act
+        platform
+            .inject_event(AppEvent::DpiChanged {
+                window_id,
+                dpi: 192,
+            })
+            .unwrap();
+
+        // Assert
+        let window = platform.window(window_id).unwrap();
+        assert_eq!((window.width, window.height), (200, 100));
+        assert_eq!(window.dpi_scale, DpiScale::new(192));
+        assert_eq!(
+            window.control(PANEL).unwrap().rect,
+            Some(Rect::new(4, 4, 396, 64))
+        );
+        assert_eq!(
+            window.control(label).unwrap().rect,
+            Some(Rect::new(0, 68, 400, 108))
+        );
+    }
+
+    #[test]
+    fn queued_command_errors_are_recorded_not_fatal() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        handler.lock().unwrap().commands.extend([
+            PlatformCommand::SetControlText {
+                window_id,
+                control_id: BUTTON,
+                text: "x".into(),
+            },
+            PlatformCommand::SetWindowTitle {
+                window_id,
+                title: "Renamed".into(),
+            },
+        ]);
+
+        // Act
+        platform
+            .main_event_loop(handler, Arc::new(Mutex::new(NoMarkers)), vec![])
+            .unwrap();
+
+        // Assert
+        assert_eq!(platform.command_errors().len(), 1);
+        assert_eq!(platform.window(window_id).unwrap().title, "Renamed");
+    }
+
+    #[test]
+    // [CDU-Styling-CustomIdsV1] Application-defined style ids are defined and applied like built-in ones.
+    fn custom_style_ids_are_defined_and_applied() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let custom = StyleId::Custom(7);
+        let style = ControlStyle {
+            text_color: Some(Color::rgb(1, 2, 3)),
+            ..ControlStyle::default()
+        };
+        platform
+            .execute_command(PlatformCommand::CreateButton {
+                window_id,
+                parent_control_id: None,
+                control_id: ControlId(1),
+                text: "Go".to_string(),
+                image: None,
+            })
+            .unwrap();
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::DefineStyle {
+                style_id: custom,
+                style: style.clone(),
+            })
+            .unwrap();
+        platform
+            .execute_command(PlatformCommand::ApplyStyleToControl {
+                window_id,
+                control_id: ControlId(1),
+                style_id: custom,
+            })
+            .unwrap();
+
+        // Assert
+        assert_eq!(platform.defined_style(custom), Some(&style));
+        assert!(platform.defined_style(StyleId::Custom(8)).is_none());
+        assert_eq!(
+            platform
+                .window(window_id)
+                .unwrap()
+                .control(ControlId(1))
+                .unwrap()
+                .style_id,
+            Some(custom)
+        );
+    }
+
+    #[test]
+    // [CDU-Styling-InheritanceV1] Redefining a base style changes every derived style and the controls inside styled panels.
+    fn redefining_a_base_style_updates_derived_and_child_styles() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let base = StyleId::Custom(1);
+        let label = ControlId(3);
+        let define = |style_id, style| PlatformCommand::DefineStyle { style_id, style };
+        let sized = |size| ControlStyle {
+            font: Some(crate::FontDescription {
+                size: Some(size),
+                ..Default::default()
+            }),
+            background_color: Some(Color::rgb(9, 9, 9)),
+            ..ControlStyle::default()
+        };
+        for command in [
+            define(base, sized(9)),
+            define(
+                StyleId::PanelBackground,
+                ControlStyle {
+                    base: Some(base),
+                    ..ControlStyle::default()
+                },
+            ),
+            define(
+                StyleId::StatusLabelNormal,
+                ControlStyle {
+                    text_color: Some(Color::rgb(1, 2, 3)),
+                    ..ControlStyle::default()
+                },
+            ),
+            PlatformCommand::CreatePanel {
+                window_id,
+                parent_control_id: None,
+                control_id: PANEL,
+            },
+            PlatformCommand::CreateLabel {
+                window_id,
+                parent_control_id: Some(PANEL),
+                control_id: label,
+                initial_text: "Status".into(),
+                class: crate::types::LabelClass::Default,
+            },
+            PlatformCommand::ApplyStyleToControl {
+                window_id,
+                control_id: PANEL,
+                style_id: StyleId::PanelBackground,
+            },
+            PlatformCommand::ApplyStyleToControl {
+                window_id,
+                control_id: label,
+                style_id: StyleId::StatusLabelNormal,
+            },
+        ] {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Act
+        platform.execute_command(define(base, sized(14))).unwrap();
+
+        // Assert
+        let font_size = |style: Option<ControlStyle>| style.and_then(|s| s.font?.size);
+        assert_eq!(
+            font_size(platform.resolved_style(StyleId::PanelBackground)),
+            Some(14)
+        );
+        let label_style = platform.effective_style(window_id, label).unwrap();
+        assert_eq!(label_style.text_color, Some(Color::rgb(1, 2, 3)));
+        assert_eq!(label_style.background_color, Some(Color::rgb(9, 9, 9)));
+        assert_eq!(font_size(Some(label_style)), Some(14));
+    }
+
+    #[test]
+    // [CDU-Styling-InheritanceV1] A definition that closes an inheritance cycle fails and keeps the previous one.
+    fn define_style_rejects_inheritance_cycles() {
+        // Arrange
+        let (mut platform, _window_id) = setup();
+        let based_on = |base| ControlStyle {
+            base: Some(base),
+            ..ControlStyle::default()
+        };
+        platform
+            .execute_command(PlatformCommand::DefineStyle {
+                style_id: StyleId::Custom(1),
+                style: based_on(StyleId::Custom(2)),
+            })
+            .unwrap();
+
+        // Act
+        let result = platform.execute_command(PlatformCommand::DefineStyle {
+            style_id: StyleId::Custom(2),
+            style: based_on(StyleId::Custom(1)),
+        });
+
+        // Assert
+        assert!(
+            matches!(result, Err(PlatformError::OperationFailed(msg)) if msg.contains("cycle"))
+        );
+        assert!(platform.defined_style(StyleId::Custom(2)).is_none());
+        assert!(platform.resolved_style(StyleId::Custom(1)).is_some());
+    }
+
+    #[test]
+    // [CDU-Styling-ThemeV1] ApplyTheme defines every style of the theme and replaces earlier definitions.
+    fn apply_theme_defines_all_theme_styles() {
+        // Arrange
+        let (mut platform, _window_id) = setup();
+        platform
+            .execute_command(PlatformCommand::DefineStyle {
+                style_id: StyleId::DefaultText,
+                style: ControlStyle::default(),
+            })
+            .unwrap();
+        let light = Theme::light();
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::ApplyTheme {
+                theme: light.clone(),
+            })
+            .unwrap();
+
+        // Assert
+        assert_eq!(platform.theme().name, "Light");
+        assert_eq!(
+            platform.defined_style(StyleId::DefaultText),
+            light.style(StyleId::DefaultText)
+        );
+        assert!(
+            light
+                .styles
+                .keys()
+                .all(|id| platform.defined_style(*id).is_some())
+        );
+    }
+
+    struct IndexCells;
+
+    impl ListViewDataProvider for IndexCells {
+        fn cell_text(
+            &self,
+            _window_id: WindowId,
+            _control_id: ControlId,
+            row: usize,
+            column: usize,
+        ) -> String {
+            format!("r{row}c{column}")
+        }
+    }
+
+    fn list_view(window_id: WindowId, control_id: ControlId) -> PlatformCommand {
+        PlatformCommand::CreateListView {
+            window_id,
+            parent_control_id: None,
+            control_id,
+            columns: vec![
+                ListViewColumn::new("Name").sortable(),
+                ListViewColumn::new("Size"),
+            ],
+        }
+    }
+
+    #[test]
+    // [CDU-Control-ListViewV1] Cells come from the stored rows or, in virtual mode, from the data provider.
+    fn list_view_cells_come_from_rows_or_provider() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let list = ControlId::new(20);
+        let provider = Arc::new(Mutex::new(IndexCells));
+        platform.set_list_view_data_provider(provider.clone());
+        platform
+            .execute_command(list_view(window_id, list))
+            .unwrap();
+        platform
+            .execute_command(PlatformCommand::SetListViewRows {
+                window_id,
+                control_id: list,
+                rows: vec![vec!["a.txt".into(), "12".into()], vec!["b.txt".into()]],
+            })
+            .unwrap();
+        let stored = platform.list_view_cell(window_id, list, 0, 1);
+        let missing_cell = platform.list_view_cell(window_id, list, 1, 1);
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::SetListViewRowCount {
+                window_id,
+                control_id: list,
+                row_count: 100_000,
+            })
+            .unwrap();
+
+        // Assert
+        assert_eq!(stored.as_deref(), Some("12"));
+        assert_eq!(missing_cell.as_deref(), Some(""));
+        let control = platform.control(window_id, list).unwrap();
+        assert!(control.virtual_rows && control.rows.is_empty());
+        assert_eq!(
+            platform
+                .list_view_cell(window_id, list, 99_999, 1)
+                .as_deref(),
+            Some("r99999c1")
+        );
+        assert_eq!(platform.list_view_cell(window_id, list, 100_000, 0), None);
+    }
+
+    #[test]
+    // [CDU-Control-ListViewV1] Sort requests need a sortable column; selection and activation a valid row.
+    fn list_view_events_are_validated_against_columns_and_rows() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let list = ControlId::new(20);
+        platform
+            .execute_command(list_view(window_id, list))
+            .unwrap();
+        platform
+            .execute_command(PlatformCommand::SetListViewRowCount {
+                window_id,
+                control_id: list,
+                row_count: 3,
+            })
+            .unwrap();
+
+        // Act
+        let sort_name = platform.inject_event(AppEvent::ListViewSortRequested {
+            window_id,
+            control_id: list,
+            column: 0,
+            direction: SortDirection::Ascending,
+        });
+        let sort_size = platform.inject_event(AppEvent::ListViewSortRequested {
+            window_id,
+            control_id: list,
+            column: 1,
+            direction: SortDirection::Ascending,
+        });
+        let select = platform.inject_event(AppEvent::ListViewSelectionChanged {
+            window_id,
+            control_id: list,
+            row: Some(2),
+        });
+        let activate_missing = platform.inject_event(AppEvent::ListViewRowActivated {
+            window_id,
+            control_id: list,
+            row: 3,
+        });
+
+        // Assert
+        assert!(sort_name.is_ok());
+        assert!(matches!(sort_size, Err(PlatformError::OperationFailed(_))));
+        assert!(select.is_ok());
+        assert!(matches!(
+            activate_missing,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        assert_eq!(
+            platform.control(window_id, list).unwrap().selected_index,
+            Some(2)
+        );
+    }
+
+    #[test]
+    // [CDU-Control-SliderV1] Slider values are snapped by commands and validated for user events.
+    fn slider_values_snap_to_range() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let slider = ControlId::new(21);
+        platform
+            .execute_command(PlatformCommand::CreateSlider {
+                window_id,
+                parent_control_id: None,
+                control_id: slider,
+                range: SliderRange::new(0, 100).step(10),
+                orientation: SliderOrientation::Vertical,
+                value: 44,
+            })
+            .unwrap();
+        let created_value = platform.control(window_id, slider).unwrap().value;
+
+        // Act
+        platform
+            .execute_command(PlatformCommand::SetSliderValue {
+                window_id,
+                control_id: slider,
+                value: 250,
+            })
+            .unwrap();
+        let set_value = platform.control(window_id, slider).unwrap().value;
+        let drag = platform.inject_event(AppEvent::SliderValueChanged {
+            window_id,
+            control_id: slider,
+            value: 30,
+            committed: false,
+        });
+        let off_grid = platform.inject_event(AppEvent::SliderValueChanged {
+            window_id,
+            control_id: slider,
+            value: 35,
+            committed: true,
+        });
+        let inverted = platform.execute_command(PlatformCommand::CreateSlider {
+            window_id,
+            parent_control_id: None,
+            control_id: ControlId::new(22),
+            range: SliderRange::new(10, 0),
+            orientation: SliderOrientation::Horizontal,
+            value: 0,
+        });
+
+        // Assert
+        assert_eq!(created_value, 40);
+        assert_eq!(set_value, 100);
+        assert!(drag.is_ok());
+        assert!(matches!(off_grid, Err(PlatformError::OperationFailed(_))));
+        assert!(matches!(inverted, Err(PlatformError::OperationFailed(_))));
+        let control = platform.control(window_id, slider).unwrap();
+        assert_eq!(control.value, 30);
+        assert_eq!(control.slider_orientation, SliderOrientation::Vertical);
+    }
+
+    #[test]
+    // [CDU-Control-NumericInputV1] Typed text raises typed values; invalid text switches to the error style.
+    fn numeric_input_validates_typed_text() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let input = ControlId::new(23);
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        platform
+            .main_event_loop(
+                handler.clone(),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![
+                    PlatformCommand::DefineStyle {
+                        style_id: StyleId::DefaultInputError,
+                        style: ControlStyle {
+                            text_color: Some(Color::rgb(200, 0, 0)),
+                            ..Default::default()
+                        },
+                    },
+                    PlatformCommand::CreateNumericInput {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: input,
+                        spec: NumericSpec::new(0.0, 10.0).decimals(1).unit("s"),
+                        value: 2.0,
+                    },
+                ],
+            )
+            .unwrap();
+        let created_text = platform.control(window_id, input).unwrap().text.clone();
+
+        // Act
+        platform.type_numeric_text(window_id, input, "12").unwrap();
+        let invalid_style = platform.effective_style(window_id, input);
+        platform
+            .type_numeric_text(window_id, input, "4.5 s")
+            .unwrap();
+        platform
+            .type_numeric_text(window_id, input, "4.50")
+            .unwrap();
+        let out_of_range = platform.inject_event(AppEvent::NumericValueChanged {
+            window_id,
+            control_id: input,
+            value: 11.0,
+        });
+
+        // Assert
+        assert_eq!(created_text, "2.0 s");
+        assert_eq!(
+            invalid_style.and_then(|style| style.text_color),
+            Some(Color::rgb(200, 0, 0))
+        );
+        let control = platform.control(window_id, input).unwrap();
+        assert!(!control.input_invalid);
+        assert_eq!(control.numeric_value, 4.5);
+        assert!(matches!(
+            out_of_range,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        let received = &handler.lock().unwrap().received;
+        assert_eq!(received.len(), 1, "unchanged values raise no second event");
+        assert!(matches!(
+            received[0],
+            AppEvent::NumericValueChanged { value, .. } if value == 4.5
+        ));
+    }
+
+    #[test]
+    // [CDU-Control-StatusBarV1] Segments split the docked bar, take text and progress, and report clicks.
+    fn status_bar_segments_lay_out_and_report_clicks() {
+        // Arrange
+        let mut platform = HeadlessPlatform::new().with_text_metrics(FixedMetrics);
+        let window_id = platform
+            .create_window(WindowConfig {
+                title: "Test",
+                width: 300,
+                height: 100,
+            })
+            .unwrap();
+        let bar = ControlId::new(24);
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        platform
+            .main_event_loop(
+                handler.clone(),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![
+                    PlatformCommand::CreateStatusBar {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: bar,
+                        segments: vec![
+                            StatusSegment::fill(),
+                            StatusSegment::auto(),
+                            StatusSegment::fixed(80).progress(),
+                        ],
+                    },
+                    PlatformCommand::DefineLayout {
+                        window_id,
+                        rules: vec![LayoutRule {
+                            size_mode: SizeMode::Auto,
+                            ..LayoutRule::new(bar, DockStyle::Bottom)
+                        }],
+                    },
+                    PlatformCommand::SetStatusSegmentText {
+                        window_id,
+                        control_id: bar,
+                        segment: 1,
+                        text: "Ln 12".into(),
+                        severity: MessageSeverity::Warning,
+                    },
+                    PlatformCommand::SetStatusSegmentProgress {
+                        window_id,
+                        control_id: bar,
+                        segment: 2,
+                        progress: Some(150),
+                    },
+                ],
+            )
+            .unwrap();
+
+        // Act
+        let widths = platform.status_segment_widths(window_id, bar).unwrap();
+        let text_on_progress_only =
+            platform.execute_command(PlatformCommand::SetStatusSegmentProgress {
+                window_id,
+                control_id: bar,
+                segment: 0,
+                progress: Some(10),
+            });
+        let missing_segment = platform.execute_command(PlatformCommand::SetStatusSegmentText {
+            window_id,
+            control_id: bar,
+            segment: 3,
+            text: "x".into(),
+            severity: MessageSeverity::None,
+        });
+        let click = platform.inject_event(AppEvent::StatusSegmentClicked {
+            window_id,
+            control_id: bar,
+            segment: 1,
+        });
+        let click_outside = platform.inject_event(AppEvent::StatusSegmentClicked {
+            window_id,
+            control_id: bar,
+            segment: 3,
+        });
+
+        // Assert
+        let control = platform.control(window_id, bar).unwrap();
+        assert_eq!(control.rect, Some(Rect::new(0, 74, 300, 100)));
+        assert_eq!(widths, vec![158, 62, 80]);
+        assert_eq!(
+            control.segment_contents[1].severity,
+            MessageSeverity::Warning
+        );
+        assert_eq!(control.segment_contents[2].progress, Some(100));
+        assert!(matches!(
+            text_on_progress_only,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        assert!(matches!(
+            missing_segment,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        assert!(click.is_ok());
+        assert!(matches!(
+            click_outside,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        let received = &handler.lock().unwrap().received;
+        assert!(matches!(
+            received.as_slice(),
+            [AppEvent::StatusSegmentClicked { segment: 1, .. }]
+        ));
+    }
+
+    #[test]
+    // [CDU-Control-ToolbarV1] Toolbar clicks raise MenuActionClicked; toggles flip and disabled buttons refuse.
+    fn toolbar_buttons_route_through_menu_actions() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let bar = ControlId::new(25);
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        let (open, wrap, build) = (MenuActionId(1), MenuActionId(2), MenuActionId(3));
+        platform
+            .main_event_loop(
+                handler.clone(),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![
+                    PlatformCommand::CreateToolbar {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: bar,
+                        items: vec![
+                            ToolbarItem::push(open, "Open").tooltip("Open a profile"),
+                            ToolbarItem::separator(),
+                            ToolbarItem::toggle(wrap, "Wrap"),
+                            ToolbarItem::spacer(),
+                            ToolbarItem::dropdown(build, "Build"),
+                        ],
+                    },
+                    PlatformCommand::SetToolbarItemEnabled {
+                        window_id,
+                        control_id: bar,
+                        action_id: build,
+                        enabled: false,
+                    },
+                ],
+            )
+            .unwrap();
+
+        // Act
+        let click_open = platform.click_toolbar_item(window_id, bar, open);
+        let click_wrap = platform.click_toolbar_item(window_id, bar, wrap);
+        let click_disabled = platform.click_toolbar_item(window_id, bar, build);
+        let injected_disabled =
+            platform.inject_event(AppEvent::MenuActionClicked { action_id: build });
+        let check_push = platform.execute_command(PlatformCommand::SetToolbarItemChecked {
+            window_id,
+            control_id: bar,
+            action_id: open,
+            checked: true,
+        });
+
+        // Assert
+        assert!(click_open.is_ok());
+        assert!(click_wrap.is_ok());
+        assert!(matches!(
+            click_disabled,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        assert!(matches!(
+            injected_disabled,
+            Err(PlatformError::InvalidHandle(_))
+        ));
+        assert!(matches!(check_push, Err(PlatformError::OperationFailed(_))));
+        let items = &platform.control(window_id, bar).unwrap().toolbar_items;
+        assert!(items[2].checked);
+        assert!(!items[4].enabled);
+        let received = &handler.lock().unwrap().received;
+        assert!(matches!(
+            received.as_slice(),
+            [
+                AppEvent::MenuActionClicked {
+                    action_id: MenuActionId(1)
+                },
+                AppEvent::MenuActionClicked {
+                    action_id: MenuActionId(2)
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    // [CDU-Control-ToolbarV1] An injected MenuActionClicked flips a toolbar toggle like a click.
+    fn injected_toolbar_actions_flip_toggles() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let bar = ControlId::new(25);
+        let wrap = MenuActionId(2);
+        platform
+            .main_event_loop(
+                Arc::new(Mutex::new(ScriptedHandler::default())),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![PlatformCommand::CreateToolbar {
+                    window_id,
+                    parent_control_id: None,
+                    control_id: bar,
+                    items: vec![ToolbarItem::toggle(wrap, "Wrap")],
+                }],
+            )
+            .unwrap();
+
+        // Act
+        platform
+            .inject_event(AppEvent::MenuActionClicked { action_id: wrap })
+            .unwrap();
+        let after_injected = platform.control(window_id, bar).unwrap().toolbar_items[0].checked;
+        platform.click_toolbar_item(window_id, bar, wrap).unwrap();
+        let after_clicked = platform.control(window_id, bar).unwrap().toolbar_items[0].checked;
+
+        // Assert
+        assert!(after_injected);
+        assert!(!after_clicked);
+    }
+
+    // A 1x1 24 bit BMP holding one red pixel.
+    fn red_pixel_bmp() -> Vec<u8> {
+        let mut bytes = b"BM".to_vec();
+        bytes.extend_from_slice(&(14u32 + 40 + 4).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&54u32.to_le_bytes());
+        bytes.extend_from_slice(&40u32.to_le_bytes());
+        bytes.extend_from_slice(&1i32.to_le_bytes());
+        bytes.extend_from_slice(&1i32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&24u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 24]);
+        bytes.extend_from_slice(&[0, 0, 255, 0]);
+        bytes
+    }
+
+    #[test]
+    // [CDU-ImagesV1] [CDU-Control-ImageV1] Images are registered once and referenced by id; unknown ids are refused.
+    fn image_controls_show_registered_images_only() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let picture = ControlId::new(26);
+        let logo = ImageId(1);
+
+        // Act
+        let registered = platform.execute_command(PlatformCommand::RegisterImage {
+            image_id: logo,
+            bytes: red_pixel_bmp(),
+        });
+        let garbage = platform.execute_command(PlatformCommand::RegisterImage {
+            image_id: ImageId(2),
+            bytes: vec![1, 2, 3],
+        });
+        let create_unknown = platform.execute_command(PlatformCommand::CreateImage {
+            window_id,
+            parent_control_id: None,
+            control_id: picture,
+            image_id: Some(ImageId(2)),
+        });
+        let created = platform.execute_command(PlatformCommand::CreateImage {
+            window_id,
+            parent_control_id: None,
+            control_id: picture,
+            image_id: Some(logo),
+        });
+        let set_unknown = platform.execute_command(PlatformCommand::SetImage {
+            window_id,
+            control_id: picture,
+            image_id: Some(ImageId(3)),
+        });
+
+        // Assert
+        assert!(registered.is_ok());
+        assert!(matches!(garbage, Err(PlatformError::OperationFailed(_))));
+        assert!(matches!(
+            create_unknown,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        assert!(created.is_ok());
+        assert!(matches!(
+            set_unknown,
+            Err(PlatformError::OperationFailed(_))
+        ));
+        assert_eq!(
+            platform.control(window_id, picture).unwrap().image,
+            Some(logo)
+        );
+        assert_eq!(platform.images().natural_size(logo), Some((1, 1)));
+        assert_eq!(
+            platform.images().variants(logo).unwrap()[0].pixel(0, 0),
+            [255, 0, 0, 255]
+        );
+    }
+
+    // Answers tooltips for tree item 2 and every second tab.
+    struct TooltipProvider;
+
+    impl UiStateProvider for TooltipProvider {
+        fn is_tree_item_new(&self, _window_id: WindowId, _item_id: TreeItemId) -> bool {
+            false
+        }
+
+        fn tree_item_tooltip(&self, _window_id: WindowId, item_id: TreeItemId) -> Option<String> {
+            (item_id == TreeItemId(2)).then(|| "Provided".to_string())
+        }
+
+        fn tab_tooltip(
+            &self,
+            _window_id: WindowId,
+            _control_id: ControlId,
+            index: usize,
+        ) -> Option<String> {
+            (index % 2 == 1).then(|| format!("Tab {index}"))
+        }
+    }
+
+    #[test]
+    // [CDU-TooltipsV1] Tooltips attach to any control; items use their own text, else the provider's.
+    fn tooltips_come_from_commands_descriptors_and_the_provider() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let (tree, tabs) = (ControlId::new(27), ControlId::new(28));
+        let mut own = tree_item(1, vec![tree_item(2, Vec::new())]);
+        own.tooltip = Some("Own\nhelp".into());
+        let provider = Arc::new(Mutex::new(TooltipProvider));
+        platform
+            .main_event_loop(
+                Arc::new(Mutex::new(ScriptedHandler::default())),
+                provider.clone(),
+                vec![
+                    PlatformCommand::CreateButton {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: BUTTON,
+                        text: "Go".into(),
+                        image: None,
+                    },
+                    PlatformCommand::CreateTreeView {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: tree,
+                    },
+                    PlatformCommand::PopulateTreeView {
+                        window_id,
+                        control_id: tree,
+                        items: vec![own],
+                    },
+                    PlatformCommand::CreateTabBar {
+                        window_id,
+                        control_id: tabs,
+                        parent_control_id: None,
+                        items: vec!["A".into(), "B".into(), "C".into()],
+                        images: Vec::new(),
+                        tooltips: vec![Some("First".into())],
+                    },
+                ],
+            )
+            .unwrap();
+
+        // Act
+        let set = platform.execute_command(PlatformCommand::SetControlTooltip {
+            window_id,
+            control_id: BUTTON,
+            text: "Runs the\nscan".into(),
+            options: TooltipOptions::default().persistent(),
+        });
+        let unknown = platform.execute_command(PlatformCommand::SetControlTooltip {
+            window_id,
+            control_id: ControlId::new(99),
+            text: "Nowhere".into(),
+            options: TooltipOptions::default(),
+        });
+
+        // Assert
+        assert!(set.is_ok());
+        assert!(matches!(unknown, Err(PlatformError::InvalidHandle(_))));
+        let button = platform.control(window_id, BUTTON).unwrap();
+        assert_eq!(button.tooltip.as_deref(), Some("Runs the scan"));
+        assert!(button.tooltip_options.persistent);
+        let tree_tips: Vec<Option<String>> = [1, 2, 3]
+            .into_iter()
+            .map(|id| platform.tree_item_tooltip(window_id, tree, TreeItemId(id)))
+            .collect();
+        assert_eq!(
+            tree_tips,
+            vec![Some("Own\nhelp".into()), Some("Provided".into()), None]
+        );
+        let tab_tips: Vec<Option<String>> = (0..4)
+            .map(|index| platform.tab_tooltip(window_id, tabs, index))
+            .collect();
+        assert_eq!(
+            tab_tips,
+            vec![Some("First".into()), Some("Tab 1".into()), None, None]
+        );
+    }
+
+    #[test]
+    fn empty_tooltip_text_removes_the_tooltip() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let tooltip = |text: &str| PlatformCommand::SetControlTooltip {
+            window_id,
+            control_id: BUTTON,
+            text: text.into(),
+            options: TooltipOptions::default(),
+        };
+        platform
+            .execute_command(PlatformCommand::CreateButton {
+                window_id,
+                parent_control_id: None,
+                control_id: BUTTON,
+                text: "Go".into(),
+                image: None,
+            })
+            .unwrap();
+        platform.execute_command(tooltip("Help")).unwrap();
+
+        // Act
+        platform.execute_command(tooltip("")).unwrap();
+
+        // Assert
+        assert_eq!(platform.control(window_id, BUTTON).unwrap().tooltip, None);
+    }
+
+    #[test]
+    // [CDU-ContextMenusV1] A context menu request names the hit tree item; the chosen item raises MenuActionClicked.
+    fn context_menu_choices_route_through_menu_actions() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let handler = Arc::new(Mutex::new(ScriptedHandler::default()));
+        let (open, reveal) = (MenuActionId(11), MenuActionId(12));
+        platform
+            .main_event_loop(
+                handler.clone(),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![
+                    PlatformCommand::CreateTreeView {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: TREE,
+                    },
+                    PlatformCommand::PopulateTreeView {
+                        window_id,
+                        control_id: TREE,
+                        items: vec![tree_item(1, vec![tree_item(2, vec![])])],
+                    },
+                ],
+            )
+            .unwrap();
+        let request = |item_id| AppEvent::ContextMenuRequested {
+            window_id,
+            control_id: TREE,
+            screen_x: 300,
+            screen_y: 200,
+            client_x: 20,
+            client_y: 16,
+            item_id,
+        };
+        let menu_item = |action_id, text: &str| MenuItemConfig {
+            action: Some(action_id),
+            text: text.into(),
+            children: vec![],
+        };
+
+        // Act
+        let requested = platform.inject_event(request(Some(TreeItemId(2))));
+        let unknown_item = platform.inject_event(request(Some(TreeItemId(9))));
+        let not_open = platform.choose_context_menu_item(window_id, open);
+        platform
+            .execute_command(PlatformCommand::ShowContextMenu {
+                window_id,
+                control_id: TREE,
+                menu_items: vec![menu_item(open, "Open"), menu_item(reveal, "Reveal")],
+                screen_x: 300,
+                screen_y: 200,
+            })
+            .unwrap();
+        let chosen = platform.choose_context_menu_item(window_id, reveal);
+        let closed = platform.inject_event(AppEvent::MenuActionClicked { action_id: open });
+
+        // Assert
+        assert!(requested.is_ok());
+        assert!(matches!(unknown_item, Err(PlatformError::InvalidHandle(_))));
+        assert!(matches!(not_open, Err(PlatformError::OperationFailed(_))));
+        assert!(chosen.is_ok());
+        assert!(matches!(closed, Err(PlatformError::InvalidHandle(_))));
+        assert!(platform.window(window_id).unwrap().context_menu.is_none());
+        let received = &handler.lock().unwrap().received;
+        assert!(matches!(
+            received.as_slice(),
+            [
+                AppEvent::ContextMenuRequested {
+                    item_id: Some(TreeItemId(2)),
+                    ..
+                },
+                AppEvent::MenuActionClicked {
+                    action_id: MenuActionId(12)
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    // [CDU-ContextMenusV1] A context menu for an unknown control is rejected like any other control command.
+    fn context_menu_for_unknown_control_is_rejected() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+
+        // Act
+        let result = platform.execute_command(PlatformCommand::ShowContextMenu {
+            window_id,
+            control_id: ControlId::new(404),
+            menu_items: vec![],
+            screen_x: 0,
+            screen_y: 0,
+        });
+
+        // Assert
+        assert!(matches!(result, Err(PlatformError::InvalidHandle(_))));
+        assert!(platform.window(window_id).unwrap().context_menu.is_none());
+    }
+
+    #[test]
+    // [CDU-ContextMenusV1] An injected MenuActionClicked closes the open menu holding the action.
+    fn injected_context_menu_actions_close_the_menu() {
+        // Arrange
+        let (mut platform, window_id) = setup();
+        let (open, elsewhere) = (MenuActionId(11), MenuActionId(13));
+        platform
+            .main_event_loop(
+                Arc::new(Mutex::new(ScriptedHandler::default())),
+                Arc::new(Mutex::new(NoMarkers)),
+                vec![
+                    PlatformCommand::CreateMainMenu {
+                        window_id,
+                        menu_items: vec![MenuItemConfig {
+                            action: Some(elsewhere),
+                            text: "Elsewhere".into(),
+                            children: vec![],
+                        }],
+                    },
+                    PlatformCommand::CreateTreeView {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: TREE,
+                    },
+                    PlatformCommand::ShowContextMenu {
+                        window_id,
+                        control_id: TREE,
+                        menu_items: vec![MenuItemConfig {
+                            action: Some(open),
+                            text: "Open".into(),
+                            children: vec![],
+                        }],
+                        screen_x: 0,
+                        screen_y: 0,
+                    },
+                ],
+            )
+            .unwrap();
+
+        // Act
+        platform
+            .inject_event(AppEvent::MenuActionClicked {
+                action_id: elsewhere,
+            })
+            .unwrap();
+        let open_after_other_action = platform.window(window_id).unwrap().context_menu.is_some();
+        platform
+            .inject_event(AppEvent::MenuActionClicked { action_id: open })
+            .unwrap();
+
+        // Assert
+        assert!(open_after_other_action);
+        assert!(platform.window(window_id).unwrap().context_menu.is_none());
+    }
+}
@@ -0,0 +1,683 @@
+/*
+ * Image resources shared by every backend. An application registers encoded
+ * PNG, ICO or BMP bytes under an `ImageId` once (`PlatformCommand::RegisterImage`)
+ * and then refers to the id from image controls, buttons, tabs, toolbar items
+ * and tree items. Decoding happens here, in portable code, into straight-alpha
+ * RGBA variants: an ICO file usually carries several sizes, the other formats
+ * one. When a control draws an image it asks for a pixel size that already
+ * includes the monitor's DPI scale; the variant closest above that size is
+ * picked and scaled down, so crisp hand-drawn small icons win over a large
+ * variant shrunk to fit.
+ * [CDU-ImagesV1]
+ */
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use crate::dpi::DpiScale;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::types::ImageId;
+
+/// Edge length in DIPs of the images drawn next to button, tab and tree item text.
+pub const SMALL_IMAGE_SIZE: i32 = 16;
+/// Space in DIPs between an item's image and its text.
+pub const IMAGE_TEXT_GAP: i32 = 4;
+
+/// Largest width or height in pixels accepted from an encoded image.
+const MAX_DIMENSION: u32 = 8192;
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Encodings understood by `decode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Ico,
+    Bmp,
+}
+
+impl ImageFormat {
+    /// Recognizes the encoding from the leading bytes.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(PNG_SIGNATURE) {
+            Some(Self::Png)
+        } else if bytes.starts_with(b"BM") {
+            Some(Self::Bmp)
+        } else if bytes.starts_with(&[0, 0, 1, 0]) {
+            Some(Self::Ico)
+        } else {
+            None
+        }
+    }
+}
+
+/// A decoded image: rows top to bottom, four bytes (R, G, B, straight alpha) per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Wraps `pixels`, which must hold exactly `width * height` RGBA pixels.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> PlatformResult<Self> {
+        if pixels.len() as u64 != u64::from(width) * u64::from(height) * 4 {
+            return Err(PlatformError::OperationFailed(format!(
+                "{} bytes do not hold a {width}x{height} RGBA image",
+                pixels.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let start = ((y * self.width + x) * 4) as usize;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.pixels[start..start + 4]);
+        pixel
+    }
+
+    /// The longer of width and height, which variant selection compares.
+    pub fn edge(&self) -> u32 {
+        self.width.max(self.height)
+    }
+
+    /// Pixels as premultiplied BGRA, the layout GDI alpha blending expects.
+    pub fn to_premultiplied_bgra(&self) -> Vec<u8> {
+        let premultiply =
+            |channel: u8, alpha: u8| ((u16::from(channel) * u16::from(alpha) + 127) / 255) as u8;
+        self.pixels
+            .chunks_exact(4)
+            .flat_map(|pixel| {
+                let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                [premultiply(b, a), premultiply(g, a), premultiply(r, a), a]
+            })
+            .collect()
+    }
+
+    /*
+     * The image resampled to `width` x `height` pixels. Each target pixel
+     * averages the source area it covers, weighted by alpha so transparent
+     * pixels do not darken the edges of the shape.
+     */
+    pub fn scaled(&self, width: u32, height: u32) -> RgbaImage {
+        if (width, height) == (self.width, self.height) {
+            return self.clone();
+        }
+        let mut pixels = vec![0; (width as usize) * (height as usize) * 4];
+        if self.width == 0 || self.height == 0 {
+            return RgbaImage {
+                width,
+                height,
+                pixels,
+            };
+        }
+        let step_x = f64::from(self.width) / f64::from(width);
+        let step_y = f64::from(self.height) / f64::from(height);
+        for target_y in 0..height {
+            let top = f64::from(target_y) * step_y;
+            let bottom = top + step_y;
+            for target_x in 0..width {
+                let left = f64::from(target_x) * step_x;
+                let right = left + step_x;
+                // Premultiplied color sums, alpha sum and covered area.
+                let mut sums = [0.0f64; 4];
+                let mut area = 0.0;
+                for y in (top as u32)..(bottom.ceil() as u32).min(self.height) {
+                    let weight_y = bottom.min(f64::from(y + 1)) - top.max(f64::from(y));
+                    for x in (left as u32)..(right.ceil() as u32).min(self.width) {
+                        let weight =
+                            weight_y * (right.min(f64::from(x + 1)) - left.max(f64::from(x)));
+                        let [r, g, b, a] = self.pixel(x, y);
+                        let alpha = f64::from(a) / 255.0;
+                        sums[0] += f64::from(r) * alpha * weight;
+                        sums[1] += f64::from(g) * alpha * weight;
+                        sums[2] += f64::from(b) * alpha * weight;
+                        sums[3] += alpha * weight;
+                        area += weight;
+                    }
+                }
+                if sums[3] <= 0.0 || area <= 0.0 {
+                    continue;
+                }
+                let start = ((target_y * width + target_x) * 4) as usize;
+                for channel in 0..3 {
+                    pixels[start + channel] = (sums[channel] / sums[3]).round().min(255.0) as u8;
+                }
+                pixels[start + 3] = (sums[3] / area * 255.0).round().min(255.0) as u8;
+            }
+        }
+        RgbaImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Decodes PNG, ICO or BMP bytes into their variants, in the order the file stores them.
+pub fn decode(bytes: &[u8]) -> PlatformResult<Vec<RgbaImage>> {
+    match ImageFormat::detect(bytes) {
+        Some(ImageFormat::Png) => Ok(vec![decode_png(bytes)?]),
+        Some(ImageFormat::Bmp) => Ok(vec![decode_bmp(bytes)?]),
+        Some(ImageFormat::Ico) => decode_ico(bytes),
+        None => Err(PlatformError::OperationFailed(
+            "Image data is not PNG, ICO or BMP".to_string(),
+        )),
+    }
+}
+
+/*
+ * The variant to draw at `size` pixels: an exact match, else the smallest
+ * variant larger than `size` (scaling down looks better than scaling up),
+ * else the largest one.
+ */
+pub fn select_variant(variants: &[RgbaImage], size: u32) -> Option<&RgbaImage> {
+    variants
+        .iter()
+        .filter(|variant| variant.edge() >= size)
+        .min_by_key(|variant| variant.edge())
+        .or_else(|| variants.iter().max_by_key(|variant| variant.edge()))
+}
+
+/// Preferred size of a control's `content` in pixels once an image is drawn before it.
+pub fn with_leading_image((width, height): (i32, i32), scale: DpiScale) -> (i32, i32) {
+    let image_size = scale.scale(SMALL_IMAGE_SIZE);
+    (
+        width + image_size + scale.scale(IMAGE_TEXT_GAP),
+        height.max(image_size),
+    )
+}
+
+/// The largest size of `width` x `height` that fits the box while keeping the aspect ratio.
+pub fn fit_within(width: u32, height: u32, box_width: i32, box_height: i32) -> (i32, i32) {
+    if width == 0 || height == 0 || box_width <= 0 || box_height <= 0 {
+        return (0, 0);
+    }
+    let scale =
+        (f64::from(box_width) / f64::from(width)).min(f64::from(box_height) / f64::from(height));
+    (
+        ((f64::from(width) * scale).round() as i32).clamp(1, box_width),
+        ((f64::from(height) * scale).round() as i32).clamp(1, box_height),
+    )
+}
+
+/// Decoded images by id, as registered through `PlatformCommand::RegisterImage`.
+#[derive(Debug, Clone, Default)]
+pub struct ImageRegistry {
+    images: HashMap<ImageId, Vec<RgbaImage>>,
+}
+
+impl ImageRegistry {
+    /// Decodes `bytes` and stores the variants under `image_id`, replacing earlier ones.
+    pub fn register(&mut self, image_id: ImageId, bytes: &[u8]) -> PlatformResult<()> {
+        let variants = decode(bytes).map_err(|err| match err {
+            PlatformError::OperationFailed(message) => {
+                PlatformError::OperationFailed(format!("Image {image_id:?}: {message}"))
+            }
+            other => other,
+        })?;
+        self.images.insert(image_id, variants);
+        Ok(())
+    }
+
+    pub fn contains(&self, image_id: ImageId) -> bool {
+        self.images.contains_key(&image_id)
+    }
+
+    pub fn variants(&self, image_id: ImageId) -> Option<&[RgbaImage]> {
+        self.images.get(&image_id).map(Vec::as_slice)
+    }
+
+    /// Pixel size of the largest variant, which sets the aspect ratio of an image control.
+    pub fn natural_size(&self, image_id: ImageId) -> Option<(u32, u32)> {
+        let variants = self.images.get(&image_id)?;
+        let largest = variants.iter().max_by_key(|variant| variant.edge())?;
+        Some((largest.width, largest.height))
+    }
+
+    /// The image drawn at exactly `width` x `height` pixels, from the best variant.
+    pub fn render(&self, image_id: ImageId, width: u32, height: u32) -> Option<RgbaImage> {
+        let variants = self.images.get(&image_id)?;
+        let variant = select_variant(variants, width.max(height))?;
+        Some(variant.scaled(width, height))
+    }
+
+    /// Checks that an id used by a command or descriptor has been registered.
+    pub fn require(&self, image_id: ImageId) -> PlatformResult<()> {
+        if self.contains(image_id) {
+            Ok(())
+        } else {
+            Err(PlatformError::OperationFailed(format!(
+                "Image {image_id:?} has not been registered"
+            )))
+        }
+    }
+}
+
+fn invalid(format: &str, detail: impl std::fmt::Display) -> PlatformError {
+    PlatformError::OperationFailed(format!("Invalid {format} image: {detail}"))
+}
+
+fn check_dimensions(format: &str, width: u32, height: u32) -> PlatformResult<()> {
+    if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err(invalid(
+            format,
+            format!("unsupported size {width}x{height}"),
+        ));
+    }
+    Ok(())
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let field = bytes.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([field[0], field[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let field = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([field[0], field[1], field[2], field[3]]))
+}
+
+fn decode_png(bytes: &[u8]) -> PlatformResult<RgbaImage> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|err| invalid("PNG", err))?;
+    let buffer_size = reader
+        .output_buffer_size()
+        .ok_or_else(|| invalid("PNG", "image too large"))?;
+    let mut buffer = vec![0; buffer_size];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|err| invalid("PNG", err))?;
+    check_dimensions("PNG", info.width, info.height)?;
+    let channels = match info.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        png::ColorType::Indexed => return Err(invalid("PNG", "palette was not expanded")),
+    };
+    let mut pixels = Vec::with_capacity((info.width * info.height * 4) as usize);
+    for row in buffer.chunks(info.line_size).take(info.height as usize) {
+        for source in row.chunks_exact(channels).take(info.width as usize) {
+            pixels.extend_from_slice(&match *source {
+                [gray] => [gray, gray, gray, 255],
+                [gray, alpha] => [gray, gray, gray, alpha],
+                [r, g, b] => [r, g, b, 255],
+                [r, g, b, a] => [r, g, b, a],
+                _ => unreachable!("chunks_exact yields {channels} bytes"),
+            });
+        }
+    }
+    RgbaImage::new(info.width, info.height, pixels)
+}
+
+fn decode_bmp(bytes: &[u8]) -> PlatformResult<RgbaImage> {
+    // BITMAPFILEHEADER: "BM", file size, two reserved words, offset of the pixels.
+    const FILE_HEADER_SIZE: usize = 14;
+    let pixel_offset = read_u32(bytes, 10)
+        .map(|offset| offset as usize)
+        .filter(|&offset| offset >= FILE_HEADER_SIZE)
+        .ok_or_else(|| invalid("BMP", "truncated file header"))?;
+    decode_dib(
+        &bytes[FILE_HEADER_SIZE..],
+        Some(pixel_offset - FILE_HEADER_SIZE),
+        false,
+    )
+}
+
+/*
+ * Decodes a device-independent bitmap (BITMAPINFOHEADER or a later version)
+ * as found in BMP files and ICO entries. Supports 1, 4 and 8 bit palettes and
+ * 24 and 32 bit pixels, the latter also with BI_BITFIELDS masks. Icon bitmaps
+ * (`icon_mask`) store twice their height: the color rows followed by a 1 bit
+ * AND mask that marks transparent pixels of images without an alpha channel.
+ */
+fn decode_dib(
+    dib: &[u8],
+    pixel_offset: Option<usize>,
+    icon_mask: bool,
+) -> PlatformResult<RgbaImage> {
+    const BI_RGB: u32 = 0;
+    const BI_BITFIELDS: u32 = 3;
+    let format = if icon_mask { "ICO" } else { "BMP" };
+    let truncated = || invalid(format, "truncated bitmap");
+
+    let header_size = read_u32(dib, 0).ok_or_else(truncated)? as usize;
+    if header_size < 40 {
+        return Err(invalid(
+            format,
+            format!("unsupported header size {header_size}"),
+        ));
+    }
+    let width = read_u32(dib, 4).ok_or_else(truncated)? as i32;
+    let mut raw_height = read_u32(dib, 8).ok_or_else(truncated)? as i32;
+    let bit_count = read_u16(dib, 14).ok_or_else(truncated)?;
+    let compression = read_u32(dib, 16).ok_or_else(truncated)?;
+    let colors_used = read_u32(dib, 32).ok_or_else(truncated)?;
+    if icon_mask {
+        raw_height /= 2;
+    }
+    let top_down = raw_height < 0;
+    let (width, height) = (width.max(0) as u32, raw_height.unsigned_abs());
+    check_dimensions(format, width, height)?;
+
+    let masks = match (compression, bit_count) {
+        (BI_RGB, 1 | 4 | 8 | 24 | 32) => None,
+        (BI_BITFIELDS, 32) => {
+            // Masks follow a v1 header and are part of the v4 and v5 headers.
+            let field = |index: usize| read_u32(dib, 40 + index * 4).ok_or_else(truncated);
+            Some([field(0)?, field(1)?, field(2)?, field(3).unwrap_or(0)])
+        }
+        _ => {
+            return Err(invalid(
+                format,
+                format!("unsupported encoding ({bit_count} bit, compression {compression})"),
+            ));
+        }
+    };
+    let masks_size = if compression == BI_BITFIELDS && header_size == 40 {
+        12
+    } else {
+        0
+    };
+
+    let palette_start = header_size + masks_size;
+    let palette: Vec<[u8; 4]> = if bit_count <= 8 {
+        let count = match colors_used {
+            0 => 1usize << bit_count,
+            count => (count as usize).min(1 << bit_count),
+        };
+        let entries = dib
+            .get(palette_start..palette_start + count * 4)
+            .ok_or_else(truncated)?;
+        entries
+            .chunks_exact(4)
+            .map(|entry| [entry[2], entry[1], entry[0], 255])
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let pixels_start = pixel_offset.unwrap_or(palette_start + palette.len() * 4);
+
+    let stride = (width as usize * bit_count as usize).div_ceil(32) * 4;
+    let data = dib
+        .get(pixels_start..pixels_start + stride * height as usize)
+        .ok_or_else(truncated)?;
+    let source_row = |y: u32| {
+        let row = if top_down { y } else { height - 1 - y } as usize;
+        &data[row * stride..(row + 1) * stride]
+    };
+
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        let row = source_row(y);
+        for x in 0..width as usize {
+            let pixel = match bit_count {
+                1 | 4 | 8 => {
+                    let bits = bit_count as usize;
+                    let byte = row[x * bits / 8];
+                    let shift = 8 - bits - (x * bits % 8);
+                    let index = (byte >> shift) as usize & ((1 << bits) - 1);
+                    *palette
+                        .get(index)
+                        .ok_or_else(|| invalid(format, "palette index out of range"))?
+                }
+                24 => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 255],
+                _ => {
+                    let value = u32::from_le_bytes([
+                        row[x * 4],
+                        row[x * 4 + 1],
+                        row[x * 4 + 2],
+                        row[x * 4 + 3],
+                    ]);
+                    match masks {
+                        Some([r, g, b, a]) => [
+                            extract_channel(value, r),
+                            extract_channel(value, g),
+                            extract_channel(value, b),
+                            if a == 0 {
+                                255
+                            } else {
+                                extract_channel(value, a)
+                            },
+                        ],
+                        None => [row[x * 4 + 2], row[x * 4 + 1], row[x * 4], row[x * 4 + 3]],
+                    }
+                }
+            };
+            pixels.extend_from_slice(&pixel);
+        }
+    }
+
+    // 32 bit BI_RGB bitmaps often leave the fourth byte unused: all zero means opaque.
+    let has_alpha = bit_count == 32 && pixels.chunks_exact(4).any(|pixel| pixel[3] != 0);
+    if bit_count == 32 && !has_alpha {
+        pixels.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+    }
+    if icon_mask && !has_alpha {
+        let mask_stride = (width as usize).div_ceil(32) * 4;
+        let mask_start = pixels_start + stride * height as usize;
+        // Some icons omit the mask when the colors carry alpha; treat a missing one as opaque.
+        if let Some(mask) = dib.get(mask_start..mask_start + mask_stride * height as usize) {
+            for y in 0..height {
+                let row = if top_down { y } else { height - 1 - y } as usize;
+                let mask_row = &mask[row * mask_stride..(row + 1) * mask_stride];
+                for x in 0..width as usize {
+                    if mask_row[x / 8] & (0x80 >> (x % 8)) != 0 {
+                        pixels[(y as usize * width as usize + x) * 4 + 3] = 0;
+                    }
+                }
+            }
+        }
+    }
+    RgbaImage::new(width, height, pixels)
+}
+
+/// Scales the bits selected by `mask` to 0..=255.
+fn extract_channel(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+    let bits = (value & mask) >> mask.trailing_zeros();
+    let max = mask >> mask.trailing_zeros();
+    ((u64::from(bits) * 255 + u64::from(max) / 2) / u64::from(max)) as u8
+}
+
+fn decode_ico(bytes: &[u8]) -> PlatformResult<Vec<RgbaImage>> {
+    // ICONDIR: reserved, type (1 = icon), entry count; then 16-byte ICONDIRENTRYs.
+    const ENTRY_SIZE: usize = 16;
+    let count = read_u16(bytes, 4).ok_or_else(|| invalid("ICO", "truncated header"))?;
+    if count == 0 {
+        return Err(invalid("ICO", "no images"));
+    }
+    (0..count as usize)
+        .map(|index| {
+            let entry = 6 + index * ENTRY_SIZE;
+            let size = read_u32(bytes, entry + 8);
+            let offset = read_u32(bytes, entry + 12);
+            let data = size
+                .zip(offset)
+                .and_then(|(size, offset)| {
+                    let start = offset as usize;
+                    bytes.get(start..start.checked_add(size as usize)?)
+                })
+                .ok_or_else(|| invalid("ICO", format!("entry {index} is truncated")))?;
+            if data.starts_with(PNG_SIGNATURE) {
+                decode_png(data)
+            } else {
+                decode_dib(data, None, true)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+    fn solid(edge: u32, pixel: [u8; 4]) -> RgbaImage {
+        RgbaImage::new(edge, edge, pixel.repeat((edge * edge) as usize)).unwrap()
+    }
+
+    fn encode_png(image: &RgbaImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, image.width, image.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&image.pixels).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    // A 2x2 24 bit bottom-up BMP: top row red, blue; bottom row blue, red.
+    fn two_by_two_bmp() -> Vec<u8> {
+        let mut bytes = b"BM".to_vec();
+        bytes.extend_from_slice(&(14u32 + 40 + 16).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&54u32.to_le_bytes());
+        bytes.extend_from_slice(&40u32.to_le_bytes());
+        bytes.extend_from_slice(&2i32.to_le_bytes());
+        bytes.extend_from_slice(&2i32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&24u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 24]);
+        // Rows are stored bottom first, BGR, padded to four bytes.
+        bytes.extend_from_slice(&[255, 0, 0, 0, 0, 255, 0, 0]);
+        bytes.extend_from_slice(&[0, 0, 255, 255, 0, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    // [CDU-ImagesV1] PNG and BMP bytes decode to the same RGBA pixels on every platform.
+    fn png_and_bmp_decode_to_rgba() {
+        // Arrange
+        let expected = RgbaImage::new(2, 2, [RED, BLUE, BLUE, RED].concat()).unwrap();
+
+        // Act
+        let from_png = decode(&encode_png(&expected)).unwrap();
+        let from_bmp = decode(&two_by_two_bmp()).unwrap();
+
+        // Assert
+        assert_eq!(from_png, vec![expected.clone()]);
+        assert_eq!(from_bmp, vec![expected]);
+    }
+
+    #[test]
+    // [CDU-ImagesV1] Each ICO entry becomes a variant; the AND mask makes pixels transparent.
+    fn ico_entries_become_variants() {
+        // Arrange: one PNG entry (4x4) and one 1x1 32 bit DIB entry with the mask bit set.
+        let png = encode_png(&solid(4, BLUE));
+        let mut dib = Vec::new();
+        dib.extend_from_slice(&40u32.to_le_bytes());
+        dib.extend_from_slice(&1i32.to_le_bytes());
+        dib.extend_from_slice(&2i32.to_le_bytes());
+        dib.extend_from_slice(&1u16.to_le_bytes());
+        dib.extend_from_slice(&32u16.to_le_bytes());
+        dib.extend_from_slice(&[0; 24]);
+        dib.extend_from_slice(&[0, 0, 255, 0]);
+        dib.extend_from_slice(&[0x80, 0, 0, 0]);
+        let mut ico = vec![0, 0, 1, 0, 2, 0];
+        let mut offset = 6 + 2 * 16;
+        for (edge, data) in [(4u8, &png), (1u8, &dib)] {
+            ico.extend_from_slice(&[edge, edge, 0, 0, 1, 0, 32, 0]);
+            ico.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            ico.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += data.len();
+        }
+        ico.extend_from_slice(&png);
+        ico.extend_from_slice(&dib);
+
+        // Act
+        let variants = decode(&ico).unwrap();
+
+        // Assert
+        assert_eq!(variants, vec![solid(4, BLUE), solid(1, [255, 0, 0, 0])]);
+    }
+
+    #[test]
+    fn decode_rejects_unknown_and_truncated_data() {
+        // Act & Assert
+        assert!(decode(b"GIF89a").is_err());
+        assert!(decode(&two_by_two_bmp()[..60]).is_err());
+        assert!(decode(&[0, 0, 1, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    // [CDU-ImagesV1] The smallest variant at least as large as the DPI-scaled size is drawn.
+    fn select_variant_prefers_the_next_larger_size() {
+        // Arrange
+        let variants = [solid(16, RED), solid(32, RED), solid(48, RED)];
+
+        // Act
+        let picked: Vec<u32> = [16, 20, 32, 64]
+            .into_iter()
+            .map(|size| select_variant(&variants, size).unwrap().width)
+            .collect();
+
+        // Assert
+        assert_eq!(picked, vec![16, 32, 32, 48]);
+    }
+
+    #[test]
+    fn scaling_averages_colors_weighted_by_alpha() {
+        // Arrange: red and transparent halves.
+        let image = RgbaImage::new(2, 1, [RED, CLEAR].concat()).unwrap();
+
+        // Act
+        let scaled = image.scaled(1, 1);
+
+        // Assert: transparent black does not darken the red.
+        assert_eq!(scaled.pixels, vec![255, 0, 0, 128]);
+    }
+
+    #[test]
+    fn premultiplied_bgra_swaps_channels_and_scales_by_alpha() {
+        // Arrange
+        let image =
+            RgbaImage::new(2, 1, [[200, 100, 50, 255], [200, 100, 50, 128]].concat()).unwrap();
+
+        // Act
+        let bgra = image.to_premultiplied_bgra();
+
+        // Assert
+        assert_eq!(bgra, vec![50, 100, 200, 255, 25, 50, 100, 128]);
+    }
+
+    #[test]
+    fn registry_renders_registered_images_only() {
+        // Arrange
+        let mut registry = ImageRegistry::default();
+        registry
+            .register(ImageId(1), &encode_png(&solid(32, BLUE)))
+            .unwrap();
+
+        // Act
+        let rendered = registry.render(ImageId(1), 16, 16);
+
+        // Assert
+        assert_eq!(rendered, Some(solid(16, BLUE)));
+        assert_eq!(registry.natural_size(ImageId(1)), Some((32, 32)));
+        assert!(registry.require(ImageId(2)).is_err());
+        assert!(registry.register(ImageId(2), b"junk").is_err());
+        assert!(!registry.contains(ImageId(2)));
+    }
+
+    #[test]
+    fn fit_within_keeps_the_aspect_ratio() {
+        // Act & Assert
+        assert_eq!(fit_within(200, 100, 50, 50), (50, 25));
+        assert_eq!(fit_within(10, 40, 100, 100), (25, 100));
+        assert_eq!(fit_within(10, 10, 0, 20), (0, 0));
+    }
+}
@@ -0,0 +1,1940 @@
+/*
+ * Platform-neutral docking, grid and flow layout engine. Given the `LayoutRule`s sent with
+ * `PlatformCommand::DefineLayout` and the client size of a window, this module
+ * computes the rectangle of every control in the nested panel tree. The Win32
+ * backend only applies the result to native windows, so applications can
+ * unit-test their layout rules on any platform.
+ *
+ * Rectangles are expressed relative to the client area of the control's
+ * parent (the window for top-level rules, otherwise the parent panel), which
+ * is the coordinate system native child windows are positioned in.
+ * [CDU-LayoutSystemV1]
+ */
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::types::{
+    ContainerLayout, ControlId, DockStyle, FlowAlignment, FlowLayout, GridDefinition, GridTrack,
+    LayoutAxis, LayoutRule, SizeMode,
+};
+
+/// An axis-aligned rectangle in DIPs, or in physical pixels when computed from
+/// rules scaled with `DpiScale::scale_rules`. `right` and `bottom` are exclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    pub const fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// A rectangle anchored at the origin, e.g. a client area.
+    pub const fn from_size(width: i32, height: i32) -> Self {
+        Self::new(0, 0, width, height)
+    }
+
+    pub fn width(&self) -> i32 {
+        (self.right - self.left).max(0)
+    }
+
+    pub fn height(&self) -> i32 {
+        (self.bottom - self.top).max(0)
+    }
+
+    pub fn offset(&self, dx: i32, dy: i32) -> Self {
+        Self::new(
+            self.left + dx,
+            self.top + dy,
+            self.right + dx,
+            self.bottom + dy,
+        )
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.left < other.right
+            && other.left < self.right
+            && self.top < other.bottom
+            && other.top < self.bottom
+    }
+}
+
+/*
+ * Computes the full nested layout for a window with the given client size.
+ * Top-level rules (`parent_control_id == None`) are laid out in the client
+ * area; every control that is itself the parent of other rules is then laid
+ * out recursively using its own size as the client area. The returned
+ * rectangles are relative to each control's parent.
+ */
+pub fn compute_layout(
+    client_width: i32,
+    client_height: i32,
+    rules: &[LayoutRule],
+) -> HashMap<ControlId, Rect> {
+    let mut result = HashMap::new();
+    let mut visited = HashSet::new();
+    layout_children(
+        None,
+        Rect::from_size(client_width, client_height),
+        rules,
+        &mut result,
+        &mut visited,
+    );
+    result
+}
+
+fn layout_children(
+    parent_id: Option<ControlId>,
+    parent_rect: Rect,
+    all_rules: &[LayoutRule],
+    result: &mut HashMap<ControlId, Rect>,
+    visited: &mut HashSet<ControlId>,
+) {
+    let child_rules: Vec<LayoutRule> = all_rules
+        .iter()
+        .filter(|r| r.parent_control_id == parent_id && !visited.contains(&r.control_id))
+        .cloned()
+        .collect();
+    if child_rules.is_empty() {
+        return;
+    }
+
+    let container = parent_id.and_then(|id| {
+        all_rules
+            .iter()
+            .find(|r| r.control_id == id)
+            .and_then(|r| r.container.as_ref())
+    });
+    let layout_map = calculate_container_layout(parent_rect, container, &child_rules);
+    for (control_id, rect) in &layout_map {
+        visited.insert(*control_id);
+        result.insert(*control_id, *rect);
+    }
+    for (control_id, rect) in layout_map {
+        if all_rules
+            .iter()
+            .any(|r| r.parent_control_id == Some(control_id))
+        {
+            layout_children(
+                Some(control_id),
+                Rect::from_size(rect.width(), rect.height()),
+                all_rules,
+                result,
+                visited,
+            );
+        }
+    }
+}
+
+/*
+ * Pure layout calculation for a group of sibling controls. Returns the
+ * rectangle for each control within `parent_rect`. Docked edges are consumed
+ * in `order`, proportional fills then share the remaining space along their
+ * axis by weight, and the first `Fill` rule takes whatever is left. `None`
+ * rules with `absolute_bounds` are placed relative to `parent_rect`. Every
+ * result honours the rule's `SizeConstraints`.
+ */
+pub fn calculate_layout(parent_rect: Rect, rules: &[LayoutRule]) -> HashMap<ControlId, Rect> {
+    calculate_container_layout(parent_rect, None, rules)
+}
+
+/*
+ * Same as `calculate_layout`, for siblings whose parent declares a
+ * `ContainerLayout`. The container's cells are arranged in the space left
+ * after the docked edges. Cells are ignored when `container` is `None`.
+ */
+pub fn calculate_container_layout(
+    parent_rect: Rect,
+    container: Option<&ContainerLayout>,
+    rules: &[LayoutRule],
+) -> HashMap<ControlId, Rect> {
+    let mut sorted = rules.to_vec();
+    sorted.sort_by_key(|r| r.order);
+
+    let mut result = HashMap::new();
+    let mut current_available_rect = parent_rect;
+    let mut fill_candidate: Option<&LayoutRule> = None;
+    let mut proportional_fill_candidates: Vec<&LayoutRule> = Vec::new();
+    let mut container_items: Vec<&LayoutRule> = Vec::new();
+
+    for rule in &sorted {
+        let constraints = &rule.constraints;
+        match rule.dock_style {
+            DockStyle::Top | DockStyle::Bottom | DockStyle::Left | DockStyle::Right => {
+                let mut item_rect = inset(current_available_rect, rule.margin);
+                let size = docked_extent(rule);
+                match rule.dock_style {
+                    DockStyle::Top => {
+                        item_rect.bottom = item_rect.top + constraints.clamp_height(size);
+                        item_rect.right =
+                            item_rect.left + constraints.clamp_width(item_rect.width());
+                        current_available_rect.top = item_rect.bottom + rule.margin.2;
+                    }
+                    DockStyle::Bottom => {
+                        item_rect.top = item_rect.bottom - constraints.clamp_height(size);
+                        item_rect.right =
+                            item_rect.left + constraints.clamp_width(item_rect.width());
+                        current_available_rect.bottom = item_rect.top - rule.margin.0;
+                    }
+                    DockStyle::Left => {
+                        item_rect.right = item_rect.left + constraints.clamp_width(size);
+                        item_rect.bottom =
+                            item_rect.top + constraints.clamp_height(item_rect.height());
+                        current_available_rect.left = item_rect.right + rule.margin.1;
+                    }
+                    DockStyle::Right => {
+                        item_rect.left = item_rect.right - constraints.clamp_width(size);
+                        item_rect.bottom =
+                            item_rect.top + constraints.clamp_height(item_rect.height());
+                        current_available_rect.right = item_rect.left - rule.margin.3;
+                    }
+                    _ => unreachable!(),
+                }
+                result.insert(rule.control_id, item_rect);
+            }
+            DockStyle::Fill => {
+                if fill_candidate.is_none() {
+                    fill_candidate = Some(rule);
+                }
+            }
+            DockStyle::ProportionalFill { .. } => {
+                proportional_fill_candidates.push(rule);
+            }
+            DockStyle::GridCell { .. } | DockStyle::FlowItem => {
+                container_items.push(rule);
+            }
+            DockStyle::None => {
+                if let Some(bounds) = rule.absolute_bounds {
+                    let left = parent_rect.left + bounds.left;
+                    let top = parent_rect.top + bounds.top;
+                    result.insert(
+                        rule.control_id,
+                        Rect::new(
+                            left,
+                            top,
+                            left + constraints.clamp_width(bounds.width()),
+                            top + constraints.clamp_height(bounds.height()),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(first) = proportional_fill_candidates.first() {
+        // Validation guarantees siblings share one axis; the first rule decides.
+        let horizontal = match first.dock_style {
+            DockStyle::ProportionalFill { axis, .. } => axis == LayoutAxis::Horizontal,
+            _ => true,
+        };
+        let available = current_available_rect;
+        let (total_main, total_cross) = if horizontal {
+            (available.width(), available.height())
+        } else {
+            (available.height(), available.width())
+        };
+        if let Some(allocations) =
+            distribute_proportional(total_main, &proportional_fill_candidates, horizontal)
+        {
+            let mut cursor = if horizontal {
+                available.left
+            } else {
+                available.top
+            };
+            for (rule, allocation) in proportional_fill_candidates.iter().zip(allocations) {
+                let (top, right, bottom, left) = rule.margin;
+                let constraints = &rule.constraints;
+                let rect = if horizontal {
+                    let x = cursor + left;
+                    let y = available.top + top;
+                    let width = (allocation - left - right).max(0);
+                    let height = constraints.clamp_height((total_cross - top - bottom).max(0));
+                    Rect::new(x, y, x + width, y + height)
+                } else {
+                    let x = available.left + left;
+                    let y = cursor + top;
+                    let width = constraints.clamp_width((total_cross - left - right).max(0));
+                    let height = (allocation - top - bottom).max(0);
+                    Rect::new(x, y, x + width, y + height)
+                };
+                result.insert(rule.control_id, rect);
+                cursor += allocation;
+            }
+        }
+    }
+
+    if let Some(rule) = fill_candidate {
+        let mut fill_rect = inset(current_available_rect, rule.margin);
+        fill_rect.right = fill_rect.left + rule.constraints.clamp_width(fill_rect.width());
+        fill_rect.bottom = fill_rect.top + rule.constraints.clamp_height(fill_rect.height());
+        result.insert(rule.control_id, fill_rect);
+    }
+
+    if !container_items.is_empty() {
+        match container {
+            Some(ContainerLayout::Grid(grid)) => result.extend(calculate_grid_layout(
+                current_available_rect,
+                grid,
+                &container_items,
+            )),
+            Some(ContainerLayout::Flow(flow)) => result.extend(calculate_flow_layout(
+                current_available_rect,
+                flow,
+                &container_items,
+            )),
+            None => {}
+        }
+    }
+
+    result
+}
+
+/*
+ * Places flow items inside `area`. Each item takes its preferred size (or
+ * its minimum when no preferred size is known), clamped by its constraints,
+ * plus margins. Items that do not match the container's kind are ignored.
+ */
+fn calculate_flow_layout(
+    area: Rect,
+    flow: &FlowLayout,
+    items: &[&LayoutRule],
+) -> HashMap<ControlId, Rect> {
+    let horizontal = flow.axis == LayoutAxis::Horizontal;
+    let spacing = flow.spacing.max(0);
+    let items: Vec<(&LayoutRule, i32, i32)> = items
+        .iter()
+        .filter(|rule| rule.dock_style == DockStyle::FlowItem)
+        .map(|rule| {
+            (
+                *rule,
+                preferred_extent(rule, horizontal),
+                preferred_extent(rule, !horizontal),
+            )
+        })
+        .collect();
+    let (main_origin, cross_origin, available) = if horizontal {
+        (area.left, area.top, area.width())
+    } else {
+        (area.top, area.left, area.height())
+    };
+
+    let mut lines: Vec<Vec<(&LayoutRule, i32, i32)>> = Vec::new();
+    let mut line_length = 0;
+    for item in items {
+        let main = item.1;
+        match lines.last_mut() {
+            Some(line) if !flow.wrap || line_length + spacing + main <= available => {
+                line_length += spacing + main;
+                line.push(item);
+            }
+            _ => {
+                line_length = main;
+                lines.push(vec![item]);
+            }
+        }
+    }
+
+    let mut result = HashMap::new();
+    let mut cross_cursor = cross_origin;
+    for line in lines {
+        let length =
+            line.iter().map(|(_, main, _)| main).sum::<i32>() + spacing * (line.len() as i32 - 1);
+        let thickness = line.iter().map(|(_, _, cross)| *cross).max().unwrap_or(0);
+        let mut main_cursor = main_origin + align_offset(flow.alignment, available - length);
+        for (rule, main, cross) in line {
+            let cross_start = cross_cursor + align_offset(flow.cross_alignment, thickness - cross);
+            let outer = if horizontal {
+                Rect::new(
+                    main_cursor,
+                    cross_start,
+                    main_cursor + main,
+                    cross_start + cross,
+                )
+            } else {
+                Rect::new(
+                    cross_start,
+                    main_cursor,
+                    cross_start + cross,
+                    main_cursor + main,
+                )
+            };
+            result.insert(rule.control_id, inset(outer, rule.margin));
+            main_cursor += main + spacing;
+        }
+        cross_cursor += thickness + flow.line_spacing.max(0);
+    }
+    result
+}
+
+// Offset that aligns content within `free` pixels; overflowing content starts at zero.
+fn align_offset(alignment: FlowAlignment, free: i32) -> i32 {
+    match alignment {
+        FlowAlignment::Start => 0,
+        FlowAlignment::Center => free.max(0) / 2,
+        FlowAlignment::End => free.max(0),
+    }
+}
+
+/*
+ * Places grid cells inside `area`. Fixed tracks take their size, auto tracks
+ * take the largest preferred extent (plus margins) of the cells that sit in
+ * them without spanning, and star tracks share whatever is left by weight.
+ * Each cell covers its spanned tracks and the spacing between them, minus its
+ * margins, and is clamped by its `SizeConstraints` from the top-left corner.
+ */
+fn calculate_grid_layout(
+    area: Rect,
+    grid: &GridDefinition,
+    cells: &[&LayoutRule],
+) -> HashMap<ControlId, Rect> {
+    let column_sizes = resolve_tracks(
+        &grid.columns,
+        area.width(),
+        grid.column_spacing,
+        cells,
+        true,
+    );
+    let row_sizes = resolve_tracks(&grid.rows, area.height(), grid.row_spacing, cells, false);
+    let column_starts = track_starts(area.left, &column_sizes, grid.column_spacing);
+    let row_starts = track_starts(area.top, &row_sizes, grid.row_spacing);
+
+    let mut result = HashMap::new();
+    for rule in cells {
+        let DockStyle::GridCell {
+            row,
+            column,
+            row_span,
+            column_span,
+        } = rule.dock_style
+        else {
+            continue;
+        };
+        let (Some(columns), Some(rows)) = (
+            span_range(column, column_span, column_sizes.len()),
+            span_range(row, row_span, row_sizes.len()),
+        ) else {
+            continue;
+        };
+        let left = column_starts[columns.start];
+        let top = row_starts[rows.start];
+        let right = column_starts[columns.end - 1] + column_sizes[columns.end - 1];
+        let bottom = row_starts[rows.end - 1] + row_sizes[rows.end - 1];
+        let mut cell_rect = inset(Rect::new(left, top, right, bottom), rule.margin);
+        cell_rect.right = cell_rect.left + rule.constraints.clamp_width(cell_rect.width());
+        cell_rect.bottom = cell_rect.top + rule.constraints.clamp_height(cell_rect.height());
+        result.insert(rule.control_id, cell_rect);
+    }
+    result
+}
+
+fn span_range(start: u32, span: u32, track_count: usize) -> Option<std::ops::Range<usize>> {
+    let start = start as usize;
+    let end = start.checked_add(span as usize)?;
+    (span > 0 && end <= track_count).then_some(start..end)
+}
+
+// Resolves the pixel size of every track along one axis of the grid.
+fn resolve_tracks(
+    tracks: &[GridTrack],
+    total: i32,
+    spacing: i32,
+    cells: &[&LayoutRule],
+    horizontal: bool,
+) -> Vec<i32> {
+    let mut sizes: Vec<i32> = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Fixed(size) => (*size).max(0),
+            GridTrack::Auto | GridTrack::Star(_) => 0,
+        })
+        .collect();
+
+    for rule in cells {
+        let DockStyle::GridCell {
+            row,
+            column,
+            row_span,
+            column_span,
+        } = rule.dock_style
+        else {
+            continue;
+        };
+        let (index, span) = if horizontal {
+            (column as usize, column_span)
+        } else {
+            (row as usize, row_span)
+        };
+        if span != 1 || !matches!(tracks.get(index), Some(GridTrack::Auto)) {
+            continue;
+        }
+        let desired = preferred_extent(rule, horizontal);
+        sizes[index] = sizes[index].max(desired);
+    }
+
+    let gaps = spacing.max(0) * (tracks.len() as i32 - 1).max(0);
+    let remaining = (total - gaps - sizes.iter().sum::<i32>()).max(0);
+    let star_weights: Vec<(usize, f32)> = tracks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, track)| match track {
+            GridTrack::Star(weight) if *weight > 0.0 => Some((index, *weight)),
+            _ => None,
+        })
+        .collect();
+    let total_weight: f32 = star_weights.iter().map(|(_, weight)| weight).sum();
+    if total_weight > 0.0 {
+        let mut handed_out = 0;
+        for (position, (index, weight)) in star_weights.iter().enumerate() {
+            // The last star track absorbs rounding so the grid fills the area exactly.
+            let share = if position + 1 == star_weights.len() {
+                remaining - handed_out
+            } else {
+                (remaining as f32 * (weight / total_weight)) as i32
+            };
+            sizes[*index] = share;
+            handed_out += share;
+        }
+    }
+    sizes
+}
+
+// Preferred size of a cell along one axis, including its margins.
+fn preferred_extent(rule: &LayoutRule, horizontal: bool) -> i32 {
+    let (top, right, bottom, left) = rule.margin;
+    let c = &rule.constraints;
+    if horizontal {
+        let width = rule
+            .preferred_size
+            .map(|(width, _)| width)
+            .or(c.min_width)
+            .unwrap_or(0);
+        c.clamp_width(width) + left + right
+    } else {
+        let height = rule
+            .preferred_size
+            .map(|(_, height)| height)
+            .or(c.min_height)
+            .unwrap_or(0);
+        c.clamp_height(height) + top + bottom
+    }
+}
+
+fn track_starts(origin: i32, sizes: &[i32], spacing: i32) -> Vec<i32> {
+    let mut cursor = origin;
+    sizes
+        .iter()
+        .map(|size| {
+            let start = cursor;
+            cursor += size + spacing.max(0);
+            start
+        })
+        .collect()
+}
+
+// Height of a Top/Bottom rule or width of a Left/Right rule, before constraints.
+fn docked_extent(rule: &LayoutRule) -> i32 {
+    match rule.size_mode {
+        SizeMode::Fixed => rule.fixed_size.unwrap_or(0),
+        SizeMode::Auto => rule
+            .preferred_size
+            .map(|(width, height)| match rule.dock_style {
+                DockStyle::Top | DockStyle::Bottom => height,
+                _ => width,
+            })
+            .unwrap_or(0),
+    }
+}
+
+// Shrinks `rect` by a (top, right, bottom, left) margin tuple.
+fn inset(rect: Rect, margin: (i32, i32, i32, i32)) -> Rect {
+    Rect {
+        left: rect.left + margin.3,
+        top: rect.top + margin.0,
+        right: rect.right - margin.1,
+        bottom: rect.bottom - margin.2,
+    }
+}
+
+/*
+ * Splits `total` pixels along the main axis between proportional siblings.
+ * Allocations include each sibling's margins. Siblings whose weighted share
+ * violates their min/max are frozen at the limit and the rest is
+ * redistributed among the others, like flexbox. Returns `None` when the
+ * total weight is zero, in which case no sibling is positioned.
+ */
+fn distribute_proportional(
+    total: i32,
+    rules: &[&LayoutRule],
+    horizontal: bool,
+) -> Option<Vec<i32>> {
+    let weights: Vec<f32> = rules
+        .iter()
+        .map(|r| match r.dock_style {
+            DockStyle::ProportionalFill { weight, .. } => weight.max(0.0),
+            _ => 0.0,
+        })
+        .collect();
+    if weights.iter().sum::<f32>() <= 0.0 {
+        return None;
+    }
+    let limits: Vec<(i32, Option<i32>)> = rules
+        .iter()
+        .map(|r| {
+            let (margins, min, max) = if horizontal {
+                (
+                    r.margin.1 + r.margin.3,
+                    r.constraints.min_width,
+                    r.constraints.max_width,
+                )
+            } else {
+                (
+                    r.margin.0 + r.margin.2,
+                    r.constraints.min_height,
+                    r.constraints.max_height,
+                )
+            };
+            (
+                min.map_or(0, |min| min + margins),
+                max.map(|max| max + margins),
+            )
+        })
+        .collect();
+
+    let mut frozen: Vec<Option<i32>> = vec![None; rules.len()];
+    loop {
+        let shares = weighted_shares(total, &weights, &frozen);
+        let mut violations: Vec<(usize, i32)> = Vec::new();
+        for (index, share) in shares.iter().enumerate() {
+            if frozen[index].is_some() {
+                continue;
+            }
+            let (min, max) = limits[index];
+            let clamped = max.map_or(*share, |max| (*share).min(max)).max(min);
+            if clamped != *share {
+                violations.push((index, clamped - share));
+            }
+        }
+        if violations.is_empty() {
+            return Some(shares);
+        }
+        // Freeze only the violations pointing in the dominant direction so the
+        // remaining siblings can absorb the difference on the next pass.
+        let net: i32 = violations.iter().map(|(_, delta)| delta).sum();
+        for (index, delta) in violations {
+            if net == 0 || (net > 0) == (delta > 0) {
+                frozen[index] = Some(shares[index] + delta);
+            }
+        }
+    }
+}
+
+fn weighted_shares(total: i32, weights: &[f32], frozen: &[Option<i32>]) -> Vec<i32> {
+    let remaining = (total - frozen.iter().flatten().sum::<i32>()).max(0);
+    let free_weight: f32 = weights
+        .iter()
+        .zip(frozen)
+        .filter(|(_, f)| f.is_none())
+        .map(|(w, _)| w)
+        .sum();
+    weights
+        .iter()
+        .zip(frozen)
+        .map(|(weight, fixed)| match fixed {
+            Some(size) => *size,
+            None if free_weight > 0.0 => (remaining as f32 * (weight / free_weight)) as i32,
+            None => 0,
+        })
+        .collect()
+}
+
+/*
+ * Validates a complete rule set before it is stored by `DefineLayout`.
+ * Docked edges need either `SizeMode::Auto` or a non-negative `fixed_size`
+ * within their min/max limits, each parent may have at most one `Fill` child,
+ * and constraints must not contradict each other (min above max, proportional
+ * siblings on different axes, `Fill` competing with proportional fills,
+ * bounds on docked rules).
+ * Grid cells and flow items need a parent whose rule declares the matching
+ * container, and grid cells must lie inside that grid.
+ */
+pub fn validate_layout_rules(rules: &[LayoutRule]) -> PlatformResult<()> {
+    let mut fill_by_parent: HashMap<Option<ControlId>, Vec<ControlId>> = HashMap::new();
+    let mut proportional_axes_by_parent: HashMap<Option<ControlId>, Vec<(ControlId, LayoutAxis)>> =
+        HashMap::new();
+    let mut container_items_by_parent: HashMap<Option<ControlId>, Vec<&LayoutRule>> =
+        HashMap::new();
+    for rule in rules {
+        validate_constraints(rule)?;
+        match &rule.container {
+            Some(ContainerLayout::Grid(grid)) => validate_grid_definition(rule.control_id, grid)?,
+            Some(ContainerLayout::Flow(flow)) if flow.spacing < 0 || flow.line_spacing < 0 => {
+                return Err(PlatformError::OperationFailed(format!(
+                    "DefineLayout rejected: flow on control {} has negative spacing.",
+                    rule.control_id.raw()
+                )));
+            }
+            Some(ContainerLayout::Flow(_)) | None => {}
+        }
+        match rule.dock_style {
+            DockStyle::Top | DockStyle::Bottom | DockStyle::Left | DockStyle::Right => {
+                match (rule.size_mode, rule.fixed_size) {
+                    (SizeMode::Fixed, None) => {
+                        return Err(PlatformError::OperationFailed(format!(
+                            "DefineLayout rejected: control {} uses {:?} without fixed_size. Docked edges require explicit fixed_size or SizeMode::Auto.",
+                            rule.control_id.raw(),
+                            rule.dock_style
+                        )));
+                    }
+                    (SizeMode::Auto, Some(size)) => {
+                        return Err(PlatformError::OperationFailed(format!(
+                            "DefineLayout rejected: control {} sets fixed_size {size} but uses SizeMode::Auto.",
+                            rule.control_id.raw()
+                        )));
+                    }
+                    _ => {}
+                }
+                if let Some(size) = rule.fixed_size
+                    && size < 0
+                {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "DefineLayout rejected: control {} has negative fixed_size {} for {:?}.",
+                        rule.control_id.raw(),
+                        size,
+                        rule.dock_style
+                    )));
+                }
+                if let Some(size) = rule.fixed_size {
+                    let (name, min, max) = match rule.dock_style {
+                        DockStyle::Top | DockStyle::Bottom => (
+                            "height",
+                            rule.constraints.min_height,
+                            rule.constraints.max_height,
+                        ),
+                        _ => (
+                            "width",
+                            rule.constraints.min_width,
+                            rule.constraints.max_width,
+                        ),
+                    };
+                    if min.is_some_and(|min| size < min) || max.is_some_and(|max| size > max) {
+                        return Err(PlatformError::OperationFailed(format!(
+                            "DefineLayout rejected: control {} has fixed_size {size} outside its {name} limits (min {min:?}, max {max:?}).",
+                            rule.control_id.raw()
+                        )));
+                    }
+                }
+            }
+            DockStyle::ProportionalFill { weight, axis } => {
+                if !weight.is_finite() || weight < 0.0 {
+                    return Err(PlatformError::OperationFailed(format!(
+                        "DefineLayout rejected: control {} has invalid ProportionalFill weight {weight}.",
+                        rule.control_id.raw()
+                    )));
+                }
+                proportional_axes_by_parent
+                    .entry(rule.parent_control_id)
+                    .or_default()
+                    .push((rule.control_id, axis));
+            }
+            DockStyle::GridCell { .. } | DockStyle::FlowItem => {
+                container_items_by_parent
+                    .entry(rule.parent_control_id)
+                    .or_default()
+                    .push(rule);
+            }
+            DockStyle::Fill | DockStyle::None => {}
+        }
+
+        if rule.absolute_bounds.is_some() && rule.dock_style != DockStyle::None {
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: control {} sets absolute_bounds but uses {:?}. Only DockStyle::None is positioned absolutely.",
+                rule.control_id.raw(),
+                rule.dock_style
+            )));
+        }
+
+        if rule.dock_style == DockStyle::Fill {
+            fill_by_parent
+                .entry(rule.parent_control_id)
+                .or_default()
+                .push(rule.control_id);
+        }
+    }
+
+    for (parent_id, fill_controls) in &fill_by_parent {
+        if fill_controls.len() > 1 {
+            let control_ids = fill_controls
+                .iter()
+                .map(|id| id.raw().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: parent {} has multiple DockStyle::Fill children ({control_ids}). CommanDuctUI supports exactly one Fill child per parent.",
+                describe_parent(*parent_id)
+            )));
+        }
+    }
+
+    for (parent_id, siblings) in &proportional_axes_by_parent {
+        if siblings.iter().any(|(_, axis)| *axis != siblings[0].1) {
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: parent {} mixes ProportionalFill axes. All proportional siblings must share one axis.",
+                describe_parent(*parent_id)
+            )));
+        }
+        if fill_by_parent.contains_key(parent_id) {
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: parent {} has both DockStyle::Fill and ProportionalFill children competing for the remaining space.",
+                describe_parent(*parent_id)
+            )));
+        }
+    }
+
+    for (parent_id, items) in &container_items_by_parent {
+        let container = parent_id.and_then(|id| {
+            rules
+                .iter()
+                .find(|r| r.control_id == id)
+                .and_then(|r| r.container.as_ref())
+        });
+        for item in items {
+            match (item.dock_style, container) {
+                (DockStyle::GridCell { .. }, Some(ContainerLayout::Grid(grid))) => {
+                    validate_grid_cell(item, grid)?
+                }
+                (DockStyle::FlowItem, Some(ContainerLayout::Flow(_))) => {}
+                (style, _) => {
+                    let kind = if style == DockStyle::FlowItem {
+                        "flow"
+                    } else {
+                        "grid"
+                    };
+                    return Err(PlatformError::OperationFailed(format!(
+                        "DefineLayout rejected: control {} uses {style:?} but parent {} does not declare a {kind} container.",
+                        item.control_id.raw(),
+                        describe_parent(*parent_id)
+                    )));
+                }
+            }
+        }
+        if fill_by_parent.contains_key(parent_id)
+            || proportional_axes_by_parent.contains_key(parent_id)
+        {
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: parent {} has container items competing with Fill or ProportionalFill children for the remaining space.",
+                describe_parent(*parent_id)
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_grid_definition(control_id: ControlId, grid: &GridDefinition) -> PlatformResult<()> {
+    if grid.rows.is_empty() || grid.columns.is_empty() {
+        return Err(PlatformError::OperationFailed(format!(
+            "DefineLayout rejected: grid on control {} needs at least one row and one column.",
+            control_id.raw()
+        )));
+    }
+    if grid.row_spacing < 0 || grid.column_spacing < 0 {
+        return Err(PlatformError::OperationFailed(format!(
+            "DefineLayout rejected: grid on control {} has negative spacing.",
+            control_id.raw()
+        )));
+    }
+    for track in grid.rows.iter().chain(&grid.columns) {
+        let valid = match *track {
+            GridTrack::Fixed(size) => size >= 0,
+            GridTrack::Auto => true,
+            GridTrack::Star(weight) => weight.is_finite() && weight > 0.0,
+        };
+        if !valid {
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: grid on control {} has invalid track {track:?}.",
+                control_id.raw()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_grid_cell(rule: &LayoutRule, grid: &GridDefinition) -> PlatformResult<()> {
+    let DockStyle::GridCell {
+        row,
+        column,
+        row_span,
+        column_span,
+    } = rule.dock_style
+    else {
+        return Ok(());
+    };
+    if span_range(row, row_span, grid.rows.len()).is_none()
+        || span_range(column, column_span, grid.columns.len()).is_none()
+    {
+        return Err(PlatformError::OperationFailed(format!(
+            "DefineLayout rejected: control {} occupies row {row} (span {row_span}), column {column} (span {column_span}), outside the {}x{} grid of its parent.",
+            rule.control_id.raw(),
+            grid.rows.len(),
+            grid.columns.len()
+        )));
+    }
+    Ok(())
+}
+
+fn validate_constraints(rule: &LayoutRule) -> PlatformResult<()> {
+    let c = &rule.constraints;
+    for (name, value) in [
+        ("min_width", c.min_width),
+        ("max_width", c.max_width),
+        ("min_height", c.min_height),
+        ("max_height", c.max_height),
+    ] {
+        if let Some(value) = value
+            && value < 0
+        {
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: control {} has negative {name} {value}.",
+                rule.control_id.raw()
+            )));
+        }
+    }
+    for (axis, min, max) in [
+        ("width", c.min_width, c.max_width),
+        ("height", c.min_height, c.max_height),
+    ] {
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return Err(PlatformError::OperationFailed(format!(
+                "DefineLayout rejected: control {} has min_{axis} {min} greater than max_{axis} {max}.",
+                rule.control_id.raw()
+            )));
+        }
+    }
+    if let Some(bounds) = rule.absolute_bounds
+        && (bounds.right < bounds.left || bounds.bottom < bounds.top)
+    {
+        return Err(PlatformError::OperationFailed(format!(
+            "DefineLayout rejected: control {} has inverted absolute_bounds {bounds:?}.",
+            rule.control_id.raw()
+        )));
+    }
+    Ok(())
+}
+
+fn describe_parent(parent_id: Option<ControlId>) -> String {
+    parent_id
+        .map(|id| format!("control {}", id.raw()))
+        .unwrap_or_else(|| "main window".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SizeConstraints, SizeMode};
+
+    /*
+     * Unit tests for the pure layout calculation. These tests ensure the
+     * geometry is computed correctly without creating any native windows.
+     */
+
+    #[test]
+    // [CDU-LayoutSystemV1] Docking rules produce deterministic rectangles even without native HWNDs.
+    fn test_calculate_layout_top_and_fill() {
+        // Arrange
+        let rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(1),
+                parent_control_id: None,
+                dock_style: DockStyle::Top,
+                order: 0,
+                fixed_size: Some(20),
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(2),
+                parent_control_id: None,
+                dock_style: DockStyle::Fill,
+                order: 1,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+        let parent_rect = Rect {
+            left: 0,
+            top: 0,
+            right: 100,
+            bottom: 100,
+        };
+        // Act
+        let map = calculate_layout(parent_rect, &rules);
+        // Assert
+        assert_eq!(map.get(&ControlId::new(1)).unwrap().bottom, 20);
+        assert_eq!(map.get(&ControlId::new(2)).unwrap().top, 20);
+        assert_eq!(map.get(&ControlId::new(2)).unwrap().bottom, 100);
+    }
+
+    #[test]
+    fn define_layout_validation_rejects_multiple_fill_siblings() {
+        let rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(10),
+                parent_control_id: Some(ControlId::new(1)),
+                dock_style: DockStyle::Fill,
+                order: 0,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(11),
+                parent_control_id: Some(ControlId::new(1)),
+                dock_style: DockStyle::Fill,
+                order: 1,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+
+        let err =
+            validate_layout_rules(&rules).expect_err("multiple Fill siblings should be rejected");
+        let message = err.to_string();
+        assert!(message.contains("multiple DockStyle::Fill children"));
+        assert!(message.contains("10"));
+        assert!(message.contains("11"));
+    }
+
+    #[test]
+    fn define_layout_validation_allows_one_fill_per_parent() {
+        let rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(20),
+                parent_control_id: Some(ControlId::new(1)),
+                dock_style: DockStyle::Fill,
+                order: 0,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(21),
+                parent_control_id: Some(ControlId::new(2)),
+                dock_style: DockStyle::Fill,
+                order: 0,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(22),
+                parent_control_id: Some(ControlId::new(1)),
+                dock_style: DockStyle::Top,
+                order: 1,
+                fixed_size: Some(10),
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+
+        validate_layout_rules(&rules).expect("one Fill child per parent should be valid");
+    }
+
+    #[test]
+    fn define_layout_validation_rejects_docked_rule_without_fixed_size() {
+        let rules = vec![LayoutRule {
+            control_id: ControlId::new(30),
+            parent_control_id: None,
+            dock_style: DockStyle::Top,
+            order: 0,
+            fixed_size: None,
+            size_mode: SizeMode::Fixed,
+            margin: (0, 0, 0, 0),
+            constraints: SizeConstraints::default(),
+            absolute_bounds: None,
+            preferred_size: None,
+            container: None,
+        }];
+
+        let err = validate_layout_rules(&rules)
+            .expect_err("Top dock without fixed_size should be rejected");
+        assert!(err.to_string().contains("without fixed_size"));
+    }
+
+    #[test]
+    fn define_layout_validation_rejects_negative_fixed_size() {
+        let rules = vec![LayoutRule {
+            control_id: ControlId::new(31),
+            parent_control_id: None,
+            dock_style: DockStyle::Left,
+            order: 0,
+            fixed_size: Some(-1),
+            size_mode: SizeMode::Fixed,
+            margin: (0, 0, 0, 0),
+            constraints: SizeConstraints::default(),
+            absolute_bounds: None,
+            preferred_size: None,
+            container: None,
+        }];
+
+        let err =
+            validate_layout_rules(&rules).expect_err("Negative fixed_size should be rejected");
+        assert!(err.to_string().contains("negative fixed_size"));
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] Proportional fills divide available space using the declarative weights.
+    fn test_calculate_layout_proportional_fill() {
+        // Arrange
+        let rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(1),
+                parent_control_id: None,
+                dock_style: DockStyle::ProportionalFill {
+                    weight: 1.0,
+                    axis: LayoutAxis::Horizontal,
+                },
+                order: 0,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(2),
+                parent_control_id: None,
+                dock_style: DockStyle::ProportionalFill {
+                    weight: 2.0,
+                    axis: LayoutAxis::Horizontal,
+                },
+                order: 1,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+        let parent_rect = Rect {
+            left: 0,
+            top: 0,
+            right: 100,
+            bottom: 20,
+        };
+        // Act
+        let map = calculate_layout(parent_rect, &rules);
+        // Assert
+        let rect1 = map.get(&ControlId::new(1)).unwrap();
+        let rect2 = map.get(&ControlId::new(2)).unwrap();
+        assert_eq!(rect1.right - rect1.left, 33);
+        assert_eq!(rect2.left, 33);
+        assert_eq!(rect2.right - rect2.left, 66);
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1][CDU-Control-PanelV1] Nested panel layouts respect parent-child docking relationships.
+    fn test_calculate_layout_nested_panels() {
+        // Arrange
+        let outer_rule = LayoutRule {
+            control_id: ControlId::new(1),
+            parent_control_id: None,
+            dock_style: DockStyle::Fill,
+            order: 0,
+            fixed_size: None,
+            size_mode: SizeMode::Fixed,
+            margin: (0, 0, 0, 0),
+            constraints: SizeConstraints::default(),
+            absolute_bounds: None,
+            preferred_size: None,
+            container: None,
+        };
+        let inner_rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(2),
+                parent_control_id: Some(ControlId::new(1)),
+                dock_style: DockStyle::Top,
+                order: 0,
+                fixed_size: Some(10),
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(3),
+                parent_control_id: Some(ControlId::new(1)),
+                dock_style: DockStyle::Fill,
+                order: 1,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+        let parent_rect = Rect {
+            left: 0,
+            top: 0,
+            right: 50,
+            bottom: 50,
+        };
+        // Act
+        let outer_map = calculate_layout(parent_rect, std::slice::from_ref(&outer_rule));
+        let outer_rect = outer_map.get(&ControlId::new(1)).unwrap();
+        let inner_map = calculate_layout(
+            Rect {
+                left: 0,
+                top: 0,
+                right: outer_rect.right - outer_rect.left,
+                bottom: outer_rect.bottom - outer_rect.top,
+            },
+            &inner_rules,
+        );
+        // Assert
+        assert_eq!(outer_rect.right - outer_rect.left, 50);
+        assert_eq!(inner_map.get(&ControlId::new(2)).unwrap().bottom, 10);
+        assert_eq!(inner_map.get(&ControlId::new(3)).unwrap().top, 10);
+    }
+
+    #[test]
+    fn test_calculate_layout_header_checkbox_and_fill_non_overlap() {
+        let rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(40),
+                parent_control_id: None,
+                dock_style: DockStyle::Top,
+                order: 0,
+                fixed_size: Some(28),
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 4, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(41),
+                parent_control_id: None,
+                dock_style: DockStyle::Top,
+                order: 1,
+                fixed_size: Some(24),
+                size_mode: SizeMode::Fixed,
+                margin: (4, 0, 4, 4),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(42),
+                parent_control_id: None,
+                dock_style: DockStyle::Fill,
+                order: 2,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+        let parent_rect = Rect {
+            left: 0,
+            top: 0,
+            right: 600,
+            bottom: 400,
+        };
+
+        let map = calculate_layout(parent_rect, &rules);
+        let header_rect = map.get(&ControlId::new(40)).expect("header rect");
+        let checkbox_rect = map.get(&ControlId::new(41)).expect("checkbox rect");
+        let fill_rect = map.get(&ControlId::new(42)).expect("fill rect");
+
+        assert!(header_rect.bottom <= checkbox_rect.top);
+        assert!(checkbox_rect.bottom <= fill_rect.top);
+        assert!(fill_rect.bottom > fill_rect.top);
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1][CDU-Control-PanelV1] The full tree is computed in one call with parent-relative rects.
+    fn compute_layout_resolves_nested_tree() {
+        // Arrange
+        let rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(1),
+                parent_control_id: None,
+                dock_style: DockStyle::Left,
+                order: 0,
+                fixed_size: Some(200),
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(2),
+                parent_control_id: None,
+                dock_style: DockStyle::Fill,
+                order: 1,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(3),
+                parent_control_id: Some(ControlId::new(2)),
+                dock_style: DockStyle::Bottom,
+                order: 0,
+                fixed_size: Some(30),
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(4),
+                parent_control_id: Some(ControlId::new(2)),
+                dock_style: DockStyle::Fill,
+                order: 1,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (5, 5, 5, 5),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+
+        // Act
+        let map = compute_layout(800, 600, &rules);
+
+        // Assert
+        assert_eq!(map[&ControlId::new(1)], Rect::new(0, 0, 200, 600));
+        assert_eq!(map[&ControlId::new(2)], Rect::new(200, 0, 800, 600));
+        assert_eq!(map[&ControlId::new(3)], Rect::new(0, 570, 600, 600));
+        assert_eq!(map[&ControlId::new(4)], Rect::new(5, 5, 595, 565));
+    }
+
+    #[test]
+    fn compute_layout_ignores_rules_unreachable_from_window() {
+        // Arrange: control 5 hangs off a parent that has no rule of its own,
+        // and control 6 lists itself as parent.
+        let rules = vec![
+            LayoutRule {
+                control_id: ControlId::new(5),
+                parent_control_id: Some(ControlId::new(99)),
+                dock_style: DockStyle::Fill,
+                order: 0,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(6),
+                parent_control_id: None,
+                dock_style: DockStyle::Fill,
+                order: 0,
+                fixed_size: None,
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+            LayoutRule {
+                control_id: ControlId::new(6),
+                parent_control_id: Some(ControlId::new(6)),
+                dock_style: DockStyle::Top,
+                order: 0,
+                fixed_size: Some(10),
+                size_mode: SizeMode::Fixed,
+                margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
+            },
+        ];
+
+        // Act
+        let map = compute_layout(100, 100, &rules);
+
+        // Assert
+        assert!(!map.contains_key(&ControlId::new(5)));
+        assert_eq!(map[&ControlId::new(6)], Rect::from_size(100, 100));
+    }
+
+    fn proportional(id: i32, weight: f32, axis: LayoutAxis) -> LayoutRule {
+        LayoutRule::new(
+            ControlId::new(id),
+            DockStyle::ProportionalFill { weight, axis },
+        )
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] Vertical proportional fills split the height instead of the width.
+    fn proportional_fill_vertical_axis_splits_height() {
+        // Arrange
+        let rules = vec![
+            proportional(1, 1.0, LayoutAxis::Vertical),
+            LayoutRule {
+                order: 1,
+                ..proportional(2, 3.0, LayoutAxis::Vertical)
+            },
+        ];
+
+        // Act
+        let map = calculate_layout(Rect::from_size(50, 200), &rules);
+
+        // Assert
+        assert_eq!(map[&ControlId::new(1)], Rect::new(0, 0, 50, 50));
+        assert_eq!(map[&ControlId::new(2)], Rect::new(0, 50, 50, 200));
+    }
+
+    #[test]
+    fn proportional_fill_min_width_prevents_collapse() {
+        // Arrange: the left pane would get 10px by weight but needs 120px.
+        let mut left = proportional(1, 1.0, LayoutAxis::Horizontal);
+        left.constraints.min_width = Some(120);
+        let right = LayoutRule {
+            order: 1,
+            ..proportional(2, 9.0, LayoutAxis::Horizontal)
+        };
+
+        // Act
+        let map = calculate_layout(Rect::from_size(100, 40), &[left, right]);
+
+        // Assert
+        assert_eq!(map[&ControlId::new(1)].width(), 120);
+        assert_eq!(map[&ControlId::new(2)].left, 120);
+        assert_eq!(map[&ControlId::new(2)].width(), 0);
+    }
+
+    #[test]
+    fn proportional_fill_max_width_redistributes_remainder() {
+        // Arrange
+        let mut capped = proportional(1, 1.0, LayoutAxis::Horizontal);
+        capped.constraints.max_width = Some(100);
+        capped.margin = (0, 5, 0, 5);
+        let rules = vec![
+            capped,
+            LayoutRule {
+                order: 1,
+                ..proportional(2, 1.0, LayoutAxis::Horizontal)
+            },
+            LayoutRule {
+                order: 2,
+                ..proportional(3, 1.0, LayoutAxis::Horizontal)
+            },
+        ];
+
+        // Act
+        let map = calculate_layout(Rect::from_size(610, 10), &rules);
+
+        // Assert: 110px (100 + margins) frozen, the other two share 500px.
+        assert_eq!(map[&ControlId::new(1)], Rect::new(5, 0, 105, 10));
+        assert_eq!(map[&ControlId::new(2)], Rect::new(110, 0, 360, 10));
+        assert_eq!(map[&ControlId::new(3)], Rect::new(360, 0, 610, 10));
+    }
+
+    #[test]
+    fn docked_and_fill_rules_clamp_to_constraints() {
+        // Arrange
+        let mut top = LayoutRule::new(ControlId::new(1), DockStyle::Top);
+        top.fixed_size = Some(30);
+        top.constraints.max_width = Some(200);
+        let mut fill = LayoutRule::new(ControlId::new(2), DockStyle::Fill);
+        fill.order = 1;
+        fill.constraints.max_height = Some(100);
+
+        // Act
+        let map = calculate_layout(Rect::from_size(500, 400), &[top, fill]);
+
+        // Assert
+        assert_eq!(map[&ControlId::new(1)], Rect::new(0, 0, 200, 30));
+        assert_eq!(map[&ControlId::new(2)], Rect::new(0, 30, 500, 130));
+    }
+
+    #[test]
+    fn dock_none_uses_absolute_bounds_or_stays_unmanaged() {
+        // Arrange
+        let mut placed = LayoutRule::new(ControlId::new(1), DockStyle::None);
+        placed.absolute_bounds = Some(Rect::new(10, 20, 110, 45));
+        let unmanaged = LayoutRule::new(ControlId::new(2), DockStyle::None);
+        let fill = LayoutRule::new(ControlId::new(3), DockStyle::Fill);
+
+        // Act
+        let map = calculate_layout(Rect::new(100, 100, 400, 400), &[placed, unmanaged, fill]);
+
+        // Assert: absolute placement is relative to the parent and does not consume space.
+        assert_eq!(map[&ControlId::new(1)], Rect::new(110, 120, 210, 145));
+        assert!(!map.contains_key(&ControlId::new(2)));
+        assert_eq!(map[&ControlId::new(3)], Rect::new(100, 100, 400, 400));
+    }
+
+    #[test]
+    fn validation_rejects_contradictory_constraints() {
+        // Arrange
+        let mut min_above_max = LayoutRule::new(ControlId::new(1), DockStyle::Fill);
+        min_above_max.constraints.min_width = Some(50);
+        min_above_max.constraints.max_width = Some(40);
+
+        let mut fixed_outside_limits = LayoutRule::new(ControlId::new(2), DockStyle::Left);
+        fixed_outside_limits.fixed_size = Some(20);
+        fixed_outside_limits.constraints.min_width = Some(30);
+
+        let mut bounds_on_docked = LayoutRule::new(ControlId::new(3), DockStyle::Fill);
+        bounds_on_docked.absolute_bounds = Some(Rect::from_size(10, 10));
+
+        let mixed_axes = vec![
+            proportional(4, 1.0, LayoutAxis::Horizontal),
+            proportional(5, 1.0, LayoutAxis::Vertical),
+        ];
+        let fill_with_proportional = vec![
+            proportional(6, 1.0, LayoutAxis::Horizontal),
+            LayoutRule::new(ControlId::new(7), DockStyle::Fill),
+        ];
+        let negative_weight = vec![proportional(8, -1.0, LayoutAxis::Horizontal)];
+
+        // Act
+        let results = [
+            validate_layout_rules(&[min_above_max]),
+            validate_layout_rules(&[fixed_outside_limits]),
+            validate_layout_rules(&[bounds_on_docked]),
+            validate_layout_rules(&mixed_axes),
+            validate_layout_rules(&fill_with_proportional),
+            validate_layout_rules(&negative_weight),
+        ];
+
+        // Assert
+        let messages: Vec<String> = results
+            .into_iter()
+            .map(|r| {
+                r.expect_err("contradictory rules must be rejected")
+                    .to_string()
+            })
+            .collect();
+        assert!(messages[0].contains("greater than max_width"));
+        assert!(messages[1].contains("outside its width limits"));
+        assert!(messages[2].contains("absolute_bounds"));
+        assert!(messages[3].contains("mixes ProportionalFill axes"));
+        assert!(messages[4].contains("both DockStyle::Fill and ProportionalFill"));
+        assert!(messages[5].contains("invalid ProportionalFill weight"));
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] Fill and ProportionalFill under one parent used to overlap; now rejected.
+    fn validation_rejects_fill_next_to_proportional_fill() {
+        // Arrange
+        let mut fill = LayoutRule::new(ControlId::new(3), DockStyle::Fill);
+        fill.order = 2;
+        let top_level = vec![
+            proportional(1, 1.0, LayoutAxis::Horizontal),
+            proportional(2, 2.0, LayoutAxis::Horizontal),
+            fill.clone(),
+        ];
+        let nested = vec![
+            LayoutRule::new(ControlId::new(10), DockStyle::Fill),
+            LayoutRule {
+                parent_control_id: Some(ControlId::new(10)),
+                ..proportional(11, 1.0, LayoutAxis::Vertical)
+            },
+            LayoutRule {
+                parent_control_id: Some(ControlId::new(10)),
+                ..fill
+            },
+        ];
+
+        // Act
+        let top_level_error = validate_layout_rules(&top_level)
+            .expect_err("Fill next to ProportionalFill must be rejected")
+            .to_string();
+        let nested_error = validate_layout_rules(&nested)
+            .expect_err("Fill next to ProportionalFill must be rejected in panels too")
+            .to_string();
+
+        // Assert
+        assert!(top_level_error.contains("both DockStyle::Fill and ProportionalFill"));
+        assert!(nested_error.contains("both DockStyle::Fill and ProportionalFill"));
+        assert!(nested_error.contains("10"));
+    }
+
+    fn grid_panel(id: i32, rows: Vec<GridTrack>, columns: Vec<GridTrack>) -> LayoutRule {
+        LayoutRule {
+            container: Some(ContainerLayout::Grid(GridDefinition {
+                rows,
+                columns,
+                row_spacing: 0,
+                column_spacing: 0,
+            })),
+            ..LayoutRule::new(ControlId::new(id), DockStyle::Fill)
+        }
+    }
+
+    fn cell(id: i32, parent: i32, row: u32, column: u32) -> LayoutRule {
+        LayoutRule {
+            parent_control_id: Some(ControlId::new(parent)),
+            ..LayoutRule::new(
+                ControlId::new(id),
+                DockStyle::GridCell {
+                    row,
+                    column,
+                    row_span: 1,
+                    column_span: 1,
+                },
+            )
+        }
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] Grid tracks resolve fixed, auto and star sizes, and spans cover the spacing.
+    fn grid_resolves_fixed_auto_and_star_tracks() {
+        // Arrange
+        let mut panel = grid_panel(
+            1,
+            vec![GridTrack::Auto, GridTrack::Star(1.0)],
+            vec![
+                GridTrack::Fixed(40),
+                GridTrack::Star(1.0),
+                GridTrack::Star(2.0),
+            ],
+        );
+        if let Some(ContainerLayout::Grid(grid)) = &mut panel.container {
+            grid.column_spacing = 5;
+        }
+        let label = LayoutRule {
+            preferred_size: Some((30, 24)),
+            ..cell(10, 1, 0, 0)
+        };
+        let wide = LayoutRule {
+            dock_style: DockStyle::GridCell {
+                row: 1,
+                column: 0,
+                row_span: 1,
+                column_span: 3,
+            },
+            ..cell(11, 1, 0, 0)
+        };
+        let star = cell(12, 1, 0, 2);
+        let rules = vec![panel, label, wide, star];
+
+        // Act
+        let map = compute_layout(250, 100, &rules);
+
+        // Assert
+        assert_eq!(map[&ControlId::new(10)], Rect::new(0, 0, 40, 24));
+        assert_eq!(map[&ControlId::new(11)], Rect::new(0, 24, 250, 100));
+        // 250 - 40 fixed - 10 spacing leaves 200 for the stars: 66 and 134.
+        assert_eq!(map[&ControlId::new(12)], Rect::new(116, 0, 250, 24));
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] A grid shares its parent with docked edges and uses the space they leave.
+    fn grid_cells_are_placed_after_docked_siblings() {
+        // Arrange
+        let rules = vec![
+            grid_panel(1, vec![GridTrack::Star(1.0)], vec![GridTrack::Star(1.0)]),
+            LayoutRule {
+                parent_control_id: Some(ControlId::new(1)),
+                fixed_size: Some(20),
+                ..LayoutRule::new(ControlId::new(2), DockStyle::Top)
+            },
+            LayoutRule {
+                margin: (1, 2, 3, 4),
+                constraints: SizeConstraints {
+                    max_width: Some(50),
+                    ..SizeConstraints::default()
+                },
+                ..cell(3, 1, 0, 0)
+            },
+        ];
+
+        // Act
+        let map = compute_layout(100, 100, &rules);
+
+        // Assert
+        assert_eq!(map[&ControlId::new(2)], Rect::new(0, 0, 100, 20));
+        assert_eq!(map[&ControlId::new(3)], Rect::new(4, 21, 54, 97));
+    }
+
+    #[test]
+    fn validation_rejects_invalid_grid_cells() {
+        // Arrange
+        let panel = grid_panel(1, vec![GridTrack::Auto], vec![GridTrack::Star(1.0)]);
+        let out_of_range = vec![panel.clone(), cell(2, 1, 1, 0)];
+        let zero_span = vec![
+            panel.clone(),
+            LayoutRule {
+                dock_style: DockStyle::GridCell {
+                    row: 0,
+                    column: 0,
+                    row_span: 0,
+                    column_span: 1,
+                },
+                ..cell(3, 1, 0, 0)
+            },
+        ];
+        let no_grid_parent = vec![
+            LayoutRule::new(ControlId::new(4), DockStyle::Fill),
+            cell(5, 4, 0, 0),
+        ];
+        let with_fill = vec![
+            panel.clone(),
+            cell(6, 1, 0, 0),
+            LayoutRule {
+                parent_control_id: Some(ControlId::new(1)),
+                ..LayoutRule::new(ControlId::new(7), DockStyle::Fill)
+            },
+        ];
+        let bad_track = vec![grid_panel(
+            8,
+            vec![GridTrack::Fixed(-1)],
+            vec![GridTrack::Auto],
+        )];
+
+        // Act
+        let results = [
+            validate_layout_rules(&out_of_range),
+            validate_layout_rules(&zero_span),
+            validate_layout_rules(&no_grid_parent),
+            validate_layout_rules(&with_fill),
+            validate_layout_rules(&bad_track),
+        ];
+
+        // Assert
+        let messages: Vec<String> = results
+            .into_iter()
+            .map(|r| r.expect_err("invalid grid must be rejected").to_string())
+            .collect();
+        assert!(messages[0].contains("outside the 1x1 grid"));
+        assert!(messages[1].contains("span 0"));
+        assert!(messages[2].contains("does not declare a grid container"));
+        assert!(messages[3].contains("container items competing"));
+        assert!(messages[4].contains("invalid track Fixed(-1)"));
+        assert!(validate_layout_rules(&[panel, cell(9, 1, 0, 0)]).is_ok());
+    }
+
+    fn flow_panel(id: i32, flow: FlowLayout) -> LayoutRule {
+        LayoutRule {
+            container: Some(ContainerLayout::Flow(flow)),
+            ..LayoutRule::new(ControlId::new(id), DockStyle::Fill)
+        }
+    }
+
+    fn flow_item(id: i32, parent: i32, order: u32, size: (i32, i32)) -> LayoutRule {
+        LayoutRule {
+            parent_control_id: Some(ControlId::new(parent)),
+            order,
+            preferred_size: Some(size),
+            ..LayoutRule::new(ControlId::new(id), DockStyle::FlowItem)
+        }
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] A wrapping horizontal flow reflows items onto new lines when the window narrows.
+    fn flow_wraps_items_and_aligns_lines() {
+        // Arrange
+        let rules = vec![
+            flow_panel(
+                1,
+                FlowLayout {
+                    spacing: 10,
+                    line_spacing: 4,
+                    alignment: FlowAlignment::Center,
+                    cross_alignment: FlowAlignment::Center,
+                    wrap: true,
+                    ..FlowLayout::default()
+                },
+            ),
+            flow_item(2, 1, 0, (40, 20)),
+            flow_item(3, 1, 1, (40, 30)),
+            flow_item(4, 1, 2, (40, 20)),
+        ];
+
+        // Act
+        let wide = compute_layout(200, 100, &rules);
+        let narrow = compute_layout(100, 100, &rules);
+
+        // Assert
+        // Wide: one line of 140 px centred in 200 px, items centred in the 30 px line.
+        assert_eq!(wide[&ControlId::new(2)], Rect::new(30, 5, 70, 25));
+        assert_eq!(wide[&ControlId::new(3)], Rect::new(80, 0, 120, 30));
+        assert_eq!(wide[&ControlId::new(4)], Rect::new(130, 5, 170, 25));
+        // Narrow: the third item moves to a second line below the first.
+        assert_eq!(narrow[&ControlId::new(2)], Rect::new(5, 5, 45, 25));
+        assert_eq!(narrow[&ControlId::new(3)], Rect::new(55, 0, 95, 30));
+        assert_eq!(narrow[&ControlId::new(4)], Rect::new(30, 34, 70, 54));
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] A vertical stack without wrapping keeps one column and honours margins.
+    fn flow_vertical_stack_without_wrap() {
+        // Arrange
+        let rules = vec![
+            flow_panel(
+                1,
+                FlowLayout {
+                    axis: LayoutAxis::Vertical,
+                    spacing: 2,
+                    alignment: FlowAlignment::End,
+                    ..FlowLayout::default()
+                },
+            ),
+            LayoutRule {
+                margin: (1, 1, 1, 1),
+                ..flow_item(2, 1, 0, (30, 20))
+            },
+            flow_item(3, 1, 1, (50, 60)),
+        ];
+
+        // Act
+        let map = compute_layout(100, 50, &rules);
+
+        // Assert
+        // 84 px of content overflows 50 px, so End alignment falls back to the start.
+        assert_eq!(map[&ControlId::new(2)], Rect::new(1, 1, 31, 21));
+        assert_eq!(map[&ControlId::new(3)], Rect::new(0, 24, 50, 84));
+    }
+
+    #[test]
+    fn validation_rejects_flow_items_without_flow_parent() {
+        // Arrange
+        let grid_parent = vec![
+            grid_panel(1, vec![GridTrack::Auto], vec![GridTrack::Auto]),
+            flow_item(2, 1, 0, (10, 10)),
+        ];
+        let negative_spacing = vec![flow_panel(
+            3,
+            FlowLayout {
+                spacing: -1,
+                ..FlowLayout::default()
+            },
+        )];
+
+        // Act
+        let grid_err = validate_layout_rules(&grid_parent).expect_err("flow item under grid");
+        let spacing_err =
+            validate_layout_rules(&negative_spacing).expect_err("negative flow spacing");
+
+        // Assert
+        assert!(
+            grid_err
+                .to_string()
+                .contains("does not declare a flow container")
+        );
+        assert!(spacing_err.to_string().contains("negative spacing"));
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] Auto-sized docked edges take their measured extent, clamped by constraints.
+    fn auto_sized_docked_edges_use_preferred_size() {
+        // Arrange
+        let rules = vec![
+            LayoutRule {
+                size_mode: SizeMode::Auto,
+                preferred_size: Some((80, 18)),
+                ..LayoutRule::new(ControlId::new(1), DockStyle::Top)
+            },
+            LayoutRule {
+                order: 1,
+                size_mode: SizeMode::Auto,
+                preferred_size: Some((80, 18)),
+                constraints: SizeConstraints {
+                    max_width: Some(60),
+                    ..SizeConstraints::default()
+                },
+                ..LayoutRule::new(ControlId::new(2), DockStyle::Left)
+            },
+        ];
+
+        // Act
+        let map = calculate_layout(Rect::from_size(200, 100), &rules);
+
+        // Assert
+        assert_eq!(map[&ControlId::new(1)], Rect::new(0, 0, 200, 18));
+        assert_eq!(map[&ControlId::new(2)], Rect::new(0, 18, 60, 100));
+        assert!(validate_layout_rules(&rules).is_ok());
+    }
+
+    #[test]
+    fn validation_rejects_fixed_size_on_auto_sized_rule() {
+        // Arrange
+        let rules = vec![LayoutRule {
+            size_mode: SizeMode::Auto,
+            fixed_size: Some(20),
+            ..LayoutRule::new(ControlId::new(1), DockStyle::Top)
+        }];
+
+        // Act
+        let err = validate_layout_rules(&rules).expect_err("fixed_size conflicts with Auto");
+
+        // Assert
+        assert!(err.to_string().contains("but uses SizeMode::Auto"));
+    }
+}
@@ -7,33 +7,69 @@
  * The library exposes only the safe API surface (`PlatformInterface`, `PlatformCommand`,
  * etc.) while keeping Win32 internals scoped to the crate. Conditional compilation keeps
  * portable pieces (types, styling primitives) available on every platform so non-Windows
- * builds can still compile and test logic that depends on these types.
+ * builds can still compile and test logic that depends on these types. The
+ * `headless` backend executes the same commands in memory on every platform.
+ * Control logic both backends need, such as slider snapping, numeric parsing
+ * and status bar or toolbar layout, lives in portable modules for the same
+ * reason: the Win32 and headless backends then behave identically.
  */
 #[cfg(target_os = "windows")]
 pub mod app;
+pub mod builder;
+pub mod color;
 #[cfg(target_os = "windows")]
 pub(crate) mod command_executor;
 #[cfg(target_os = "windows")]
 pub(crate) mod controls;
+#[cfg(feature = "description")]
+pub mod description;
+pub mod dpi;
 pub mod error;
+pub mod headless;
+pub mod image;
+pub mod layout;
+pub mod measure;
+pub mod numeric;
+#[cfg(feature = "recording")]
+pub mod recording;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod slider;
+pub mod status_bar;
 pub(crate) mod styling_primitives;
-#[cfg(not(target_os = "windows"))]
-pub(crate) mod styling_stub;
 #[cfg(target_os = "windows")]
 pub(crate) mod styling_windows;
-#[cfg(not(target_os = "windows"))]
-pub(crate) use styling_stub as styling;
 #[cfg(target_os = "windows")]
 pub(crate) use styling_windows as styling;
+pub mod theme;
+#[cfg(feature = "theme-file")]
+pub mod theme_file;
+pub mod toolbar;
+pub mod tooltip;
 pub mod types;
 #[cfg(target_os = "windows")]
 pub(crate) mod window_common;
 
 #[cfg(target_os = "windows")]
 pub use app::PlatformInterface;
+pub use dpi::DpiScale;
 pub use error::Result as PlatformResult;
-pub use styling_primitives::{Color, ControlStyle, FontDescription, FontWeight, StyleId};
+pub use headless::HeadlessPlatform;
+pub use image::{ImageRegistry, RgbaImage};
+pub use layout::Rect;
+pub use measure::{MeasureKind, TextMetrics};
+pub use numeric::NumericSpec;
+pub use slider::{SliderOrientation, SliderRange};
+pub use status_bar::{SegmentWidth, StatusSegment};
+pub use styling_primitives::{
+    Color, ControlStyle, FontDescription, FontWeight, InteractionState, StateColors, StyleId,
+    resolve_style,
+};
+pub use theme::Theme;
+pub use toolbar::{ToolbarItem, ToolbarItemKind};
+pub use tooltip::TooltipOptions;
 pub use types::{
-    AppEvent, ChartDataPacket, ChartLineData, CheckState, MessageSeverity, PlatformCommand,
-    PlatformEventHandler, TreeItemDescriptor, TreeItemId, UiStateProvider, WindowConfig, WindowId,
+    AppEvent, ChartDataPacket, ChartLineData, CheckState, ColumnAlignment, ImageId, ListViewColumn,
+    ListViewDataProvider, MessageSeverity, PlatformCommand, PlatformEventHandler, SortDirection,
+    TreeItemDescriptor, TreeItemId, UiStateProvider, WindowConfig, WindowId,
 };
@@ -0,0 +1,239 @@
+/*
+ * Platform-neutral intrinsic size measurement. A control's preferred size is
+ * its text extent, measured through a `TextMetrics` implementation, plus the
+ * chrome its kind draws around the text (button padding, the check box glyph,
+ * the toggle pill, the combo box arrow, the spin buttons, toolbar icons).
+ * Backends use it to fill in `LayoutRule::preferred_size` for rules with
+ * `SizeMode::Auto`, so labels and buttons follow their font instead of
+ * hand-tuned pixel heights.
+ *
+ * The chrome constants are in DIPs (pixels at 96 DPI), mirror the geometry
+ * the Win32 control handlers draw with and are scaled to the window's DPI.
+ * [CDU-LayoutSystemV1]
+ */
+
+use crate::dpi::DpiScale;
+use crate::styling_primitives::{FontDescription, FontWeight};
+use crate::toolbar::ICON_SIZE as TOOLBAR_ICON_SIZE;
+
+/// Point size assumed when a style does not specify one.
+pub const DEFAULT_FONT_POINT_SIZE: i32 = 9;
+
+const BUTTON_PADDING_X: i32 = 12;
+const BUTTON_PADDING_Y: i32 = 5;
+const BUTTON_MIN_HEIGHT: i32 = 23;
+const CHECK_GLYPH_SIZE: i32 = 13;
+const CHECK_LABEL_GAP: i32 = 6;
+const CHECK_PADDING_Y: i32 = 2;
+const TOGGLE_PILL_MARGIN_LEFT: i32 = 6;
+const TOGGLE_PILL_W: i32 = 28;
+const TOGGLE_PILL_H: i32 = 16;
+const TOGGLE_LABEL_GAP: i32 = 8;
+const INPUT_PADDING_X: i32 = 4;
+const INPUT_PADDING_Y: i32 = 4;
+const COMBO_ARROW_W: i32 = 17;
+/// Width of the up/down buttons beside a numeric input's edit field.
+pub const SPIN_BUTTONS_W: i32 = 16;
+const PROGRESS_BAR_HEIGHT: i32 = 16;
+const STATUS_BAR_PADDING_Y: i32 = 3;
+const TOOLBAR_PADDING_Y: i32 = 5;
+
+/*
+ * Measures text in a given font. The Win32 backend implements this with GDI;
+ * tests can supply a stub with predictable numbers.
+ */
+pub trait TextMetrics {
+    /// Returns the (width, height) in pixels of `text`, one line per `'\n'`.
+    /// An empty string still has the height of one line.
+    fn measure_text(&self, text: &str, font: &FontDescription) -> (i32, i32);
+}
+
+/// The control kinds that have an intrinsic size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeasureKind {
+    Label,
+    Button,
+    CheckBox,
+    RadioButton,
+    ToggleSwitch,
+    Input,
+    NumericInput,
+    ComboBox,
+    ProgressBar,
+    StatusBar,
+    Toolbar,
+}
+
+/*
+ * Computes the preferred (width, height) of a control in pixels at `scale`,
+ * excluding margins. `metrics` must measure at the same scale; the chrome is
+ * scaled to it. For combo boxes `text` holds the items separated by
+ * newlines; the widest item decides the width and the closed box is one line
+ * tall.
+ */
+pub fn preferred_size(
+    kind: MeasureKind,
+    text: &str,
+    font: &FontDescription,
+    metrics: &dyn TextMetrics,
+    scale: DpiScale,
+) -> (i32, i32) {
+    let (text_width, text_height) = metrics.measure_text(text, font);
+    let px = |dips: i32| scale.scale(dips);
+    match kind {
+        MeasureKind::Label => (text_width, text_height),
+        MeasureKind::Button => (
+            text_width + px(2 * BUTTON_PADDING_X),
+            (text_height + px(2 * BUTTON_PADDING_Y)).max(px(BUTTON_MIN_HEIGHT)),
+        ),
+        MeasureKind::CheckBox | MeasureKind::RadioButton => (
+            px(CHECK_GLYPH_SIZE + CHECK_LABEL_GAP) + text_width,
+            text_height.max(px(CHECK_GLYPH_SIZE)) + px(2 * CHECK_PADDING_Y),
+        ),
+        MeasureKind::ToggleSwitch => (
+            px(TOGGLE_PILL_MARGIN_LEFT + TOGGLE_PILL_W + TOGGLE_LABEL_GAP) + text_width,
+            text_height.max(px(TOGGLE_PILL_H)) + px(2 * CHECK_PADDING_Y),
+        ),
+        MeasureKind::Input => (
+            text_width + px(2 * INPUT_PADDING_X),
+            text_height + px(2 * INPUT_PADDING_Y),
+        ),
+        MeasureKind::NumericInput => (
+            text_width + px(2 * INPUT_PADDING_X + SPIN_BUTTONS_W),
+            text_height + px(2 * INPUT_PADDING_Y),
+        ),
+        MeasureKind::ComboBox => {
+            let (_, line_height) = metrics.measure_text("", font);
+            (
+                text_width + px(2 * INPUT_PADDING_X + COMBO_ARROW_W),
+                line_height + px(2 * INPUT_PADDING_Y),
+            )
+        }
+        MeasureKind::ProgressBar => (0, px(PROGRESS_BAR_HEIGHT)),
+        // Segments split whatever width the layout gives; only the height is intrinsic.
+        MeasureKind::StatusBar => {
+            let (_, line_height) = metrics.measure_text("", font);
+            (0, line_height + px(2 * STATUS_BAR_PADDING_Y))
+        }
+        // Like the status bar, the width comes from the layout; buttons fit a line or an icon.
+        MeasureKind::Toolbar => {
+            let (_, line_height) = metrics.measure_text("", font);
+            (
+                0,
+                line_height.max(px(TOOLBAR_ICON_SIZE)) + px(2 * TOOLBAR_PADDING_Y),
+            )
+        }
+    }
+}
+
+/*
+ * Font-independent estimate used when no real text renderer exists, such as
+ * in the headless backend: every character is half the font's pixel size wide
+ * (a little more for bold) and a line is 1.25 times the pixel size tall.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ApproximateTextMetrics {
+    pub scale: DpiScale,
+}
+
+impl TextMetrics for ApproximateTextMetrics {
+    fn measure_text(&self, text: &str, font: &FontDescription) -> (i32, i32) {
+        let point_size = font.size.unwrap_or(DEFAULT_FONT_POINT_SIZE);
+        let pixel_size = self.scale.font_pixel_size(point_size);
+        let char_width = match font.weight {
+            Some(FontWeight::Bold) => pixel_size as f32 * 0.55,
+            _ => pixel_size as f32 * 0.5,
+        };
+        let line_height = (pixel_size as f32 * 1.25).ceil() as i32;
+        let lines: Vec<&str> = text.split('\n').collect();
+        let widest = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        (
+            (widest as f32 * char_width).ceil() as i32,
+            line_height * lines.len() as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Ten pixels per character and twenty per line, regardless of font.
+    struct FixedMetrics;
+
+    impl TextMetrics for FixedMetrics {
+        fn measure_text(&self, text: &str, _font: &FontDescription) -> (i32, i32) {
+            let lines: Vec<&str> = text.split('\n').collect();
+            let widest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+            (widest as i32 * 10, lines.len() as i32 * 20)
+        }
+    }
+
+    #[test]
+    // [CDU-LayoutSystemV1] Preferred sizes add each kind's chrome to the measured text.
+    fn preferred_size_adds_chrome_per_kind() {
+        // Arrange
+        let font = FontDescription::default();
+
+        // Act
+        let size = |kind, text| preferred_size(kind, text, &font, &FixedMetrics, DpiScale::DEFAULT);
+
+        // Assert
+        assert_eq!(size(MeasureKind::Label, "Name"), (40, 20));
+        assert_eq!(size(MeasureKind::Button, "Go"), (44, 30));
+        assert_eq!(size(MeasureKind::CheckBox, "Wrap"), (59, 24));
+        assert_eq!(size(MeasureKind::ToggleSwitch, ""), (42, 24));
+        assert_eq!(size(MeasureKind::ComboBox, "a\nlonger"), (85, 28));
+        assert_eq!(size(MeasureKind::NumericInput, "42"), (44, 28));
+        assert_eq!(size(MeasureKind::ProgressBar, "ignored"), (0, 16));
+        assert_eq!(size(MeasureKind::StatusBar, "two\nlines"), (0, 26));
+        assert_eq!(size(MeasureKind::Toolbar, ""), (0, 30));
+    }
+
+    #[test]
+    // [CDU-DpiAwarenessV1] The chrome around the text is scaled to the window's DPI.
+    fn preferred_size_scales_chrome_with_dpi() {
+        // Arrange
+        let font = FontDescription::default();
+        let scale = DpiScale::new(144);
+
+        // Act
+        let size = |kind, text| preferred_size(kind, text, &font, &FixedMetrics, scale);
+
+        // Assert
+        assert_eq!(size(MeasureKind::Button, "Go"), (56, 35));
+        assert_eq!(size(MeasureKind::CheckBox, "Wrap"), (69, 26));
+        assert_eq!(size(MeasureKind::ProgressBar, ""), (0, 24));
+    }
+
+    #[test]
+    fn approximate_metrics_follow_font_size_weight_and_dpi() {
+        // Arrange
+        let regular = FontDescription {
+            size: Some(12),
+            ..FontDescription::default()
+        };
+        let bold = FontDescription {
+            weight: Some(FontWeight::Bold),
+            ..regular.clone()
+        };
+        let high_dpi = ApproximateTextMetrics {
+            scale: DpiScale::new(192),
+        };
+
+        // Act
+        let base = ApproximateTextMetrics::default().measure_text("abcd\nab", &regular);
+        let heavier = ApproximateTextMetrics::default().measure_text("abcd", &bold);
+        let scaled = high_dpi.measure_text("abcd", &regular);
+
+        // Assert
+        assert_eq!(base, (32, 40));
+        assert_eq!(heavier, (36, 20));
+        assert_eq!(scaled, (64, 40));
+    }
+}
@@ -0,0 +1,253 @@
+/*
+ * Parsing, formatting and stepping of numeric input controls: which text is
+ * accepted, how values are shown and how far a step moves them. A
+ * `NumericSpec` describes the allowed values: a `min..=max` range, the amount
+ * the spin buttons, arrow keys and mouse wheel add per step, the number of
+ * decimals (zero for integer inputs) and an optional unit suffix that is
+ * shown after the number and may be omitted when typing.
+ * [CDU-Control-NumericInputV1]
+ */
+
+use crate::error::{PlatformError, Result as PlatformResult};
+
+/// Most decimals a numeric input can show; beyond this `f64` stops being exact.
+pub const MAX_DECIMALS: u8 = 10;
+
+/// The values a numeric input accepts and how it displays them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumericSpec {
+    pub min: f64,
+    pub max: f64,
+    #[cfg_attr(feature = "serde", serde(default = "NumericSpec::default_step"))]
+    pub step: f64,
+    /// Digits after the decimal point; 0 makes an integer input.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decimals: u8,
+    /// Shown after the number, separated by a space (e.g. `"ms"`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unit: Option<String>,
+}
+
+impl NumericSpec {
+    /// An integer input over `min..=max` stepping by 1.
+    pub fn new(min: f64, max: f64) -> Self {
+        Self {
+            min,
+            max,
+            step: Self::default_step(),
+            decimals: 0,
+            unit: None,
+        }
+    }
+
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn decimals(mut self, decimals: u8) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    fn default_step() -> f64 {
+        1.0
+    }
+
+    /// Rejects non-finite or inverted bounds, non-positive steps and too many decimals.
+    pub fn validate(&self) -> PlatformResult<()> {
+        if !self.min.is_finite() || !self.max.is_finite() || self.max < self.min {
+            return Err(PlatformError::OperationFailed(format!(
+                "Numeric range {}..={} is not a finite, ordered range",
+                self.min, self.max
+            )));
+        }
+        if !(self.step.is_finite() && self.step > 0.0) {
+            return Err(PlatformError::OperationFailed(format!(
+                "Numeric step must be positive, got {}",
+                self.step
+            )));
+        }
+        if self.decimals > MAX_DECIMALS {
+            return Err(PlatformError::OperationFailed(format!(
+                "Numeric inputs show at most {MAX_DECIMALS} decimals, got {}",
+                self.decimals
+            )));
+        }
+        Ok(())
+    }
+
+    fn round(&self, value: f64) -> f64 {
+        let factor = 10f64.powi(i32::from(self.decimals.min(MAX_DECIMALS)));
+        (value * factor).round() / factor
+    }
+
+    /// Rounds `value` to the shown decimals, then clamps it into the range.
+    pub fn clamp(&self, value: f64) -> f64 {
+        if value.is_nan() {
+            return self.min;
+        }
+        // Clamping last keeps bounds with more decimals than shown from being rounded past.
+        self.round(value).clamp(self.min, self.max.max(self.min))
+    }
+
+    /// The text shown for `value`: the number with `decimals` digits, then the unit.
+    pub fn format(&self, value: f64) -> String {
+        let number = format!("{:.*}", usize::from(self.decimals), self.clamp(value));
+        match &self.unit {
+            Some(unit) if !unit.is_empty() => format!("{number} {unit}"),
+            _ => number,
+        }
+    }
+
+    /*
+     * Parses user text. Surrounding whitespace and the unit suffix are
+     * optional; the number must lie in the range and have no more
+     * significant decimals than `decimals` allows. Returns `None` for
+     * anything else, which the controls show with
+     * `StyleId::DefaultInputError`.
+     */
+    pub fn parse(&self, text: &str) -> Option<f64> {
+        let mut number = text.trim();
+        if let Some(unit) = self.unit.as_deref().filter(|unit| !unit.is_empty()) {
+            number = number.strip_suffix(unit).unwrap_or(number).trim_end();
+        }
+        if number.is_empty() || number.contains(['e', 'E']) {
+            return None;
+        }
+        let value: f64 = number
+            .parse()
+            .ok()
+            .filter(|value: &f64| value.is_finite())?;
+        // Trailing zeros add no precision, so "4.50" is fine with one decimal.
+        let fraction_digits = number
+            .split_once('.')
+            .map_or(0, |(_, digits)| digits.trim_end_matches('0').len());
+        if fraction_digits > usize::from(self.decimals) {
+            return None;
+        }
+        (self.min..=self.max)
+            .contains(&value)
+            .then(|| self.round(value))
+    }
+
+    /// Adds `steps` times `step` (negative steps subtract), clamped to the range.
+    pub fn step_by(&self, value: f64, steps: i32) -> f64 {
+        self.clamp(value + f64::from(steps) * self.step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-NumericInputV1] Text parses with an optional unit and is rejected outside range or precision.
+    fn parse_accepts_units_and_rejects_invalid_text() {
+        // Arrange
+        let spec = NumericSpec::new(0.0, 10.0)
+            .step(0.25)
+            .decimals(2)
+            .unit("ms");
+
+        // Act
+        let parsed: Vec<Option<f64>> = [
+            " 2.5 ms", "2.50", "10", "-0.5", "10.01", "1.234", "abc", "", "1e1", "ms",
+        ]
+        .into_iter()
+        .map(|text| spec.parse(text))
+        .collect();
+
+        // Assert
+        assert_eq!(
+            parsed,
+            vec![
+                Some(2.5),
+                Some(2.5),
+                Some(10.0),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    // [CDU-Control-NumericInputV1] Integer inputs reject fractions and format without decimals.
+    fn integer_inputs_reject_fractions() {
+        // Arrange
+        let spec = NumericSpec::new(-5.0, 5.0);
+
+        // Act & Assert
+        assert_eq!(spec.parse("-3"), Some(-3.0));
+        assert_eq!(spec.parse("1.5"), None);
+        assert_eq!(spec.parse("1."), Some(1.0));
+        assert_eq!(spec.parse("2.00"), Some(2.0));
+        assert_eq!(spec.format(2.4), "2");
+    }
+
+    #[test]
+    // [CDU-Control-NumericInputV1] Stepping adds the step and stops at the range ends.
+    fn step_by_clamps_to_range() {
+        // Arrange
+        let spec = NumericSpec::new(0.0, 1.0).step(0.1).decimals(1);
+
+        // Act
+        let up = spec.step_by(0.2, 1);
+        let past_max = spec.step_by(0.95, 3);
+        let past_min = spec.step_by(0.1, -5);
+
+        // Assert
+        assert_eq!(up, 0.3);
+        assert_eq!(past_max, 1.0);
+        assert_eq!(past_min, 0.0);
+    }
+
+    #[test]
+    fn format_rounds_clamps_and_appends_unit() {
+        // Arrange
+        let spec = NumericSpec::new(0.0, 100.0).decimals(1).unit("%");
+
+        // Act & Assert
+        assert_eq!(spec.format(12.345), "12.3 %");
+        assert_eq!(spec.format(250.0), "100.0 %");
+        assert_eq!(spec.clamp(f64::NAN), 0.0);
+    }
+
+    #[test]
+    // [CDU-Control-NumericInputV1] Bounds finer than the shown decimals still hold after rounding.
+    fn clamp_never_rounds_past_the_bounds() {
+        // Arrange
+        let spec = NumericSpec::new(0.05, 0.95).decimals(1);
+
+        // Act
+        let above = spec.clamp(1.0);
+        let below = spec.clamp(0.0);
+        let inside = spec.clamp(0.44);
+
+        // Assert
+        assert_eq!(above, 0.95);
+        assert_eq!(below, 0.05);
+        assert_eq!(inside, 0.4);
+    }
+
+    #[test]
+    fn validate_rejects_bad_ranges_steps_and_decimals() {
+        // Act & Assert
+        assert!(NumericSpec::new(0.0, 1.0).step(0.5).validate().is_ok());
+        assert!(NumericSpec::new(2.0, 1.0).validate().is_err());
+        assert!(NumericSpec::new(0.0, f64::INFINITY).validate().is_err());
+        assert!(NumericSpec::new(0.0, 1.0).step(0.0).validate().is_err());
+        assert!(NumericSpec::new(0.0, 1.0).decimals(11).validate().is_err());
+    }
+}
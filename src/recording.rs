@@ -0,0 +1,554 @@
+/*
+ * Session recording and replay, for attaching reproducible traces to bug
+ * reports. A `SessionRecorder` installed on a backend (`PlatformInterface` or
+ * `HeadlessPlatform`) writes every created window, every executed
+ * `PlatformCommand` and every `AppEvent` delivered to the application, each
+ * with the milliseconds elapsed since recording started. `HeadlessPlatform`
+ * also records the text typed into numeric inputs, because text that does
+ * not parse raises no event but still marks the input invalid.
+ *
+ * The file format is JSON Lines: a header object naming the format and its
+ * version, then one `RecordedEntry` per line. Lines are flushed as they are
+ * written, so a trace survives a crash up to the last entry. Recording never
+ * fails the UI: write errors are logged and the entry is dropped.
+ *
+ * A recorded session is replayed either into a `HeadlessPlatform` (commands
+ * are executed and user events re-applied to the virtual tree, see
+ * `HeadlessPlatform::replay`) or into a real backend through
+ * `ReplayEventHandler`, which hands the recorded commands to the run loop.
+ *
+ * Commands and events are written with the derives and wire format of the
+ * `serde` feature (see `serialization`), which the `recording` feature
+ * enables.
+ */
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::types::{
+    AppEvent, ControlId, PlatformCommand, PlatformEventHandler, WindowConfig, WindowId,
+};
+
+/// Identifies session files in the header line.
+pub const SESSION_FORMAT: &str = "commanductui-session";
+/// Bumped whenever the entry layout changes incompatibly.
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SessionHeader {
+    format: String,
+    version: u32,
+    started_unix_ms: u64,
+}
+
+/// One recorded step of a UI session.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum SessionEntry {
+    WindowCreated {
+        window_id: WindowId,
+        title: String,
+        width: i32,
+        height: i32,
+    },
+    // Boxed because a theme makes some commands far larger than the other entries.
+    Command(Box<PlatformCommand>),
+    Event(AppEvent),
+    // Text typed into a numeric input; text that does not parse raises no event.
+    NumericTextTyped {
+        window_id: WindowId,
+        control_id: ControlId,
+        text: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordedEntry {
+    pub elapsed_ms: u64, // Milliseconds since the recorder was created.
+    pub entry: SessionEntry,
+}
+
+// Borrowing mirror of `SessionEntry`, so recording does not clone commands.
+#[derive(Serialize)]
+enum SessionEntryRef<'a> {
+    WindowCreated {
+        window_id: WindowId,
+        title: &'a str,
+        width: i32,
+        height: i32,
+    },
+    Command(&'a PlatformCommand),
+    Event(&'a AppEvent),
+    NumericTextTyped {
+        window_id: WindowId,
+        control_id: ControlId,
+        text: &'a str,
+    },
+}
+
+#[derive(Serialize)]
+struct RecordedEntryRef<'a> {
+    elapsed_ms: u64,
+    entry: SessionEntryRef<'a>,
+}
+
+/*
+ * Writes a session trace. The recorder is shared with the backend through an
+ * `Arc` and may be written from the UI thread only; the internal mutex just
+ * keeps the type `Sync` like the rest of the platform state.
+ */
+pub struct SessionRecorder {
+    started: Instant,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl SessionRecorder {
+    /// Creates (or truncates) `path` and writes the session header.
+    pub fn create(path: &Path) -> PlatformResult<Self> {
+        let file = File::create(path).map_err(|e| {
+            PlatformError::OperationFailed(format!(
+                "Failed to create session file {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_writer(BufWriter::new(file))
+    }
+
+    /// Records into any writer, e.g. an in-memory buffer in tests.
+    pub fn from_writer(writer: impl Write + Send + 'static) -> PlatformResult<Self> {
+        let recorder = Self {
+            started: Instant::now(),
+            writer: Mutex::new(Box::new(writer)),
+        };
+        let started_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let header = SessionHeader {
+            format: SESSION_FORMAT.to_string(),
+            version: SESSION_FORMAT_VERSION,
+            started_unix_ms,
+        };
+        recorder.write_line(&header).map_err(|e| {
+            PlatformError::OperationFailed(format!("Failed to write session header: {e}"))
+        })?;
+        Ok(recorder)
+    }
+
+    pub fn record_window_created(&self, window_id: WindowId, config: &WindowConfig) {
+        self.record(SessionEntryRef::WindowCreated {
+            window_id,
+            title: config.title,
+            width: config.width,
+            height: config.height,
+        });
+    }
+
+    pub fn record_command(&self, command: &PlatformCommand) {
+        self.record(SessionEntryRef::Command(command));
+    }
+
+    pub fn record_event(&self, event: &AppEvent) {
+        self.record(SessionEntryRef::Event(event));
+    }
+
+    pub fn record_numeric_text(&self, window_id: WindowId, control_id: ControlId, text: &str) {
+        self.record(SessionEntryRef::NumericTextTyped {
+            window_id,
+            control_id,
+            text,
+        });
+    }
+
+    fn record(&self, entry: SessionEntryRef) {
+        let line = RecordedEntryRef {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            entry,
+        };
+        if let Err(e) = self.write_line(&line) {
+            log::error!("SessionRecorder: Failed to write entry: {e}");
+        }
+    }
+
+    fn write_line(&self, value: &impl Serialize) -> std::io::Result<()> {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| std::io::Error::other("session writer mutex poisoned"))?;
+        serde_json::to_writer(&mut *writer, value)?;
+        writer.write_all(b"\n")?;
+        writer.flush()
+    }
+}
+
+/// A session loaded back from a trace, in recorded order.
+#[derive(Debug)]
+pub struct RecordedSession {
+    pub started_unix_ms: u64,
+    pub entries: Vec<RecordedEntry>,
+}
+
+impl RecordedSession {
+    pub fn load(path: &Path) -> PlatformResult<Self> {
+        let file = File::open(path).map_err(|e| {
+            PlatformError::OperationFailed(format!(
+                "Failed to open session file {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(BufReader::new(file))
+    }
+
+    /// Parses a trace, rejecting unknown formats and newer versions.
+    pub fn parse(reader: impl BufRead) -> PlatformResult<Self> {
+        let mut lines = reader.lines().enumerate();
+        let header: SessionHeader = match lines.next() {
+            Some((_, line)) => parse_line(1, &read_line(1, line)?)?,
+            None => {
+                return Err(PlatformError::OperationFailed(
+                    "Session file is empty".to_string(),
+                ));
+            }
+        };
+        if header.format != SESSION_FORMAT || header.version > SESSION_FORMAT_VERSION {
+            return Err(PlatformError::OperationFailed(format!(
+                "Unsupported session format {:?} version {} (expected {SESSION_FORMAT:?} up to version {SESSION_FORMAT_VERSION})",
+                header.format, header.version
+            )));
+        }
+
+        let mut entries = Vec::new();
+        for (index, line) in lines {
+            let line = read_line(index + 1, line)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(parse_line(index + 1, &line)?);
+        }
+        Ok(Self {
+            started_unix_ms: header.started_unix_ms,
+            entries,
+        })
+    }
+
+    /// The recorded commands, in order, without events or window creations.
+    pub fn commands(&self) -> impl Iterator<Item = &PlatformCommand> {
+        self.entries.iter().filter_map(|e| match &e.entry {
+            SessionEntry::Command(command) => Some(command.as_ref()),
+            _ => None,
+        })
+    }
+}
+
+fn read_line(line_number: usize, line: std::io::Result<String>) -> PlatformResult<String> {
+    line.map_err(|e| {
+        PlatformError::OperationFailed(format!("Session line {line_number}: read failed: {e}"))
+    })
+}
+
+fn parse_line<T: for<'de> Deserialize<'de>>(line_number: usize, line: &str) -> PlatformResult<T> {
+    serde_json::from_str(line)
+        .map_err(|e| PlatformError::OperationFailed(format!("Session line {line_number}: {e}")))
+}
+
+/// How `ReplayEventHandler` spaces the recorded commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReplayPacing {
+    #[default]
+    AsFastAsPossible,
+    RealTime, // Each command waits until its recorded offset has elapsed.
+}
+
+/*
+ * A `PlatformEventHandler` that plays the commands of a recorded session into
+ * a backend's run loop, standing in for the application. Events the backend
+ * raises during the replay are kept in `received_events` for comparison with
+ * the recorded ones. Windows must be created beforehand, in recorded order, so
+ * their ids match (see `RecordedSession::entries`).
+ */
+pub struct ReplayEventHandler {
+    commands: std::collections::VecDeque<(u64, PlatformCommand)>,
+    pacing: ReplayPacing,
+    started: Option<Instant>,
+    pub received_events: Vec<AppEvent>,
+}
+
+impl ReplayEventHandler {
+    pub fn new(session: RecordedSession, pacing: ReplayPacing) -> Self {
+        let commands = session
+            .entries
+            .into_iter()
+            .filter_map(|e| match e.entry {
+                SessionEntry::Command(command) => Some((e.elapsed_ms, *command)),
+                _ => None,
+            })
+            .collect();
+        Self {
+            commands,
+            pacing,
+            started: None,
+            received_events: Vec::new(),
+        }
+    }
+
+    /// True once every recorded command has been handed out.
+    pub fn is_finished(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+impl PlatformEventHandler for ReplayEventHandler {
+    fn handle_event(&mut self, event: AppEvent) {
+        self.received_events.push(event);
+    }
+
+    fn try_dequeue_command(&mut self) -> Option<PlatformCommand> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let (elapsed_ms, _) = self.commands.front()?;
+        if self.pacing == ReplayPacing::RealTime
+            && started.elapsed() < Duration::from_millis(*elapsed_ms)
+        {
+            return None;
+        }
+        self.commands.pop_front().map(|(_, command)| command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessPlatform;
+    use crate::numeric::NumericSpec;
+    use crate::toolbar::ToolbarItem;
+    use crate::types::{MenuActionId, MenuItemConfig, MessageSeverity};
+    use std::sync::Arc;
+
+    // A clonable in-memory sink so the test can read back what was recorded.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const CONFIG: WindowConfig = WindowConfig {
+        title: "Recorded",
+        width: 400,
+        height: 300,
+    };
+
+    #[test]
+    // [CDU-Tech-SessionRecordingV1] A recorded headless session replays into a fresh backend with the same state.
+    fn recorded_session_replays_into_headless_backend() {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let recorder = Arc::new(SessionRecorder::from_writer(buffer.clone()).unwrap());
+        let mut original = HeadlessPlatform::new();
+        original.set_session_recorder(Some(recorder));
+        let window_id = original.create_window(CONFIG).unwrap();
+        let label = ControlId::new(7);
+        for command in [
+            PlatformCommand::CreateLabel {
+                window_id,
+                parent_control_id: None,
+                control_id: label,
+                initial_text: "Idle".into(),
+                class: crate::types::LabelClass::Default,
+            },
+            PlatformCommand::UpdateLabelText {
+                window_id,
+                control_id: label,
+                text: "Scanning".into(),
+                severity: MessageSeverity::Warning,
+            },
+        ] {
+            original.execute_command(command).unwrap();
+        }
+
+        // Act
+        let bytes = buffer.0.lock().unwrap().clone();
+        let session = RecordedSession::parse(bytes.as_slice()).unwrap();
+        let mut replayed = HeadlessPlatform::new();
+        replayed.replay(&session);
+
+        // Assert
+        assert_eq!(session.entries.len(), 3);
+        assert_eq!(session.commands().count(), 2);
+        let control = replayed.control(window_id, label).unwrap();
+        assert_eq!(control.text, "Scanning");
+        assert_eq!(control.severity, MessageSeverity::Warning);
+        assert_eq!(replayed.window(window_id).unwrap().title, "Recorded");
+    }
+
+    struct IdleApp;
+
+    impl PlatformEventHandler for IdleApp {
+        fn handle_event(&mut self, _event: AppEvent) {}
+
+        fn try_dequeue_command(&mut self) -> Option<PlatformCommand> {
+            None
+        }
+    }
+
+    impl crate::types::UiStateProvider for IdleApp {
+        fn is_tree_item_new(
+            &self,
+            _window_id: WindowId,
+            _item_id: crate::types::TreeItemId,
+        ) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    // [CDU-Tech-SessionRecordingV1] Replaying user input rebuilds toggles, context menus and typed numeric text.
+    fn replayed_user_input_matches_the_recorded_control_state() {
+        // Arrange
+        let buffer = SharedBuffer::default();
+        let recorder = Arc::new(SessionRecorder::from_writer(buffer.clone()).unwrap());
+        let mut original = HeadlessPlatform::new();
+        original.set_session_recorder(Some(recorder));
+        let window_id = original.create_window(CONFIG).unwrap();
+        let (bar, tree, number) = (ControlId::new(1), ControlId::new(2), ControlId::new(3));
+        let (wrap, open) = (MenuActionId(1), MenuActionId(2));
+        let app = Arc::new(Mutex::new(IdleApp));
+        original
+            .main_event_loop(
+                app.clone(),
+                app.clone(),
+                vec![
+                    PlatformCommand::CreateToolbar {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: bar,
+                        items: vec![ToolbarItem::toggle(wrap, "Wrap")],
+                    },
+                    PlatformCommand::CreateTreeView {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: tree,
+                    },
+                    PlatformCommand::CreateNumericInput {
+                        window_id,
+                        parent_control_id: None,
+                        control_id: number,
+                        spec: NumericSpec::new(0.0, 10.0).decimals(2).unit("mm"),
+                        value: 1.0,
+                    },
+                    PlatformCommand::ShowContextMenu {
+                        window_id,
+                        control_id: tree,
+                        menu_items: vec![MenuItemConfig {
+                            action: Some(open),
+                            text: "Open".into(),
+                            children: vec![],
+                        }],
+                        screen_x: 0,
+                        screen_y: 0,
+                    },
+                ],
+            )
+            .unwrap();
+        original.click_toolbar_item(window_id, bar, wrap).unwrap();
+        original.choose_context_menu_item(window_id, open).unwrap();
+        original
+            .type_numeric_text(window_id, number, "2.5")
+            .unwrap();
+        let live_text = original.control(window_id, number).unwrap().text.clone();
+        original
+            .type_numeric_text(window_id, number, "2.5x")
+            .unwrap();
+
+        // Act
+        let bytes = buffer.0.lock().unwrap().clone();
+        let session = RecordedSession::parse(bytes.as_slice()).unwrap();
+        let mut replayed = HeadlessPlatform::new();
+        replayed.replay(&session);
+
+        // Assert
+        let events = session
+            .entries
+            .iter()
+            .filter(|e| matches!(e.entry, SessionEntry::Event(_)))
+            .count();
+        assert_eq!(events, 3);
+        assert_eq!(live_text, "2.5");
+        for platform in [&original, &replayed] {
+            let toolbar = platform.control(window_id, bar).unwrap();
+            assert!(toolbar.toolbar_items[0].checked);
+            assert!(platform.window(window_id).unwrap().context_menu.is_none());
+            let input = platform.control(window_id, number).unwrap();
+            assert_eq!(input.text, "2.5x");
+            assert!(input.input_invalid);
+            assert_eq!(input.numeric_value, 2.5);
+        }
+        assert!(replayed.command_errors().is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_format_and_reports_line_numbers() {
+        // Arrange
+        let foreign = b"{\"format\":\"other\",\"version\":1,\"started_unix_ms\":0}\n";
+        let corrupt = b"{\"format\":\"commanductui-session\",\"version\":1,\"started_unix_ms\":0}\n{not json}\n";
+
+        // Act
+        let foreign_err = RecordedSession::parse(&foreign[..]).unwrap_err();
+        let corrupt_err = RecordedSession::parse(&corrupt[..]).unwrap_err();
+
+        // Assert
+        assert!(
+            foreign_err
+                .to_string()
+                .contains("Unsupported session format")
+        );
+        assert!(corrupt_err.to_string().contains("Session line 2"));
+    }
+
+    #[test]
+    fn replay_handler_hands_out_commands_in_order() {
+        // Arrange
+        let window_id = WindowId::new(1);
+        let session = RecordedSession {
+            started_unix_ms: 0,
+            entries: vec![
+                RecordedEntry {
+                    elapsed_ms: 0,
+                    entry: SessionEntry::Command(Box::new(PlatformCommand::ShowWindow {
+                        window_id,
+                    })),
+                },
+                RecordedEntry {
+                    elapsed_ms: 5,
+                    entry: SessionEntry::Event(AppEvent::WindowCloseRequestedByUser { window_id }),
+                },
+                RecordedEntry {
+                    elapsed_ms: 10,
+                    entry: SessionEntry::Command(Box::new(PlatformCommand::QuitApplication)),
+                },
+            ],
+        };
+        let mut handler = ReplayEventHandler::new(session, ReplayPacing::AsFastAsPossible);
+
+        // Act
+        let first = handler.try_dequeue_command();
+        let second = handler.try_dequeue_command();
+
+        // Assert
+        assert!(matches!(first, Some(PlatformCommand::ShowWindow { .. })));
+        assert!(matches!(second, Some(PlatformCommand::QuitApplication)));
+        assert!(handler.is_finished());
+        assert!(handler.try_dequeue_command().is_none());
+    }
+}
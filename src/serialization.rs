@@ -0,0 +1,1139 @@
+/*
+ * Versioned serialization of the public command, event and layout types,
+ * available with the `serde` cargo feature. The types themselves derive
+ * `Serialize`/`Deserialize` with serde's default externally tagged enum
+ * representation, so a command serializes as `{"VariantName": {fields}}` and
+ * id newtypes as their raw number. That shape is the stable wire format:
+ * variants and fields are only ever added, fields added after version 1 carry
+ * `#[serde(default)]`, and anything that changes the meaning of existing data
+ * bumps `SERIALIZATION_VERSION`.
+ *
+ * Payloads that leave the process (IPC, log files, snapshots) should be wrapped
+ * in `Versioned` so a reader can reject data written by a newer format.
+ * [CDU-Tech-SerdeV1]
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::PlatformResult;
+use crate::error::PlatformError;
+
+/// Version of the serialized form of the public types.
+pub const SERIALIZATION_VERSION: u32 = 1;
+
+/*
+ * Envelope pairing a payload with the `SERIALIZATION_VERSION` it was written
+ * with, e.g. `{"version": 1, "payload": {"ShowWindow": {"window_id": 1}}}`.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub version: u32,
+    pub payload: T,
+}
+
+impl<T> Versioned<T> {
+    /// Wraps `payload` with the current `SERIALIZATION_VERSION`.
+    pub fn new(payload: T) -> Self {
+        Self {
+            version: SERIALIZATION_VERSION,
+            payload,
+        }
+    }
+
+    /*
+     * Unwraps the payload, failing for data written by a newer format version
+     * that this build may misinterpret. Older versions are readable because
+     * the format only grows.
+     */
+    pub fn into_payload(self) -> PlatformResult<T> {
+        if self.version > SERIALIZATION_VERSION {
+            return Err(PlatformError::OperationFailed(format!(
+                "Serialized data has format version {}, newer than the supported version {SERIALIZATION_VERSION}",
+                self.version
+            )));
+        }
+        Ok(self.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Rect;
+    use crate::numeric::NumericSpec;
+    use crate::slider::{SliderOrientation, SliderRange};
+    use crate::status_bar::StatusSegment;
+    use crate::styling_primitives::{
+        Color, ControlStyle, FontDescription, FontWeight, StateColors, StyleId,
+    };
+    use crate::theme::{SliderPalette, StatusBarPalette, Theme, ToolbarPalette, TooltipPalette};
+    use crate::toolbar::ToolbarItem;
+    use crate::tooltip::TooltipOptions;
+    use crate::types::*;
+    use serde::de::DeserializeOwned;
+    use std::collections::HashSet;
+    use std::fmt::Debug;
+    use std::path::PathBuf;
+
+    const WINDOW: WindowId = WindowId::new(1);
+    const CONTROL: ControlId = ControlId::new(7);
+    const PARENT: ControlId = ControlId::new(6);
+
+    /*
+     * Serializes, deserializes and serializes again. The types have no
+     * `PartialEq`, so the round trip is checked on both the JSON and the
+     * `Debug` rendering.
+     */
+    fn assert_round_trip<T: Serialize + DeserializeOwned + Debug>(value: &T) {
+        let json = serde_json::to_value(value).expect("serialize");
+        let back: T = serde_json::from_value(json.clone()).expect("deserialize");
+        assert_eq!(serde_json::to_value(&back).expect("reserialize"), json);
+        assert_eq!(format!("{back:?}"), format!("{value:?}"));
+    }
+
+    // Exhaustive on purpose: a new variant fails to compile here until it
+    // gets a name and, alongside it, a sample in `sample_commands`.
+    fn command_name(command: &PlatformCommand) -> &'static str {
+        match command {
+            PlatformCommand::SetWindowTitle { .. } => "SetWindowTitle",
+            PlatformCommand::ShowWindow { .. } => "ShowWindow",
+            PlatformCommand::CloseWindow { .. } => "CloseWindow",
+            PlatformCommand::PopulateTreeView { .. } => "PopulateTreeView",
+            PlatformCommand::UpdateTreeItemVisualState { .. } => "UpdateTreeItemVisualState",
+            PlatformCommand::UpdateTreeItemText { .. } => "UpdateTreeItemText",
+            PlatformCommand::ShowSaveFileDialog { .. } => "ShowSaveFileDialog",
+            PlatformCommand::ShowOpenFileDialog { .. } => "ShowOpenFileDialog",
+            PlatformCommand::ShowProfileSelectionDialog { .. } => "ShowProfileSelectionDialog",
+            PlatformCommand::ShowInputDialog { .. } => "ShowInputDialog",
+            PlatformCommand::ShowExcludePatternsDialog { .. } => "ShowExcludePatternsDialog",
+            PlatformCommand::ShowMessageBox { .. } => "ShowMessageBox",
+            PlatformCommand::ShowFolderPickerDialog { .. } => "ShowFolderPickerDialog",
+            PlatformCommand::SetControlEnabled { .. } => "SetControlEnabled",
+            PlatformCommand::QuitApplication => "QuitApplication",
+            PlatformCommand::CreateMainMenu { .. } => "CreateMainMenu",
+            PlatformCommand::CreateButton { .. } => "CreateButton",
+            PlatformCommand::CreateTreeView { .. } => "CreateTreeView",
+            PlatformCommand::SignalMainWindowUISetupComplete { .. } => {
+                "SignalMainWindowUISetupComplete"
+            }
+            PlatformCommand::DefineLayout { .. } => "DefineLayout",
+            PlatformCommand::CreatePanel { .. } => "CreatePanel",
+            PlatformCommand::CreateLabel { .. } => "CreateLabel",
+            PlatformCommand::CreateInput { .. } => "CreateInput",
+            PlatformCommand::CreateRichEdit { .. } => "CreateRichEdit",
+            PlatformCommand::CreateChart { .. } => "CreateChart",
+            PlatformCommand::SetChartData { .. } => "SetChartData",
+            PlatformCommand::CreateProgressBar { .. } => "CreateProgressBar",
+            PlatformCommand::CreateSplitter { .. } => "CreateSplitter",
+            PlatformCommand::SetProgressBarRange { .. } => "SetProgressBarRange",
+            PlatformCommand::SetProgressBarPosition { .. } => "SetProgressBarPosition",
+            PlatformCommand::SetControlText { .. } => "SetControlText",
+            PlatformCommand::SetInputText { .. } => "SetInputText",
+            PlatformCommand::SetViewerContent { .. } => "SetViewerContent",
+            PlatformCommand::SetRichEditContent { .. } => "SetRichEditContent",
+            PlatformCommand::SetScrollPosition { .. } => "SetScrollPosition",
+            PlatformCommand::SetTreeViewSelection { .. } => "SetTreeViewSelection",
+            PlatformCommand::UpdateLabelText { .. } => "UpdateLabelText",
+            PlatformCommand::ExpandVisibleTreeItems { .. } => "ExpandVisibleTreeItems",
+            PlatformCommand::ExpandAllTreeItems { .. } => "ExpandAllTreeItems",
+            PlatformCommand::RedrawTreeItem { .. } => "RedrawTreeItem",
+            PlatformCommand::CreateComboBox { .. } => "CreateComboBox",
+            PlatformCommand::SetComboBoxItems { .. } => "SetComboBoxItems",
+            PlatformCommand::SetComboBoxSelection { .. } => "SetComboBoxSelection",
+            PlatformCommand::CreateRadioButton { .. } => "CreateRadioButton",
+            PlatformCommand::SetRadioButtonChecked { .. } => "SetRadioButtonChecked",
+            PlatformCommand::CreateCheckBox { .. } => "CreateCheckBox",
+            PlatformCommand::SetCheckBoxChecked { .. } => "SetCheckBoxChecked",
+            PlatformCommand::CreateTabBar { .. } => "CreateTabBar",
+            PlatformCommand::SetTabBarItems { .. } => "SetTabBarItems",
+            PlatformCommand::SetTabBarSelection { .. } => "SetTabBarSelection",
+            PlatformCommand::SetTabBarStyle { .. } => "SetTabBarStyle",
+            PlatformCommand::DefineStyle { .. } => "DefineStyle",
+            PlatformCommand::ApplyTheme { .. } => "ApplyTheme",
+            PlatformCommand::ApplyStyleToControl { .. } => "ApplyStyleToControl",
+            PlatformCommand::CreateToggleSwitch { .. } => "CreateToggleSwitch",
+            PlatformCommand::SetToggleSwitchState { .. } => "SetToggleSwitchState",
+            PlatformCommand::SetToggleSwitchStyle { .. } => "SetToggleSwitchStyle",
+            PlatformCommand::CreateListView { .. } => "CreateListView",
+            PlatformCommand::SetListViewRows { .. } => "SetListViewRows",
+            PlatformCommand::SetListViewRowCount { .. } => "SetListViewRowCount",
+            PlatformCommand::SetListViewSortIndicator { .. } => "SetListViewSortIndicator",
+            PlatformCommand::SetListViewSelection { .. } => "SetListViewSelection",
+            PlatformCommand::CreateSlider { .. } => "CreateSlider",
+            PlatformCommand::SetSliderValue { .. } => "SetSliderValue",
+            PlatformCommand::SetSliderStyle { .. } => "SetSliderStyle",
+            PlatformCommand::CreateNumericInput { .. } => "CreateNumericInput",
+            PlatformCommand::SetNumericValue { .. } => "SetNumericValue",
+            PlatformCommand::CreateStatusBar { .. } => "CreateStatusBar",
+            PlatformCommand::SetStatusSegmentText { .. } => "SetStatusSegmentText",
+            PlatformCommand::SetStatusSegmentProgress { .. } => "SetStatusSegmentProgress",
+            PlatformCommand::CreateToolbar { .. } => "CreateToolbar",
+            PlatformCommand::SetToolbarItemEnabled { .. } => "SetToolbarItemEnabled",
+            PlatformCommand::SetToolbarItemChecked { .. } => "SetToolbarItemChecked",
+            PlatformCommand::RegisterImage { .. } => "RegisterImage",
+            PlatformCommand::CreateImage { .. } => "CreateImage",
+            PlatformCommand::SetImage { .. } => "SetImage",
+            PlatformCommand::SetControlTooltip { .. } => "SetControlTooltip",
+            PlatformCommand::ShowContextMenu { .. } => "ShowContextMenu",
+        }
+    }
+
+    fn event_name(event: &AppEvent) -> &'static str {
+        match event {
+            AppEvent::WindowCloseRequestedByUser { .. } => "WindowCloseRequestedByUser",
+            AppEvent::WindowResized { .. } => "WindowResized",
+            AppEvent::WindowDestroyed { .. } => "WindowDestroyed",
+            AppEvent::DpiChanged { .. } => "DpiChanged",
+            AppEvent::TreeViewItemToggledByUser { .. } => "TreeViewItemToggledByUser",
+            AppEvent::TreeViewItemSelectionChanged { .. } => "TreeViewItemSelectionChanged",
+            AppEvent::ButtonClicked { .. } => "ButtonClicked",
+            AppEvent::MenuActionClicked { .. } => "MenuActionClicked",
+            AppEvent::FileSaveDialogCompleted { .. } => "FileSaveDialogCompleted",
+            AppEvent::FileOpenProfileDialogCompleted { .. } => "FileOpenProfileDialogCompleted",
+            AppEvent::ProfileSelectionDialogCompleted { .. } => "ProfileSelectionDialogCompleted",
+            AppEvent::GenericInputDialogCompleted { .. } => "GenericInputDialogCompleted",
+            AppEvent::ExcludePatternsDialogCompleted { .. } => "ExcludePatternsDialogCompleted",
+            AppEvent::FolderPickerDialogCompleted { .. } => "FolderPickerDialogCompleted",
+            AppEvent::MainWindowUISetupComplete { .. } => "MainWindowUISetupComplete",
+            AppEvent::ControlScrolled { .. } => "ControlScrolled",
+            AppEvent::InputTextChanged { .. } => "InputTextChanged",
+            AppEvent::SplitterDragging { .. } => "SplitterDragging",
+            AppEvent::SplitterDragEnded { .. } => "SplitterDragEnded",
+            AppEvent::ComboBoxSelectionChanged { .. } => "ComboBoxSelectionChanged",
+            AppEvent::RadioButtonSelected { .. } => "RadioButtonSelected",
+            AppEvent::CheckBoxToggled { .. } => "CheckBoxToggled",
+            AppEvent::TabBarSelectionChanged { .. } => "TabBarSelectionChanged",
+            AppEvent::ToggleSwitchToggled { .. } => "ToggleSwitchToggled",
+            AppEvent::ListViewSortRequested { .. } => "ListViewSortRequested",
+            AppEvent::ListViewSelectionChanged { .. } => "ListViewSelectionChanged",
+            AppEvent::ListViewRowActivated { .. } => "ListViewRowActivated",
+            AppEvent::SliderValueChanged { .. } => "SliderValueChanged",
+            AppEvent::NumericValueChanged { .. } => "NumericValueChanged",
+            AppEvent::StatusSegmentClicked { .. } => "StatusSegmentClicked",
+            AppEvent::ContextMenuRequested { .. } => "ContextMenuRequested",
+        }
+    }
+
+    fn sample_font() -> FontDescription {
+        FontDescription {
+            name: Some("Segoe UI".into()),
+            size: Some(10),
+            weight: Some(FontWeight::Bold),
+            italic: Some(true),
+            underline: None,
+            strikethrough: Some(false),
+        }
+    }
+
+    fn sample_style() -> ControlStyle {
+        ControlStyle {
+            base: Some(StyleId::DefaultText),
+            font: Some(sample_font()),
+            text_color: Some(Color::rgb(10, 20, 30)),
+            background_color: None,
+            border_color: Some(Color::rgb(1, 1, 1)),
+            border_width: Some(1),
+            corner_radius: Some(4),
+            padding: Some((2, 4, 2, 4)),
+            hover: Some(StateColors {
+                background_color: Some(Color::rgb(5, 5, 5)),
+                ..StateColors::default()
+            }),
+            pressed: None,
+            disabled: Some(StateColors {
+                text_color: Some(Color::rgb(9, 9, 9)),
+                ..StateColors::default()
+            }),
+            focused: None,
+        }
+    }
+
+    fn sample_chart() -> ChartDataPacket {
+        ChartDataPacket {
+            lines: vec![ChartLineData {
+                label: "alpha".into(),
+                weekly_counts: vec![0, 4, u32::MAX],
+                color: 0x00FF_8000,
+            }],
+            week_labels: vec!["W1".into(), "W2".into(), "W3".into()],
+            is_loading: false,
+        }
+    }
+
+    fn sample_tree() -> Vec<TreeItemDescriptor> {
+        vec![TreeItemDescriptor {
+            id: TreeItemId(1),
+            text: "src".into(),
+            is_folder: true,
+            state: CheckState::Unchecked,
+            children: vec![TreeItemDescriptor {
+                id: TreeItemId(2),
+                text: "lib.rs".into(),
+                is_folder: false,
+                state: CheckState::Checked,
+                children: Vec::new(),
+                style_override: Some(StyleId::TreeItemDisabled),
+                image: Some(ImageId(4)),
+                tooltip: Some("Line one\nline \"two\"".into()),
+            }],
+            style_override: Some(StyleId::Custom(3)),
+            image: None,
+            tooltip: None,
+        }]
+    }
+
+    fn sample_menu() -> Vec<MenuItemConfig> {
+        vec![MenuItemConfig {
+            action: None,
+            text: "&File".into(),
+            children: vec![MenuItemConfig {
+                action: Some(MenuActionId(3)),
+                text: "E&xit".into(),
+                children: Vec::new(),
+            }],
+        }]
+    }
+
+    // One rule per dock style and container kind.
+    fn sample_rules() -> Vec<LayoutRule> {
+        let styles = [
+            DockStyle::None,
+            DockStyle::Top,
+            DockStyle::Bottom,
+            DockStyle::Left,
+            DockStyle::Right,
+            DockStyle::Fill,
+            DockStyle::ProportionalFill {
+                weight: 1.5,
+                axis: LayoutAxis::Vertical,
+            },
+            DockStyle::GridCell {
+                row: 1,
+                column: 2,
+                row_span: 1,
+                column_span: 3,
+            },
+            DockStyle::FlowItem,
+        ];
+        let mut rules: Vec<LayoutRule> = styles
+            .into_iter()
+            .enumerate()
+            .map(|(i, style)| LayoutRule {
+                parent_control_id: Some(PARENT),
+                order: i as u32,
+                margin: (1, 2, 3, 4),
+                ..LayoutRule::new(ControlId::new(100 + i as i32), style)
+            })
+            .collect();
+        rules[0].absolute_bounds = Some(Rect::new(5, 6, 70, 80));
+        rules[1].fixed_size = Some(30);
+        rules[2].size_mode = SizeMode::Auto;
+        rules[2].preferred_size = Some((120, 24));
+        rules[3].constraints = SizeConstraints {
+            min_width: Some(50),
+            max_width: Some(300),
+            min_height: None,
+            max_height: None,
+        };
+        rules[4].container = Some(ContainerLayout::Grid(GridDefinition {
+            rows: vec![GridTrack::Fixed(20), GridTrack::Auto, GridTrack::Star(2.0)],
+            columns: vec![GridTrack::Star(1.0)],
+            row_spacing: 4,
+            column_spacing: 8,
+        }));
+        rules[5].container = Some(ContainerLayout::Flow(FlowLayout {
+            axis: LayoutAxis::Horizontal,
+            spacing: 6,
+            line_spacing: 2,
+            alignment: FlowAlignment::Center,
+            cross_alignment: FlowAlignment::End,
+            wrap: true,
+        }));
+        rules
+    }
+
+    fn sample_commands() -> Vec<PlatformCommand> {
+        vec![
+            PlatformCommand::SetWindowTitle {
+                window_id: WINDOW,
+                title: "title".into(),
+            },
+            PlatformCommand::ShowWindow { window_id: WINDOW },
+            PlatformCommand::CloseWindow { window_id: WINDOW },
+            PlatformCommand::PopulateTreeView {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                items: sample_tree(),
+            },
+            PlatformCommand::UpdateTreeItemVisualState {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                item_id: TreeItemId(42),
+                new_state: CheckState::Checked,
+            },
+            PlatformCommand::UpdateTreeItemText {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                item_id: TreeItemId(42),
+                text: "text".into(),
+            },
+            PlatformCommand::ShowSaveFileDialog {
+                window_id: WINDOW,
+                title: "title".into(),
+                default_filename: "default_filename".into(),
+                filter_spec: "filter_spec".into(),
+                initial_dir: Some(PathBuf::from("C:/work")),
+            },
+            PlatformCommand::ShowOpenFileDialog {
+                window_id: WINDOW,
+                title: "title".into(),
+                filter_spec: "filter_spec".into(),
+                initial_dir: Some(PathBuf::from("C:/work")),
+            },
+            PlatformCommand::ShowProfileSelectionDialog {
+                window_id: WINDOW,
+                available_profiles: vec!["a".into(), "b\n\"c\"".into()],
+                title: "title".into(),
+                prompt: "prompt".into(),
+            },
+            PlatformCommand::ShowInputDialog {
+                window_id: WINDOW,
+                title: "title".into(),
+                prompt: "prompt".into(),
+                default_text: Some("default_text".into()),
+                context_tag: Some("context_tag".into()),
+            },
+            PlatformCommand::ShowExcludePatternsDialog {
+                window_id: WINDOW,
+                title: "title".into(),
+                patterns: "patterns".into(),
+            },
+            PlatformCommand::ShowMessageBox {
+                window_id: WINDOW,
+                title: "title".into(),
+                message: "message".into(),
+                severity: MessageSeverity::Warning,
+            },
+            PlatformCommand::ShowFolderPickerDialog {
+                window_id: WINDOW,
+                title: "title".into(),
+                initial_dir: Some(PathBuf::from("C:/work")),
+            },
+            PlatformCommand::SetControlEnabled {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                enabled: true,
+            },
+            PlatformCommand::QuitApplication,
+            PlatformCommand::CreateMainMenu {
+                window_id: WINDOW,
+                menu_items: sample_menu(),
+            },
+            PlatformCommand::CreateButton {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                text: "text".into(),
+                image: Some(ImageId(4)),
+            },
+            PlatformCommand::CreateTreeView {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+            },
+            PlatformCommand::SignalMainWindowUISetupComplete { window_id: WINDOW },
+            PlatformCommand::DefineLayout {
+                window_id: WINDOW,
+                rules: sample_rules(),
+            },
+            PlatformCommand::CreatePanel {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+            },
+            PlatformCommand::CreateLabel {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                initial_text: "initial_text".into(),
+                class: LabelClass::StatusBar,
+            },
+            PlatformCommand::CreateInput {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                initial_text: "initial_text".into(),
+                read_only: true,
+                multiline: true,
+                vertical_scroll: true,
+            },
+            PlatformCommand::CreateRichEdit {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+            },
+            PlatformCommand::CreateChart {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+            },
+            PlatformCommand::SetChartData {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                data: sample_chart(),
+            },
+            PlatformCommand::CreateProgressBar {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+            },
+            PlatformCommand::CreateSplitter {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                orientation: SplitterOrientation::Vertical,
+            },
+            PlatformCommand::SetProgressBarRange {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                min: 3,
+                max: 3,
+            },
+            PlatformCommand::SetProgressBarPosition {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                position: 3,
+            },
+            PlatformCommand::SetControlText {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                text: "text".into(),
+            },
+            PlatformCommand::SetInputText {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                text: "text".into(),
+            },
+            PlatformCommand::SetViewerContent {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                text: "text".into(),
+            },
+            PlatformCommand::SetRichEditContent {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                rtf_text: "rtf_text".into(),
+            },
+            PlatformCommand::SetScrollPosition {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                vertical_pos: 3,
+                horizontal_pos: 3,
+            },
+            PlatformCommand::SetTreeViewSelection {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                item_id: TreeItemId(42),
+            },
+            PlatformCommand::UpdateLabelText {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                text: "text".into(),
+                severity: MessageSeverity::Warning,
+            },
+            PlatformCommand::ExpandVisibleTreeItems {
+                window_id: WINDOW,
+                control_id: CONTROL,
+            },
+            PlatformCommand::ExpandAllTreeItems {
+                window_id: WINDOW,
+                control_id: CONTROL,
+            },
+            PlatformCommand::RedrawTreeItem {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                item_id: TreeItemId(42),
+            },
+            PlatformCommand::CreateComboBox {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+            },
+            PlatformCommand::SetComboBoxItems {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                items: vec!["a".into(), "b\n\"c\"".into()],
+            },
+            PlatformCommand::SetComboBoxSelection {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                selected_index: Some(1),
+            },
+            PlatformCommand::CreateRadioButton {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                text: "text".into(),
+                group_start: true,
+            },
+            PlatformCommand::SetRadioButtonChecked {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                checked: true,
+            },
+            PlatformCommand::CreateCheckBox {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                text: "text".into(),
+            },
+            PlatformCommand::SetCheckBoxChecked {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                checked: true,
+            },
+            PlatformCommand::CreateTabBar {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                parent_control_id: Some(PARENT),
+                items: vec!["a".into(), "b\n\"c\"".into()],
+                images: vec![Some(ImageId(4)), None],
+                tooltips: vec![None, Some("Second".into())],
+            },
+            PlatformCommand::SetTabBarItems {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                items: vec!["a".into(), "b\n\"c\"".into()],
+                images: Vec::new(),
+                tooltips: Vec::new(),
+            },
+            PlatformCommand::SetTabBarSelection {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                selected_index: 2,
+            },
+            PlatformCommand::SetTabBarStyle {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                background_color: Color::rgb(1, 2, 3),
+                text_color: Color::rgb(1, 2, 3),
+                accent_color: Color::rgb(1, 2, 3),
+                font: Some(sample_font()),
+            },
+            PlatformCommand::DefineStyle {
+                style_id: StyleId::TabBarAccent,
+                style: sample_style(),
+            },
+            PlatformCommand::ApplyTheme {
+                theme: Theme::light().with_style(
+                    StyleId::Custom(1),
+                    ControlStyle {
+                        base: Some(StyleId::DefaultButton),
+                        text_color: Some(Color::rgb(255, 0, 0)),
+                        ..Default::default()
+                    },
+                ),
+            },
+            PlatformCommand::ApplyStyleToControl {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                style_id: StyleId::TabBarAccent,
+            },
+            PlatformCommand::CreateToggleSwitch {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                label: "label".into(),
+                checked: true,
+            },
+            PlatformCommand::SetToggleSwitchState {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                checked: true,
+            },
+            PlatformCommand::SetToggleSwitchStyle {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                background: Color::rgb(1, 2, 3),
+                pill_off: Color::rgb(1, 2, 3),
+                pill_on: Color::rgb(1, 2, 3),
+                knob: Color::rgb(1, 2, 3),
+                text: Color::rgb(1, 2, 3),
+            },
+            PlatformCommand::CreateListView {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                columns: vec![
+                    ListViewColumn::new("Name").sortable(),
+                    ListViewColumn::new("Size")
+                        .width(-4)
+                        .alignment(ColumnAlignment::Right),
+                ],
+            },
+            PlatformCommand::SetListViewRows {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                rows: vec![vec!["a".into(), "b\n\"c\"".into()], Vec::new()],
+            },
+            PlatformCommand::SetListViewRowCount {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                row_count: 100_000,
+            },
+            PlatformCommand::SetListViewSortIndicator {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                column: Some(1),
+                direction: SortDirection::Descending,
+            },
+            PlatformCommand::SetListViewSelection {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                row: None,
+            },
+            PlatformCommand::CreateSlider {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                range: SliderRange::new(-10, 10).step(2).ticks(5),
+                orientation: SliderOrientation::Vertical,
+                value: 4,
+            },
+            PlatformCommand::SetSliderValue {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                value: -6,
+            },
+            PlatformCommand::SetSliderStyle {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                background: Color::rgb(1, 2, 3),
+                track: Color::rgb(1, 2, 3),
+                fill: Color::rgb(1, 2, 3),
+                thumb: Color::rgb(1, 2, 3),
+                tick: Color::rgb(1, 2, 3),
+            },
+            PlatformCommand::CreateNumericInput {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                spec: NumericSpec::new(-1.5, 20.0)
+                    .step(0.25)
+                    .decimals(2)
+                    .unit("ms"),
+                value: 1.25,
+            },
+            PlatformCommand::SetNumericValue {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                value: -0.75,
+            },
+            PlatformCommand::CreateStatusBar {
+                window_id: WINDOW,
+                parent_control_id: None,
+                control_id: CONTROL,
+                segments: vec![
+                    StatusSegment::fill(),
+                    StatusSegment::auto(),
+                    StatusSegment::fixed(120).progress(),
+                ],
+            },
+            PlatformCommand::SetStatusSegmentText {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                segment: 1,
+                text: "Ln 4, Col 2".into(),
+                severity: MessageSeverity::Error,
+            },
+            PlatformCommand::SetStatusSegmentProgress {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                segment: 2,
+                progress: Some(40),
+            },
+            PlatformCommand::CreateToolbar {
+                window_id: WINDOW,
+                parent_control_id: None,
+                control_id: CONTROL,
+                items: vec![
+                    ToolbarItem::push(MenuActionId(1), "Open")
+                        .icon('\u{E8E5}')
+                        .tooltip("Open a profile"),
+                    ToolbarItem::separator(),
+                    ToolbarItem::toggle(MenuActionId(2), "Wrap").checked(true),
+                    ToolbarItem::spacer(),
+                    ToolbarItem::dropdown(MenuActionId(3), "Build").enabled(false),
+                    ToolbarItem::push(MenuActionId(4), "").image(ImageId(4)),
+                ],
+            },
+            PlatformCommand::SetToolbarItemEnabled {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                action_id: MenuActionId(3),
+                enabled: true,
+            },
+            PlatformCommand::SetToolbarItemChecked {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                action_id: MenuActionId(2),
+                checked: false,
+            },
+            PlatformCommand::RegisterImage {
+                image_id: ImageId(4),
+                bytes: vec![0x89, b'P', b'N', b'G', 0, 255],
+            },
+            PlatformCommand::CreateImage {
+                window_id: WINDOW,
+                parent_control_id: Some(PARENT),
+                control_id: CONTROL,
+                image_id: Some(ImageId(4)),
+            },
+            PlatformCommand::SetImage {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                image_id: None,
+            },
+            PlatformCommand::SetControlTooltip {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                text: "Saves\nthe profile".into(),
+                options: TooltipOptions::default().multiline().initial_delay(250),
+            },
+            PlatformCommand::ShowContextMenu {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                menu_items: sample_menu(),
+                screen_x: -1200,
+                screen_y: 340,
+            },
+        ]
+    }
+
+    fn sample_events() -> Vec<AppEvent> {
+        vec![
+            AppEvent::WindowCloseRequestedByUser { window_id: WINDOW },
+            AppEvent::WindowResized {
+                window_id: WINDOW,
+                width: -4,
+                height: -4,
+            },
+            AppEvent::WindowDestroyed { window_id: WINDOW },
+            AppEvent::DpiChanged {
+                window_id: WINDOW,
+                dpi: 144,
+            },
+            AppEvent::TreeViewItemToggledByUser {
+                window_id: WINDOW,
+                item_id: TreeItemId(42),
+                new_state: CheckState::Checked,
+            },
+            AppEvent::TreeViewItemSelectionChanged {
+                window_id: WINDOW,
+                item_id: TreeItemId(42),
+            },
+            AppEvent::ButtonClicked {
+                window_id: WINDOW,
+                control_id: CONTROL,
+            },
+            AppEvent::MenuActionClicked {
+                action_id: MenuActionId(9),
+            },
+            AppEvent::FileSaveDialogCompleted {
+                window_id: WINDOW,
+                result: None,
+            },
+            AppEvent::FileOpenProfileDialogCompleted {
+                window_id: WINDOW,
+                result: Some(PathBuf::from("C:/work")),
+            },
+            AppEvent::ProfileSelectionDialogCompleted {
+                window_id: WINDOW,
+                chosen_profile_name: Some("chosen_profile_name".into()),
+                create_new_requested: true,
+                user_cancelled: true,
+            },
+            AppEvent::GenericInputDialogCompleted {
+                window_id: WINDOW,
+                text: Some("text".into()),
+                context_tag: Some("context_tag".into()),
+            },
+            AppEvent::ExcludePatternsDialogCompleted {
+                window_id: WINDOW,
+                saved: true,
+                patterns: "patterns".into(),
+            },
+            AppEvent::FolderPickerDialogCompleted {
+                window_id: WINDOW,
+                path: Some(PathBuf::from("C:/work")),
+            },
+            AppEvent::MainWindowUISetupComplete { window_id: WINDOW },
+            AppEvent::ControlScrolled {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                vertical_pos: 3,
+                horizontal_pos: 3,
+            },
+            AppEvent::InputTextChanged {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                text: "text".into(),
+            },
+            AppEvent::SplitterDragging {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                desired_left_width: -4,
+            },
+            AppEvent::SplitterDragEnded {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                desired_left_width: -4,
+            },
+            AppEvent::ComboBoxSelectionChanged {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                selected_index: Some(1),
+            },
+            AppEvent::RadioButtonSelected {
+                window_id: WINDOW,
+                control_id: CONTROL,
+            },
+            AppEvent::CheckBoxToggled {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                checked: true,
+            },
+            AppEvent::TabBarSelectionChanged {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                selected_index: 2,
+            },
+            AppEvent::ToggleSwitchToggled {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                checked: true,
+            },
+            AppEvent::ListViewSortRequested {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                column: 1,
+                direction: SortDirection::Descending,
+            },
+            AppEvent::ListViewSelectionChanged {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                row: Some(2),
+            },
+            AppEvent::ListViewRowActivated {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                row: 2,
+            },
+            AppEvent::SliderValueChanged {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                value: -4,
+                committed: false,
+            },
+            AppEvent::NumericValueChanged {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                value: 0.1,
+            },
+            AppEvent::StatusSegmentClicked {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                segment: 2,
+            },
+            AppEvent::ContextMenuRequested {
+                window_id: WINDOW,
+                control_id: CONTROL,
+                screen_x: -1200,
+                screen_y: 340,
+                client_x: 12,
+                client_y: 48,
+                item_id: Some(TreeItemId(7)),
+            },
+        ]
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] Every PlatformCommand variant survives a JSON round trip.
+    fn every_platform_command_round_trips() {
+        // Arrange
+        let commands = sample_commands();
+        let names: HashSet<&str> = commands.iter().map(command_name).collect();
+
+        // Act & Assert
+        assert_eq!(names.len(), commands.len(), "each variant sampled once");
+        for command in &commands {
+            assert_round_trip(command);
+        }
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] Every AppEvent variant survives a JSON round trip.
+    fn every_app_event_round_trips() {
+        // Arrange
+        let events = sample_events();
+        let names: HashSet<&str> = events.iter().map(event_name).collect();
+
+        // Act & Assert
+        assert_eq!(names.len(), events.len(), "each variant sampled once");
+        for event in &events {
+            assert_round_trip(event);
+        }
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] Style, marker and severity enums round trip variant by variant.
+    fn unit_enums_round_trip() {
+        // Arrange
+        let markers = [
+            TreeItemMarkerKind::None,
+            TreeItemMarkerKind::Blue,
+            TreeItemMarkerKind::Green,
+            TreeItemMarkerKind::Yellow,
+            TreeItemMarkerKind::Red,
+            TreeItemMarkerKind::Purple,
+            TreeItemMarkerKind::Gray,
+        ];
+        let severities = [
+            MessageSeverity::None,
+            MessageSeverity::Information,
+            MessageSeverity::Warning,
+            MessageSeverity::Error,
+        ];
+
+        // Act & Assert
+        for marker in &markers {
+            assert_round_trip(marker);
+        }
+        for severity in &severities {
+            assert_round_trip(severity);
+        }
+        assert_round_trip(&SplitterOrientation::Horizontal);
+        assert_round_trip(&LabelClass::Default);
+        assert_round_trip(&FontWeight::Normal);
+        assert_round_trip(&StyleId::DefaultText);
+        assert_round_trip(&StyleId::Custom(42));
+        assert_eq!(
+            serde_json::to_string(&StyleId::Custom(42)).unwrap(),
+            r#"{"Custom":42}"#
+        );
+        assert_round_trip(&GridTrack::Auto);
+        assert_round_trip(&Color::rgba(1, 2, 3, 128));
+        assert_eq!(
+            serde_json::to_string(&Color::rgb(1, 2, 3)).unwrap(),
+            r#"{"r":1,"g":2,"b":3}"#
+        );
+        assert_round_trip(&SizeMode::Fixed);
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] The wire format is pinned: externally tagged variants, bare id numbers.
+    fn wire_format_is_stable() {
+        // Arrange
+        let command = PlatformCommand::SetControlText {
+            window_id: WINDOW,
+            control_id: CONTROL,
+            text: "Hi".into(),
+        };
+
+        // Act
+        let json = serde_json::to_string(&Versioned::new(command)).unwrap();
+
+        // Assert
+        assert_eq!(
+            json,
+            r#"{"version":1,"payload":{"SetControlText":{"window_id":1,"control_id":7,"text":"Hi"}}}"#
+        );
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] Version 1 layout rules written before the newer fields existed still load.
+    fn layout_rule_defaults_fields_added_after_version_one() {
+        // Arrange
+        let json = r#"{"control_id":3,"parent_control_id":null,"dock_style":"Top","order":0,"fixed_size":24,"margin":[0,0,0,0]}"#;
+
+        // Act
+        let rule: LayoutRule = serde_json::from_str(json).unwrap();
+
+        // Assert
+        assert_eq!(rule.fixed_size, Some(24));
+        assert_eq!(rule.size_mode, SizeMode::Fixed);
+        assert_eq!(rule.constraints, SizeConstraints::default());
+        assert!(rule.container.is_none());
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] Version 1 styles written before borders, padding and state colors existed still load.
+    fn control_style_defaults_fields_added_after_version_one() {
+        // Arrange
+        let json = r#"{"font":{"name":"Segoe UI","size":9,"weight":"Bold"},"text_color":{"r":1,"g":2,"b":3},"background_color":null}"#;
+
+        // Act
+        let style: ControlStyle = serde_json::from_str(json).unwrap();
+
+        // Assert
+        let font = style.font.as_ref().unwrap();
+        assert_eq!(font.size, Some(9));
+        assert!(font.italic.is_none() && font.underline.is_none());
+        assert_eq!(style.text_color, Some(Color::rgb(1, 2, 3)));
+        assert!(style.border_color.is_none());
+        assert!(style.padding.is_none());
+        assert!(style.hover.is_none() && style.pressed.is_none());
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] Themes written before the newer palettes existed still load.
+    fn theme_defaults_palettes_added_after_version_one() {
+        // Arrange
+        let mut json = serde_json::to_value(Theme::light()).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        fields.remove("slider");
+        fields.remove("status_bar");
+        fields.remove("toolbar");
+        fields.remove("tooltip");
+
+        // Act
+        let theme: Theme = serde_json::from_value(json).unwrap();
+
+        // Assert
+        assert_eq!(theme.colors, Theme::light().colors);
+        assert_eq!(theme.slider, SliderPalette::default());
+        assert_eq!(theme.status_bar, StatusBarPalette::default());
+        assert_eq!(theme.toolbar, ToolbarPalette::default());
+        assert_eq!(theme.tooltip, TooltipPalette::default());
+    }
+
+    #[test]
+    // [CDU-Tech-SerdeV1] Theme styles are keyed by name, so custom ids serialize to JSON too.
+    fn theme_styles_are_keyed_by_style_name() {
+        // Arrange
+        let theme = Theme::dark().with_style(StyleId::Custom(7), ControlStyle::default());
+
+        // Act
+        let json = serde_json::to_value(&theme).unwrap();
+        let back: Theme = serde_json::from_value(json.clone()).unwrap();
+
+        // Assert
+        let styles = json["styles"].as_object().unwrap();
+        assert!(styles.contains_key("DefaultText"));
+        assert!(styles.contains_key("Custom(7)"));
+        assert_eq!(back, theme);
+    }
+
+    #[test]
+    fn newer_format_version_is_rejected() {
+        // Arrange
+        let json = r#"{"version":99,"payload":"QuitApplication"}"#;
+        let envelope: Versioned<PlatformCommand> = serde_json::from_str(json).unwrap();
+
+        // Act
+        let result = envelope.into_payload();
+
+        // Assert
+        assert!(matches!(result, Err(PlatformError::OperationFailed(_))));
+    }
+}
@@ -0,0 +1,262 @@
+/*
+ * Value and position math of the slider control: snapping values to steps and
+ * converting between values and thumb positions. A `SliderRange` allows the
+ * values `min`, `min + step`, `min + 2 * step`, ... up to `max`; `max` itself
+ * is always reachable even when the span is not a multiple of `step`.
+ * Positions are offsets along the track, from the `min` end, in whatever unit
+ * the caller measures the track in.
+ * [CDU-Control-SliderV1]
+ */
+
+use crate::error::{PlatformError, Result as PlatformResult};
+
+/// Direction of the slider track. Vertical sliders have `max` at the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SliderOrientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// The values a slider can take and where its tick marks are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SliderRange {
+    pub min: i32,
+    pub max: i32,
+    #[cfg_attr(feature = "serde", serde(default = "SliderRange::default_step"))]
+    pub step: i32,
+    /// Distance between tick marks in value units; `None` draws no ticks.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tick_interval: Option<i32>,
+}
+
+impl SliderRange {
+    pub fn new(min: i32, max: i32) -> Self {
+        Self {
+            min,
+            max,
+            step: Self::default_step(),
+            tick_interval: None,
+        }
+    }
+
+    pub fn step(mut self, step: i32) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn ticks(mut self, interval: i32) -> Self {
+        self.tick_interval = Some(interval);
+        self
+    }
+
+    fn default_step() -> i32 {
+        1
+    }
+
+    /// Rejects an inverted range and non-positive steps or tick intervals.
+    pub fn validate(&self) -> PlatformResult<()> {
+        if self.max < self.min {
+            return Err(PlatformError::OperationFailed(format!(
+                "Slider range is inverted: min {} > max {}",
+                self.min, self.max
+            )));
+        }
+        if self.step <= 0 {
+            return Err(PlatformError::OperationFailed(format!(
+                "Slider step must be positive, got {}",
+                self.step
+            )));
+        }
+        if let Some(interval) = self.tick_interval
+            && interval <= 0
+        {
+            return Err(PlatformError::OperationFailed(format!(
+                "Slider tick interval must be positive, got {interval}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn span(&self) -> i64 {
+        i64::from(self.max) - i64::from(self.min)
+    }
+
+    // The largest allowed value at or below `value`, which must be in range.
+    fn floor_to_step(&self, value: i64) -> i64 {
+        let min = i64::from(self.min);
+        let step = i64::from(self.step.max(1));
+        min + (value - min) / step * step
+    }
+
+    /// Clamps `value` into the range and rounds it to the nearest allowed value (up on ties).
+    pub fn snap(&self, value: i32) -> i32 {
+        let value = i64::from(value.clamp(self.min, self.max.max(self.min)));
+        let lower = self.floor_to_step(value);
+        let upper = (lower + i64::from(self.step.max(1))).min(i64::from(self.max));
+        let snapped = if value - lower < upper - value {
+            lower
+        } else {
+            upper
+        };
+        snapped as i32
+    }
+
+    /*
+     * Moves `steps` allowed values up (positive) or down (negative) from
+     * `value`, stopping at the ends. Used for arrow and page keys.
+     */
+    pub fn step_by(&self, value: i32, steps: i32) -> i32 {
+        let current = i64::from(self.snap(value));
+        let step = i64::from(self.step.max(1));
+        let (min, max) = (i64::from(self.min), i64::from(self.max));
+        let start = self.floor_to_step(current);
+        let mut steps = i64::from(steps);
+        // From an off-grid `max` the first step down lands on the allowed value below it.
+        if steps < 0 && start != current {
+            steps += 1;
+        }
+        (start + steps * step).clamp(min, max) as i32
+    }
+
+    /// Offset of `value` along a track of `track_length`, from the `min` end, rounded.
+    pub fn position_of(&self, value: i32, track_length: i32) -> i32 {
+        let span = self.span();
+        if span <= 0 || track_length <= 0 {
+            return 0;
+        }
+        let offset = i64::from(value.clamp(self.min, self.max)) - i64::from(self.min);
+        ((2 * offset * i64::from(track_length) + span) / (2 * span)) as i32
+    }
+
+    /// The allowed value nearest to an offset along a track of `track_length`.
+    pub fn value_at(&self, position: i32, track_length: i32) -> i32 {
+        if track_length <= 0 {
+            return self.min;
+        }
+        let position = i64::from(position.clamp(0, track_length));
+        let length = i64::from(track_length);
+        let raw = i64::from(self.min) + (2 * position * self.span() + length) / (2 * length);
+        self.snap(raw as i32)
+    }
+
+    /// Values that get a tick mark: every `tick_interval` from `min`, plus `max`.
+    pub fn tick_values(&self) -> Vec<i32> {
+        let Some(interval) = self.tick_interval.filter(|i| *i > 0) else {
+            return Vec::new();
+        };
+        let mut ticks: Vec<i32> = (i64::from(self.min)..=i64::from(self.max))
+            .step_by(interval as usize)
+            .map(|value| value as i32)
+            .collect();
+        if ticks.last() != Some(&self.max) && self.max >= self.min {
+            ticks.push(self.max);
+        }
+        ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-SliderV1] Values snap to the nearest step and max stays reachable off the grid.
+    fn snap_rounds_to_steps_and_keeps_max_reachable() {
+        // Arrange
+        let range = SliderRange::new(0, 10).step(4);
+
+        // Act
+        let snapped: Vec<i32> = [-5, 1, 2, 5, 9, 10, 50]
+            .into_iter()
+            .map(|value| range.snap(value))
+            .collect();
+
+        // Assert
+        assert_eq!(snapped, vec![0, 0, 4, 4, 10, 10, 10]);
+    }
+
+    #[test]
+    // [CDU-Control-SliderV1] Keyboard steps walk the allowed values and stop at the ends.
+    fn step_by_walks_allowed_values() {
+        // Arrange
+        let range = SliderRange::new(-5, 8).step(5);
+
+        // Act
+        let up = range.step_by(-5, 2);
+        let to_max = range.step_by(5, 10);
+        let down_from_max = range.step_by(8, -1);
+        let to_min = range.step_by(0, -4);
+
+        // Assert
+        assert_eq!(up, 5);
+        assert_eq!(to_max, 8);
+        assert_eq!(down_from_max, 5);
+        assert_eq!(to_min, -5);
+    }
+
+    #[test]
+    // [CDU-Control-SliderV1] Huge step counts are computed directly and clamp to the ends.
+    fn step_by_handles_extreme_step_counts() {
+        // Arrange
+        let range = SliderRange::new(-5, 8).step(5);
+        let wide = SliderRange::new(i32::MIN, i32::MAX).step(i32::MAX);
+
+        // Act
+        let to_min = range.step_by(8, i32::MIN);
+        let to_max = range.step_by(-5, i32::MAX);
+        let wide_down = wide.step_by(i32::MAX, i32::MIN);
+        let wide_up = wide.step_by(i32::MIN, i32::MAX);
+
+        // Assert
+        assert_eq!(to_min, -5);
+        assert_eq!(to_max, 8);
+        assert_eq!(wide_down, i32::MIN);
+        assert_eq!(wide_up, i32::MAX);
+    }
+
+    #[test]
+    // [CDU-Control-SliderV1] Positions and values convert both ways along the track.
+    fn positions_and_values_round_trip() {
+        // Arrange
+        let range = SliderRange::new(10, 20).step(2);
+
+        // Act
+        let positions: Vec<i32> = [10, 15, 20]
+            .into_iter()
+            .map(|value| range.position_of(value, 200))
+            .collect();
+        let values: Vec<i32> = [-10, 39, 41, 100, 250]
+            .into_iter()
+            .map(|position| range.value_at(position, 200))
+            .collect();
+
+        // Assert
+        assert_eq!(positions, vec![0, 100, 200]);
+        assert_eq!(values, vec![10, 12, 12, 16, 20]);
+        assert_eq!(SliderRange::new(5, 5).position_of(5, 100), 0);
+        assert_eq!(range.value_at(50, 0), 10);
+    }
+
+    #[test]
+    fn tick_values_include_both_ends() {
+        // Act
+        let ticks = SliderRange::new(0, 10).ticks(4).tick_values();
+        let none = SliderRange::new(0, 10).tick_values();
+
+        // Assert
+        assert_eq!(ticks, vec![0, 4, 8, 10]);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn validate_rejects_inverted_ranges_and_bad_steps() {
+        // Act & Assert
+        assert!(SliderRange::new(0, 10).step(3).ticks(5).validate().is_ok());
+        assert!(SliderRange::new(3, 2).validate().is_err());
+        assert!(SliderRange::new(0, 10).step(0).validate().is_err());
+        assert!(SliderRange::new(0, 10).ticks(-1).validate().is_err());
+    }
+}
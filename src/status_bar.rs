@@ -0,0 +1,244 @@
+/*
+ * Segment layout of the status bar control: how the bar's width is split
+ * between its segments. Each `StatusSegment` is a fixed width in DIPs, as wide
+ * as its text (`Auto`) or an equal share of the space the other segments leave
+ * over (`Fill`). Widths are assigned left to right; when the bar is too narrow
+ * the fill segments shrink to nothing first, then the rightmost segments are
+ * clipped at the bar's edge.
+ * [CDU-Control-StatusBarV1]
+ */
+
+use crate::dpi::DpiScale;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::types::MessageSeverity;
+
+/// Space in DIPs between a segment's edges and its text or progress bar.
+pub const SEGMENT_PADDING_X: i32 = 6;
+
+/// How a status bar segment gets its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SegmentWidth {
+    /// A fixed width in DIPs.
+    Fixed(i32),
+    /// The width of the segment's current text plus padding.
+    #[default]
+    Auto,
+    /// An equal share of the width left by the other segments.
+    Fill,
+}
+
+/// One segment of a status bar, fixed when the bar is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusSegment {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub width: SegmentWidth,
+    /// Whether the segment can show a progress bar instead of its text.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub progress: bool,
+}
+
+impl StatusSegment {
+    pub fn fixed(dips: i32) -> Self {
+        Self {
+            width: SegmentWidth::Fixed(dips),
+            progress: false,
+        }
+    }
+
+    pub fn auto() -> Self {
+        Self::default()
+    }
+
+    pub fn fill() -> Self {
+        Self {
+            width: SegmentWidth::Fill,
+            progress: false,
+        }
+    }
+
+    /// Lets `SetStatusSegmentProgress` show a progress bar in this segment.
+    pub fn progress(mut self) -> Self {
+        self.progress = true;
+        self
+    }
+}
+
+/// What a segment currently shows; set with the `SetStatusSegment*` commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentContent {
+    pub text: String,
+    /// Picks the text color: warnings and errors use the palette's colors.
+    pub severity: MessageSeverity,
+    /// Percent done (0-100) while a progress bar replaces the text.
+    pub progress: Option<u32>,
+}
+
+impl Default for SegmentContent {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            severity: MessageSeverity::None,
+            progress: None,
+        }
+    }
+}
+
+/// Rejects an empty segment list and negative fixed widths.
+pub fn validate_segments(segments: &[StatusSegment]) -> PlatformResult<()> {
+    if segments.is_empty() {
+        return Err(PlatformError::OperationFailed(
+            "A status bar needs at least one segment".to_string(),
+        ));
+    }
+    if let Some(width) = segments.iter().find_map(|segment| match segment.width {
+        SegmentWidth::Fixed(width) if width < 0 => Some(width),
+        _ => None,
+    }) {
+        return Err(PlatformError::OperationFailed(format!(
+            "Status bar segment width must not be negative, got {width}"
+        )));
+    }
+    Ok(())
+}
+
+/*
+ * Splits `total_width` pixels into the segments' widths. `text_widths` holds
+ * the measured pixel width of each segment's text (missing entries count as
+ * empty); padding and fixed widths are scaled to `scale`. Leftover pixels of
+ * an uneven fill split go to the leftmost fill segments.
+ */
+pub fn segment_widths(
+    segments: &[StatusSegment],
+    text_widths: &[i32],
+    total_width: i32,
+    scale: DpiScale,
+) -> Vec<i32> {
+    let total_width = total_width.max(0);
+    let mut widths: Vec<i32> = segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| match segment.width {
+            SegmentWidth::Fixed(dips) => scale.scale(dips.max(0)),
+            SegmentWidth::Auto => {
+                text_widths.get(index).copied().unwrap_or(0).max(0)
+                    + scale.scale(2 * SEGMENT_PADDING_X)
+            }
+            SegmentWidth::Fill => 0,
+        })
+        .collect();
+
+    let fill_count = segments
+        .iter()
+        .filter(|segment| segment.width == SegmentWidth::Fill)
+        .count() as i32;
+    if fill_count > 0 {
+        let leftover = (total_width - widths.iter().sum::<i32>()).max(0);
+        let mut extra = leftover % fill_count;
+        for (width, segment) in widths.iter_mut().zip(segments) {
+            if segment.width == SegmentWidth::Fill {
+                *width = leftover / fill_count + i32::from(extra > 0);
+                extra -= 1;
+            }
+        }
+    }
+
+    let mut left = 0;
+    for width in &mut widths {
+        *width = (*width).min(total_width - left).max(0);
+        left += *width;
+    }
+    widths
+}
+
+/// The segment under `x` (pixels from the bar's left edge), given its widths.
+pub fn segment_at(widths: &[i32], x: i32) -> Option<usize> {
+    if x < 0 {
+        return None;
+    }
+    let mut right = 0;
+    widths.iter().position(|&width| {
+        right += width;
+        x < right
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-StatusBarV1] Fixed and auto segments keep their width and fill segments share the rest.
+    fn fill_segments_share_the_leftover_width() {
+        // Arrange
+        let segments = [
+            StatusSegment::fill(),
+            StatusSegment::auto(),
+            StatusSegment::fixed(50),
+            StatusSegment::fill(),
+        ];
+
+        // Act
+        let widths = segment_widths(&segments, &[0, 30, 0, 0], 200, DpiScale::DEFAULT);
+
+        // Assert
+        assert_eq!(widths, vec![54, 42, 50, 54]);
+    }
+
+    #[test]
+    // [CDU-Control-StatusBarV1] Narrow bars drop the fill space first, then clip the rightmost segments.
+    fn narrow_bars_clip_from_the_right() {
+        // Arrange
+        let segments = [
+            StatusSegment::fixed(60),
+            StatusSegment::fill(),
+            StatusSegment::fixed(60),
+            StatusSegment::auto(),
+        ];
+
+        // Act
+        let widths = segment_widths(&segments, &[], 100, DpiScale::DEFAULT);
+        let empty = segment_widths(&segments, &[], -5, DpiScale::DEFAULT);
+
+        // Assert
+        assert_eq!(widths, vec![60, 0, 40, 0]);
+        assert_eq!(empty, vec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn fixed_widths_and_padding_follow_the_dpi_scale() {
+        // Arrange
+        let segments = [StatusSegment::fixed(40), StatusSegment::auto()];
+
+        // Act
+        let widths = segment_widths(&segments, &[0, 20], 1000, DpiScale::new(192));
+
+        // Assert
+        assert_eq!(widths, vec![80, 44]);
+    }
+
+    #[test]
+    // [CDU-Control-StatusBarV1] Clicks map to the segment under the pointer.
+    fn segment_at_finds_the_segment_under_x() {
+        // Arrange
+        let widths = [10, 0, 20];
+
+        // Act
+        let hits: Vec<Option<usize>> = [-1, 0, 9, 10, 29, 30]
+            .into_iter()
+            .map(|x| segment_at(&widths, x))
+            .collect();
+
+        // Assert
+        assert_eq!(hits, vec![None, Some(0), Some(0), Some(2), Some(2), None]);
+    }
+
+    #[test]
+    fn validate_rejects_empty_bars_and_negative_widths() {
+        // Act & Assert
+        assert!(validate_segments(&[StatusSegment::fill(), StatusSegment::auto()]).is_ok());
+        assert!(validate_segments(&[]).is_err());
+        assert!(validate_segments(&[StatusSegment::fixed(-1)]).is_err());
+    }
+}
@@ -6,17 +6,17 @@
  * code can reference when defining UI appearance.
  */
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub struct Color {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
-}
+use std::collections::HashMap;
+
+use crate::error::{PlatformError, Result as PlatformResult};
+
+pub use crate::color::Color;
 
 /*
  * Defines the weight (e.g., boldness) of a font.
  */
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontWeight {
     #[default]
     Normal,
@@ -29,32 +29,248 @@ pub enum FontWeight {
  * default font.
  */
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct FontDescription {
     pub name: Option<String>,
     pub size: Option<i32>,
     pub weight: Option<FontWeight>,
-    // italic, underline, etc. can be added here
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub strikethrough: Option<bool>,
+}
+
+impl FontDescription {
+    /// Fills every field `self` leaves unset from `base`.
+    pub fn inherit_from(&self, base: &FontDescription) -> FontDescription {
+        FontDescription {
+            name: self.name.clone().or_else(|| base.name.clone()),
+            size: self.size.or(base.size),
+            weight: self.weight.clone().or_else(|| base.weight.clone()),
+            italic: self.italic.or(base.italic),
+            underline: self.underline.or(base.underline),
+            strikethrough: self.strikethrough.or(base.strikethrough),
+        }
+    }
+}
+
+/*
+ * Colors that replace a style's base colors while a control is in a given
+ * `InteractionState`. Unset colors fall back to the base style.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct StateColors {
+    pub text_color: Option<Color>,
+    pub background_color: Option<Color>,
+    pub border_color: Option<Color>,
+}
+
+impl StateColors {
+    /// Fills every color `self` leaves unset from `base`.
+    pub fn or(&self, base: &StateColors) -> StateColors {
+        StateColors {
+            text_color: self.text_color.clone().or_else(|| base.text_color.clone()),
+            background_color: self
+                .background_color
+                .clone()
+                .or_else(|| base.background_color.clone()),
+            border_color: self
+                .border_color
+                .clone()
+                .or_else(|| base.border_color.clone()),
+        }
+    }
+}
+
+/*
+ * The interaction state an owner-drawn control is painted in. Tab bars paint
+ * their selected tab, and toggle switches their "on" pill, as `Pressed`.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InteractionState {
+    Normal,
+    Hovered,
+    Pressed,
+    Focused,
+    Disabled,
 }
 
 /*
  * The master struct that holds all possible style properties for a control.
  * The UI description layer produces these and the platform layer consumes
- * them when rendering controls.
+ * them when rendering controls. A style may name a `base` style whose values
+ * fill every field it leaves unset; see `resolve_style`.
  */
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ControlStyle {
+    pub base: Option<StyleId>,
     pub font: Option<FontDescription>,
     pub text_color: Option<Color>,
     pub background_color: Option<Color>,
-    // Properties for border, hover, etc., will be added in later phases.
+    // Border and shape, drawn by owner-drawn controls (buttons, tab bars, toggle switches).
+    pub border_color: Option<Color>,
+    pub border_width: Option<i32>,
+    pub corner_radius: Option<i32>,
+    pub padding: Option<(i32, i32, i32, i32)>, // (top, right, bottom, left), like `LayoutRule::margin`.
+    // Per-state color overrides; see `colors_for`.
+    pub hover: Option<StateColors>,
+    pub pressed: Option<StateColors>,
+    pub disabled: Option<StateColors>,
+    pub focused: Option<StateColors>,
+}
+
+impl ControlStyle {
+    /*
+     * Fills every field `self` leaves unset from `base`. Fonts and state color
+     * sets merge field by field, so a style can change only the font size or
+     * only the hover text color of its base. The result keeps `self.base`.
+     */
+    pub fn inherit_from(&self, base: &ControlStyle) -> ControlStyle {
+        ControlStyle {
+            base: self.base,
+            font: merge_option(&self.font, &base.font, FontDescription::inherit_from),
+            text_color: self.text_color.clone().or_else(|| base.text_color.clone()),
+            background_color: self
+                .background_color
+                .clone()
+                .or_else(|| base.background_color.clone()),
+            border_color: self
+                .border_color
+                .clone()
+                .or_else(|| base.border_color.clone()),
+            border_width: self.border_width.or(base.border_width),
+            corner_radius: self.corner_radius.or(base.corner_radius),
+            padding: self.padding.or(base.padding),
+            hover: merge_option(&self.hover, &base.hover, StateColors::or),
+            pressed: merge_option(&self.pressed, &base.pressed, StateColors::or),
+            disabled: merge_option(&self.disabled, &base.disabled, StateColors::or),
+            focused: merge_option(&self.focused, &base.focused, StateColors::or),
+        }
+    }
+
+    /*
+     * Fills the inherited properties `self` leaves unset from the style of the
+     * control's parent panel: the font and the text and background colors.
+     * Borders, corner radius, padding and state colors belong to the control
+     * itself and are not inherited.
+     */
+    pub fn inherit_from_parent(&self, parent: &ControlStyle) -> ControlStyle {
+        ControlStyle {
+            font: merge_option(&self.font, &parent.font, FontDescription::inherit_from),
+            text_color: self
+                .text_color
+                .clone()
+                .or_else(|| parent.text_color.clone()),
+            background_color: self
+                .background_color
+                .clone()
+                .or_else(|| parent.background_color.clone()),
+            ..self.clone()
+        }
+    }
+
+    /// The base colors, used in `InteractionState::Normal`.
+    pub fn base_colors(&self) -> StateColors {
+        StateColors {
+            text_color: self.text_color.clone(),
+            background_color: self.background_color.clone(),
+            border_color: self.border_color.clone(),
+        }
+    }
+
+    /*
+     * Resolves the colors for `state`: the state's own overrides, then (for
+     * `Pressed` only) the hover overrides, then the base colors. A pressed
+     * control is usually also hovered, so a style that only sets `hover` keeps
+     * its hover look while the mouse button is down.
+     */
+    pub fn colors_for(&self, state: InteractionState) -> StateColors {
+        let base = self.base_colors();
+        let overrides = match state {
+            InteractionState::Normal => None,
+            InteractionState::Hovered => self.hover.as_ref(),
+            InteractionState::Pressed => self.pressed.as_ref().or(self.hover.as_ref()),
+            InteractionState::Focused => self.focused.as_ref(),
+            InteractionState::Disabled => self.disabled.as_ref(),
+        };
+        match overrides {
+            Some(overrides) => overrides.or(&base),
+            None => base,
+        }
+    }
+
+    /// The border width to draw in `state`: `border_width` (1 when unset) if
+    /// the state resolves a border color, otherwise 0.
+    pub fn border_width_for(&self, state: InteractionState) -> i32 {
+        if self.colors_for(state).border_color.is_some() {
+            self.border_width.unwrap_or(1).max(0)
+        } else {
+            0
+        }
+    }
+}
+
+fn merge_option<T: Clone>(own: &Option<T>, base: &Option<T>, merge: fn(&T, &T) -> T) -> Option<T> {
+    match (own, base) {
+        (Some(own), Some(base)) => Some(merge(own, base)),
+        (Some(value), None) | (None, Some(value)) => Some(value.clone()),
+        (None, None) => None,
+    }
+}
+
+/*
+ * Resolves `style_id` against `definitions` by merging each style over its
+ * `base` chain. Returns `Ok(None)` when `style_id` is not defined; a base that
+ * is not (yet) defined contributes nothing, so styles may be defined in any
+ * order. A chain that revisits a style is an error naming the cycle. The
+ * resolved style has no `base`. [CDU-Styling-InheritanceV1]
+ */
+pub fn resolve_style(
+    style_id: StyleId,
+    definitions: &HashMap<StyleId, ControlStyle>,
+) -> PlatformResult<Option<ControlStyle>> {
+    let Some(style) = definitions.get(&style_id) else {
+        return Ok(None);
+    };
+    let mut chain = vec![style_id];
+    let mut resolved = style.clone();
+    let mut next = style.base;
+    while let Some(base_id) = next {
+        if chain.contains(&base_id) {
+            chain.push(base_id);
+            let names: Vec<String> = chain.iter().map(|id| format!("{id:?}")).collect();
+            return Err(PlatformError::OperationFailed(format!(
+                "Style inheritance cycle: {}",
+                names.join(" -> ")
+            )));
+        }
+        chain.push(base_id);
+        let Some(base) = definitions.get(&base_id) else {
+            break;
+        };
+        resolved = resolved.inherit_from(base);
+        next = base.base;
+    }
+    resolved.base = None;
+    Ok(Some(resolved))
 }
 
 /*
  * A unique, semantic identifier for a reusable style definition. These IDs
  * are used by the application logic to refer to styles without embedding
- * platform-specific details.
+ * platform-specific details. The named variants are the built-in styles that
+ * themes define and controls look up; applications name their own styles with
+ * `Custom`, which works anywhere a built-in id does (`DefineStyle`,
+ * `ApplyStyleToControl`, `TreeItemDescriptor::style_override`).
+ * [CDU-Styling-CustomIdsV1]
  */
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StyleId {
     // General Controls
     DefaultText,
@@ -90,4 +306,256 @@ pub enum StyleId {
     // TabBar custom control
     TabBar,
     TabBarAccent,
+    // ListView control and its selected row
+    ListView,
+    ListViewSelectedRow,
+    // Application-defined style; the number is chosen by the application.
+    Custom(u32),
+}
+
+impl StyleId {
+    /// Every predefined style id, in declaration order.
+    pub const BUILT_IN: [StyleId; 28] = [
+        StyleId::DefaultText,
+        StyleId::DefaultButton,
+        StyleId::DefaultInput,
+        StyleId::MainWindowBackground,
+        StyleId::PanelBackground,
+        StyleId::StatusBarBackground,
+        StyleId::DefaultInputError,
+        StyleId::TreeView,
+        StyleId::StatusLabelNormal,
+        StyleId::StatusLabelWarning,
+        StyleId::StatusLabelError,
+        StyleId::ViewerMonospace,
+        StyleId::ViewerReadable,
+        StyleId::SummaryFolderText,
+        StyleId::SummaryFolderMissingFile,
+        StyleId::HeaderLabel,
+        StyleId::ProgressBar,
+        StyleId::Splitter,
+        StyleId::TreeItemDisabled,
+        StyleId::TreeViewSelectedRow,
+        StyleId::TreeViewSelectionAccent,
+        StyleId::ComboBox,
+        StyleId::RadioButton,
+        StyleId::CheckBox,
+        StyleId::TabBar,
+        StyleId::TabBarAccent,
+        StyleId::ListView,
+        StyleId::ListViewSelectedRow,
+    ];
+
+    pub const fn is_built_in(self) -> bool {
+        !matches!(self, StyleId::Custom(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(v: u8) -> Color {
+        Color::rgb(v, v, v)
+    }
+
+    #[test]
+    // [CDU-Styling-InteractionStatesV1] Undescribed states use the base colors.
+    fn state_colors_fall_back_to_base_colors() {
+        // Arrange
+        let style = ControlStyle {
+            text_color: Some(color(1)),
+            background_color: Some(color(2)),
+            border_color: Some(color(3)),
+            hover: Some(StateColors {
+                background_color: Some(color(20)),
+                ..StateColors::default()
+            }),
+            disabled: Some(StateColors {
+                text_color: Some(color(40)),
+                ..StateColors::default()
+            }),
+            ..ControlStyle::default()
+        };
+
+        // Act
+        let hovered = style.colors_for(InteractionState::Hovered);
+        let disabled = style.colors_for(InteractionState::Disabled);
+        let focused = style.colors_for(InteractionState::Focused);
+
+        // Assert
+        assert_eq!(hovered.background_color, Some(color(20)));
+        assert_eq!(hovered.text_color, Some(color(1)));
+        assert_eq!(disabled.text_color, Some(color(40)));
+        assert_eq!(disabled.border_color, Some(color(3)));
+        assert_eq!(focused, style.base_colors());
+    }
+
+    #[test]
+    // [CDU-Styling-InteractionStatesV1] Pressed inherits hover overrides it does not set.
+    fn pressed_state_falls_back_to_hover_overrides() {
+        // Arrange
+        let hover_only = ControlStyle {
+            hover: Some(StateColors {
+                background_color: Some(color(20)),
+                ..StateColors::default()
+            }),
+            ..ControlStyle::default()
+        };
+        let with_pressed = ControlStyle {
+            pressed: Some(StateColors {
+                background_color: Some(color(30)),
+                ..StateColors::default()
+            }),
+            ..hover_only.clone()
+        };
+
+        // Act
+        let from_hover = hover_only.colors_for(InteractionState::Pressed);
+        let from_pressed = with_pressed.colors_for(InteractionState::Pressed);
+
+        // Assert
+        assert_eq!(from_hover.background_color, Some(color(20)));
+        assert_eq!(from_pressed.background_color, Some(color(30)));
+    }
+
+    #[test]
+    // [CDU-Styling-InteractionStatesV1] Borders are drawn only where a border color resolves.
+    fn border_is_drawn_only_in_states_with_a_border_color() {
+        // Arrange
+        let width_only = ControlStyle {
+            border_width: Some(2),
+            ..ControlStyle::default()
+        };
+        let complete = ControlStyle {
+            border_color: Some(color(9)),
+            ..width_only.clone()
+        };
+        let focus_ring = ControlStyle {
+            focused: Some(StateColors {
+                border_color: Some(color(9)),
+                ..StateColors::default()
+            }),
+            ..ControlStyle::default()
+        };
+
+        // Act / Assert
+        assert_eq!(width_only.border_width_for(InteractionState::Normal), 0);
+        assert_eq!(complete.border_width_for(InteractionState::Hovered), 2);
+        assert_eq!(focus_ring.border_width_for(InteractionState::Normal), 0);
+        assert_eq!(focus_ring.border_width_for(InteractionState::Focused), 1);
+    }
+
+    fn font(name: &str, size: i32) -> FontDescription {
+        FontDescription {
+            name: Some(name.to_string()),
+            size: Some(size),
+            ..FontDescription::default()
+        }
+    }
+
+    #[test]
+    // [CDU-Styling-InheritanceV1] A style fills unset fields down its base chain, font field by field.
+    fn resolve_style_merges_the_base_chain() {
+        // Arrange
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            StyleId::StatusLabelNormal,
+            ControlStyle {
+                font: Some(font("Segoe UI", 9)),
+                text_color: Some(color(200)),
+                background_color: Some(color(30)),
+                ..ControlStyle::default()
+            },
+        );
+        definitions.insert(
+            StyleId::StatusLabelWarning,
+            ControlStyle {
+                base: Some(StyleId::StatusLabelNormal),
+                text_color: Some(color(250)),
+                ..ControlStyle::default()
+            },
+        );
+        definitions.insert(
+            StyleId::Custom(1),
+            ControlStyle {
+                base: Some(StyleId::StatusLabelWarning),
+                font: Some(FontDescription {
+                    italic: Some(true),
+                    ..FontDescription::default()
+                }),
+                ..ControlStyle::default()
+            },
+        );
+
+        // Act
+        let resolved = resolve_style(StyleId::Custom(1), &definitions)
+            .unwrap()
+            .unwrap();
+
+        // Assert
+        assert!(resolved.base.is_none());
+        assert_eq!(resolved.text_color, Some(color(250)));
+        assert_eq!(resolved.background_color, Some(color(30)));
+        let resolved_font = resolved.font.unwrap();
+        assert_eq!(resolved_font.name.as_deref(), Some("Segoe UI"));
+        assert_eq!(resolved_font.size, Some(9));
+        assert_eq!(resolved_font.italic, Some(true));
+        assert!(
+            resolve_style(StyleId::HeaderLabel, &definitions)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    // [CDU-Styling-InheritanceV1] Inheritance cycles are reported instead of looping.
+    fn resolve_style_rejects_cycles() {
+        // Arrange
+        let based_on = |base| ControlStyle {
+            base: Some(base),
+            ..ControlStyle::default()
+        };
+        let mut definitions = HashMap::new();
+        definitions.insert(StyleId::Custom(1), based_on(StyleId::Custom(2)));
+        definitions.insert(StyleId::Custom(2), based_on(StyleId::Custom(1)));
+        definitions.insert(StyleId::Custom(3), based_on(StyleId::Custom(3)));
+
+        // Act
+        let two_step = resolve_style(StyleId::Custom(1), &definitions);
+        let self_reference = resolve_style(StyleId::Custom(3), &definitions);
+
+        // Assert
+        assert!(matches!(two_step, Err(PlatformError::OperationFailed(msg))
+            if msg.contains("Custom(1) -> Custom(2) -> Custom(1)")));
+        assert!(self_reference.is_err());
+    }
+
+    #[test]
+    // [CDU-Styling-InheritanceV1] Controls inherit font and colors, but not box properties, from their parent panel.
+    fn parent_style_supplies_only_inherited_properties() {
+        // Arrange
+        let panel = ControlStyle {
+            font: Some(font("Segoe UI", 10)),
+            text_color: Some(color(220)),
+            background_color: Some(color(40)),
+            border_color: Some(color(90)),
+            padding: Some((4, 4, 4, 4)),
+            ..ControlStyle::default()
+        };
+        let label = ControlStyle {
+            text_color: Some(color(255)),
+            ..ControlStyle::default()
+        };
+
+        // Act
+        let effective = label.inherit_from_parent(&panel);
+
+        // Assert
+        assert_eq!(effective.text_color, Some(color(255)));
+        assert_eq!(effective.background_color, Some(color(40)));
+        assert_eq!(effective.font, Some(font("Segoe UI", 10)));
+        assert!(effective.border_color.is_none());
+        assert!(effective.padding.is_none());
+    }
 }
@@ -7,7 +7,19 @@
 
 pub use super::styling_primitives::{Color, ControlStyle, FontWeight, StyleId};
 
-use windows::Win32::Graphics::Gdi::{DeleteObject, HBRUSH, HFONT, HGDIOBJ};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::controls::styling_handler;
+use crate::dpi::DpiScale;
+use crate::styling_primitives::FontDescription;
+use crate::window_common;
+use windows::Win32::Foundation::{GetLastError, HWND};
+use windows::Win32::Graphics::Gdi::{
+    CLIP_DEFAULT_PRECIS, CreateFontW, DEFAULT_CHARSET, DEFAULT_QUALITY, DeleteObject, FF_DONTCARE,
+    FW_BOLD, FW_NORMAL, HBRUSH, HFONT, HGDIOBJ, OUT_DEFAULT_PRECIS,
+};
+use windows::core::HSTRING;
 
 /*
  * The `platform_layer`'s internal, processed representation of a `ControlStyle`.
@@ -15,13 +27,17 @@ use windows::Win32::Graphics::Gdi::{DeleteObject, HBRUSH, HFONT, HGDIOBJ};
  * created from the platform-agnostic descriptions. This encapsulates Win32-specific
  * types and handles their cleanup via the `Drop` trait.
  */
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub(crate) struct ParsedControlStyle {
-    pub(crate) font_handle: Option<HFONT>,
+    // The style's font at each DPI it has been used at, created on demand.
+    // [CDU-DpiAwarenessV1]
+    fonts: Mutex<HashMap<u32, HFONT>>,
     pub(crate) text_color: Option<Color>,
     pub(crate) background_color: Option<Color>,
     pub(crate) background_brush: Option<HBRUSH>,
-    // ... other parsed properties ...
+    // The description this style was parsed from. Owner-draw paths resolve
+    // interaction-state colors, borders, corner radius and padding from it.
+    pub(crate) source: ControlStyle,
 }
 
 // SAFETY: Parsed styles are created, consumed, and destroyed on the platform thread.
@@ -30,6 +46,80 @@ pub(crate) struct ParsedControlStyle {
 unsafe impl Send for ParsedControlStyle {}
 unsafe impl Sync for ParsedControlStyle {}
 
+impl ParsedControlStyle {
+    pub(crate) fn new(source: ControlStyle, background_brush: Option<HBRUSH>) -> Self {
+        Self {
+            fonts: Mutex::new(HashMap::new()),
+            text_color: source.text_color.clone(),
+            background_color: source.background_color.clone(),
+            background_brush,
+            source,
+        }
+    }
+
+    /*
+     * The style's font at `scale`, or `None` if the style has no font or it
+     * could not be created. Fonts are cached per DPI and live as long as the
+     * style.
+     */
+    pub(crate) fn font_for_dpi(&self, scale: DpiScale) -> Option<HFONT> {
+        let description = self.source.font.as_ref()?;
+        let mut fonts = self.fonts.lock().ok()?;
+        if let Some(font) = fonts.get(&scale.dpi) {
+            return Some(*font);
+        }
+        let font = create_font(description, scale)?;
+        fonts.insert(scale.dpi, font);
+        Some(font)
+    }
+
+    /// The style's font at the DPI of the monitor `hwnd` is on.
+    pub(crate) fn font_for_window(&self, hwnd: HWND) -> Option<HFONT> {
+        self.font_for_dpi(window_common::dpi_scale_for_window(hwnd))
+    }
+}
+
+// Creates a font from a description, with its point size converted at `scale`.
+fn create_font(font_desc: &FontDescription, scale: DpiScale) -> Option<HFONT> {
+    let logical_font_height = font_desc
+        .size
+        .map_or(0, |point_size| -scale.font_pixel_size(point_size));
+    let weight = match font_desc.weight {
+        Some(FontWeight::Bold) => FW_BOLD.0 as i32,
+        _ => FW_NORMAL.0 as i32,
+    };
+    let font_name = font_desc.name.as_deref().unwrap_or("MS Shell Dlg 2");
+    let (italic, underline, strike_out) = styling_handler::font_decoration_flags(font_desc);
+
+    let h_font = unsafe {
+        CreateFontW(
+            logical_font_height,
+            0,
+            0,
+            0,
+            weight,
+            italic,
+            underline,
+            strike_out,
+            DEFAULT_CHARSET,
+            OUT_DEFAULT_PRECIS,
+            CLIP_DEFAULT_PRECIS,
+            DEFAULT_QUALITY,
+            FF_DONTCARE.0 as u32,
+            &HSTRING::from(font_name),
+        )
+    };
+    if h_font.is_invalid() {
+        log::error!(
+            "Styling: CreateFontW failed for {font_desc:?} at {} DPI: {:?}",
+            scale.dpi,
+            unsafe { GetLastError() }
+        );
+        return None;
+    }
+    Some(h_font)
+}
+
 impl Drop for ParsedControlStyle {
     /*
      * Ensures that native GDI resources, such as HFONTs and HBRUSHes, are properly
@@ -37,9 +127,11 @@ impl Drop for ParsedControlStyle {
      * resource leaks.
      */
     fn drop(&mut self) {
-        if let Some(hfont) = self.font_handle.take()
-            && !hfont.is_invalid()
-        {
+        let fonts = match self.fonts.get_mut() {
+            Ok(fonts) => std::mem::take(fonts),
+            Err(poisoned) => std::mem::take(poisoned.into_inner()),
+        };
+        for hfont in fonts.into_values().filter(|f| !f.is_invalid()) {
             // It's safe to call DeleteObject on a font handle.
             unsafe {
                 _ = DeleteObject(HGDIOBJ(hfont.0));
@@ -0,0 +1,735 @@
+/*
+ * Runtime themes. A `Theme` maps every built-in `StyleId` to a `ControlStyle`
+ * and carries the palettes of the owner-drawn controls (chart, splitter, tab
+ * bar, toggle switch, tooltips, sunken borders), so the whole look of an
+ * application can be switched with one `PlatformCommand::ApplyTheme`. Both are
+ * derived from a small set of semantic `ThemeColors`; `Theme::dark` and
+ * `Theme::light` are the built-in themes, and applications can start from
+ * either and override styles or palette entries.
+ *
+ * The palettes' `Default` implementations are the dark theme, which is what
+ * the controls use until a theme is applied.
+ * [CDU-Styling-ThemeV1]
+ */
+
+use std::collections::BTreeMap;
+
+use crate::styling_primitives::{
+    Color, ControlStyle, FontDescription, FontWeight, InteractionState, StateColors, StyleId,
+};
+
+/// The semantic colors a theme is built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeColors {
+    pub window_background: Color,
+    pub panel_background: Color,
+    /// Background of inputs, tree views, viewers and charts.
+    pub surface: Color,
+    /// Resting fill of buttons and the "off" toggle pill.
+    pub control: Color,
+    pub text: Color,
+    pub secondary_text: Color,
+    pub disabled_text: Color,
+    pub muted_text: Color,
+    pub accent: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub selection: Color,
+    pub grid: Color,
+    pub divider: Color,
+    pub divider_hover: Color,
+    pub border: Color,
+    /// Toggle switch knob.
+    pub knob: Color,
+}
+
+impl ThemeColors {
+    pub const DARK: Self = Self {
+        window_background: Color::rgb(0x2E, 0x32, 0x39),
+        panel_background: Color::rgb(0x2B, 0x2B, 0x2B),
+        surface: Color::rgb(0x1E, 0x22, 0x28),
+        control: Color::rgb(0x4B, 0x4F, 0x57),
+        text: Color::rgb(0xE0, 0xE5, 0xEC),
+        secondary_text: Color::rgb(0xCC, 0xCC, 0xCC),
+        disabled_text: Color::rgb(0x80, 0x85, 0x8C),
+        muted_text: Color::rgb(0x80, 0x80, 0x80),
+        accent: Color::rgb(0x00, 0x80, 0xFF),
+        warning: Color::rgb(0xE5, 0xC0, 0x7B),
+        error: Color::rgb(0xE0, 0x6C, 0x75),
+        selection: Color::rgb(0x26, 0x4F, 0x78),
+        grid: Color::rgb(0x3A, 0x3F, 0x47),
+        divider: Color::rgb(0x40, 0x44, 0x4B),
+        divider_hover: Color::rgb(0x55, 0x5A, 0x64),
+        border: Color::rgb(0x60, 0x60, 0x60),
+        knob: Color::rgb(0xF0, 0xF0, 0xF0),
+    };
+
+    pub const LIGHT: Self = Self {
+        window_background: Color::rgb(0xF3, 0xF3, 0xF3),
+        panel_background: Color::rgb(0xFA, 0xFA, 0xFA),
+        surface: Color::rgb(0xFF, 0xFF, 0xFF),
+        control: Color::rgb(0xC8, 0xCC, 0xD2),
+        text: Color::rgb(0x1F, 0x23, 0x28),
+        secondary_text: Color::rgb(0x4A, 0x4F, 0x57),
+        disabled_text: Color::rgb(0xA0, 0xA4, 0xAA),
+        muted_text: Color::rgb(0x80, 0x80, 0x80),
+        accent: Color::rgb(0x00, 0x67, 0xC0),
+        warning: Color::rgb(0x9A, 0x67, 0x00),
+        error: Color::rgb(0xC4, 0x2B, 0x1C),
+        selection: Color::rgb(0xCC, 0xE4, 0xF7),
+        grid: Color::rgb(0xDD, 0xE1, 0xE6),
+        divider: Color::rgb(0xD0, 0xD4, 0xDA),
+        divider_hover: Color::rgb(0xB4, 0xBA, 0xC2),
+        border: Color::rgb(0xA0, 0xA4, 0xAA),
+        knob: Color::rgb(0xFF, 0xFF, 0xFF),
+    };
+}
+
+/// Colors of the owner-drawn line chart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChartPalette {
+    pub background: Color,
+    pub grid: Color,
+    /// The "Loading…" placeholder.
+    pub text: Color,
+}
+
+impl ChartPalette {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            background: colors.surface.clone(),
+            grid: colors.grid.clone(),
+            text: colors.muted_text.clone(),
+        }
+    }
+}
+
+impl Default for ChartPalette {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/// Colors of the splitter bar at rest and under the mouse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitterPalette {
+    pub normal: Color,
+    pub hover: Color,
+}
+
+impl SplitterPalette {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            normal: colors.divider.clone(),
+            hover: colors.divider_hover.clone(),
+        }
+    }
+}
+
+impl Default for SplitterPalette {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/// The primary tab bar colors; the inactive text and hover fill are derived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabBarColors {
+    pub background: Color,
+    pub text: Color,
+    pub accent: Color,
+}
+
+impl TabBarColors {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            background: colors.window_background.clone(),
+            text: colors.text.clone(),
+            accent: colors.accent.clone(),
+        }
+    }
+}
+
+impl Default for TabBarColors {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/// Colors of the sliding toggle switch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToggleSwitchPalette {
+    pub background: Color,
+    pub pill_off: Color,
+    pub pill_on: Color,
+    pub knob: Color,
+    pub text: Color,
+}
+
+impl ToggleSwitchPalette {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            background: colors.panel_background.clone(),
+            pill_off: colors.control.clone(),
+            pill_on: colors.accent.clone(),
+            knob: colors.knob.clone(),
+            text: colors.secondary_text.clone(),
+        }
+    }
+}
+
+impl Default for ToggleSwitchPalette {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/// Colors of the slider: track, the filled part up to the thumb, thumb and tick marks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SliderPalette {
+    pub background: Color,
+    pub track: Color,
+    pub fill: Color,
+    pub thumb: Color,
+    pub tick: Color,
+}
+
+impl SliderPalette {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            background: colors.panel_background.clone(),
+            track: colors.control.clone(),
+            fill: colors.accent.clone(),
+            thumb: colors.knob.clone(),
+            tick: colors.secondary_text.clone(),
+        }
+    }
+}
+
+impl Default for SliderPalette {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/// Colors of the status bar: text per severity, segment separators and progress fill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusBarPalette {
+    pub background: Color,
+    pub text: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub separator: Color,
+    /// Empty part of a segment's progress bar.
+    pub progress_track: Color,
+    pub progress_fill: Color,
+}
+
+impl StatusBarPalette {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            background: colors.window_background.clone(),
+            text: colors.secondary_text.clone(),
+            warning: colors.warning.clone(),
+            error: colors.error.clone(),
+            separator: colors.divider.clone(),
+            progress_track: colors.surface.clone(),
+            progress_fill: colors.accent.clone(),
+        }
+    }
+}
+
+impl Default for StatusBarPalette {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/// Colors of the toolbar: button text, hover/pressed/checked fills and separators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToolbarPalette {
+    pub background: Color,
+    pub text: Color,
+    pub disabled_text: Color,
+    pub hover: Color,
+    pub pressed: Color,
+    /// Fill of toggle buttons while checked.
+    pub checked: Color,
+    pub separator: Color,
+}
+
+impl ToolbarPalette {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            background: colors.window_background.clone(),
+            text: colors.text.clone(),
+            disabled_text: colors.disabled_text.clone(),
+            hover: colors.control.clone(),
+            pressed: colors.selection.clone(),
+            checked: colors.selection.clone(),
+            separator: colors.divider.clone(),
+        }
+    }
+}
+
+impl Default for ToolbarPalette {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/// Colors of tooltips, including the tree view's item tips.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TooltipPalette {
+    pub background: Color,
+    pub text: Color,
+}
+
+impl TooltipPalette {
+    pub fn from_colors(colors: &ThemeColors) -> Self {
+        Self {
+            background: colors.control.clone(),
+            text: colors.text.clone(),
+        }
+    }
+}
+
+impl Default for TooltipPalette {
+    fn default() -> Self {
+        Self::from_colors(&ThemeColors::DARK)
+    }
+}
+
+/*
+ * `Theme::styles` as a map keyed by style name. Formats such as JSON only
+ * allow string keys, which `StyleId::Custom` is not, so built-in ids keep the
+ * variant name they always serialized as and custom ids become `Custom(n)`.
+ */
+#[cfg(feature = "serde")]
+mod style_map {
+    use std::collections::BTreeMap;
+
+    use serde::de::{Deserialize, Deserializer, Error, value::StrDeserializer};
+    use serde::ser::Serializer;
+
+    use crate::styling_primitives::{ControlStyle, StyleId};
+
+    // Unit variants debug-format as their serde name.
+    pub(super) fn key(style_id: StyleId) -> String {
+        match style_id {
+            StyleId::Custom(n) => format!("Custom({n})"),
+            built_in => format!("{built_in:?}"),
+        }
+    }
+
+    pub(super) fn parse_key<E: Error>(key: &str) -> Result<StyleId, E> {
+        match key
+            .strip_prefix("Custom(")
+            .and_then(|n| n.strip_suffix(')'))
+        {
+            Some(n) => n
+                .parse()
+                .map(StyleId::Custom)
+                .map_err(|_| E::custom(format!("invalid custom style id `{key}`"))),
+            None => StyleId::deserialize(StrDeserializer::<E>::new(key)),
+        }
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        styles: &BTreeMap<StyleId, ControlStyle>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            styles
+                .iter()
+                .map(|(&style_id, style)| (key(style_id), style)),
+        )
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<StyleId, ControlStyle>, D::Error> {
+        BTreeMap::<String, ControlStyle>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, style)| Ok((parse_key(&key)?, style)))
+            .collect()
+    }
+}
+
+/*
+ * A complete theme. `styles` is applied with `DefineStyle` semantics, so
+ * controls that already carry one of these `StyleId`s pick up the new look;
+ * the palettes are pushed into the owner-drawn controls. `is_dark` selects the
+ * native dark or light rendering of title bars, menus and scroll bars.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    pub name: String,
+    pub is_dark: bool,
+    pub colors: ThemeColors,
+    #[cfg_attr(feature = "serde", serde(with = "style_map"))]
+    pub styles: BTreeMap<StyleId, ControlStyle>,
+    pub chart: ChartPalette,
+    pub splitter: SplitterPalette,
+    pub tab_bar: TabBarColors,
+    pub toggle_switch: ToggleSwitchPalette,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub slider: SliderPalette,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status_bar: StatusBarPalette,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub toolbar: ToolbarPalette,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tooltip: TooltipPalette,
+    /// Border drawn over the sunken edge of combo boxes and progress bars.
+    pub border: Color,
+}
+
+impl Theme {
+    /// Derives every built-in style and palette from `colors`.
+    pub fn from_colors(name: impl Into<String>, is_dark: bool, colors: ThemeColors) -> Self {
+        let styles = StyleId::BUILT_IN
+            .iter()
+            .map(|&style_id| (style_id, base_style(style_id, &colors)))
+            .collect();
+        Self {
+            name: name.into(),
+            is_dark,
+            styles,
+            chart: ChartPalette::from_colors(&colors),
+            splitter: SplitterPalette::from_colors(&colors),
+            tab_bar: TabBarColors::from_colors(&colors),
+            toggle_switch: ToggleSwitchPalette::from_colors(&colors),
+            slider: SliderPalette::from_colors(&colors),
+            status_bar: StatusBarPalette::from_colors(&colors),
+            toolbar: ToolbarPalette::from_colors(&colors),
+            tooltip: TooltipPalette::from_colors(&colors),
+            border: colors.border.clone(),
+            colors,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self::from_colors("Dark", true, ThemeColors::DARK)
+    }
+
+    pub fn light() -> Self {
+        Self::from_colors("Light", false, ThemeColors::LIGHT)
+    }
+
+    pub fn style(&self, style_id: StyleId) -> Option<&ControlStyle> {
+        self.styles.get(&style_id)
+    }
+
+    /// Replaces (or adds) one style.
+    pub fn with_style(mut self, style_id: StyleId, style: ControlStyle) -> Self {
+        self.styles.insert(style_id, style);
+        self
+    }
+
+    /*
+     * The tab bar colors as the `TabBar`/`TabBarAccent` styles resolve them:
+     * background and text from `TabBar`, accent from the `TabBarAccent`
+     * background, each falling back to the `tab_bar` palette.
+     */
+    pub fn resolved_tab_bar_colors(&self) -> TabBarColors {
+        let tab_bar = self.style(StyleId::TabBar);
+        let accent = self.style(StyleId::TabBarAccent);
+        TabBarColors {
+            background: tab_bar
+                .and_then(|s| s.background_color.clone())
+                .unwrap_or_else(|| self.tab_bar.background.clone()),
+            text: tab_bar
+                .and_then(|s| s.text_color.clone())
+                .unwrap_or_else(|| self.tab_bar.text.clone()),
+            accent: accent
+                .and_then(|s| s.background_color.clone())
+                .unwrap_or_else(|| self.tab_bar.accent.clone()),
+        }
+    }
+}
+
+/// A style state whose text would be hard to read on its background.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastWarning {
+    pub style_id: StyleId,
+    pub state: InteractionState,
+    pub text: Color,
+    pub background: Color,
+    pub ratio: f64,
+}
+
+impl Theme {
+    /*
+     * Checks the text of every style against its background, in the normal,
+     * hovered, pressed and focused states, and reports each combination whose
+     * WCAG contrast ratio is below `min_ratio` (typically
+     * `color::WCAG_AA_NORMAL_TEXT`). Styles without a background are checked
+     * against `colors.panel_background`, and translucent colors are composited
+     * first. Disabled states, `TreeItemDisabled` and styles whose text color is
+     * a fill (progress bars, accents) are exempt.
+     * [CDU-Styling-ColorV1]
+     */
+    pub fn contrast_warnings(&self, min_ratio: f64) -> Vec<ContrastWarning> {
+        const CHECKED_STATES: [InteractionState; 4] = [
+            InteractionState::Normal,
+            InteractionState::Hovered,
+            InteractionState::Pressed,
+            InteractionState::Focused,
+        ];
+        let mut warnings = Vec::new();
+        for (&style_id, style) in &self.styles {
+            if !has_readable_text(style_id) {
+                continue;
+            }
+            for state in CHECKED_STATES {
+                let colors = style.colors_for(state);
+                let Some(text) = colors.text_color else {
+                    continue;
+                };
+                let background = colors.background_color.map_or_else(
+                    || self.colors.panel_background.clone(),
+                    |bg| bg.over(&self.colors.panel_background),
+                );
+                let text = text.over(&background);
+                let ratio = text.contrast_ratio(&background);
+                if ratio < min_ratio {
+                    warnings.push(ContrastWarning {
+                        style_id,
+                        state,
+                        text,
+                        background,
+                        ratio,
+                    });
+                }
+            }
+        }
+        warnings
+    }
+}
+
+/// Whether a style's text color is used for text that must stay readable.
+fn has_readable_text(style_id: StyleId) -> bool {
+    !matches!(
+        style_id,
+        StyleId::ProgressBar
+            | StyleId::TreeItemDisabled
+            | StyleId::TreeViewSelectionAccent
+            | StyleId::TabBarAccent
+    )
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+fn base_style(style_id: StyleId, c: &ThemeColors) -> ControlStyle {
+    let colored = |text: &Color, background: Option<&Color>| ControlStyle {
+        text_color: Some(text.clone()),
+        background_color: background.cloned(),
+        ..ControlStyle::default()
+    };
+    let with_font = |style: ControlStyle, font: FontDescription| ControlStyle {
+        font: Some(font),
+        ..style
+    };
+    match style_id {
+        StyleId::DefaultText
+        | StyleId::PanelBackground
+        | StyleId::RadioButton
+        | StyleId::CheckBox
+        | StyleId::SummaryFolderText => colored(&c.text, Some(&c.panel_background)),
+        StyleId::DefaultButton => ControlStyle {
+            border_color: Some(c.border.clone()),
+            border_width: Some(1),
+            corner_radius: Some(4),
+            hover: Some(StateColors {
+                background_color: Some(c.divider_hover.clone()),
+                ..StateColors::default()
+            }),
+            pressed: Some(StateColors {
+                background_color: Some(c.selection.clone()),
+                border_color: Some(c.accent.clone()),
+                ..StateColors::default()
+            }),
+            disabled: Some(StateColors {
+                text_color: Some(c.disabled_text.clone()),
+                ..StateColors::default()
+            }),
+            focused: Some(StateColors {
+                border_color: Some(c.accent.clone()),
+                ..StateColors::default()
+            }),
+            ..colored(&c.text, Some(&c.control))
+        },
+        StyleId::DefaultInput | StyleId::TreeView | StyleId::ComboBox | StyleId::ListView => {
+            colored(&c.text, Some(&c.surface))
+        }
+        StyleId::MainWindowBackground | StyleId::TabBar => {
+            colored(&c.text, Some(&c.window_background))
+        }
+        StyleId::StatusBarBackground | StyleId::StatusLabelNormal => {
+            colored(&c.secondary_text, Some(&c.window_background))
+        }
+        StyleId::DefaultInputError => colored(&c.error, Some(&c.surface)),
+        StyleId::StatusLabelWarning => colored(&c.warning, Some(&c.window_background)),
+        StyleId::StatusLabelError => colored(&c.error, Some(&c.window_background)),
+        StyleId::ViewerMonospace => with_font(
+            colored(&c.text, Some(&c.surface)),
+            FontDescription {
+                name: Some("Consolas".to_string()),
+                size: Some(10),
+                weight: None,
+                ..FontDescription::default()
+            },
+        ),
+        StyleId::ViewerReadable => with_font(
+            colored(&c.text, Some(&c.surface)),
+            FontDescription {
+                name: Some("Segoe UI".to_string()),
+                size: Some(10),
+                weight: None,
+                ..FontDescription::default()
+            },
+        ),
+        StyleId::SummaryFolderMissingFile => colored(&c.error, None),
+        StyleId::HeaderLabel => with_font(
+            colored(&c.text, Some(&c.panel_background)),
+            FontDescription {
+                name: None,
+                size: None,
+                weight: Some(FontWeight::Bold),
+                ..FontDescription::default()
+            },
+        ),
+        // Progress bars use the text color as the bar fill.
+        StyleId::ProgressBar => colored(&c.accent, Some(&c.surface)),
+        StyleId::Splitter => colored(&c.text, Some(&c.divider)),
+        StyleId::TreeItemDisabled => colored(&c.disabled_text, None),
+        StyleId::TreeViewSelectedRow | StyleId::ListViewSelectedRow => {
+            colored(&c.text, Some(&c.selection))
+        }
+        StyleId::TreeViewSelectionAccent | StyleId::TabBarAccent => {
+            colored(&c.accent, Some(&c.accent))
+        }
+        // Application styles are defined by the application, not the theme.
+        StyleId::Custom(_) => ControlStyle::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::WCAG_AA_LARGE_TEXT;
+
+    #[test]
+    // [CDU-Styling-ThemeV1] Built-in themes give every built-in StyleId a style.
+    fn built_in_themes_define_every_built_in_style() {
+        // Arrange
+        let themes = [Theme::dark(), Theme::light()];
+
+        // Act
+        let missing: Vec<(&str, StyleId)> = themes
+            .iter()
+            .flat_map(|theme| {
+                StyleId::BUILT_IN
+                    .iter()
+                    .filter(|id| {
+                        theme
+                            .style(**id)
+                            .and_then(|s| s.text_color.as_ref())
+                            .is_none()
+                    })
+                    .map(move |id| (theme.name.as_str(), *id))
+            })
+            .collect();
+
+        // Assert
+        assert!(missing.is_empty(), "styles without colors: {missing:?}");
+        assert_eq!(themes[0].styles.len(), StyleId::BUILT_IN.len());
+        assert!(themes[0].is_dark && !themes[1].is_dark);
+    }
+
+    #[test]
+    // [CDU-Styling-ThemeV1] Control palettes default to the dark theme and follow the theme colors.
+    fn palettes_derive_from_theme_colors() {
+        // Arrange
+        let light = Theme::light();
+
+        // Act
+        let default_toggle = ToggleSwitchPalette::default();
+
+        // Assert
+        assert_eq!(default_toggle, Theme::dark().toggle_switch);
+        assert_eq!(default_toggle.background, Color::rgb(0x2B, 0x2B, 0x2B));
+        assert_eq!(light.chart.background, ThemeColors::LIGHT.surface);
+        assert_eq!(light.splitter.hover, ThemeColors::LIGHT.divider_hover);
+        assert_eq!(light.slider.fill, ThemeColors::LIGHT.accent);
+        assert_eq!(light.status_bar.warning, ThemeColors::LIGHT.warning);
+        assert_eq!(light.toolbar.text, ThemeColors::LIGHT.text);
+        assert_eq!(light.tooltip.background, ThemeColors::LIGHT.control);
+        assert_eq!(light.border, ThemeColors::LIGHT.border);
+    }
+
+    #[test]
+    fn resolved_tab_bar_colors_prefer_styles_over_palette() {
+        // Arrange
+        let custom_accent = Color::rgb(1, 2, 3);
+        let theme = Theme::dark().with_style(
+            StyleId::TabBarAccent,
+            ControlStyle {
+                background_color: Some(custom_accent.clone()),
+                ..ControlStyle::default()
+            },
+        );
+
+        // Act
+        let colors = theme.resolved_tab_bar_colors();
+
+        // Assert
+        assert_eq!(colors.accent, custom_accent);
+        assert_eq!(colors.background, ThemeColors::DARK.window_background);
+    }
+
+    #[test]
+    // [CDU-Styling-ColorV1] Built-in themes pass the contrast check; unreadable overrides are reported.
+    fn contrast_warnings_report_unreadable_styles() {
+        // Arrange
+        let grey = Color::rgb(0x30, 0x30, 0x30);
+        let theme = Theme::dark().with_style(
+            StyleId::HeaderLabel,
+            ControlStyle {
+                text_color: Some(grey.clone()),
+                ..ControlStyle::default()
+            },
+        );
+
+        // Act
+        let built_in: Vec<ContrastWarning> = [Theme::dark(), Theme::light()]
+            .iter()
+            .flat_map(|t| t.contrast_warnings(WCAG_AA_LARGE_TEXT))
+            .collect();
+        let warnings = theme.contrast_warnings(WCAG_AA_LARGE_TEXT);
+
+        // Assert
+        assert!(built_in.is_empty(), "{built_in:?}");
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings.iter().all(|w| w.style_id == StyleId::HeaderLabel));
+        assert_eq!(warnings[0].state, InteractionState::Normal);
+        assert_eq!(warnings[0].text, grey);
+        assert_eq!(warnings[0].background, ThemeColors::DARK.panel_background);
+        assert!(warnings[0].ratio < 1.5);
+    }
+}
@@ -0,0 +1,543 @@
+/*
+ * Theme files. A TOML or JSON document (chosen by the `.json` extension)
+ * starts from a built-in theme, overrides any of its semantic `ThemeColors`,
+ * defines or replaces styles and replaces owner-drawn control palettes, so
+ * colors can be tuned without recompiling.
+ *
+ * ```toml
+ * name = "Solarized"
+ * base = "Dark"
+ *
+ * [colors]
+ * panel_background = "#002B36"
+ * accent = "#268BD2"
+ *
+ * [[style]]
+ * id = "HeaderLabel"
+ * base = "DefaultText"
+ * font = { size = 12, weight = "Bold" }
+ *
+ * [[style]]
+ * id = { Custom = 1 }
+ * text_color = "rgb(203, 75, 22)"
+ *
+ * [splitter]
+ * normal = "#073642"
+ * hover = "#586E75"
+ * ```
+ *
+ * `ThemeFile::to_theme` builds the complete `Theme` for `ApplyTheme`;
+ * `define_style_commands` emits only the file's styles as `DefineStyle`
+ * commands, for applications that manage the rest of their theme themselves.
+ * Syntax errors report their line and column; validation errors name the
+ * offending `[colors]` key or `[[style]]` entry. A `ThemeWatcher` reloads the
+ * file when it changes, and `PlatformInterface::watch_theme_file` uses one to
+ * re-apply it to the running windows.
+ * [CDU-Styling-ThemeFileV1]
+ */
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::styling_primitives::{Color, ControlStyle, StyleId, resolve_style};
+use crate::theme::{
+    ChartPalette, SliderPalette, SplitterPalette, StatusBarPalette, TabBarColors, Theme,
+    ThemeColors, ToggleSwitchPalette, ToolbarPalette, TooltipPalette,
+};
+use crate::types::PlatformCommand;
+
+/// The built-in theme a theme file starts from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum BaseTheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// A theme file: a base theme plus the colors, styles and palettes it overrides.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeFile {
+    /// Defaults to the base theme's name.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub base: BaseTheme,
+    /// Defaults to whether the base theme is dark.
+    #[serde(default)]
+    pub is_dark: Option<bool>,
+    /// `ThemeColors` field names mapped to colors; every style and palette is
+    /// derived from the result before the entries below are applied.
+    #[serde(default)]
+    pub colors: BTreeMap<String, Color>,
+    #[serde(default, rename = "style")]
+    pub styles: Vec<ThemeStyle>,
+    #[serde(default)]
+    pub chart: Option<ChartPalette>,
+    #[serde(default)]
+    pub splitter: Option<SplitterPalette>,
+    #[serde(default)]
+    pub tab_bar: Option<TabBarColors>,
+    #[serde(default)]
+    pub toggle_switch: Option<ToggleSwitchPalette>,
+    #[serde(default)]
+    pub slider: Option<SliderPalette>,
+    #[serde(default)]
+    pub status_bar: Option<StatusBarPalette>,
+    #[serde(default)]
+    pub toolbar: Option<ToolbarPalette>,
+    #[serde(default)]
+    pub tooltip: Option<TooltipPalette>,
+    #[serde(default)]
+    pub border: Option<Color>,
+}
+
+/// A `[[style]]` entry: the style id plus the `ControlStyle` fields inline.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThemeStyle {
+    pub id: StyleId,
+    #[serde(flatten)]
+    pub style: ControlStyle,
+    // Keys `ControlStyle` does not have; flattening would otherwise drop them.
+    #[serde(flatten)]
+    extra: BTreeMap<String, IgnoredAny>,
+}
+
+impl ThemeFile {
+    /// Parses a TOML theme file. Syntax errors report their line and column.
+    pub fn from_toml_str(source: &str) -> PlatformResult<Self> {
+        toml::from_str(source).map_err(|e| {
+            PlatformError::OperationFailed(format!("Theme file could not be parsed: {e}"))
+        })
+    }
+
+    /// Parses a JSON theme file. Syntax errors report their line and column.
+    pub fn from_json_str(source: &str) -> PlatformResult<Self> {
+        serde_json::from_str(source).map_err(|e| {
+            PlatformError::OperationFailed(format!("Theme file could not be parsed: {e}"))
+        })
+    }
+
+    /// Reads a theme file, as JSON if its extension is `.json` and as TOML otherwise.
+    pub fn load(path: &Path) -> PlatformResult<Self> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            PlatformError::OperationFailed(format!(
+                "Failed to read theme file {}: {e}",
+                path.display()
+            ))
+        })?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let parsed = if is_json {
+            Self::from_json_str(&source)
+        } else {
+            Self::from_toml_str(&source)
+        };
+        parsed.map_err(|e| with_path(path, e))
+    }
+
+    /// Reads and validates a theme file into the theme it describes.
+    pub fn load_theme(path: &Path) -> PlatformResult<Theme> {
+        Self::load(path)?.to_theme().map_err(|e| with_path(path, e))
+    }
+
+    /*
+     * Builds the theme: the base theme's colors with `colors` applied, every
+     * built-in style derived from them, then the file's styles and palettes.
+     * Unknown color names and style keys, styles listed twice and style
+     * inheritance cycles are rejected.
+     */
+    pub fn to_theme(&self) -> PlatformResult<Theme> {
+        let (base_name, base_is_dark, mut colors) = match self.base {
+            BaseTheme::Dark => ("Dark", true, ThemeColors::DARK),
+            BaseTheme::Light => ("Light", false, ThemeColors::LIGHT),
+        };
+        for (name, color) in &self.colors {
+            let slot = color_slot(&mut colors, name).ok_or_else(|| {
+                PlatformError::OperationFailed(format!(
+                    "colors.{name}: unknown theme color; expected one of {}",
+                    THEME_COLOR_NAMES.join(", ")
+                ))
+            })?;
+            *slot = color.clone();
+        }
+        let mut theme = Theme::from_colors(
+            self.name.clone().unwrap_or_else(|| base_name.to_string()),
+            self.is_dark.unwrap_or(base_is_dark),
+            colors,
+        );
+        for style in &self.styles {
+            theme.styles.insert(style.id, style.style.clone());
+        }
+        self.validate_styles(&theme.styles)?;
+        if let Some(chart) = &self.chart {
+            theme.chart = chart.clone();
+        }
+        if let Some(splitter) = &self.splitter {
+            theme.splitter = splitter.clone();
+        }
+        if let Some(tab_bar) = &self.tab_bar {
+            theme.tab_bar = tab_bar.clone();
+        }
+        if let Some(toggle_switch) = &self.toggle_switch {
+            theme.toggle_switch = toggle_switch.clone();
+        }
+        if let Some(slider) = &self.slider {
+            theme.slider = slider.clone();
+        }
+        if let Some(status_bar) = &self.status_bar {
+            theme.status_bar = status_bar.clone();
+        }
+        if let Some(toolbar) = &self.toolbar {
+            theme.toolbar = toolbar.clone();
+        }
+        if let Some(tooltip) = &self.tooltip {
+            theme.tooltip = tooltip.clone();
+        }
+        if let Some(border) = &self.border {
+            theme.border = border.clone();
+        }
+        Ok(theme)
+    }
+
+    /*
+     * The file's styles as `DefineStyle` commands, in file order. They are
+     * validated as `to_theme` would, so bases may name built-in styles, but
+     * the colors and palettes of the file are not part of the result.
+     */
+    pub fn define_style_commands(&self) -> PlatformResult<Vec<PlatformCommand>> {
+        self.to_theme()?;
+        Ok(self
+            .styles
+            .iter()
+            .map(|style| PlatformCommand::DefineStyle {
+                style_id: style.id,
+                style: style.style.clone(),
+            })
+            .collect())
+    }
+
+    fn validate_styles(&self, styles: &BTreeMap<StyleId, ControlStyle>) -> PlatformResult<()> {
+        let definitions: HashMap<StyleId, ControlStyle> = styles
+            .iter()
+            .map(|(id, style)| (*id, style.clone()))
+            .collect();
+        let mut seen = HashSet::new();
+        for (index, style) in self.styles.iter().enumerate() {
+            let location = format!("style #{} (id = {:?})", index + 1, style.id);
+            if let Some(key) = style.extra.keys().next() {
+                return Err(PlatformError::OperationFailed(format!(
+                    "{location}: unknown style key `{key}`"
+                )));
+            }
+            if !seen.insert(style.id) {
+                return Err(PlatformError::OperationFailed(format!(
+                    "{location}: the style is defined more than once"
+                )));
+            }
+            resolve_style(style.id, &definitions).map_err(|e| match e {
+                PlatformError::OperationFailed(msg) => {
+                    PlatformError::OperationFailed(format!("{location}: {msg}"))
+                }
+                other => other,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+const THEME_COLOR_NAMES: [&str; 17] = [
+    "window_background",
+    "panel_background",
+    "surface",
+    "control",
+    "text",
+    "secondary_text",
+    "disabled_text",
+    "muted_text",
+    "accent",
+    "warning",
+    "error",
+    "selection",
+    "grid",
+    "divider",
+    "divider_hover",
+    "border",
+    "knob",
+];
+
+fn color_slot<'a>(colors: &'a mut ThemeColors, name: &str) -> Option<&'a mut Color> {
+    Some(match name {
+        "window_background" => &mut colors.window_background,
+        "panel_background" => &mut colors.panel_background,
+        "surface" => &mut colors.surface,
+        "control" => &mut colors.control,
+        "text" => &mut colors.text,
+        "secondary_text" => &mut colors.secondary_text,
+        "disabled_text" => &mut colors.disabled_text,
+        "muted_text" => &mut colors.muted_text,
+        "accent" => &mut colors.accent,
+        "warning" => &mut colors.warning,
+        "error" => &mut colors.error,
+        "selection" => &mut colors.selection,
+        "grid" => &mut colors.grid,
+        "divider" => &mut colors.divider,
+        "divider_hover" => &mut colors.divider_hover,
+        "border" => &mut colors.border,
+        "knob" => &mut colors.knob,
+        _ => return None,
+    })
+}
+
+fn with_path(path: &Path, error: PlatformError) -> PlatformError {
+    match error {
+        PlatformError::OperationFailed(msg) => {
+            PlatformError::OperationFailed(format!("{}: {msg}", path.display()))
+        }
+        other => other,
+    }
+}
+
+/*
+ * Polls a theme file for changes. The file is considered changed when its
+ * modification time or size differs from the previous check; the first check
+ * compares against the state recorded by `new`, so an unchanged file is not
+ * reloaded. A file that changed but fails to load is reported once, and the
+ * next change is loaded again.
+ */
+#[derive(Debug)]
+pub struct ThemeWatcher {
+    path: PathBuf,
+    poll_interval: Duration,
+    next_poll: Instant,
+    signature: Option<(SystemTime, u64)>,
+}
+
+impl ThemeWatcher {
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            signature: file_signature(&path),
+            path,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            next_poll: Instant::now(),
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Like `check`, but at most once per poll interval; cheap to call every loop iteration.
+    pub fn poll(&mut self) -> Option<PlatformResult<Theme>> {
+        let now = Instant::now();
+        if now < self.next_poll {
+            return None;
+        }
+        self.next_poll = now + self.poll_interval;
+        self.check()
+    }
+
+    /// Returns the reloaded theme, or the load error, if the file changed since the last check.
+    pub fn check(&mut self) -> Option<PlatformResult<Theme>> {
+        let signature = file_signature(&self.path);
+        if signature == self.signature {
+            return None;
+        }
+        self.signature = signature;
+        Some(ThemeFile::load_theme(&self.path))
+    }
+}
+
+fn file_signature(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::HeadlessPlatform;
+
+    const SAMPLE: &str = r##"
+name = "Ocean"
+base = "Light"
+
+[colors]
+panel_background = "#E0F0FF"
+text = { r = 0, g = 32, b = 64 }
+
+[[style]]
+id = "HeaderLabel"
+base = "DefaultText"
+font = { size = 14 }
+
+[[style]]
+id = { Custom = 1 }
+text_color = "rgb(200, 0, 0)"
+
+[splitter]
+normal = "#102030"
+hover = "#203040"
+"##;
+
+    #[test]
+    // [CDU-Styling-ThemeFileV1] A theme file overrides colors, styles and palettes of its base theme.
+    fn theme_file_overrides_its_base_theme() {
+        // Arrange
+        let file = ThemeFile::from_toml_str(SAMPLE).unwrap();
+
+        // Act
+        let theme = file.to_theme().unwrap();
+
+        // Assert
+        assert_eq!(theme.name, "Ocean");
+        assert!(!theme.is_dark);
+        assert_eq!(theme.colors.panel_background, Color::rgb(0xE0, 0xF0, 0xFF));
+        assert_eq!(theme.colors.surface, ThemeColors::LIGHT.surface);
+        let default_text = theme.style(StyleId::DefaultText).unwrap();
+        assert_eq!(default_text.text_color, Some(Color::rgb(0, 32, 64)));
+        let header = theme.style(StyleId::HeaderLabel).unwrap();
+        assert_eq!(header.base, Some(StyleId::DefaultText));
+        assert_eq!(
+            theme.style(StyleId::Custom(1)).unwrap().text_color,
+            Some(Color::rgb(200, 0, 0))
+        );
+        assert_eq!(theme.splitter.hover, Color::rgb(0x20, 0x30, 0x40));
+        assert_eq!(theme.chart, Theme::light().chart);
+    }
+
+    #[test]
+    // [CDU-Styling-ThemeFileV1] TOML and JSON theme files load to the same theme.
+    fn json_theme_files_match_toml() {
+        // Arrange
+        let json = r##"{
+            "name": "Ocean",
+            "base": "Light",
+            "colors": { "panel_background": "#E0F0FF", "text": { "r": 0, "g": 32, "b": 64 } },
+            "style": [
+                { "id": "HeaderLabel", "base": "DefaultText", "font": { "size": 14 } },
+                { "id": { "Custom": 1 }, "text_color": "rgb(200, 0, 0)" }
+            ],
+            "splitter": { "normal": "#102030", "hover": "#203040" }
+        }"##;
+
+        // Act
+        let from_json = ThemeFile::from_json_str(json).unwrap().to_theme().unwrap();
+
+        // Assert
+        let from_toml = ThemeFile::from_toml_str(SAMPLE)
+            .unwrap()
+            .to_theme()
+            .unwrap();
+        assert_eq!(from_json, from_toml);
+    }
+
+    #[test]
+    // [CDU-Styling-ThemeFileV1] Errors name the line, color key or style entry at fault.
+    fn theme_file_errors_report_their_location() {
+        // Arrange
+        let bad_color = "[colors]\naccent = \"#12345\"\n";
+        let unknown_color = "[colors]\nbackground = \"#123456\"\n";
+        let unknown_key = "[[style]]\nid = \"HeaderLabel\"\ntxt_color = \"#123456\"\n";
+        let cycle = "[[style]]\nid = \"TreeView\"\n\n[[style]]\nid = \"HeaderLabel\"\nbase = \"ComboBox\"\n\n[[style]]\nid = \"ComboBox\"\nbase = \"HeaderLabel\"\n";
+
+        // Act
+        let bad_color = ThemeFile::from_toml_str(bad_color).unwrap_err().to_string();
+        let unknown_color = ThemeFile::from_toml_str(unknown_color)
+            .unwrap()
+            .to_theme()
+            .unwrap_err()
+            .to_string();
+        let unknown_key = ThemeFile::from_toml_str(unknown_key)
+            .unwrap()
+            .to_theme()
+            .unwrap_err()
+            .to_string();
+        let cycle = ThemeFile::from_toml_str(cycle)
+            .unwrap()
+            .define_style_commands()
+            .unwrap_err()
+            .to_string();
+
+        // Assert
+        assert!(bad_color.contains("line 2"), "{bad_color}");
+        assert!(
+            unknown_color.contains("colors.background"),
+            "{unknown_color}"
+        );
+        assert!(
+            unknown_key.contains("style #1 (id = HeaderLabel): unknown style key `txt_color`"),
+            "{unknown_key}"
+        );
+        assert!(cycle.contains("style #2 (id = HeaderLabel)"), "{cycle}");
+        assert!(cycle.contains("cycle"), "{cycle}");
+    }
+
+    #[test]
+    // [CDU-Styling-ThemeFileV1] The file's styles compile to DefineStyle commands.
+    fn define_style_commands_define_the_file_styles() {
+        // Arrange
+        let mut platform = HeadlessPlatform::new();
+        let file = ThemeFile::from_toml_str(SAMPLE).unwrap();
+
+        // Act
+        let commands = file.define_style_commands().unwrap();
+        for command in commands {
+            platform.execute_command(command).unwrap();
+        }
+
+        // Assert
+        assert_eq!(
+            platform
+                .resolved_style(StyleId::HeaderLabel)
+                .unwrap()
+                .font
+                .unwrap()
+                .size,
+            Some(14)
+        );
+        assert!(platform.defined_style(StyleId::Custom(1)).is_some());
+        assert!(platform.defined_style(StyleId::TreeView).is_none());
+    }
+
+    #[test]
+    // [CDU-Styling-ThemeFileV1] The watcher reloads a theme file only after it changes.
+    fn theme_watcher_reloads_changed_files() {
+        // Arrange
+        let dir = std::env::temp_dir().join(format!("cdu-theme-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("theme.toml");
+        std::fs::write(&path, "name = \"First\"\n").unwrap();
+        let mut watcher = ThemeWatcher::new(&path);
+
+        // Act
+        let unchanged = watcher.check();
+        std::fs::write(&path, "name = \"Second\"\nbase = \"Light\"\n").unwrap();
+        let changed = watcher.check();
+        let settled = watcher.check();
+        std::fs::write(&path, "name = 3\n").unwrap();
+        let broken = watcher.check();
+
+        // Assert
+        assert!(unchanged.is_none());
+        assert_eq!(changed.unwrap().unwrap().name, "Second");
+        assert!(settled.is_none());
+        let error = broken.unwrap().unwrap_err().to_string();
+        assert!(error.contains("theme.toml"), "{error}");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,308 @@
+/*
+ * Items and layout of the toolbar control: what a toolbar holds and where each
+ * item sits. Buttons (push, toggle and dropdown) carry a `MenuActionId`:
+ * clicking one raises the same `AppEvent::MenuActionClicked` as the menu entry
+ * with that action, so an application handles both in one place. Separators
+ * draw a divider and spacers share the width left over, pushing the items
+ * after them right. Items are laid out left to right like status bar segments,
+ * with buttons as wide as their icon and text.
+ * [CDU-Control-ToolbarV1]
+ */
+
+use crate::dpi::DpiScale;
+use crate::error::{PlatformError, Result as PlatformResult};
+use crate::status_bar::{self, SEGMENT_PADDING_X, SegmentWidth, StatusSegment};
+use crate::types::{ImageId, MenuActionId};
+
+/// Width and height in DIPs of the box an item's icon glyph or image is drawn in.
+pub const ICON_SIZE: i32 = 16;
+/// Space in DIPs between an icon and the text after it.
+pub const ICON_TEXT_GAP: i32 = 4;
+/// Width in DIPs reserved for the arrow of a dropdown button.
+pub const DROPDOWN_ARROW_WIDTH: i32 = 10;
+/// Width in DIPs of a separator, including the space around its line.
+pub const SEPARATOR_WIDTH: i32 = 9;
+/// Space in DIPs between a button's edges and its content.
+pub const BUTTON_PADDING_X: i32 = SEGMENT_PADDING_X;
+
+/// What a toolbar item does when clicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ToolbarItemKind {
+    /// Raises its action once per click.
+    Push,
+    /// Flips its checked state, then raises its action.
+    Toggle,
+    /// Raises its action; drawn with an arrow to announce a menu.
+    Dropdown,
+    /// A vertical divider between groups of buttons.
+    Separator,
+    /// Empty space sharing the width the other items leave over.
+    Spacer,
+}
+
+/// One item of a toolbar. Build items with `push`, `toggle`, `dropdown`, `separator` or `spacer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToolbarItem {
+    pub kind: ToolbarItemKind,
+    /// Action raised through `MenuActionClicked`; required for buttons.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub action: Option<MenuActionId>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub text: String,
+    /// Glyph from the platform's symbol font (Segoe MDL2 Assets on Windows).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub icon: Option<char>,
+    /// Registered image drawn instead of the glyph. [CDU-ImagesV1]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub image: Option<ImageId>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tooltip: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default = "ToolbarItem::default_enabled"))]
+    pub enabled: bool,
+    /// Pressed state of a toggle button.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub checked: bool,
+}
+
+impl ToolbarItem {
+    fn new(kind: ToolbarItemKind, action: Option<MenuActionId>, text: String) -> Self {
+        Self {
+            kind,
+            action,
+            text,
+            icon: None,
+            image: None,
+            tooltip: None,
+            enabled: Self::default_enabled(),
+            checked: false,
+        }
+    }
+
+    fn default_enabled() -> bool {
+        true
+    }
+
+    pub fn push(action: MenuActionId, text: impl Into<String>) -> Self {
+        Self::new(ToolbarItemKind::Push, Some(action), text.into())
+    }
+
+    pub fn toggle(action: MenuActionId, text: impl Into<String>) -> Self {
+        Self::new(ToolbarItemKind::Toggle, Some(action), text.into())
+    }
+
+    pub fn dropdown(action: MenuActionId, text: impl Into<String>) -> Self {
+        Self::new(ToolbarItemKind::Dropdown, Some(action), text.into())
+    }
+
+    pub fn separator() -> Self {
+        Self::new(ToolbarItemKind::Separator, None, String::new())
+    }
+
+    pub fn spacer() -> Self {
+        Self::new(ToolbarItemKind::Spacer, None, String::new())
+    }
+
+    pub fn icon(mut self, glyph: char) -> Self {
+        self.icon = Some(glyph);
+        self
+    }
+
+    pub fn image(mut self, image_id: ImageId) -> Self {
+        self.image = Some(image_id);
+        self
+    }
+
+    pub fn tooltip(mut self, text: impl Into<String>) -> Self {
+        self.tooltip = Some(text.into());
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Whether the item can be clicked at all (push, toggle and dropdown buttons).
+    pub fn is_button(&self) -> bool {
+        matches!(
+            self.kind,
+            ToolbarItemKind::Push | ToolbarItemKind::Toggle | ToolbarItemKind::Dropdown
+        )
+    }
+
+    /// Whether the item draws a glyph or an image before its text.
+    pub fn has_icon(&self) -> bool {
+        self.icon.is_some() || self.image.is_some()
+    }
+
+    /*
+     * Pixel width of a button's icon, text and dropdown arrow at `scale`,
+     * given the measured pixel width of its text. Padding is not included.
+     */
+    pub fn content_width(&self, text_width: i32, scale: DpiScale) -> i32 {
+        let has_text = !self.text.is_empty();
+        let mut width = if has_text { text_width.max(0) } else { 0 };
+        if self.has_icon() {
+            width += scale.scale(ICON_SIZE);
+            if has_text {
+                width += scale.scale(ICON_TEXT_GAP);
+            }
+        }
+        if self.kind == ToolbarItemKind::Dropdown {
+            width += scale.scale(DROPDOWN_ARROW_WIDTH);
+        }
+        width
+    }
+}
+
+/*
+ * Rejects an empty toolbar, buttons without an action or without both text
+ * and icon, separators and spacers with an action, and checked items that
+ * are not toggles.
+ */
+pub fn validate_items(items: &[ToolbarItem]) -> PlatformResult<()> {
+    if items.is_empty() {
+        return Err(PlatformError::OperationFailed(
+            "A toolbar needs at least one item".to_string(),
+        ));
+    }
+    for (index, item) in items.iter().enumerate() {
+        let problem = if item.is_button() && item.action.is_none() {
+            Some("has no action")
+        } else if item.is_button() && item.text.is_empty() && !item.has_icon() {
+            Some("has neither text nor icon")
+        } else if !item.is_button() && item.action.is_some() {
+            Some("is not a button but has an action")
+        } else if item.checked && item.kind != ToolbarItemKind::Toggle {
+            Some("is checked but not a toggle")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(PlatformError::OperationFailed(format!(
+                "Toolbar item {index} ({:?}) {problem}",
+                item.kind
+            )));
+        }
+    }
+    Ok(())
+}
+
+/*
+ * Splits `total_width` pixels into the items' widths. `text_widths` holds the
+ * measured pixel width of each item's text (missing entries count as empty).
+ * Spacers share the leftover width and the rightmost items are clipped when
+ * the toolbar is too narrow, exactly as for status bar segments.
+ */
+pub fn item_widths(
+    items: &[ToolbarItem],
+    text_widths: &[i32],
+    total_width: i32,
+    scale: DpiScale,
+) -> Vec<i32> {
+    let segments: Vec<StatusSegment> = items
+        .iter()
+        .map(|item| StatusSegment {
+            width: match item.kind {
+                ToolbarItemKind::Separator => SegmentWidth::Fixed(SEPARATOR_WIDTH),
+                ToolbarItemKind::Spacer => SegmentWidth::Fill,
+                _ => SegmentWidth::Auto,
+            },
+            progress: false,
+        })
+        .collect();
+    let content_widths: Vec<i32> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            item.content_width(text_widths.get(index).copied().unwrap_or(0), scale)
+        })
+        .collect();
+    status_bar::segment_widths(&segments, &content_widths, total_width, scale)
+}
+
+/// The button under `x` (pixels from the toolbar's left edge), given the item widths.
+pub fn button_at(items: &[ToolbarItem], widths: &[i32], x: i32) -> Option<usize> {
+    status_bar::segment_at(widths, x)
+        .filter(|&index| items.get(index).is_some_and(|item| item.is_button()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-Control-ToolbarV1] Buttons fit their icon and text; spacers take the rest.
+    fn buttons_fit_content_and_spacers_fill() {
+        // Arrange
+        let items = [
+            ToolbarItem::push(MenuActionId(1), "Open").icon('\u{E8E5}'),
+            ToolbarItem::separator(),
+            ToolbarItem::toggle(MenuActionId(2), "").icon('\u{E71C}'),
+            ToolbarItem::spacer(),
+            ToolbarItem::dropdown(MenuActionId(3), "View"),
+        ];
+
+        // Act
+        let widths = item_widths(&items, &[24, 0, 0, 0, 20], 200, DpiScale::DEFAULT);
+
+        // Assert
+        assert_eq!(widths, vec![56, 9, 28, 65, 42]);
+    }
+
+    #[test]
+    fn content_width_follows_the_dpi_scale() {
+        // Arrange
+        let item = ToolbarItem::dropdown(MenuActionId(1), "Go").icon('*');
+
+        // Act
+        let width = item.content_width(30, DpiScale::new(192));
+
+        // Assert
+        assert_eq!(width, 30 + 32 + 8 + 20);
+    }
+
+    #[test]
+    // [CDU-Control-ToolbarV1] Only buttons are hit by clicks; separators and spacers are not.
+    fn button_at_skips_separators_and_spacers() {
+        // Arrange
+        let items = [
+            ToolbarItem::push(MenuActionId(1), "A"),
+            ToolbarItem::separator(),
+            ToolbarItem::spacer(),
+            ToolbarItem::push(MenuActionId(2), "B"),
+        ];
+        let widths = [20, 9, 30, 20];
+
+        // Act
+        let hits: Vec<Option<usize>> = [5, 25, 40, 65, 80]
+            .into_iter()
+            .map(|x| button_at(&items, &widths, x))
+            .collect();
+
+        // Assert
+        assert_eq!(hits, vec![Some(0), None, None, Some(3), None]);
+    }
+
+    #[test]
+    fn validate_rejects_inconsistent_items() {
+        // Act & Assert
+        assert!(validate_items(&[ToolbarItem::push(MenuActionId(1), "A")]).is_ok());
+        assert!(validate_items(&[]).is_err());
+        assert!(validate_items(&[ToolbarItem::push(MenuActionId(1), "")]).is_err());
+        assert!(
+            validate_items(&[ToolbarItem::push(MenuActionId(1), "").image(ImageId(1))]).is_ok()
+        );
+        assert!(validate_items(&[ToolbarItem::push(MenuActionId(1), "A").checked(true)]).is_err());
+        let mut separator = ToolbarItem::separator();
+        separator.action = Some(MenuActionId(1));
+        assert!(validate_items(&[separator]).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+/*
+ * Hover help: the text a tooltip shows and the options that control when and
+ * how it appears. `SetControlTooltip` attaches a tooltip to any control; tree
+ * items and tab bar tabs take theirs from their descriptors or, when those
+ * have none, from the `UiStateProvider`. `TooltipOptions` decide whether line
+ * breaks are kept, how long the pointer must rest before the tip appears and
+ * whether it stays up for the longest time Win32 allows. Colors come from the
+ * theme's `TooltipPalette`.
+ * [CDU-TooltipsV1]
+ */
+
+/// Width in DIPs at which multi-line tooltips wrap long lines.
+pub const MULTILINE_MAX_WIDTH: i32 = 400;
+/// How long in milliseconds a persistent tooltip stays up; the longest Win32 accepts.
+pub const PERSISTENT_DISPLAY_MS: u32 = 32_767;
+
+/// How a tooltip is shown. The default is a single line with the platform's delays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TooltipOptions {
+    /// Keeps line breaks and wraps long lines; otherwise the text is shown on one line.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub multiline: bool,
+    /// Milliseconds the pointer must rest before the tip appears; `None` uses the platform's delay.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub initial_delay_ms: Option<u32>,
+    /// Keeps the tip up for the longest time Win32 allows (about 33 s) instead of a few seconds.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub persistent: bool,
+}
+
+impl TooltipOptions {
+    pub fn multiline(mut self) -> Self {
+        self.multiline = true;
+        self
+    }
+
+    pub fn initial_delay(mut self, milliseconds: u32) -> Self {
+        self.initial_delay_ms = Some(milliseconds);
+        self
+    }
+
+    pub fn persistent(mut self) -> Self {
+        self.persistent = true;
+        self
+    }
+
+    /// How long the tip stays up; `None` uses the platform's duration.
+    pub fn display_ms(&self) -> Option<u32> {
+        self.persistent.then_some(PERSISTENT_DISPLAY_MS)
+    }
+}
+
+/*
+ * The text a tooltip shows. Multi-line tooltips keep their lines (without
+ * trailing blanks); single-line tooltips join the non-empty lines with a
+ * space. Text that is blank after this shows no tooltip at all.
+ */
+pub fn displayed_text(text: &str, options: &TooltipOptions) -> Option<String> {
+    let shown = if options.multiline {
+        text.lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_string()
+    } else {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    (!shown.trim().is_empty()).then_some(shown)
+}
+
+/*
+ * The tooltip of a tree item or tab: its own text when it has a non-blank
+ * one, otherwise whatever `provided` returns. Items keep their line breaks.
+ */
+pub fn item_tooltip(
+    own: Option<&str>,
+    provided: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    let multiline = TooltipOptions::default().multiline();
+    own.and_then(|text| displayed_text(text, &multiline))
+        .or_else(|| provided().and_then(|text| displayed_text(&text, &multiline)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // [CDU-TooltipsV1] Single-line tooltips join their lines; multi-line ones keep them.
+    fn displayed_text_follows_the_multiline_option() {
+        // Arrange
+        let text = "Open a profile\r\n  from disk  \n\n";
+
+        // Act
+        let single = displayed_text(text, &TooltipOptions::default());
+        let multi = displayed_text(text, &TooltipOptions::default().multiline());
+        let blank = displayed_text(" \n ", &TooltipOptions::default().multiline());
+
+        // Assert
+        assert_eq!(single.as_deref(), Some("Open a profile from disk"));
+        assert_eq!(multi.as_deref(), Some("Open a profile\n  from disk"));
+        assert_eq!(blank, None);
+    }
+
+    #[test]
+    // [CDU-TooltipsV1] An item's own tooltip wins; the provider is only asked when it has none.
+    fn item_tooltip_prefers_the_descriptor() {
+        // Act
+        let own = item_tooltip(Some("Own"), || panic!("provider must not be asked"));
+        let provided = item_tooltip(None, || Some("Provided".to_string()));
+        let blank_own = item_tooltip(Some("  "), || Some("Provided".to_string()));
+        let none = item_tooltip(None, || None);
+
+        // Assert
+        assert_eq!(own.as_deref(), Some("Own"));
+        assert_eq!(provided.as_deref(), Some("Provided"));
+        assert_eq!(blank_own.as_deref(), Some("Provided"));
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn persistent_tooltips_stay_up_for_the_longest_time() {
+        // Act & Assert
+        assert_eq!(TooltipOptions::default().display_ms(), None);
+        assert_eq!(
+            TooltipOptions::default().persistent().display_ms(),
+            Some(PERSISTENT_DISPLAY_MS)
+        );
+    }
+}
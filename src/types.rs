@@ -10,7 +10,14 @@
 
 use std::path::PathBuf;
 
+use super::layout::Rect;
+use super::numeric::NumericSpec;
+use super::slider::{SliderOrientation, SliderRange};
+use super::status_bar::StatusSegment;
 use super::styling_primitives::{Color, ControlStyle, FontDescription, StyleId};
+use super::theme::Theme;
+use super::toolbar::ToolbarItem;
+use super::tooltip::TooltipOptions;
 
 // An opaque identifier for a native window, managed by the platform layer.
 //
@@ -18,6 +25,7 @@ use super::styling_primitives::{Color, ControlStyle, FontDescription, StyleId};
 // when sending commands or receiving events, without needing to know about
 // native window handles like HWND.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WindowId(pub(crate) usize);
 
 impl WindowId {
@@ -36,6 +44,7 @@ impl WindowId {
 // uniquely identify tree items in commands and events. The platform layer
 // maps this to native tree item handles.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TreeItemId(pub u64);
 
 /*
@@ -46,6 +55,7 @@ pub struct TreeItemId(pub u64);
  * [CDU-ControlLogicalIdsV1] Every control is referenced through this strongly typed wrapper instead of raw HWND values.
  */
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ControlId(pub i32);
 
 impl ControlId {
@@ -79,8 +89,19 @@ impl From<ControlId> for i32 {
  * opaque IDs and map them to Win32 menu command identifiers.
  */
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MenuActionId(pub u32);
 
+/*
+ * Identifies an image registered with `PlatformCommand::RegisterImage`.
+ * Like `MenuActionId` the value is chosen by the application, so images can
+ * be referenced from descriptors before or after the bytes are registered.
+ * [CDU-ImagesV1]
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImageId(pub u32);
+
 // --- Data Structures for UI Description (Platform-Agnostic) ---
 
 // Configuration for creating a new native window.
@@ -96,6 +117,7 @@ pub struct WindowConfig<'a> {
 
 // Represents the visual check state of an item, typically a checkbox.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CheckState {
     Checked,
     Unchecked,
@@ -106,6 +128,7 @@ pub enum CheckState {
 // This structure is used by the application logic to define the content
 // and hierarchy of a tree view, which the platform layer then renders.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TreeItemDescriptor {
     pub id: TreeItemId,
     pub text: String,
@@ -113,10 +136,17 @@ pub struct TreeItemDescriptor {
     pub state: CheckState,
     pub children: Vec<TreeItemDescriptor>,
     pub style_override: Option<StyleId>,
+    /// Icon drawn before the text; unregistered ids draw no icon. [CDU-ImagesV1]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub image: Option<ImageId>,
+    /// Hover help; without one the `UiStateProvider` is asked. [CDU-TooltipsV1]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tooltip: Option<String>,
 }
 
 /// Identifies the optional color marker that can be rendered next to a tree item.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TreeItemMarkerKind {
     None,
     Blue,
@@ -127,6 +157,82 @@ pub enum TreeItemMarkerKind {
     Gray,
 }
 
+/// Horizontal alignment of the cells in a ListView column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Direction of a ListView sort, shown as the arrow in the column header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn reversed(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+}
+
+/*
+ * One column of a ListView, used by `PlatformCommand::CreateListView`. The
+ * width is in DIPs. Clicking the header of a sortable column raises
+ * `AppEvent::ListViewSortRequested`; other headers do nothing.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ListViewColumn {
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default = "ListViewColumn::default_width"))]
+    pub width: i32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alignment: ColumnAlignment,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sortable: bool,
+}
+
+impl ListViewColumn {
+    /// A left-aligned, unsortable column of the default width.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            width: Self::default_width(),
+            alignment: ColumnAlignment::Left,
+            sortable: false,
+        }
+    }
+
+    pub fn width(mut self, width: i32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: ColumnAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+
+    fn default_width() -> i32 {
+        100
+    }
+}
+
 /*
  * Configuration for a single menu item, used by `PlatformCommand::CreateMainMenu`.
  *
@@ -134,30 +240,159 @@ pub enum TreeItemMarkerKind {
  * omit the action and supply child `MenuItemConfig` entries instead.
  */
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MenuItemConfig {
     pub action: Option<MenuActionId>,
     pub text: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub children: Vec<MenuItemConfig>, // For submenus
 }
 
 // --- Layout Primitives ---
 
+/// The axis along which `DockStyle::ProportionalFill` siblings share space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutAxis {
+    #[default]
+    Horizontal, // Siblings are placed side by side, splitting the width.
+    Vertical, // Siblings are stacked, splitting the height.
+}
+
 /*
  * Defines how a control should dock within its parent container.
- * This is a simplified docking model. More advanced anchoring or grid systems
- * could be introduced later.
- * TODO: I think many of these have not been implemented yet.
+ * Docked edges consume space from the container in `order`; proportional
+ * fills then share what remains along their axis, and a single `Fill` takes
+ * the remainder. `None` opts out of docking: the control is placed at
+ * `LayoutRule::absolute_bounds`, or left untouched when no bounds are given.
  */
-#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DockStyle {
-    None,   // No docking, control is positioned manually or by other rules.
+    None,   // No docking, control is positioned by `absolute_bounds` or manually.
     Top,    // Docks to the top edge of the container.
     Bottom, // Docks to the bottom edge of the container.
     Left,   // Docks to the left edge of the container.
     Right,  // Docks to the right edge of the container.
     Fill,   // Fills all remaining space in the container (both axes).
-    ProportionalFill { weight: f32 }, // Fills space along main axis proportionally with siblings.
+    // Shares remaining space along `axis` by weight.
+    ProportionalFill {
+        weight: f32,
+        axis: LayoutAxis,
+    },
+    // Cell of the parent's `ContainerLayout::Grid`.
+    GridCell {
+        row: u32,
+        column: u32,
+        row_span: u32,
+        column_span: u32,
+    },
+    FlowItem, // Item of the parent's `ContainerLayout::Flow`, sized by `preferred_size`.
+}
+
+/// Sizing of a single grid row or column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridTrack {
+    Fixed(i32), // Exact size in DIPs.
+    Auto,       // Largest preferred size among the single-span cells in the track.
+    Star(f32),  // Share of the space left after fixed and auto tracks, by weight.
+}
+
+/*
+ * Row and column definitions for a grid container. Tracks are laid out in
+ * declaration order with the given spacing between neighbours; cells may
+ * span several tracks, in which case the spacing between them is included.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GridDefinition {
+    pub rows: Vec<GridTrack>,
+    pub columns: Vec<GridTrack>,
+    pub row_spacing: i32,
+    pub column_spacing: i32,
+}
+
+/// Placement of flow items along an axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlowAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+/*
+ * A stack of items placed one after another along `axis`, in `order`. With
+ * `wrap` set, an item that no longer fits starts a new line, so a row of
+ * buttons reflows when the window narrows. `alignment` positions each line
+ * along the axis; `cross_alignment` positions items within the thickness of
+ * their line.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FlowLayout {
+    pub axis: LayoutAxis,
+    pub spacing: i32,      // Gap between neighbouring items on a line.
+    pub line_spacing: i32, // Gap between wrapped lines.
+    pub alignment: FlowAlignment,
+    pub cross_alignment: FlowAlignment,
+    pub wrap: bool,
+}
+
+/*
+ * How a control arranges its own children, declared on the control's
+ * `LayoutRule`. Docked children are always placed first; the container then
+ * lays out its cells in the space that remains, just as `Fill` would.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContainerLayout {
+    Grid(GridDefinition),
+    Flow(FlowLayout),
+}
+
+/// How a docked edge gets its extent along the docking axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SizeMode {
+    #[default]
+    Fixed, // Uses `LayoutRule::fixed_size`.
+    Auto, // The backend measures the control and stores the result in `preferred_size`.
+}
+
+/*
+ * Optional size limits for a control, in DIPs, excluding margins. Docked
+ * edges clamp their `fixed_size` and cross-axis extent, proportional fills
+ * redistribute space so every sibling stays within its limits, and `Fill`
+ * clamps the remainder. A minimum wins over the available space, so a pane
+ * never collapses below it even when the container is too small.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SizeConstraints {
+    pub min_width: Option<i32>,
+    pub max_width: Option<i32>,
+    pub min_height: Option<i32>,
+    pub max_height: Option<i32>,
+}
+
+impl SizeConstraints {
+    pub fn clamp_width(&self, width: i32) -> i32 {
+        clamp_optional(width, self.min_width, self.max_width)
+    }
+
+    pub fn clamp_height(&self, height: i32) -> i32 {
+        clamp_optional(height, self.min_height, self.max_height)
+    }
+}
+
+fn clamp_optional(value: i32, min: Option<i32>, max: Option<i32>) -> i32 {
+    let capped = max.map_or(value, |max| value.min(max));
+    min.map_or(capped, |min| capped.max(min))
 }
 
 /*
@@ -169,13 +404,43 @@ pub enum DockStyle {
  * the main window client area is the parent.
  */
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayoutRule {
     pub control_id: ControlId, // The ID of the control this rule applies to.
     pub parent_control_id: Option<ControlId>, // ID of the parent control, None for main window.
     pub dock_style: DockStyle,
     pub order: u32, // Order of application (e.g., 0 for top, 1 for bottom, 10 for fill)
     pub fixed_size: Option<i32>, // For Top/Bottom, this is height. For Left/Right, this is width. Not used for Fill/None.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub size_mode: SizeMode, // `Auto` sizes docked edges, grid cells and flow items from measurement.
     pub margin: (i32, i32, i32, i32), // (top, right, bottom, left) margins around the control.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub constraints: SizeConstraints, // Optional min/max width and height.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub absolute_bounds: Option<Rect>, // Parent-relative placement for DockStyle::None only.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub preferred_size: Option<(i32, i32)>, // (width, height) used by Auto grid tracks and flow items.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub container: Option<ContainerLayout>, // How this control arranges its own children.
+}
+
+impl LayoutRule {
+    /// A rule with no margins, constraints or fixed size, ordered first under the window.
+    pub fn new(control_id: ControlId, dock_style: DockStyle) -> Self {
+        Self {
+            control_id,
+            parent_control_id: None,
+            dock_style,
+            order: 0,
+            fixed_size: None,
+            size_mode: SizeMode::Fixed,
+            margin: (0, 0, 0, 0),
+            constraints: SizeConstraints::default(),
+            absolute_bounds: None,
+            preferred_size: None,
+            container: None,
+        }
+    }
 }
 
 // --- Events from Platform to App Logic ---
@@ -189,22 +454,25 @@ pub struct LayoutRule {
  *
  * ## Splitter Event Contract
  *
- * Splitter controls (when implemented) emit events with `desired_left_width_px` in
- * **window client coordinates** (not raw mouse x). The platform layer is responsible
+ * Splitter controls (when implemented) emit events with `desired_left_width` in
+ * **window client DIPs** (not raw mouse x). The platform layer is responsible
  * for computing the desired width by:
- * 1. Converting the mouse position to window client coordinates
+ * 1. Converting the mouse position to window client coordinates, in DIPs
  * 2. Accounting for any relevant margins
  * 3. Accounting for the splitter's own thickness/width
  *
  * This ensures the application logic receives a value that directly represents
  * the intended total width of the left region, ready for clamping and state updates.
+ * DIPs are the unit of layout rules, so the width can be fed straight back into
+ * a rule's `fixed_size` on any monitor.
  */
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AppEvent {
     WindowCloseRequestedByUser {
         window_id: WindowId,
     },
-    // Signals that a window has been resized.
+    // Signals that a window has been resized. The client size is in DIPs.
     WindowResized {
         window_id: WindowId,
         width: i32,
@@ -215,6 +483,12 @@ pub enum AppEvent {
     WindowDestroyed {
         window_id: WindowId,
     },
+    // Signals that a window moved to a monitor with a different DPI (96 = 100%).
+    // Its layout and fonts have already been rescaled when this arrives.
+    DpiChanged {
+        window_id: WindowId,
+        dpi: u32,
+    },
     TreeViewItemToggledByUser {
         window_id: WindowId,
         item_id: TreeItemId,
@@ -229,7 +503,7 @@ pub enum AppEvent {
         window_id: WindowId,
         control_id: ControlId,
     },
-    // Signals that a menu item was clicked, identified by its semantic `MenuActionId`.
+    // Signals that a menu item or toolbar button was clicked, identified by its semantic `MenuActionId`.
     MenuActionClicked {
         action_id: MenuActionId,
     },
@@ -280,19 +554,19 @@ pub enum AppEvent {
     },
     // Signals that a splitter is being dragged by the user.
     // Emitted continuously during the drag operation.
-    // The `desired_left_width_px` is in window client coordinates and represents
+    // The `desired_left_width` is in window client DIPs and represents
     // the total width the left region should have (see Splitter Event Contract above).
     SplitterDragging {
         window_id: WindowId,
         control_id: ControlId,
-        desired_left_width_px: i32,
+        desired_left_width: i32,
     },
     // Signals that the user has finished dragging the splitter (mouse button released).
-    // The `desired_left_width_px` is the final position in window client coordinates.
+    // The `desired_left_width` is the final position in window client DIPs.
     SplitterDragEnded {
         window_id: WindowId,
         control_id: ControlId,
-        desired_left_width_px: i32,
+        desired_left_width: i32,
     },
     // Signals that a ComboBox selection was changed by the user.
     // None means no selection (CB_ERR).
@@ -326,6 +600,69 @@ pub enum AppEvent {
         control_id: ControlId,
         checked: bool,
     },
+    // Signals that the user clicked the header of a sortable ListView column.
+    // `direction` is the reverse of the current sort when `column` is already
+    // the sorted one, otherwise ascending. The platform does not reorder rows:
+    // the application sorts its data, re-sends it and confirms the header
+    // arrow with `SetListViewSortIndicator`.
+    ListViewSortRequested {
+        window_id: WindowId,
+        control_id: ControlId,
+        column: usize,
+        direction: SortDirection,
+    },
+    // Signals that the user changed the selected ListView row (None = no selection).
+    ListViewSelectionChanged {
+        window_id: WindowId,
+        control_id: ControlId,
+        row: Option<usize>,
+    },
+    // Signals that the user activated a ListView row (double-click or Enter).
+    ListViewRowActivated {
+        window_id: WindowId,
+        control_id: ControlId,
+        row: usize,
+    },
+    // Signals that the user moved a slider's thumb; `value` is always snapped
+    // to the range. While dragging, every change arrives with `committed:
+    // false`; releasing the mouse (or a keyboard step) sends the final value
+    // with `committed: true`, so expensive work can wait for that one.
+    SliderValueChanged {
+        window_id: WindowId,
+        control_id: ControlId,
+        value: i32,
+        committed: bool,
+    },
+    // Signals that the user changed a numeric input to a new valid value, by
+    // typing, the spin buttons, arrow keys or the mouse wheel. `value` is in
+    // range and rounded to the input's decimals. Text that does not parse
+    // raises no event; the input shows `StyleId::DefaultInputError` instead.
+    NumericValueChanged {
+        window_id: WindowId,
+        control_id: ControlId,
+        value: f64,
+    },
+    // Signals that the user clicked a status bar segment; `segment` indexes
+    // the segments the bar was created with.
+    StatusSegmentClicked {
+        window_id: WindowId,
+        control_id: ControlId,
+        segment: usize,
+    },
+    // Signals that the user asked for a context menu on a control, by a right
+    // click or from the keyboard. `screen_x`/`screen_y` are physical screen
+    // pixels to hand to `ShowContextMenu`; `client_x`/`client_y` are DIPs in the
+    // control's client area. `item_id` is the tree item under the pointer (or
+    // the selected one from the keyboard), if any. [CDU-ContextMenusV1]
+    ContextMenuRequested {
+        window_id: WindowId,
+        control_id: ControlId,
+        screen_x: i32,
+        screen_y: i32,
+        client_x: i32,
+        client_y: i32,
+        item_id: Option<TreeItemId>,
+    },
 }
 
 // Defines the severity of a message to be displayed, e.g., in the status bar.
@@ -333,6 +670,7 @@ pub enum AppEvent {
 // TODO: 'None' isn't used, is it needed?
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageSeverity {
     None,        // Clears the status, or lowest priority if not explicitly clearing
     Information, // Neutral information
@@ -343,8 +681,10 @@ pub enum MessageSeverity {
 // --- Label Classification ---
 // TODO: Only 'StatusBar' is currently used, is it needed?
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LabelClass {
+    #[default]
     Default,
     StatusBar,
 }
@@ -359,6 +699,7 @@ pub enum LabelClass {
 ///   The user drags the splitter vertically to resize the top and bottom panels.
 ///   (Reserved for future implementation)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SplitterOrientation {
     Vertical,   // Divides left/right (user drags horizontally)
     Horizontal, // Divides top/bottom (future extension)
@@ -366,6 +707,7 @@ pub enum SplitterOrientation {
 
 /// A single entity trend line sent to the chart control.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChartLineData {
     /// Entity name shown in the legend.
     pub label: String,
@@ -377,6 +719,7 @@ pub struct ChartLineData {
 
 /// Full data payload for a `SetChartData` command.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChartDataPacket {
     /// Lines to draw (index 0 = top legend entry). Max 10.
     pub lines: Vec<ChartLineData>,
@@ -393,6 +736,7 @@ pub struct ChartDataPacket {
 // TODO: All commands that create controls should use the same name for this ID. E.g. "control_id".
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlatformCommand {
     SetWindowTitle {
         window_id: WindowId,
@@ -484,6 +828,9 @@ pub enum PlatformCommand {
         parent_control_id: Option<ControlId>, // None means child of main window's client area
         control_id: ControlId, // The existing logical ID (e.g., ID_BUTTON_GENERATE_ARCHIVE)
         text: String,
+        /// Icon drawn before the text; unregistered ids draw no icon.
+        #[cfg_attr(feature = "serde", serde(default))]
+        image: Option<ImageId>,
         // Position/size will be managed by DefineLayout command.
     },
     CreateTreeView {
@@ -658,12 +1005,22 @@ pub enum PlatformCommand {
         control_id: ControlId,
         parent_control_id: Option<ControlId>,
         items: Vec<String>,
-    },
-    /// Replaces all tab labels and triggers a repaint.
+        /// Icon of each tab, by index; tabs past the end have none.
+        #[cfg_attr(feature = "serde", serde(default))]
+        images: Vec<Option<ImageId>>,
+        /// Hover help of each tab, by index; tabs without one ask the `UiStateProvider`.
+        #[cfg_attr(feature = "serde", serde(default))]
+        tooltips: Vec<Option<String>>,
+    },
+    /// Replaces all tab labels, icons and tooltips and triggers a repaint.
     SetTabBarItems {
         window_id: WindowId,
         control_id: ControlId,
         items: Vec<String>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        images: Vec<Option<ImageId>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        tooltips: Vec<Option<String>>,
     },
     /// Drives the active tab from the reducer (no event emitted for programmatic changes).
     SetTabBarSelection {
@@ -686,6 +1043,14 @@ pub enum PlatformCommand {
         style_id: StyleId,
         style: ControlStyle,
     },
+    /*
+     * Switches the application to `theme`: defines every style it maps (as
+     * `DefineStyle` would), pushes its palettes into the owner-drawn controls,
+     * switches the native dark/light rendering and repaints all windows.
+     */
+    ApplyTheme {
+        theme: Theme,
+    },
     // Applies a previously defined style to a specific control.
     ApplyStyleToControl {
         window_id: WindowId,
@@ -716,6 +1081,166 @@ pub enum PlatformCommand {
         knob: Color,
         text: Color,
     },
+    /// Creates a multi-column ListView (report view, single selection).
+    CreateListView {
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        control_id: ControlId,
+        columns: Vec<ListViewColumn>,
+    },
+    /// Replaces all rows; each row holds one cell text per column.
+    SetListViewRows {
+        window_id: WindowId,
+        control_id: ControlId,
+        rows: Vec<Vec<String>>,
+    },
+    /*
+     * Switches the ListView to virtual data: it shows `row_count` rows and
+     * asks the attached `ListViewDataProvider` for the text of the visible
+     * cells while painting. Send it again whenever the data changes.
+     */
+    SetListViewRowCount {
+        window_id: WindowId,
+        control_id: ControlId,
+        row_count: usize,
+    },
+    /// Shows the sort arrow on `column`'s header, or on none.
+    SetListViewSortIndicator {
+        window_id: WindowId,
+        control_id: ControlId,
+        column: Option<usize>,
+        direction: SortDirection,
+    },
+    /// Selects a row and scrolls it into view (no event emitted for programmatic changes).
+    SetListViewSelection {
+        window_id: WindowId,
+        control_id: ControlId,
+        row: Option<usize>,
+    },
+    /// Creates an owner-drawn slider; `value` is snapped to `range`.
+    CreateSlider {
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        control_id: ControlId,
+        range: SliderRange,
+        orientation: SliderOrientation,
+        value: i32,
+    },
+    /// Moves the slider thumb to `value`, snapped to the range (no event emitted).
+    SetSliderValue {
+        window_id: WindowId,
+        control_id: ControlId,
+        value: i32,
+    },
+    /// Pushes resolved palette colors into a slider control.
+    SetSliderStyle {
+        window_id: WindowId,
+        control_id: ControlId,
+        background: Color,
+        track: Color,
+        fill: Color,
+        thumb: Color,
+        tick: Color,
+    },
+    /// Creates a numeric input (edit field plus up/down buttons) showing `value`.
+    CreateNumericInput {
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        control_id: ControlId,
+        spec: NumericSpec,
+        value: f64,
+    },
+    /// Replaces the shown value, clamped to the range (no event emitted).
+    SetNumericValue {
+        window_id: WindowId,
+        control_id: ControlId,
+        value: f64,
+    },
+    /// Creates a status bar split into `segments`, laid out left to right.
+    CreateStatusBar {
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        control_id: ControlId,
+        segments: Vec<StatusSegment>,
+    },
+    /// Sets the text of one segment, colored by `severity`.
+    SetStatusSegmentText {
+        window_id: WindowId,
+        control_id: ControlId,
+        segment: usize,
+        text: String,
+        severity: MessageSeverity,
+    },
+    /// Shows a progress bar (percent, clamped to 100) in a progress segment; `None` shows its text again.
+    SetStatusSegmentProgress {
+        window_id: WindowId,
+        control_id: ControlId,
+        segment: usize,
+        progress: Option<u32>,
+    },
+    /// Creates a toolbar whose buttons raise `MenuActionClicked` with their action.
+    CreateToolbar {
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        control_id: ControlId,
+        items: Vec<ToolbarItem>,
+    },
+    /// Enables or disables every toolbar button with `action_id`.
+    SetToolbarItemEnabled {
+        window_id: WindowId,
+        control_id: ControlId,
+        action_id: MenuActionId,
+        enabled: bool,
+    },
+    /// Sets the checked state of every toggle button with `action_id` (no event emitted).
+    SetToolbarItemChecked {
+        window_id: WindowId,
+        control_id: ControlId,
+        action_id: MenuActionId,
+        checked: bool,
+    },
+    /// Decodes PNG, ICO or BMP bytes and stores them under `image_id` for every window.
+    RegisterImage {
+        image_id: ImageId,
+        bytes: Vec<u8>,
+    },
+    /// Creates a control showing a registered image, scaled to fit and centered.
+    CreateImage {
+        window_id: WindowId,
+        parent_control_id: Option<ControlId>,
+        control_id: ControlId,
+        image_id: Option<ImageId>,
+    },
+    /// Replaces the image of an image control; `None` leaves it empty.
+    SetImage {
+        window_id: WindowId,
+        control_id: ControlId,
+        image_id: Option<ImageId>,
+    },
+    /*
+     * Shows `text` when the pointer rests on the control, replacing any
+     * earlier tooltip; empty text removes it. [CDU-TooltipsV1]
+     */
+    SetControlTooltip {
+        window_id: WindowId,
+        control_id: ControlId,
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: TooltipOptions,
+    },
+    /*
+     * Opens the context menu of `control_id` at a point in physical screen
+     * pixels, usually the `screen_x`/`screen_y` of a `ContextMenuRequested`.
+     * A chosen item raises `MenuActionClicked` like a main menu item;
+     * dismissing the menu raises nothing. [CDU-ContextMenusV1]
+     */
+    ShowContextMenu {
+        window_id: WindowId,
+        control_id: ControlId,
+        menu_items: Vec<MenuItemConfig>,
+        screen_x: i32,
+        screen_y: i32,
+    },
 }
 
 // --- Trait for App Logic to Handle Events ---
@@ -756,6 +1281,37 @@ pub trait UiStateProvider: Send + Sync + 'static {
     fn tree_item_marker(&self, _window_id: WindowId, _item_id: TreeItemId) -> TreeItemMarkerKind {
         TreeItemMarkerKind::None
     }
+
+    /// Hover help of a tree item whose descriptor has no tooltip. [CDU-TooltipsV1]
+    fn tree_item_tooltip(&self, _window_id: WindowId, _item_id: TreeItemId) -> Option<String> {
+        None
+    }
+
+    /// Hover help of a tab bar tab that was given no tooltip. [CDU-TooltipsV1]
+    fn tab_tooltip(
+        &self,
+        _window_id: WindowId,
+        _control_id: ControlId,
+        _index: usize,
+    ) -> Option<String> {
+        None
+    }
+}
+
+/*
+ * Supplies the cell texts of ListViews in virtual mode (see
+ * `PlatformCommand::SetListViewRowCount`), so large tables never have to be
+ * copied into the platform layer. Like `UiStateProvider` it is queried
+ * synchronously while the control paints, so it must answer quickly.
+ */
+pub trait ListViewDataProvider: Send + Sync + 'static {
+    fn cell_text(
+        &self,
+        window_id: WindowId,
+        control_id: ControlId,
+        row: usize,
+        column: usize,
+    ) -> String;
 }
 
 #[cfg(test)]
@@ -13,11 +13,20 @@ use super::{
     app::Win32ApiInternalState,
     controls::{
         button_handler, checkbox_handler, combobox_handler, input_handler, label_handler,
-        paint_router, styling_handler, treeview_handler,
+        list_view_handler, numeric_input_handler, paint_router, styling_handler, tab_bar_handler,
+        toolbar_handler, treeview_handler,
     },
+    dpi::DpiScale,
     error::{PlatformError, Result as PlatformResult},
+    image,
+    layout::{self, Rect},
+    measure::{self, MeasureKind, TextMetrics},
     styling::StyleId,
-    types::{AppEvent, ControlId, DockStyle, LayoutRule, MenuActionId, MessageSeverity, WindowId},
+    styling_primitives::FontDescription,
+    types::{
+        AppEvent, ControlId, DockStyle, ImageId, LayoutRule, MenuActionId, MessageSeverity,
+        SizeMode, WindowId,
+    },
 };
 
 use windows::core::w;
@@ -25,24 +34,27 @@ use windows::{
     Win32::{
         Foundation::{
             COLORREF, ERROR_INVALID_WINDOW_HANDLE, GetLastError, HWND, LPARAM, LRESULT, POINT,
-            RECT, WPARAM,
+            RECT, SIZE, WPARAM,
         },
         Graphics::Dwm::{DWMWINDOWATTRIBUTE, DwmSetWindowAttribute},
         Graphics::Gdi::{
-            BeginPaint, CLIP_DEFAULT_PRECIS, COLOR_WINDOW, CreateFontIndirectW, CreateFontW,
-            CreateSolidBrush, DEFAULT_CHARSET, DEFAULT_GUI_FONT, DEFAULT_QUALITY, DT_CENTER,
-            DT_HIDEPREFIX, DT_SINGLELINE, DT_VCENTER, DeleteObject, DrawTextW, EndPaint,
-            FF_DONTCARE, FW_BOLD, FW_NORMAL, FillRect, GetDC, GetDeviceCaps, GetObjectW,
-            GetStockObject, GetWindowDC, HBRUSH, HDC, HFONT, HGDIOBJ, InvalidateRect, LOGFONTW,
-            LOGPIXELSY, MapWindowPoints, OUT_DEFAULT_PRECIS, OffsetRect, PAINTSTRUCT,
+            BeginPaint, CLIP_DEFAULT_PRECIS, COLOR_WINDOW, ClientToScreen, CreateFontIndirectW,
+            CreateFontW, CreateSolidBrush, DEFAULT_CHARSET, DEFAULT_GUI_FONT, DEFAULT_QUALITY,
+            DT_CENTER, DT_HIDEPREFIX, DT_SINGLELINE, DT_VCENTER, DeleteObject, DrawTextW, EndPaint,
+            FF_DONTCARE, FW_BOLD, FW_NORMAL, FillRect, GetDC, GetObjectW, GetStockObject,
+            GetTextExtentPoint32W, GetWindowDC, HBRUSH, HDC, HFONT, HGDIOBJ, InvalidateRect,
+            LOGFONTW, MapWindowPoints, OUT_DEFAULT_PRECIS, OffsetRect, PAINTSTRUCT,
             RDW_ALLCHILDREN, RDW_ERASE, RDW_INVALIDATE, RDW_UPDATENOW, RedrawWindow, ReleaseDC,
-            SetBkColor, SetBkMode, SetTextColor, TRANSPARENT,
+            ScreenToClient, SelectObject, SetBkColor, SetBkMode, SetTextColor, TRANSPARENT,
         },
         System::LibraryLoader::{GetProcAddress, LoadLibraryW},
-        System::WindowsProgramming::MulDiv,
         UI::Controls::{
             DRAWITEMSTRUCT, NM_CLICK, NM_CUSTOMDRAW, NMHDR, ODS_HOTLIGHT, ODS_NOACCEL,
-            ODS_SELECTED, SetWindowTheme, TVN_ITEMCHANGEDW,
+            ODS_SELECTED, SetWindowTheme, TVN_GETINFOTIPW, TVN_ITEMCHANGEDW,
+        },
+        UI::HiDpi::{
+            DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, GetDpiForSystem, GetDpiForWindow,
+            SetProcessDpiAwarenessContext,
         },
         UI::WindowsAndMessaging::*, // This list is massive, just import all of them.
     },
@@ -52,11 +64,105 @@ use windows::{
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Instant;
 
 use log::warn;
 
+// Converts a portable layout rectangle into the native RECT applied to child windows.
+impl From<Rect> for RECT {
+    fn from(rect: Rect) -> Self {
+        RECT {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        }
+    }
+}
+
+/*
+ * Measures text with the font a native control actually renders with
+ * (WM_GETFONT, falling back to DEFAULT_GUI_FONT), so `SizeMode::Auto` follows
+ * whatever style was applied to it. The `FontDescription` is not needed.
+ */
+struct ControlFontMetrics {
+    hwnd: HWND,
+}
+
+impl TextMetrics for ControlFontMetrics {
+    fn measure_text(&self, text: &str, _font: &FontDescription) -> (i32, i32) {
+        let hdc = unsafe { GetDC(Some(self.hwnd)) };
+        if hdc.is_invalid() {
+            return (0, 0);
+        }
+        let font_lresult =
+            unsafe { SendMessageW(self.hwnd, WM_GETFONT, Some(WPARAM(0)), Some(LPARAM(0))) };
+        let font = HFONT(font_lresult.0 as usize as *mut c_void);
+        let font_obj = if font.0.is_null() {
+            unsafe { GetStockObject(DEFAULT_GUI_FONT) }
+        } else {
+            HGDIOBJ(font.0)
+        };
+        let old_font = unsafe { SelectObject(hdc, font_obj) };
+
+        let mut width = 0;
+        let mut height = 0;
+        for line in text.split('\n') {
+            let wide: Vec<u16> = line.encode_utf16().collect();
+            // An empty line still has the font's height; measure a space and drop its width.
+            let sample: Vec<u16> = if wide.is_empty() {
+                " ".encode_utf16().collect()
+            } else {
+                wide.clone()
+            };
+            let mut size = SIZE::default();
+            let _ = unsafe { GetTextExtentPoint32W(hdc, &sample, &mut size) };
+            if !wide.is_empty() {
+                width = width.max(size.cx);
+            }
+            height += size.cy;
+        }
+
+        unsafe { SelectObject(hdc, old_font) };
+        unsafe { ReleaseDC(Some(self.hwnd), hdc) };
+        (width, height)
+    }
+}
+
+// Reads every item of a native combo box, for measuring its widest entry.
+fn combobox_item_texts(hwnd: HWND) -> Vec<String> {
+    let count = unsafe { SendMessageW(hwnd, CB_GETCOUNT, Some(WPARAM(0)), Some(LPARAM(0))) }.0;
+    (0..count.max(0))
+        .filter_map(|index| {
+            let len = unsafe {
+                SendMessageW(
+                    hwnd,
+                    CB_GETLBTEXTLEN,
+                    Some(WPARAM(index as usize)),
+                    Some(LPARAM(0)),
+                )
+            }
+            .0;
+            if len < 0 {
+                return None;
+            }
+            let mut buffer = vec![0u16; len as usize + 1];
+            let copied = unsafe {
+                SendMessageW(
+                    hwnd,
+                    CB_GETLBTEXT,
+                    Some(WPARAM(index as usize)),
+                    Some(LPARAM(buffer.as_mut_ptr() as isize)),
+                )
+            }
+            .0;
+            (copied >= 0).then(|| String::from_utf16_lossy(&buffer[..copied as usize]))
+        })
+        .collect()
+}
+
 // TOOD: Control IDs used by dialog_handler, kept here for visibility if dialog_handler needs them
 // but ideally, they should be private to dialog_handler or within a shared constants scope for dialogs.
 pub(crate) const ID_DIALOG_INPUT_EDIT: i32 = 3001;
@@ -84,6 +190,19 @@ pub(crate) const WM_APP_SPLITTER_DRAG_ENDED: u32 = WM_APP + 0x103;
 pub(crate) const WM_APP_TAB_SELECTED: u32 = WM_APP + 0x104;
 // Custom application message sent by ToggleSwitch WndProc to root on click/key-toggle.
 pub(crate) const WM_APP_TOGGLE_SWITCH_CLICKED: u32 = WM_APP + 0x105;
+// Custom application messages sent by the Slider WndProc to root while dragging
+// and when a value is committed (mouse release, capture loss or keyboard).
+pub(crate) const WM_APP_SLIDER_DRAGGING: u32 = WM_APP + 0x106;
+pub(crate) const WM_APP_SLIDER_COMMITTED: u32 = WM_APP + 0x107;
+// Custom application message sent by a NumericInput container to root when its
+// text parses to a new value.
+pub(crate) const WM_APP_NUMERIC_VALUE_CHANGED: u32 = WM_APP + 0x108;
+// Custom application message sent by the StatusBar WndProc to root on a segment click.
+pub(crate) const WM_APP_STATUS_SEGMENT_CLICKED: u32 = WM_APP + 0x109;
+// Custom application message sent by the Toolbar WndProc to root on a button click.
+pub(crate) const WM_APP_TOOLBAR_ITEM_CLICKED: u32 = WM_APP + 0x10A;
+// Custom application message sent by the TabBar WndProc to root for a tab without own tooltip.
+pub(crate) const WM_APP_TAB_TOOLTIP_REQUESTED: u32 = WM_APP + 0x10B;
 
 // General UI constants
 /// Default debounce delay for edit controls in milliseconds.
@@ -118,6 +237,18 @@ pub(crate) enum ControlKind {
     TabBar,
     /// Fully owner-drawn sliding toggle switch (pill + knob).
     ToggleSwitch,
+    /// Report-view SysListView32 in owner-data mode.
+    ListView,
+    /// Fully owner-drawn slider (track, ticks and thumb).
+    Slider,
+    /// Container window holding an EDIT and an up-down control.
+    NumericInput,
+    /// Owner-drawn segmented status bar.
+    StatusBar,
+    /// Owner-drawn toolbar of push, toggle and dropdown buttons.
+    Toolbar,
+    /// Custom-WndProc control showing a registered image.
+    Image,
 }
 
 /*
@@ -177,6 +308,8 @@ pub(crate) struct NativeWindowData {
     logical_window_id: WindowId,
     // The specific internal state for the TreeView control if one exists.
     treeview_state: Option<treeview_handler::TreeViewInternalState>,
+    // Columns, rows and sort state of each ListView, keyed by its logical ID.
+    list_view_states: HashMap<ControlId, list_view_handler::ListViewState>,
     // HWNDs for various controls (buttons, status bar, treeview, etc.)
     control_hwnd_map: HashMap<ControlId, HWND>,
     // Maps dynamically generated `i32` menu item IDs to their semantic `MenuActionId`.
@@ -190,6 +323,10 @@ pub(crate) struct NativeWindowData {
     layout_rules: Option<Vec<LayoutRule>>,
     /// The current severity for each status label, keyed by its logical ID.
     label_severities: HashMap<ControlId, MessageSeverity>,
+    /// Images drawn next to the text of buttons, keyed by the button's logical ID.
+    control_images: HashMap<ControlId, ImageId>,
+    /// Tooltip windows created by `SetControlTooltip`, keyed by the control's logical ID.
+    control_tooltips: HashMap<ControlId, HWND>,
     status_bar_font: Option<HFONT>,
     treeview_new_item_font: Option<HFONT>,
     suppress_erasebkgnd: bool,
@@ -204,6 +341,7 @@ impl NativeWindowData {
             this_window_hwnd: HWND_INVALID,
             logical_window_id,
             treeview_state: None,
+            list_view_states: HashMap::new(),
             control_hwnd_map: HashMap::new(),
             menu_action_map: HashMap::new(),
             applied_styles: HashMap::new(),
@@ -211,6 +349,8 @@ impl NativeWindowData {
             next_menu_item_id_counter: 30000,
             layout_rules: None,
             label_severities: HashMap::new(),
+            control_images: HashMap::new(),
+            control_tooltips: HashMap::new(),
             status_bar_font: None,
             treeview_new_item_font: None,
             suppress_erasebkgnd: false,
@@ -265,6 +405,28 @@ impl NativeWindowData {
         self.treeview_state.as_ref()
     }
 
+    pub(crate) fn insert_list_view_state(
+        &mut self,
+        control_id: ControlId,
+        state: list_view_handler::ListViewState,
+    ) {
+        self.list_view_states.insert(control_id, state);
+    }
+
+    pub(crate) fn list_view_state(
+        &self,
+        control_id: ControlId,
+    ) -> Option<&list_view_handler::ListViewState> {
+        self.list_view_states.get(&control_id)
+    }
+
+    pub(crate) fn list_view_state_mut(
+        &mut self,
+        control_id: ControlId,
+    ) -> Option<&mut list_view_handler::ListViewState> {
+        self.list_view_states.get_mut(&control_id)
+    }
+
     pub(crate) fn apply_style_to_control(&mut self, control_id: ControlId, style_id: StyleId) {
         // [CDU-Styling-ApplyV1] Track which logical control has an applied `StyleId` so redraw hooks can resolve palette/font info.
         self.applied_styles.insert(control_id, style_id);
@@ -274,6 +436,47 @@ impl NativeWindowData {
         self.applied_styles.get(&control_id).copied()
     }
 
+    /*
+     * The styles applied to the control's ancestors, nearest first, found by
+     * walking the native parent chain up to this window. A styled control
+     * inherits its unset font and colors from them. [CDU-Styling-InheritanceV1]
+     */
+    pub(crate) fn styled_ancestors(&self, control_id: ControlId) -> Vec<StyleId> {
+        let mut styles = Vec::new();
+        let Some(mut hwnd) = self.get_control_hwnd(control_id) else {
+            return styles;
+        };
+        while let Ok(parent) = unsafe { GetParent(hwnd) } {
+            if parent.is_invalid() || parent == self.this_window_hwnd {
+                break;
+            }
+            let parent_id = unsafe { GetDlgCtrlID(parent) };
+            if parent_id != 0
+                && let Some(style_id) = self.get_style_for_control(ControlId::new(parent_id))
+            {
+                styles.push(style_id);
+            }
+            hwnd = parent;
+        }
+        styles
+    }
+
+    /// Styled controls nested anywhere inside `control_id`, with their styles.
+    pub(crate) fn styled_descendants(&self, control_id: ControlId) -> Vec<(ControlId, StyleId)> {
+        let Some(hwnd) = self.get_control_hwnd(control_id) else {
+            return Vec::new();
+        };
+        self.applied_styles
+            .iter()
+            .filter(|(id, _)| **id != control_id)
+            .filter(|(id, _)| {
+                self.get_control_hwnd(**id)
+                    .is_some_and(|child| unsafe { IsChild(hwnd, child) }.as_bool())
+            })
+            .map(|(id, style_id)| (*id, *style_id))
+            .collect()
+    }
+
     pub(crate) fn register_control_kind(&mut self, control_id: ControlId, kind: ControlKind) {
         self.control_kinds.insert(control_id, kind);
     }
@@ -286,6 +489,24 @@ impl NativeWindowData {
         self.control_kinds.get(&control_id).copied()
     }
 
+    /// Every control with its kind, HWND and applied style, for re-theming.
+    pub(crate) fn themed_controls(
+        &self,
+    ) -> Vec<(ControlId, Option<ControlKind>, HWND, Option<StyleId>)> {
+        self.control_hwnd_map
+            .iter()
+            .filter(|(_, hwnd)| !hwnd.is_invalid())
+            .map(|(control_id, hwnd)| {
+                (
+                    *control_id,
+                    self.get_control_kind(*control_id),
+                    *hwnd,
+                    self.get_style_for_control(*control_id),
+                )
+            })
+            .collect()
+    }
+
     fn effective_native_height_for_control(&self, control_id: ControlId, base_height: i32) -> i32 {
         match self.get_control_kind(control_id) {
             Some(ControlKind::ComboBox) => match self.get_control_hwnd(control_id) {
@@ -491,132 +712,17 @@ impl NativeWindowData {
     }
 
     /*
-     * Pure layout calculation for a group of child controls. Returns the
-     * rectangle for each control without calling any Win32 APIs. The
-     * algorithm mirrors the runtime layout engine and is recursively
-     * applied by `apply_layout_rules_for_children`.
-     * [CDU-LayoutSystemV1] Declarative docking rules are validated here without touching the Win32 APIs.
-     */
-    fn calculate_layout(parent_rect: RECT, rules: &[LayoutRule]) -> HashMap<ControlId, RECT> {
-        let mut sorted = rules.to_vec();
-        sorted.sort_by_key(|r| r.order);
-
-        let mut result = HashMap::new();
-        let mut current_available_rect = parent_rect;
-        let mut fill_candidate: Option<&LayoutRule> = None;
-        let mut proportional_fill_candidates: Vec<&LayoutRule> = Vec::new();
-
-        for rule in &sorted {
-            match rule.dock_style {
-                DockStyle::Top | DockStyle::Bottom | DockStyle::Left | DockStyle::Right => {
-                    let mut item_rect = RECT {
-                        left: current_available_rect.left + rule.margin.3,
-                        top: current_available_rect.top + rule.margin.0,
-                        right: current_available_rect.right - rule.margin.1,
-                        bottom: current_available_rect.bottom - rule.margin.2,
-                    };
-                    let size = rule.fixed_size.unwrap_or(0);
-                    match rule.dock_style {
-                        DockStyle::Top => {
-                            item_rect.bottom = item_rect.top + size;
-                            current_available_rect.top = item_rect.bottom + rule.margin.2;
-                        }
-                        DockStyle::Bottom => {
-                            item_rect.top = item_rect.bottom - size;
-                            current_available_rect.bottom = item_rect.top - rule.margin.0;
-                        }
-                        DockStyle::Left => {
-                            item_rect.right = item_rect.left + size;
-                            current_available_rect.left = item_rect.right + rule.margin.1;
-                        }
-                        DockStyle::Right => {
-                            item_rect.left = item_rect.right - size;
-                            current_available_rect.right = item_rect.left - rule.margin.3;
-                        }
-                        _ => unreachable!(),
-                    }
-                    result.insert(rule.control_id, item_rect);
-                }
-                DockStyle::Fill => {
-                    if fill_candidate.is_none() {
-                        fill_candidate = Some(rule);
-                    }
-                }
-                DockStyle::ProportionalFill { .. } => {
-                    proportional_fill_candidates.push(rule);
-                }
-                DockStyle::None => {}
-            }
-        }
-
-        if !proportional_fill_candidates.is_empty() {
-            let total_width_for_proportional =
-                (current_available_rect.right - current_available_rect.left).max(0);
-            let total_height_for_proportional =
-                (current_available_rect.bottom - current_available_rect.top).max(0);
-            let total_weight: f32 = proportional_fill_candidates
-                .iter()
-                .map(|r| match r.dock_style {
-                    DockStyle::ProportionalFill { weight } => weight,
-                    _ => 0.0,
-                })
-                .sum();
-            if total_weight > 0.0 {
-                let mut current_x = current_available_rect.left;
-                for rule in proportional_fill_candidates {
-                    if let DockStyle::ProportionalFill { weight } = rule.dock_style {
-                        let proportion = weight / total_weight;
-                        let item_width_allocation =
-                            (total_width_for_proportional as f32 * proportion) as i32;
-                        let final_x = current_x + rule.margin.3;
-                        let final_y = current_available_rect.top + rule.margin.0;
-                        let final_width =
-                            (item_width_allocation - rule.margin.3 - rule.margin.1).max(0);
-                        let final_height =
-                            (total_height_for_proportional - rule.margin.0 - rule.margin.2).max(0);
-                        result.insert(
-                            rule.control_id,
-                            RECT {
-                                left: final_x,
-                                top: final_y,
-                                right: final_x + final_width,
-                                bottom: final_y + final_height,
-                            },
-                        );
-                        current_x += item_width_allocation;
-                    }
-                }
-            }
-        }
-
-        if let Some(rule) = fill_candidate {
-            let fill_rect = RECT {
-                left: current_available_rect.left + rule.margin.3,
-                top: current_available_rect.top + rule.margin.0,
-                right: current_available_rect.right - rule.margin.1,
-                bottom: current_available_rect.bottom - rule.margin.2,
-            };
-            result.insert(rule.control_id, fill_rect);
-        }
-
-        result
-    }
-
-    /*
-     * Applies layout rules recursively for a parent and its children.
-     * The heavy lifting is done by `calculate_layout`, which returns the
-     * desired rectangles for each child. This function merely calls the
-     * Win32 API to move the windows and recurses for nested containers.
+     * Applies precomputed layout rectangles recursively for a parent and its children.
+     * The geometry comes from `layout::compute_layout`; this function merely calls
+     * the Win32 API to move the windows and recurses for nested containers.
      */
     fn apply_layout_rules_for_children(
         &self,
         parent_id_for_layout: Option<ControlId>,
-        parent_rect: RECT,
+        full_layout: &HashMap<ControlId, Rect>,
     ) {
         let layout_start = Instant::now();
-        log::trace!(
-            "Applying layout for parent_id {parent_id_for_layout:?}, rect: {parent_rect:?}"
-        );
+        log::trace!("Applying layout for parent_id {parent_id_for_layout:?}");
 
         let all_window_rules = match &self.layout_rules {
             Some(rules) => rules,
@@ -645,11 +751,18 @@ impl NativeWindowData {
         }
 
         log::debug!(
-            "[Layout] Applying layout: parent_id={parent_id_for_layout:?}, rules={}, rect={parent_rect:?}",
+            "[Layout] Applying layout: parent_id={parent_id_for_layout:?}, rules={}",
             child_rules.len()
         );
 
-        let layout_map = NativeWindowData::calculate_layout(parent_rect, &child_rules);
+        let layout_map: HashMap<ControlId, RECT> = child_rules
+            .iter()
+            .filter_map(|rule| {
+                full_layout
+                    .get(&rule.control_id)
+                    .map(|rect| (rule.control_id, RECT::from(*rect)))
+            })
+            .collect();
         let parent_hwnd = if let Some(parent_id) = parent_id_for_layout {
             self.control_hwnd_map.get(&parent_id).copied()
         } else {
@@ -894,20 +1007,9 @@ impl NativeWindowData {
             if all_window_rules
                 .iter()
                 .any(|r_child| r_child.parent_control_id == Some(rule.control_id))
+                && layout_map.contains_key(&rule.control_id)
             {
-                let rect = match layout_map.get(&rule.control_id) {
-                    Some(r) => r,
-                    None => continue,
-                };
-                let width = (rect.right - rect.left).max(0);
-                let height = (rect.bottom - rect.top).max(0);
-                let panel_client_rect = RECT {
-                    left: 0,
-                    top: 0,
-                    right: width,
-                    bottom: height,
-                };
-                self.apply_layout_rules_for_children(Some(rule.control_id), panel_client_rect);
+                self.apply_layout_rules_for_children(Some(rule.control_id), full_layout);
             }
         }
 
@@ -937,67 +1039,19 @@ impl NativeWindowData {
         self.menu_action_map.len()
     }
 
-    #[cfg(test)]
     pub(crate) fn get_next_menu_item_id_counter(&self) -> i32 {
         self.next_menu_item_id_counter
     }
 
-    pub(crate) fn define_layout(&mut self, rules: Vec<LayoutRule>) -> PlatformResult<()> {
-        Self::validate_layout_rules(&rules)?;
-        self.layout_rules = Some(rules);
-        Ok(())
+    /// Forgets menu actions registered for a menu that no longer exists, such as a closed context menu.
+    pub(crate) fn unregister_menu_actions(&mut self, menu_ids: std::ops::Range<i32>) {
+        self.menu_action_map
+            .retain(|menu_id, _| !menu_ids.contains(menu_id));
     }
 
-    fn validate_layout_rules(rules: &[LayoutRule]) -> PlatformResult<()> {
-        let mut fill_by_parent: HashMap<Option<ControlId>, Vec<ControlId>> = HashMap::new();
-        for rule in rules {
-            match rule.dock_style {
-                DockStyle::Top | DockStyle::Bottom | DockStyle::Left | DockStyle::Right => {
-                    if rule.fixed_size.is_none() {
-                        return Err(PlatformError::OperationFailed(format!(
-                            "DefineLayout rejected: control {} uses {:?} without fixed_size. Docked edges require explicit fixed_size.",
-                            rule.control_id.raw(),
-                            rule.dock_style
-                        )));
-                    }
-                    if let Some(size) = rule.fixed_size
-                        && size < 0
-                    {
-                        return Err(PlatformError::OperationFailed(format!(
-                            "DefineLayout rejected: control {} has negative fixed_size {} for {:?}.",
-                            rule.control_id.raw(),
-                            size,
-                            rule.dock_style
-                        )));
-                    }
-                }
-                _ => {}
-            }
-
-            if rule.dock_style == DockStyle::Fill {
-                fill_by_parent
-                    .entry(rule.parent_control_id)
-                    .or_default()
-                    .push(rule.control_id);
-            }
-        }
-
-        for (parent_id, fill_controls) in fill_by_parent {
-            if fill_controls.len() > 1 {
-                let parent_desc = parent_id
-                    .map(|id| format!("control {}", id.raw()))
-                    .unwrap_or_else(|| "main window".to_string());
-                let control_ids = fill_controls
-                    .iter()
-                    .map(|id| id.raw().to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                return Err(PlatformError::OperationFailed(format!(
-                    "DefineLayout rejected: parent {parent_desc} has multiple DockStyle::Fill children ({control_ids}). CommanDuctUI supports exactly one Fill child per parent."
-                )));
-            }
-        }
-
+    pub(crate) fn define_layout(&mut self, rules: Vec<LayoutRule>) -> PlatformResult<()> {
+        layout::validate_layout_rules(&rules)?;
+        self.layout_rules = Some(rules);
         Ok(())
     }
 
@@ -1014,6 +1068,74 @@ impl NativeWindowData {
         false
     }
 
+    pub(crate) fn has_auto_sized_rules(&self) -> bool {
+        self.layout_rules
+            .as_ref()
+            .is_some_and(|rules| rules.iter().any(|r| r.size_mode == SizeMode::Auto))
+    }
+
+    // Fills in `preferred_size` for `SizeMode::Auto` rules from the live native controls.
+    // `rules` are already scaled to pixels, as are the measured sizes.
+    fn measured_layout_rules(&self, rules: &[LayoutRule]) -> Vec<LayoutRule> {
+        rules
+            .iter()
+            .map(|rule| {
+                let measured = (rule.size_mode == SizeMode::Auto)
+                    .then(|| self.measure_control(rule.control_id))
+                    .flatten();
+                LayoutRule {
+                    preferred_size: measured.or(rule.preferred_size),
+                    ..rule.clone()
+                }
+            })
+            .collect()
+    }
+
+    // Panels also register as `Static`; they measure as an empty label.
+    fn measure_control(&self, control_id: ControlId) -> Option<(i32, i32)> {
+        let kind = match self.get_control_kind(control_id)? {
+            ControlKind::Button => MeasureKind::Button,
+            ControlKind::Static => MeasureKind::Label,
+            ControlKind::Edit => MeasureKind::Input,
+            ControlKind::CheckBox => MeasureKind::CheckBox,
+            ControlKind::RadioButton => MeasureKind::RadioButton,
+            ControlKind::ToggleSwitch => MeasureKind::ToggleSwitch,
+            ControlKind::ComboBox => MeasureKind::ComboBox,
+            ControlKind::ProgressBar => MeasureKind::ProgressBar,
+            ControlKind::NumericInput => MeasureKind::NumericInput,
+            ControlKind::StatusBar => MeasureKind::StatusBar,
+            ControlKind::Toolbar => MeasureKind::Toolbar,
+            _ => return None,
+        };
+        let hwnd = self.get_control_hwnd(control_id)?;
+        let text = match kind {
+            MeasureKind::ComboBox => combobox_item_texts(hwnd).join("\n"),
+            MeasureKind::NumericInput => numeric_input_handler::displayed_text(hwnd),
+            _ => read_edit_control_text(hwnd).unwrap_or_default(),
+        };
+        let scale = self.dpi_scale();
+        let size = measure::preferred_size(
+            kind,
+            &text,
+            &FontDescription::default(),
+            &ControlFontMetrics { hwnd },
+            scale,
+        );
+        // Buttons with an image grow by the image and the gap before the text.
+        if self.control_images.contains_key(&control_id) {
+            return Some(image::with_leading_image(size, scale));
+        }
+        Some(size)
+    }
+
+    /// The DPI scale of the monitor this window is on.
+    pub(crate) fn dpi_scale(&self) -> DpiScale {
+        if self.this_window_hwnd.is_invalid() {
+            return system_dpi_scale();
+        }
+        dpi_scale_for_window(self.this_window_hwnd)
+    }
+
     pub(crate) fn recalculate_and_apply_layout(&self) {
         if self.layout_rules.is_none() {
             return;
@@ -1041,7 +1163,16 @@ impl NativeWindowData {
             "Layout: Applying layout with client_rect {client_rect:?} for WinID {:?}.",
             self.logical_window_id
         );
-        self.apply_layout_rules_for_children(None, client_rect);
+        // Rules are in DIPs; the client rect and control rects are pixels.
+        let full_layout = match &self.layout_rules {
+            Some(rules) => layout::compute_layout(
+                client_rect.right - client_rect.left,
+                client_rect.bottom - client_rect.top,
+                &self.measured_layout_rules(&self.dpi_scale().scale_rules(rules)),
+            ),
+            None => return,
+        };
+        self.apply_layout_rules_for_children(None, &full_layout);
 
         // Queue a full post-layout redraw pass without blocking the message loop.
         // Dynamic Prompt Lab mode/section toggles can otherwise leave stale pixels from
@@ -1067,6 +1198,31 @@ impl NativeWindowData {
         self.label_severities.get(&label_id).copied()
     }
 
+    pub(crate) fn set_control_image(&mut self, control_id: ControlId, image_id: ImageId) {
+        self.control_images.insert(control_id, image_id);
+    }
+
+    pub(crate) fn get_control_image(&self, control_id: ControlId) -> Option<ImageId> {
+        self.control_images.get(&control_id).copied()
+    }
+
+    pub(crate) fn set_control_tooltip(&mut self, control_id: ControlId, hwnd_tooltip: HWND) {
+        self.control_tooltips.insert(control_id, hwnd_tooltip);
+    }
+
+    pub(crate) fn take_control_tooltip(&mut self, control_id: ControlId) -> Option<HWND> {
+        self.control_tooltips.remove(&control_id)
+    }
+
+    pub(crate) fn get_control_tooltip(&self, control_id: ControlId) -> Option<HWND> {
+        self.control_tooltips.get(&control_id).copied()
+    }
+
+    /// Every tooltip window created by `SetControlTooltip` in this window.
+    pub(crate) fn control_tooltips(&self) -> Vec<HWND> {
+        self.control_tooltips.values().copied().collect()
+    }
+
     pub(crate) fn ensure_status_bar_font(&mut self) {
         if self.status_bar_font.is_some() {
             return;
@@ -1074,20 +1230,7 @@ impl NativeWindowData {
 
         let font_name_hstring = HSTRING::from("Segoe UI");
         let font_point_size = 9;
-        let hdc_screen = unsafe { GetDC(None) };
-        let logical_font_height = if !hdc_screen.is_invalid() {
-            let height = -unsafe {
-                MulDiv(
-                    font_point_size,
-                    GetDeviceCaps(Some(hdc_screen), LOGPIXELSY),
-                    72,
-                )
-            };
-            unsafe { ReleaseDC(None, hdc_screen) };
-            height
-        } else {
-            -font_point_size
-        };
+        let logical_font_height = -self.dpi_scale().font_pixel_size(font_point_size);
 
         let h_font = unsafe {
             CreateFontW(
@@ -1127,6 +1270,39 @@ impl NativeWindowData {
         self.status_bar_font
     }
 
+    /*
+     * Re-creates the window's own fonts after it moved to a monitor with a
+     * different DPI. Status bar labels are recognized by still using the old
+     * status bar font and get the new one before the old one is deleted.
+     */
+    pub(crate) fn recreate_fonts_for_dpi(&mut self) {
+        let old_status_bar_font = self.status_bar_font.take();
+        self.ensure_status_bar_font();
+        if let (Some(old_font), Some(new_font)) = (old_status_bar_font, self.status_bar_font) {
+            for hwnd in self.control_hwnd_map.values() {
+                let current =
+                    unsafe { SendMessageW(*hwnd, WM_GETFONT, Some(WPARAM(0)), Some(LPARAM(0))) };
+                if current.0 as usize == old_font.0 as usize {
+                    unsafe {
+                        SendMessageW(
+                            *hwnd,
+                            WM_SETFONT,
+                            Some(WPARAM(new_font.0 as usize)),
+                            Some(LPARAM(1)),
+                        )
+                    };
+                }
+            }
+        }
+        if let Some(old_font) = old_status_bar_font.filter(|f| !f.is_invalid()) {
+            unsafe {
+                let _ = DeleteObject(HGDIOBJ(old_font.0));
+            }
+        }
+        self.cleanup_treeview_new_item_font();
+        self.ensure_treeview_new_item_font();
+    }
+
     fn cleanup_status_bar_font(&mut self) {
         if let Some(h_font) = self.status_bar_font.take()
             && !h_font.is_invalid()
@@ -1179,6 +1355,9 @@ impl NativeWindowData {
             return;
         }
 
+        // The stock font is sized for the system DPI; follow this window's monitor.
+        base_log_font.lfHeight = system_dpi_scale().unscale(base_log_font.lfHeight);
+        base_log_font.lfHeight = self.dpi_scale().scale(base_log_font.lfHeight);
         base_log_font.lfWeight = FW_BOLD.0 as i32;
         base_log_font.lfItalic = 1;
 
@@ -1301,6 +1480,8 @@ pub(crate) fn register_window_class(
 /*
  * Creates a native Win32 window.
  * Uses `CreateWindowExW` and passes `WindowCreationContext` via `lpCreateParams`.
+ * `width` and `height` are DIPs, scaled by the system DPI; the window is
+ * resized by WM_DPICHANGED if it opens on a monitor with another DPI.
  */
 pub(crate) fn create_native_window(
     internal_state_arc: &Arc<Win32ApiInternalState>,
@@ -1318,6 +1499,7 @@ pub(crate) fn create_native_window(
         internal_state_arc: Arc::clone(internal_state_arc),
         window_id,
     });
+    let (width, height) = system_dpi_scale().scale_size((width, height));
 
     unsafe {
         let hwnd = CreateWindowExW(
@@ -1392,6 +1574,34 @@ pub(crate) fn hiword_from_lparam(lparam: LPARAM) -> i32 {
     ((lparam.0 >> 16) & 0xFFFF) as i32
 }
 
+/*
+ * Opts the process into per-monitor (v2) DPI awareness so Windows stops
+ * bitmap-stretching our windows and sends WM_DPICHANGED instead. Must be
+ * called before any window is created. Fails harmlessly when an application
+ * manifest already set the awareness.
+ * [CDU-DpiAwarenessV1]
+ */
+pub(crate) fn init_per_monitor_dpi_awareness() {
+    static INIT: OnceLock<()> = OnceLock::new();
+    INIT.get_or_init(|| {
+        if let Err(e) =
+            unsafe { SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) }
+        {
+            log::debug!("Platform: DPI awareness not changed (already set?): {e:?}");
+        }
+    });
+}
+
+/// The DPI scale of the monitor `hwnd` is on.
+pub(crate) fn dpi_scale_for_window(hwnd: HWND) -> DpiScale {
+    DpiScale::new(unsafe { GetDpiForWindow(hwnd) })
+}
+
+/// The DPI scale of the primary monitor, for windows that do not exist yet.
+pub(crate) fn system_dpi_scale() -> DpiScale {
+    DpiScale::new(unsafe { GetDpiForSystem() })
+}
+
 /// App-level dark mode initialization.
 ///
 /// Must be called **before** any window is created (`CreateWindowExW`).
@@ -1400,63 +1610,110 @@ pub(crate) fn hiword_from_lparam(lparam: LPARAM) -> i32 {
 /// dark rendering for title bars, menus, and scrollbars.
 pub(crate) fn init_app_dark_mode() {
     static INIT: OnceLock<()> = OnceLock::new();
-    INIT.get_or_init(|| unsafe {
+    INIT.get_or_init(|| set_app_theme_mode(true));
+}
+
+/// Whether native rendering (title bars, menus, scrollbars) is dark. Follows
+/// the `is_dark` flag of the last applied theme; dark until one is applied.
+static NATIVE_DARK_MODE: AtomicBool = AtomicBool::new(true);
+
+/*
+ * Switches the process between native dark and light rendering, e.g. when a
+ * theme is applied. Existing windows and controls pick the mode up through
+ * `apply_native_color_mode`; controls created later do so on creation.
+ */
+pub(crate) fn set_native_dark_mode(dark: bool) {
+    NATIVE_DARK_MODE.store(dark, Ordering::Relaxed);
+    set_app_theme_mode(dark);
+}
+
+fn is_native_dark_mode() -> bool {
+    NATIVE_DARK_MODE.load(Ordering::Relaxed)
+}
+
+/// The uxtheme entry points behind the app-level color mode, resolved once.
+#[derive(Default)]
+struct AppThemeModeFns {
+    set_preferred_app_mode: Option<SetPreferredAppModeFn>,
+    refresh_immersive_color_policy_state: Option<RefreshImmersiveColorPolicyStateFn>,
+    flush_menu_themes: Option<FlushMenuThemesFn>,
+}
+
+fn app_theme_mode_fns() -> &'static AppThemeModeFns {
+    static FNS: OnceLock<AppThemeModeFns> = OnceLock::new();
+    FNS.get_or_init(|| unsafe {
         let module = match LoadLibraryW(w!("uxtheme.dll")) {
             Ok(m) => m,
             Err(err) => {
                 log::debug!("Failed to load uxtheme.dll for app-level dark mode: {err:?}");
-                return;
+                return AppThemeModeFns::default();
             }
         };
-
-        // SetPreferredAppMode(AllowDark) — ordinal 135
-        if let Some(ptr) = get_uxtheme_proc_address(module, UXTHEME_ORD_SET_PREFERRED_APP_MODE) {
-            let set_preferred: SetPreferredAppModeFn =
-                std::mem::transmute::<*const c_void, SetPreferredAppModeFn>(ptr);
-            let _ = set_preferred(PreferredAppMode::AllowDark);
-            log::debug!("Dark mode: SetPreferredAppMode(AllowDark) succeeded.");
+        AppThemeModeFns {
+            // SetPreferredAppMode — ordinal 135
+            set_preferred_app_mode: get_uxtheme_proc_address(
+                module,
+                UXTHEME_ORD_SET_PREFERRED_APP_MODE,
+            )
+            .map(|ptr| std::mem::transmute::<*const c_void, SetPreferredAppModeFn>(ptr)),
+            // RefreshImmersiveColorPolicyState — ordinal 104
+            refresh_immersive_color_policy_state: get_uxtheme_proc_address(
+                module,
+                UXTHEME_ORD_REFRESH_IMMERSIVE_COLOR_POLICY_STATE,
+            )
+            .map(|ptr| {
+                std::mem::transmute::<*const c_void, RefreshImmersiveColorPolicyStateFn>(ptr)
+            }),
+            // FlushMenuThemes — ordinal 136
+            flush_menu_themes: get_uxtheme_proc_address(module, UXTHEME_ORD_FLUSH_MENU_THEMES)
+                .map(|ptr| std::mem::transmute::<*const c_void, FlushMenuThemesFn>(ptr)),
         }
+    })
+}
 
-        // RefreshImmersiveColorPolicyState — ordinal 104
-        if let Some(ptr) =
-            get_uxtheme_proc_address(module, UXTHEME_ORD_REFRESH_IMMERSIVE_COLOR_POLICY_STATE)
-        {
-            let refresh: RefreshImmersiveColorPolicyStateFn =
-                std::mem::transmute::<*const c_void, RefreshImmersiveColorPolicyStateFn>(ptr);
+/// Sets the preferred app mode to `AllowDark` or `Default`, then refreshes the
+/// immersive color policy and flushes menu themes. Unlike `init_app_dark_mode`
+/// this may be called any number of times.
+fn set_app_theme_mode(dark: bool) {
+    let fns = app_theme_mode_fns();
+    unsafe {
+        if let Some(set_preferred) = fns.set_preferred_app_mode {
+            let _ = set_preferred(PreferredAppMode::for_dark(dark));
+            log::debug!("Dark mode: SetPreferredAppMode(dark={dark}) succeeded.");
+        }
+        if let Some(refresh) = fns.refresh_immersive_color_policy_state {
             refresh();
             log::debug!("Dark mode: RefreshImmersiveColorPolicyState succeeded.");
         }
-
-        // FlushMenuThemes — ordinal 136
-        if let Some(ptr) = get_uxtheme_proc_address(module, UXTHEME_ORD_FLUSH_MENU_THEMES) {
-            let flush: FlushMenuThemesFn =
-                std::mem::transmute::<*const c_void, FlushMenuThemesFn>(ptr);
+        if let Some(flush) = fns.flush_menu_themes {
             flush();
             log::debug!("Dark mode: FlushMenuThemes succeeded.");
         }
-    });
+    }
 }
 
 /// Enables dark mode and forces classic rendering (empty theme) on button-like controls.
 ///
 /// This is the canonical setup path for RadioButton and CheckBox controls: it calls
-/// `try_enable_dark_mode` then `SetWindowTheme("", "")` so that `WM_CTLCOLORBTN` /
+/// `apply_native_color_mode` then `SetWindowTheme("", "")` so that `WM_CTLCOLORBTN` /
 /// `WM_CTLCOLORSTATIC` messages are delivered to the parent and our palette is applied.
 /// Using a single helper here prevents the split between creation-time dark-mode enablement
 /// and style-application-time classic rendering that caused previous dark-theme regressions.
 pub(crate) fn apply_button_dark_mode_classic_render(hwnd: HWND) {
-    try_enable_dark_mode(hwnd);
+    apply_native_color_mode(hwnd);
     unsafe {
         let empty = windows::core::HSTRING::new();
         let _ = SetWindowTheme(hwnd, &empty, &empty);
     }
 }
 
-/// Best-effort enablement of dark mode for non-client areas (notably scrollbars) on supported OS builds.
-pub(crate) fn try_enable_dark_mode(hwnd: HWND) {
-    try_enable_dark_menu_theme_support(hwnd);
+/// Best-effort dark (or, under a light theme, light) rendering of non-client
+/// areas (notably scrollbars) on supported OS builds.
+pub(crate) fn apply_native_color_mode(hwnd: HWND) {
+    let dark = is_native_dark_mode();
+    try_enable_dark_menu_theme_support(hwnd, dark);
     unsafe {
-        let enable_dark: i32 = 1;
+        let enable_dark = i32::from(dark);
         const DWMWA_USE_IMMERSIVE_DARK_MODE: DWMWINDOWATTRIBUTE = DWMWINDOWATTRIBUTE(20);
         // Try primary attribute ID
         let _ = DwmSetWindowAttribute(
@@ -1473,16 +1730,28 @@ pub(crate) fn try_enable_dark_mode(hwnd: HWND) {
             std::mem::size_of_val(&enable_dark) as u32,
         );
         // Explorer dark theme often yields dark scrollbars on common controls.
-        let _ = SetWindowTheme(hwnd, w!("DarkMode_Explorer"), None);
+        let theme_name = if dark {
+            w!("DarkMode_Explorer")
+        } else {
+            w!("Explorer")
+        };
+        let _ = SetWindowTheme(hwnd, theme_name, None);
     }
 }
 
 #[repr(i32)]
 #[derive(Clone, Copy)]
 enum PreferredAppMode {
+    Default = 0,
     AllowDark = 1,
 }
 
+impl PreferredAppMode {
+    fn for_dark(dark: bool) -> Self {
+        if dark { Self::AllowDark } else { Self::Default }
+    }
+}
+
 type SetPreferredAppModeFn = unsafe extern "system" fn(PreferredAppMode) -> PreferredAppMode;
 type FlushMenuThemesFn = unsafe extern "system" fn();
 type RefreshImmersiveColorPolicyStateFn = unsafe extern "system" fn();
@@ -1518,7 +1787,7 @@ fn get_uxtheme_proc_address(
     unsafe { GetProcAddress(module, PCSTR(ordinal as *const u8)) }.map(|func| func as *const c_void)
 }
 
-fn try_enable_dark_menu_theme_support(hwnd: HWND) {
+fn try_enable_dark_menu_theme_support(hwnd: HWND, dark: bool) {
     static ALLOW_DARK_MODE_FOR_WINDOW_PTR: OnceLock<Option<AllowDarkModeForWindowFn>> =
         OnceLock::new();
 
@@ -1545,7 +1814,7 @@ fn try_enable_dark_menu_theme_support(hwnd: HWND) {
         {
             let set_preferred: SetPreferredAppModeFn =
                 std::mem::transmute::<*const c_void, SetPreferredAppModeFn>(set_preferred_ptr);
-            let _ = set_preferred(PreferredAppMode::AllowDark);
+            let _ = set_preferred(PreferredAppMode::for_dark(is_native_dark_mode()));
         }
 
         if let Some(flush_menu_themes_ptr) =
@@ -1567,10 +1836,10 @@ fn try_enable_dark_menu_theme_support(hwnd: HWND) {
 
     if let Some(allow_dark_mode_for_window) = maybe_allow_window_dark {
         unsafe {
-            let _ = allow_dark_mode_for_window(hwnd, true.into());
+            let _ = allow_dark_mode_for_window(hwnd, dark.into());
         }
         // Re-flush menu themes so Windows re-evaluates the menu bar
-        // now that per-window dark mode has been enabled.
+        // now that per-window dark mode has been enabled or disabled.
         flush_menu_themes_if_available();
     }
 }
@@ -1622,7 +1891,7 @@ struct UahDrawMenuItem {
     umi: UahMenuItem,
 }
 
-/// Dark-mode colours used for the menu bar painting.
+/// Colours used for the menu bar painting.
 struct MenuBarColors {
     bar_bg: COLORREF,
     text_normal: COLORREF,
@@ -1631,54 +1900,36 @@ struct MenuBarColors {
 }
 
 impl MenuBarColors {
-    /// Derive from the `MainWindowBackground` style, falling back to sensible
-    /// dark defaults when no style is set.
+    /// Derive from the `MainWindowBackground` style, falling back to the
+    /// current theme's window colors when no style is set. Hot and pushed
+    /// items are lighter than the bar under a dark theme and darker under a
+    /// light one.
     fn from_state(state: &Win32ApiInternalState) -> Self {
         use super::controls::styling_handler::color_to_colorref;
-        use super::styling_primitives::Color;
 
-        let default_bg = Color {
-            r: 0x2E,
-            g: 0x32,
-            b: 0x39,
-        };
-        let default_text = Color {
-            r: 0xE0,
-            g: 0xE5,
-            b: 0xEC,
+        let theme = state.current_theme();
+        let parsed = state.get_parsed_style(StyleId::MainWindowBackground);
+        let bg_color = parsed
+            .as_ref()
+            .and_then(|p| p.background_color.as_ref())
+            .unwrap_or(&theme.colors.window_background);
+        let txt_color = parsed
+            .as_ref()
+            .and_then(|p| p.text_color.as_ref())
+            .unwrap_or(&theme.colors.text);
+        let shift = |amount: i16| {
+            let amount = if theme.is_dark { amount } else { -amount };
+            color_to_colorref(&bg_color.offset(amount))
         };
-
-        if let Some(parsed) = state.get_parsed_style(StyleId::MainWindowBackground) {
-            let bg_color = parsed.background_color.as_ref().unwrap_or(&default_bg);
-            let txt_color = parsed.text_color.as_ref().unwrap_or(&default_text);
-            let bg = color_to_colorref(bg_color);
-            MenuBarColors {
-                bar_bg: bg,
-                text_normal: color_to_colorref(txt_color),
-                hot_bg: COLORREF(lighten_colorref(bg, 20)),
-                pushed_bg: COLORREF(lighten_colorref(bg, 35)),
-            }
-        } else {
-            // Reasonable dark fallback.
-            let bg = color_to_colorref(&default_bg);
-            MenuBarColors {
-                bar_bg: bg,
-                text_normal: color_to_colorref(&default_text),
-                hot_bg: COLORREF(lighten_colorref(bg, 20)),
-                pushed_bg: COLORREF(lighten_colorref(bg, 35)),
-            }
+        MenuBarColors {
+            bar_bg: color_to_colorref(bg_color),
+            text_normal: color_to_colorref(txt_color),
+            hot_bg: shift(20),
+            pushed_bg: shift(35),
         }
     }
 }
 
-/// Brighten each channel of a `COLORREF` by `amount` (clamped to 255).
-fn lighten_colorref(c: COLORREF, amount: u32) -> u32 {
-    let r = (c.0 & 0xFF).min(255 - amount) + amount;
-    let g = ((c.0 >> 8) & 0xFF).min(255 - amount) + amount;
-    let b = ((c.0 >> 16) & 0xFF).min(255 - amount) + amount;
-    r | (g << 8) | (b << 16)
-}
-
 /// Fill the entire menu bar background (`WM_UAHDRAWMENU`).
 unsafe fn paint_dark_menu_bar(hwnd: HWND, hdc: HDC, bar_bg: COLORREF) {
     unsafe {
@@ -1777,6 +2028,7 @@ impl Win32ApiInternalState {
     pub(crate) fn message_name(msg: u32) -> &'static str {
         match msg {
             WM_SIZE => "WM_SIZE",
+            WM_DPICHANGED => "WM_DPICHANGED",
             WM_SIZING => "WM_SIZING",
             WM_WINDOWPOSCHANGED => "WM_WINDOWPOSCHANGED",
             WM_WINDOWPOSCHANGING => "WM_WINDOWPOSCHANGING",
@@ -1913,14 +2165,41 @@ impl Win32ApiInternalState {
             WM_APP_TAB_SELECTED => {
                 event_to_send = self.handle_wm_app_tab_selected(hwnd, wparam, lparam, window_id);
             }
+            WM_APP_TAB_TOOLTIP_REQUESTED => {
+                self.handle_wm_app_tab_tooltip_requested(wparam, lparam, window_id);
+                lresult_override = Some(LRESULT(0));
+            }
             WM_APP_TOGGLE_SWITCH_CLICKED => {
                 event_to_send =
                     self.handle_wm_app_toggle_switch_clicked(hwnd, wparam, lparam, window_id);
             }
+            WM_APP_SLIDER_DRAGGING | WM_APP_SLIDER_COMMITTED => {
+                event_to_send = self.handle_wm_app_slider(wparam, lparam, window_id, msg);
+            }
+            WM_APP_NUMERIC_VALUE_CHANGED => {
+                event_to_send = self.handle_wm_app_numeric_value_changed(wparam, window_id);
+            }
+            WM_APP_STATUS_SEGMENT_CLICKED => {
+                event_to_send =
+                    self.handle_wm_app_status_segment_clicked(wparam, lparam, window_id);
+            }
+            WM_APP_TOOLBAR_ITEM_CLICKED => {
+                event_to_send = self.handle_wm_app_toolbar_item_clicked(wparam, lparam);
+            }
+            WM_CONTEXTMENU => {
+                event_to_send = self.handle_wm_contextmenu(hwnd, wparam, lparam, window_id);
+                if event_to_send.is_some() {
+                    lresult_override = Some(LRESULT(0));
+                }
+            }
             WM_GETMINMAXINFO => {
                 lresult_override =
                     Some(self.handle_wm_getminmaxinfo(hwnd, wparam, lparam, window_id));
             }
+            WM_DPICHANGED => {
+                event_to_send = self.handle_wm_dpichanged(hwnd, wparam, lparam, window_id);
+                lresult_override = Some(SUCCESS_CODE);
+            }
             WM_UAHDRAWMENU => {
                 if self
                     .get_parsed_style(StyleId::MainWindowBackground)
@@ -1962,6 +2241,14 @@ impl Win32ApiInternalState {
                     paint_router::PaintRoute::Edit => {
                         input_handler::handle_wm_ctlcoloredit(self, window_id, hdc, hwnd_control)
                     }
+                    paint_router::PaintRoute::NumericInput => {
+                        numeric_input_handler::handle_wm_ctlcoloredit(
+                            self,
+                            window_id,
+                            hdc,
+                            hwnd_control,
+                        )
+                    }
                     paint_router::PaintRoute::LabelStatic => {
                         label_handler::handle_wm_ctlcolorstatic(self, window_id, hdc, hwnd_control)
                     }
@@ -2030,19 +2317,74 @@ impl Win32ApiInternalState {
         let nmhdr = unsafe { &*nmhdr_ptr };
         let control_id_from_notify = ControlId::new(nmhdr.idFrom as i32);
 
-        let is_treeview_notification = self.with_window_data_read(window_id, |window_data| {
-            Ok(window_data.has_treeview_state()
-                && window_data.get_control_hwnd(control_id_from_notify) == Some(nmhdr.hwndFrom))
-        });
-
-        if let Ok(true) = is_treeview_notification {
-            match nmhdr.code {
-                NM_CUSTOMDRAW => {
-                    log::trace!(
-                        "Routing NM_CUSTOMDRAW from ControlID {} to treeview_handler.",
-                        control_id_from_notify.raw()
-                    );
-                    let lresult = treeview_handler::handle_nm_customdraw(
+        let is_list_view_notification = self
+            .with_window_data_read(window_id, |window_data| {
+                Ok(window_data.get_control_kind(control_id_from_notify)
+                    == Some(ControlKind::ListView)
+                    && window_data.get_control_hwnd(control_id_from_notify) == Some(nmhdr.hwndFrom))
+            })
+            .unwrap_or(false);
+        if is_list_view_notification {
+            return match nmhdr.code {
+                list_view_handler::LVN_GETDISPINFOW => {
+                    list_view_handler::handle_lvn_getdispinfo(
+                        self,
+                        window_id,
+                        control_id_from_notify,
+                        lparam_original,
+                    );
+                    (None, Some(LRESULT(0)))
+                }
+                list_view_handler::LVN_COLUMNCLICK => (
+                    list_view_handler::handle_lvn_columnclick(
+                        self,
+                        window_id,
+                        control_id_from_notify,
+                        lparam_original,
+                    ),
+                    None,
+                ),
+                list_view_handler::LVN_ITEMCHANGED => (
+                    list_view_handler::handle_lvn_itemchanged(
+                        self,
+                        window_id,
+                        control_id_from_notify,
+                        lparam_original,
+                    ),
+                    None,
+                ),
+                list_view_handler::LVN_ITEMACTIVATE => (
+                    list_view_handler::handle_lvn_itemactivate(
+                        window_id,
+                        control_id_from_notify,
+                        lparam_original,
+                    ),
+                    None,
+                ),
+                NM_CUSTOMDRAW => (
+                    None,
+                    Some(list_view_handler::handle_nm_customdraw(
+                        self,
+                        lparam_original,
+                    )),
+                ),
+                _ => (None, None),
+            };
+        }
+
+        let is_treeview_notification = self.with_window_data_read(window_id, |window_data| {
+            Ok(window_data.has_treeview_state()
+                && window_data.get_control_hwnd(control_id_from_notify) == Some(nmhdr.hwndFrom))
+        });
+
+        if let Ok(true) = is_treeview_notification {
+            match nmhdr.code {
+                NM_CUSTOMDRAW => {
+                    log::trace!(
+                        "Routing NM_CUSTOMDRAW from ControlID {} to treeview_handler.",
+                        control_id_from_notify.raw()
+                    );
+                    let lresult = treeview_handler::handle_nm_customdraw(
                         self,
                         window_id,
                         lparam_original,
@@ -2063,6 +2405,10 @@ impl Win32ApiInternalState {
                     );
                     return (event, None);
                 }
+                TVN_GETINFOTIPW => {
+                    treeview_handler::handle_tvn_getinfotip(self, window_id, lparam_original);
+                    return (None, Some(LRESULT(0)));
+                }
                 TVN_ITEMCHANGEDW => {
                     log::trace!(
                         "Routing TVN_ITEMCHANGEDW from ControlID {} to treeview_handler.",
@@ -2119,13 +2465,28 @@ impl Win32ApiInternalState {
             .get_parsed_style(StyleId::MainWindowBackground)
             .is_some()
         {
-            try_enable_dark_mode(hwnd);
+            apply_native_color_mode(hwnd);
         }
     }
 
     /*
      * Triggers layout recalculation for the specified window.
      */
+    /*
+     * Re-runs layout after a command changed a control's text or font, but only
+     * when the window has `SizeMode::Auto` rules whose measurement could change.
+     */
+    pub(crate) fn relayout_if_auto_sized(self: &Arc<Self>, window_id: WindowId) {
+        let has_auto_rules = self
+            .with_window_data_read(window_id, |window_data| {
+                Ok(window_data.has_auto_sized_rules())
+            })
+            .unwrap_or(false);
+        if has_auto_rules {
+            self.trigger_layout_recalculation(window_id);
+        }
+    }
+
     pub(crate) fn trigger_layout_recalculation(self: &Arc<Self>, window_id: WindowId) {
         log::debug!("trigger_layout_recalculation called for WinID {window_id:?}");
 
@@ -2155,10 +2516,12 @@ impl Win32ApiInternalState {
             "Platform: WM_SIZE for WinID {window_id:?}, HWND {hwnd:?}. Client: {client_width}x{client_height}"
         );
         self.trigger_layout_recalculation(window_id);
+        // Applications size their layout rules in DIPs; report the client size the same way.
+        let scale = dpi_scale_for_window(hwnd);
         Some(AppEvent::WindowResized {
             window_id,
-            width: client_width,
-            height: client_height,
+            width: scale.unscale(client_width),
+            height: scale.unscale(client_height),
         })
     }
 
@@ -2423,7 +2786,7 @@ impl Win32ApiInternalState {
      */
     fn handle_wm_app_splitter(
         self: &Arc<Self>,
-        _hwnd_parent: HWND,
+        hwnd_parent: HWND,
         wparam: WPARAM,
         lparam: LPARAM,
         window_id: WindowId,
@@ -2431,8 +2794,8 @@ impl Win32ApiInternalState {
     ) -> Option<AppEvent> {
         // WPARAM contains the splitter's HWND
         let hwnd_splitter = HWND(wparam.0 as *mut std::ffi::c_void);
-        // LPARAM contains the desired_left_width_px
-        let desired_left_width_px = lparam.0 as i32;
+        // LPARAM contains the desired left width in pixels; events carry DIPs.
+        let desired_left_width = dpi_scale_for_window(hwnd_parent).unscale(lparam.0 as i32);
 
         // Get the control ID from the splitter's HWND
         let control_id_raw = unsafe { GetDlgCtrlID(hwnd_splitter) };
@@ -2453,14 +2816,14 @@ impl Win32ApiInternalState {
                     Ok(())
                 });
                 log::trace!(
-                    "SplitterHandler: Dragging splitter ID {} - desired_left_width_px: {}",
+                    "SplitterHandler: Dragging splitter ID {} - desired_left_width: {}",
                     control_id.raw(),
-                    desired_left_width_px
+                    desired_left_width
                 );
                 Some(AppEvent::SplitterDragging {
                     window_id,
                     control_id,
-                    desired_left_width_px,
+                    desired_left_width,
                 })
             }
             WM_APP_SPLITTER_DRAG_ENDED => {
@@ -2470,14 +2833,14 @@ impl Win32ApiInternalState {
                 });
                 self.trigger_layout_recalculation(window_id);
                 log::debug!(
-                    "SplitterHandler: Drag ended for splitter ID {} - final desired_left_width_px: {}",
+                    "SplitterHandler: Drag ended for splitter ID {} - final desired_left_width: {}",
                     control_id.raw(),
-                    desired_left_width_px
+                    desired_left_width
                 );
                 Some(AppEvent::SplitterDragEnded {
                     window_id,
                     control_id,
-                    desired_left_width_px,
+                    desired_left_width,
                 })
             }
             _ => None,
@@ -2520,6 +2883,42 @@ impl Win32ApiInternalState {
         })
     }
 
+    /*
+     * Handles WM_APP_TAB_TOOLTIP_REQUESTED messages sent by the TabBar WndProc to its root
+     * while answering a tooltip request for a tab without its own tooltip.
+     * WPARAM = HWND of the tab bar.
+     * LPARAM = index of the hovered tab.
+     * The UiStateProvider's answer is handed back to the tab bar. [CDU-TooltipsV1]
+     */
+    fn handle_wm_app_tab_tooltip_requested(
+        self: &Arc<Self>,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        window_id: WindowId,
+    ) {
+        let hwnd_tab_bar = HWND(wparam.0 as *mut std::ffi::c_void);
+        let control_id_raw = unsafe { GetDlgCtrlID(hwnd_tab_bar) };
+        if control_id_raw == 0 {
+            log::warn!(
+                "[TabBar] WM_APP_TAB_TOOLTIP_REQUESTED from HWND {hwnd_tab_bar:?} without control ID"
+            );
+            return;
+        }
+        let control_id = ControlId::new(control_id_raw);
+        let provider = self
+            .ui_state_provider()
+            .lock()
+            .ok()
+            .and_then(|holder| holder.as_ref().and_then(|weak| weak.upgrade()));
+        let text = provider.and_then(|provider| {
+            provider
+                .lock()
+                .ok()
+                .and_then(|guard| guard.tab_tooltip(window_id, control_id, lparam.0 as usize))
+        });
+        tab_bar_handler::set_provided_tooltip(hwnd_tab_bar, text);
+    }
+
     /*
      * Handles WM_APP_TOGGLE_SWITCH_CLICKED messages sent by the ToggleSwitch WndProc to its root.
      * WPARAM = HWND of the toggle switch control.
@@ -2554,6 +2953,186 @@ impl Win32ApiInternalState {
         })
     }
 
+    /*
+     * Handles WM_APP_SLIDER_DRAGGING / WM_APP_SLIDER_COMMITTED messages sent by
+     * the Slider WndProc to its root.
+     * WPARAM = HWND of the slider control.
+     * LPARAM = the snapped value.
+     */
+    fn handle_wm_app_slider(
+        self: &Arc<Self>,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        window_id: WindowId,
+        msg: u32,
+    ) -> Option<AppEvent> {
+        let hwnd_slider = HWND(wparam.0 as *mut std::ffi::c_void);
+        let control_id_raw = unsafe { GetDlgCtrlID(hwnd_slider) };
+        if control_id_raw == 0 {
+            log::warn!("[Slider] Message {msg} from HWND {hwnd_slider:?} without control ID");
+            return None;
+        }
+        let control_id = ControlId::new(control_id_raw);
+        let value = lparam.0 as i32;
+        let committed = msg == WM_APP_SLIDER_COMMITTED;
+        log::trace!(
+            "[Slider] Value changed: control_id={} value={value} committed={committed}",
+            control_id.raw()
+        );
+        Some(AppEvent::SliderValueChanged {
+            window_id,
+            control_id,
+            value,
+            committed,
+        })
+    }
+
+    /*
+     * Handles WM_APP_NUMERIC_VALUE_CHANGED sent by a NumericInput container to
+     * its root.
+     * WPARAM = HWND of the container; the value is read from its state.
+     */
+    fn handle_wm_app_numeric_value_changed(
+        self: &Arc<Self>,
+        wparam: WPARAM,
+        window_id: WindowId,
+    ) -> Option<AppEvent> {
+        let hwnd_container = HWND(wparam.0 as *mut std::ffi::c_void);
+        let control_id_raw = unsafe { GetDlgCtrlID(hwnd_container) };
+        if control_id_raw == 0 {
+            log::warn!(
+                "[NumericInput] Value change from HWND {hwnd_container:?} without control ID"
+            );
+            return None;
+        }
+        let control_id = ControlId::new(control_id_raw);
+        let value = numeric_input_handler::current_value(hwnd_container)?;
+        log::trace!(
+            "[NumericInput] Value changed: control_id={} value={value}",
+            control_id.raw()
+        );
+        Some(AppEvent::NumericValueChanged {
+            window_id,
+            control_id,
+            value,
+        })
+    }
+
+    /*
+     * Handles WM_APP_STATUS_SEGMENT_CLICKED sent by a StatusBar to its root.
+     * WPARAM = HWND of the status bar, LPARAM = index of the clicked segment.
+     */
+    fn handle_wm_app_status_segment_clicked(
+        self: &Arc<Self>,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        window_id: WindowId,
+    ) -> Option<AppEvent> {
+        let hwnd_bar = HWND(wparam.0 as *mut std::ffi::c_void);
+        let control_id_raw = unsafe { GetDlgCtrlID(hwnd_bar) };
+        if control_id_raw == 0 {
+            log::warn!("[StatusBar] Segment click from HWND {hwnd_bar:?} without control ID");
+            return None;
+        }
+        let control_id = ControlId::new(control_id_raw);
+        let segment = lparam.0 as usize;
+        log::debug!(
+            "[StatusBar] Segment clicked: control_id={} segment={segment}",
+            control_id.raw()
+        );
+        Some(AppEvent::StatusSegmentClicked {
+            window_id,
+            control_id,
+            segment,
+        })
+    }
+
+    /*
+     * Handles WM_APP_TOOLBAR_ITEM_CLICKED sent by a Toolbar to its root.
+     * WPARAM = HWND of the toolbar, LPARAM = index of the clicked item. The
+     * click is reported as the item's `MenuActionClicked`, like a menu entry.
+     */
+    fn handle_wm_app_toolbar_item_clicked(
+        self: &Arc<Self>,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> Option<AppEvent> {
+        let hwnd_bar = HWND(wparam.0 as *mut std::ffi::c_void);
+        let index = lparam.0 as usize;
+        let Some(action_id) = toolbar_handler::action_at(hwnd_bar, index) else {
+            log::warn!("[Toolbar] Click on item {index} of HWND {hwnd_bar:?} without an action");
+            return None;
+        };
+        log::debug!("[Toolbar] Item {index} clicked: action {action_id:?}");
+        Some(AppEvent::MenuActionClicked { action_id })
+    }
+
+    /*
+     * Handles WM_CONTEXTMENU, which controls pass up to their top-level
+     * window when nothing below handles it. WPARAM = HWND the user
+     * right-clicked or that had the focus for Shift+F10 or the Menu key,
+     * possibly a native child of a registered control. LPARAM = screen
+     * position, or -1/-1 from the keyboard, in which case the menu is anchored
+     * at the tree view's selected item or the control's top-left corner.
+     * [CDU-ContextMenusV1]
+     */
+    fn handle_wm_contextmenu(
+        self: &Arc<Self>,
+        hwnd: HWND,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        window_id: WindowId,
+    ) -> Option<AppEvent> {
+        let hwnd_target = HWND(wparam.0 as *mut std::ffi::c_void);
+        let (control_id, hwnd_control, kind) = self
+            .with_window_data_read(window_id, |window_data| {
+                let mut current = hwnd_target;
+                while !current.is_invalid() && current != hwnd {
+                    if let Some(control_id) = window_data.find_control_id_by_hwnd(current) {
+                        return Ok(Some((
+                            control_id,
+                            current,
+                            window_data.get_control_kind(control_id),
+                        )));
+                    }
+                    current = unsafe { GetParent(current) }.unwrap_or_default();
+                }
+                Ok(None)
+            })
+            .ok()
+            .flatten()?;
+
+        let x = (lparam.0 & 0xFFFF) as i16 as i32;
+        let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+        let click = (x != -1 || y != -1).then(|| {
+            let mut client_pt = POINT { x, y };
+            let _ = unsafe { ScreenToClient(hwnd_control, &mut client_pt) };
+            client_pt
+        });
+        let (item_id, client_pt) = if kind == Some(ControlKind::TreeView) {
+            treeview_handler::context_menu_target(self, window_id, hwnd_control, click)
+        } else {
+            (None, click.unwrap_or_default())
+        };
+        let mut screen_pt = client_pt;
+        let _ = unsafe { ClientToScreen(hwnd_control, &mut screen_pt) };
+
+        let scale = dpi_scale_for_window(hwnd_control);
+        log::debug!(
+            "Context menu requested on ControlID {} at {screen_pt:?}, item {item_id:?}",
+            control_id.raw()
+        );
+        Some(AppEvent::ContextMenuRequested {
+            window_id,
+            control_id,
+            screen_x: screen_pt.x,
+            screen_y: screen_pt.y,
+            client_x: scale.unscale(client_pt.x),
+            client_y: scale.unscale(client_pt.y),
+            item_id,
+        })
+    }
+
     fn resolve_ctlcolor_route(
         self: &Arc<Self>,
         window_id: WindowId,
@@ -2658,14 +3237,11 @@ impl Win32ApiInternalState {
             window_id
         );
 
-        // Best effort: apply dark theme one time per child/owner HWND pair.
+        // Best effort: apply the native color mode one time per child/owner HWND pair.
         // Reapplying SetWindowTheme during every WM_CTLCOLOR can cause excessive
         // repaint churn and effectively self-sustaining paint traffic.
         if Self::mark_combo_hwnd_themed(hwnd_combo_child) {
-            unsafe {
-                let _ = SetWindowTheme(hwnd_combo_child, w!("DarkMode_Explorer"), None);
-                try_enable_dark_mode(hwnd_combo_child);
-            }
+            apply_native_color_mode(hwnd_combo_child);
         }
 
         // Resolve style: try ComboBox first, fallback to DefaultInput
@@ -2791,20 +3367,105 @@ impl Win32ApiInternalState {
         SUCCESS_CODE
     }
 
+    /*
+     * Handles WM_DPICHANGED: the window moved to a monitor with another DPI.
+     * Re-creates the window's fonts and the style fonts of its controls at
+     * the new DPI, moves the window to the rectangle Windows suggests (which
+     * keeps its DIP size) and re-runs the layout with the rules scaled anew.
+     * [CDU-DpiAwarenessV1]
+     */
+    fn handle_wm_dpichanged(
+        self: &Arc<Self>,
+        hwnd: HWND,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        window_id: WindowId,
+    ) -> Option<AppEvent> {
+        // HIWORD is the vertical DPI; it always equals the horizontal one.
+        let dpi = highord_from_wparam(wparam) as u32;
+        log::debug!("Platform: WM_DPICHANGED for WinID {window_id:?}: {dpi} DPI");
+
+        let list_views = self
+            .with_window_data_read(window_id, |window_data| {
+                Ok(window_data
+                    .list_view_states
+                    .iter()
+                    .filter_map(|(control_id, state)| {
+                        window_data
+                            .get_control_hwnd(*control_id)
+                            .map(|hwnd| (hwnd, state.columns().to_vec()))
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .unwrap_or_default();
+        // Column widths are DIPs; rescale them to the new DPI.
+        for (list_hwnd, columns) in list_views {
+            list_view_handler::apply_column_widths(list_hwnd, &columns);
+        }
+
+        let styled_controls = self
+            .with_window_data_write(window_id, |window_data| {
+                window_data.recreate_fonts_for_dpi();
+                Ok(window_data
+                    .themed_controls()
+                    .into_iter()
+                    .filter(|(_, _, _, style_id)| style_id.is_some())
+                    .filter_map(|(control_id, _, control_hwnd, _)| {
+                        self.parsed_style_for_control(window_data, control_id)
+                            .map(|style| (control_hwnd, style))
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .unwrap_or_default();
+        // Owner-drawn controls pick their font at paint time; native ones need WM_SETFONT.
+        for (control_hwnd, style) in styled_controls {
+            if let Some(font) = style.font_for_window(control_hwnd) {
+                unsafe {
+                    SendMessageW(
+                        control_hwnd,
+                        WM_SETFONT,
+                        Some(WPARAM(font.0 as usize)),
+                        Some(LPARAM(1)),
+                    )
+                };
+            }
+        }
+
+        if lparam.0 != 0 {
+            let suggested = unsafe { &*(lparam.0 as *const RECT) };
+            if let Err(e) = unsafe {
+                SetWindowPos(
+                    hwnd,
+                    None,
+                    suggested.left,
+                    suggested.top,
+                    suggested.right - suggested.left,
+                    suggested.bottom - suggested.top,
+                    SWP_NOZORDER | SWP_NOACTIVATE,
+                )
+            } {
+                log::warn!("Platform: SetWindowPos after DPI change failed: {e:?}");
+            }
+        }
+        self.trigger_layout_recalculation(window_id);
+        Some(AppEvent::DpiChanged { window_id, dpi })
+    }
+
     /*
      * Handles WM_GETMINMAXINFO: Sets minimum window tracking size.
      */
     fn handle_wm_getminmaxinfo(
         self: &Arc<Self>,
-        _hwnd: HWND,
+        hwnd: HWND,
         _wparam: WPARAM,
         lparam: LPARAM,
         _window_id: WindowId,
     ) -> LRESULT {
         if lparam.0 != 0 {
             let mmi = unsafe { &mut *(lparam.0 as *mut MINMAXINFO) };
-            mmi.ptMinTrackSize.x = 300;
-            mmi.ptMinTrackSize.y = 200;
+            let (min_width, min_height) = dpi_scale_for_window(hwnd).scale_size((300, 200));
+            mmi.ptMinTrackSize.x = min_width;
+            mmi.ptMinTrackSize.y = min_height;
         }
         SUCCESS_CODE
     }
@@ -3007,6 +3668,7 @@ pub(crate) fn destroy_native_window(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{SizeConstraints, SizeMode};
     use windows::Win32::Foundation::HWND;
 
     /*
@@ -3091,141 +3753,6 @@ mod tests {
         assert_eq!(result, long_text);
     }
 
-    /*
-     * Unit tests for the pure layout calculation. These tests ensure the
-     * geometry is computed correctly without creating any native windows.
-     */
-
-    #[test]
-    // [CDU-LayoutSystemV1] Docking rules produce deterministic rectangles even without native HWNDs.
-    fn test_calculate_layout_top_and_fill() {
-        // Arrange
-        let rules = vec![
-            LayoutRule {
-                control_id: ControlId::new(1),
-                parent_control_id: None,
-                dock_style: DockStyle::Top,
-                order: 0,
-                fixed_size: Some(20),
-                margin: (0, 0, 0, 0),
-            },
-            LayoutRule {
-                control_id: ControlId::new(2),
-                parent_control_id: None,
-                dock_style: DockStyle::Fill,
-                order: 1,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-        ];
-        let parent_rect = RECT {
-            left: 0,
-            top: 0,
-            right: 100,
-            bottom: 100,
-        };
-        // Act
-        let map = NativeWindowData::calculate_layout(parent_rect, &rules);
-        // Assert
-        assert_eq!(map.get(&ControlId::new(1)).unwrap().bottom, 20);
-        assert_eq!(map.get(&ControlId::new(2)).unwrap().top, 20);
-        assert_eq!(map.get(&ControlId::new(2)).unwrap().bottom, 100);
-    }
-
-    #[test]
-    fn define_layout_validation_rejects_multiple_fill_siblings() {
-        let rules = vec![
-            LayoutRule {
-                control_id: ControlId::new(10),
-                parent_control_id: Some(ControlId::new(1)),
-                dock_style: DockStyle::Fill,
-                order: 0,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-            LayoutRule {
-                control_id: ControlId::new(11),
-                parent_control_id: Some(ControlId::new(1)),
-                dock_style: DockStyle::Fill,
-                order: 1,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-        ];
-
-        let err = NativeWindowData::validate_layout_rules(&rules)
-            .expect_err("multiple Fill siblings should be rejected");
-        let message = err.to_string();
-        assert!(message.contains("multiple DockStyle::Fill children"));
-        assert!(message.contains("10"));
-        assert!(message.contains("11"));
-    }
-
-    #[test]
-    fn define_layout_validation_allows_one_fill_per_parent() {
-        let rules = vec![
-            LayoutRule {
-                control_id: ControlId::new(20),
-                parent_control_id: Some(ControlId::new(1)),
-                dock_style: DockStyle::Fill,
-                order: 0,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-            LayoutRule {
-                control_id: ControlId::new(21),
-                parent_control_id: Some(ControlId::new(2)),
-                dock_style: DockStyle::Fill,
-                order: 0,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-            LayoutRule {
-                control_id: ControlId::new(22),
-                parent_control_id: Some(ControlId::new(1)),
-                dock_style: DockStyle::Top,
-                order: 1,
-                fixed_size: Some(10),
-                margin: (0, 0, 0, 0),
-            },
-        ];
-
-        NativeWindowData::validate_layout_rules(&rules)
-            .expect("one Fill child per parent should be valid");
-    }
-
-    #[test]
-    fn define_layout_validation_rejects_docked_rule_without_fixed_size() {
-        let rules = vec![LayoutRule {
-            control_id: ControlId::new(30),
-            parent_control_id: None,
-            dock_style: DockStyle::Top,
-            order: 0,
-            fixed_size: None,
-            margin: (0, 0, 0, 0),
-        }];
-
-        let err = NativeWindowData::validate_layout_rules(&rules)
-            .expect_err("Top dock without fixed_size should be rejected");
-        assert!(err.to_string().contains("without fixed_size"));
-    }
-
-    #[test]
-    fn define_layout_validation_rejects_negative_fixed_size() {
-        let rules = vec![LayoutRule {
-            control_id: ControlId::new(31),
-            parent_control_id: None,
-            dock_style: DockStyle::Left,
-            order: 0,
-            fixed_size: Some(-1),
-            margin: (0, 0, 0, 0),
-        }];
-
-        let err = NativeWindowData::validate_layout_rules(&rules)
-            .expect_err("Negative fixed_size should be rejected");
-        assert!(err.to_string().contains("negative fixed_size"));
-    }
-
     #[test]
     fn post_layout_redraw_skips_updatenow() {
         assert!(!NativeWindowData::post_layout_redraw_uses_updatenow());
@@ -3313,7 +3840,12 @@ mod tests {
                 dock_style: DockStyle::Fill,
                 order: 0,
                 fixed_size: None,
+                size_mode: SizeMode::Fixed,
                 margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
             },
             LayoutRule {
                 control_id: treeview_id,
@@ -3321,7 +3853,12 @@ mod tests {
                 dock_style: DockStyle::Fill,
                 order: 0,
                 fixed_size: None,
+                size_mode: SizeMode::Fixed,
                 margin: (0, 0, 0, 0),
+                constraints: SizeConstraints::default(),
+                absolute_bounds: None,
+                preferred_size: None,
+                container: None,
             },
         ]);
         data.register_control_kind(panel_id, ControlKind::Static);
@@ -3367,144 +3904,6 @@ mod tests {
         assert!(!data.is_treeview_redraw_suspended());
     }
 
-    #[test]
-    // [CDU-LayoutSystemV1] Proportional fills divide available space using the declarative weights.
-    fn test_calculate_layout_proportional_fill() {
-        // Arrange
-        let rules = vec![
-            LayoutRule {
-                control_id: ControlId::new(1),
-                parent_control_id: None,
-                dock_style: DockStyle::ProportionalFill { weight: 1.0 },
-                order: 0,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-            LayoutRule {
-                control_id: ControlId::new(2),
-                parent_control_id: None,
-                dock_style: DockStyle::ProportionalFill { weight: 2.0 },
-                order: 1,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-        ];
-        let parent_rect = RECT {
-            left: 0,
-            top: 0,
-            right: 100,
-            bottom: 20,
-        };
-        // Act
-        let map = NativeWindowData::calculate_layout(parent_rect, &rules);
-        // Assert
-        let rect1 = map.get(&ControlId::new(1)).unwrap();
-        let rect2 = map.get(&ControlId::new(2)).unwrap();
-        assert_eq!(rect1.right - rect1.left, 33);
-        assert_eq!(rect2.left, 33);
-        assert_eq!(rect2.right - rect2.left, 66);
-    }
-
-    #[test]
-    // [CDU-LayoutSystemV1][CDU-Control-PanelV1] Nested panel layouts respect parent-child docking relationships.
-    fn test_calculate_layout_nested_panels() {
-        // Arrange
-        let outer_rule = LayoutRule {
-            control_id: ControlId::new(1),
-            parent_control_id: None,
-            dock_style: DockStyle::Fill,
-            order: 0,
-            fixed_size: None,
-            margin: (0, 0, 0, 0),
-        };
-        let inner_rules = vec![
-            LayoutRule {
-                control_id: ControlId::new(2),
-                parent_control_id: Some(ControlId::new(1)),
-                dock_style: DockStyle::Top,
-                order: 0,
-                fixed_size: Some(10),
-                margin: (0, 0, 0, 0),
-            },
-            LayoutRule {
-                control_id: ControlId::new(3),
-                parent_control_id: Some(ControlId::new(1)),
-                dock_style: DockStyle::Fill,
-                order: 1,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-        ];
-        let parent_rect = RECT {
-            left: 0,
-            top: 0,
-            right: 50,
-            bottom: 50,
-        };
-        // Act
-        let outer_map =
-            NativeWindowData::calculate_layout(parent_rect, std::slice::from_ref(&outer_rule));
-        let outer_rect = outer_map.get(&ControlId::new(1)).unwrap();
-        let inner_map = NativeWindowData::calculate_layout(
-            RECT {
-                left: 0,
-                top: 0,
-                right: outer_rect.right - outer_rect.left,
-                bottom: outer_rect.bottom - outer_rect.top,
-            },
-            &inner_rules,
-        );
-        // Assert
-        assert_eq!(outer_rect.right - outer_rect.left, 50);
-        assert_eq!(inner_map.get(&ControlId::new(2)).unwrap().bottom, 10);
-        assert_eq!(inner_map.get(&ControlId::new(3)).unwrap().top, 10);
-    }
-
-    #[test]
-    fn test_calculate_layout_header_checkbox_and_fill_non_overlap() {
-        let rules = vec![
-            LayoutRule {
-                control_id: ControlId::new(40),
-                parent_control_id: None,
-                dock_style: DockStyle::Top,
-                order: 0,
-                fixed_size: Some(28),
-                margin: (0, 0, 4, 0),
-            },
-            LayoutRule {
-                control_id: ControlId::new(41),
-                parent_control_id: None,
-                dock_style: DockStyle::Top,
-                order: 1,
-                fixed_size: Some(24),
-                margin: (4, 0, 4, 4),
-            },
-            LayoutRule {
-                control_id: ControlId::new(42),
-                parent_control_id: None,
-                dock_style: DockStyle::Fill,
-                order: 2,
-                fixed_size: None,
-                margin: (0, 0, 0, 0),
-            },
-        ];
-        let parent_rect = RECT {
-            left: 0,
-            top: 0,
-            right: 600,
-            bottom: 400,
-        };
-
-        let map = NativeWindowData::calculate_layout(parent_rect, &rules);
-        let header_rect = map.get(&ControlId::new(40)).expect("header rect");
-        let checkbox_rect = map.get(&ControlId::new(41)).expect("checkbox rect");
-        let fill_rect = map.get(&ControlId::new(42)).expect("fill rect");
-
-        assert!(header_rect.bottom <= checkbox_rect.top);
-        assert!(checkbox_rect.bottom <= fill_rect.top);
-        assert!(fill_rect.bottom > fill_rect.top);
-    }
-
     #[test]
     fn resolve_dark_mode_uxtheme_ordinals_detects_expected_ordinals() {
         let ordinals = resolve_dark_mode_uxtheme_ordinals(|ordinal| {